@@ -27,13 +27,14 @@ use {crate::domain, ethrpc::Web3, std::collections::HashMap, url::Url};
 pub mod events;
 pub mod order;
 
-/// Builds a web3 client based on the ethrpc args config.
-pub fn web3_client(ethrpc: &Url, ethrpc_args: &shared::ethrpc::Arguments) -> Web3 {
+/// Builds a web3 client based on the ethrpc args config. If multiple URLs are
+/// specified, requests are load-balanced across them.
+pub fn web3_client(ethrpc: &[Url], ethrpc_args: &shared::ethrpc::Arguments) -> Web3 {
     let http_factory =
         shared::http_client::HttpClientFactory::new(&shared::http_client::Arguments {
             http_timeout: std::time::Duration::from_secs(10),
         });
-    shared::ethrpc::web3(ethrpc_args, &http_factory, ethrpc, "base")
+    shared::ethrpc::multi_web3(ethrpc_args, &http_factory, ethrpc, "base")
 }
 
 pub struct SolvableOrders {
@@ -6,6 +6,7 @@ use {
     database::{
         byte_array::ByteArray,
         order_events::{self, OrderEvent},
+        order_filter_reasons,
     },
     sqlx::{Acquire, Error, PgConnection},
     tokio::time::Instant,
@@ -16,6 +17,14 @@ impl super::Postgres {
     pub async fn delete_order_events_before(&self, timestamp: DateTime<Utc>) -> Result<u64, Error> {
         order_events::delete_order_events_before(&self.pool, timestamp).await
     }
+
+    /// Deletes order filter reasons before the provided timestamp.
+    pub async fn delete_order_filter_reasons_before(
+        &self,
+        timestamp: DateTime<Utc>,
+    ) -> Result<u64, Error> {
+        order_filter_reasons::delete_before(&self.pool, timestamp).await
+    }
 }
 
 pub async fn store_order_events(
@@ -48,3 +57,35 @@ pub async fn store_order_events(
         Err(err) => tracing::warn!(?label, count, ?err, "failed to insert order events"),
     }
 }
+
+pub async fn store_order_filter_reasons(
+    ex: &mut PgConnection,
+    reasons: Vec<(domain::OrderUid, &str)>,
+    timestamp: DateTime<Utc>,
+    batch_size: usize,
+) {
+    let start = Instant::now();
+    let count = reasons.len();
+
+    let insert = async move {
+        let mut ex = ex.begin().await?;
+
+        for chunk in reasons.chunks(batch_size) {
+            order_filter_reasons::upsert_batch(
+                &mut ex,
+                chunk
+                    .iter()
+                    .map(|(uid, reason)| (ByteArray(uid.0), *reason)),
+                timestamp,
+            )
+            .await?;
+        }
+
+        ex.commit().await
+    };
+
+    match insert.await {
+        Ok(_) => tracing::debug!(count, elapsed = ?start.elapsed(), "stored order filter reasons"),
+        Err(err) => tracing::warn!(count, ?err, "failed to store order filter reasons"),
+    }
+}
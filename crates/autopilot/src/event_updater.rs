@@ -1,6 +1,6 @@
 use {
     anyhow::Result,
-    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving},
+    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving, Reorg},
     shared::{
         event_handling::{EventHandler, EventRetrieving, EventStoring},
         maintenance::Maintaining,
@@ -76,4 +76,8 @@ where
     fn name(&self) -> &str {
         "EventUpdater"
     }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        self.0.handle_reorg(reorg).await
+    }
 }
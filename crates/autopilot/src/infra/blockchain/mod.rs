@@ -5,7 +5,6 @@ use {
     ethcontract::dyns::DynWeb3,
     ethrpc::{block_stream::CurrentBlockWatcher, extensions::DebugNamespace},
     primitive_types::U256,
-    std::time::Duration,
     thiserror::Error,
     url::Url,
 };
@@ -21,12 +20,15 @@ pub struct Rpc {
 
 impl Rpc {
     /// Instantiate an RPC client to an Ethereum (or Ethereum-compatible) node
-    /// at the specifed URL.
-    pub async fn new(
-        url: &url::Url,
-        ethrpc_args: &shared::ethrpc::Arguments,
-    ) -> Result<Self, Error> {
-        let web3 = boundary::web3_client(url, ethrpc_args);
+    /// at the specifed URLs. The first URL is the primary one, which is used
+    /// for the block stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no URLs are specified.
+    pub async fn new(urls: &[Url], ethrpc_args: &shared::ethrpc::Arguments) -> Result<Self, Error> {
+        let url = urls.first().expect("at least one node URL");
+        let web3 = boundary::web3_client(urls, ethrpc_args);
         let chain =
             Chain::try_from(web3.eth().chain_id().await?).map_err(|_| Error::UnsupportedChain)?;
 
@@ -76,12 +78,13 @@ impl Ethereum {
         chain: &Chain,
         url: Url,
         addresses: contracts::Addresses,
-        poll_interval: Duration,
+        current_block_args: &shared::current_block::Arguments,
     ) -> Self {
         let contracts = Contracts::new(&web3, chain, addresses).await;
 
         Self {
-            current_block: ethrpc::block_stream::current_block_stream(url, poll_interval)
+            current_block: current_block_args
+                .stream(url)
                 .await
                 .expect("couldn't initialize current block stream"),
             web3,
@@ -1,7 +1,10 @@
 use {
     crate::{
         boundary,
-        database::{Postgres, order_events::store_order_events},
+        database::{
+            Postgres,
+            order_events::{store_order_events, store_order_filter_reasons},
+        },
         domain::{self, eth},
         infra::persistence::dto::AuctionId,
     },
@@ -231,6 +234,29 @@ impl Persistence {
         );
     }
 
+    /// Stores the latest reason why each of the given orders was filtered from
+    /// the auction. Like for order events, errors are only logged.
+    pub fn store_order_filter_reasons(
+        &self,
+        reasons: impl IntoIterator<Item = (domain::OrderUid, &'static str)>,
+    ) {
+        let db = self.postgres.clone();
+        let reasons = reasons.into_iter().collect();
+        tokio::spawn(
+            async move {
+                let mut tx = db.pool.acquire().await.expect("failed to acquire tx");
+                store_order_filter_reasons(
+                    &mut tx,
+                    reasons,
+                    Utc::now(),
+                    db.config.insert_batch_size.get(),
+                )
+                .await;
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+
     /// Saves the given fee policies to the DB as a single batch.
     pub async fn store_fee_policies(
         &self,
@@ -12,7 +12,7 @@ use {
         event_updater::EventUpdater,
     },
     anyhow::Result,
-    ethrpc::block_stream::{BlockInfo, CurrentBlockWatcher, into_stream},
+    ethrpc::block_stream::{BlockInfo, CurrentBlockWatcher, ReorgTracker, into_stream},
     futures::StreamExt,
     prometheus::{
         HistogramVec,
@@ -38,6 +38,8 @@ pub struct Maintenance {
     cow_amm_indexer: Vec<Arc<dyn Maintaining>>,
     /// On which block we last ran an update successfully.
     last_processed: Mutex<BlockInfo>,
+    /// The reorgs the indexers already handled.
+    reorgs: Mutex<ReorgTracker>,
 }
 
 impl Maintenance {
@@ -51,6 +53,7 @@ impl Maintenance {
             cow_amm_indexer: Default::default(),
             ethflow_indexer: None,
             last_processed: Default::default(),
+            reorgs: Default::default(),
         }
     }
 
@@ -64,6 +67,13 @@ impl Maintenance {
             return;
         }
 
+        if let Some(reorg) = self.reorgs.lock().await.unhandled(new_block) {
+            self.settlement_indexer.handle_reorg(&reorg).await;
+            if let Some(indexer) = &self.ethflow_indexer {
+                indexer.handle_reorg(&reorg).await;
+            }
+        }
+
         let start = std::time::Instant::now();
         if let Err(err) = self.update_inner().await {
             tracing::warn!(?err, block = new_block.number, "failed to run maintenance");
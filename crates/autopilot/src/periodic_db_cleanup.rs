@@ -44,6 +44,13 @@ impl OrderEventsCleaner {
                     tracing::warn!(?err, "failed to delete order events before {}", timestamp)
                 }
             }
+            if let Err(err) = self.db.delete_order_filter_reasons_before(timestamp).await {
+                tracing::warn!(
+                    ?err,
+                    "failed to delete order filter reasons before {}",
+                    timestamp
+                )
+            }
         }
     }
 }
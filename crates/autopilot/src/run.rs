@@ -52,6 +52,7 @@ use {
         token_list::{AutoUpdatingTokenList, TokenListConfiguration},
     },
     std::{
+        num::NonZeroUsize,
         sync::{Arc, RwLock},
         time::{Duration, Instant},
     },
@@ -87,8 +88,8 @@ impl Liveness {
 }
 
 /// Creates Web3 transport based on the given config.
-async fn ethrpc(url: &Url, ethrpc_args: &shared::ethrpc::Arguments) -> infra::blockchain::Rpc {
-    infra::blockchain::Rpc::new(url, ethrpc_args)
+async fn ethrpc(urls: &[Url], ethrpc_args: &shared::ethrpc::Arguments) -> infra::blockchain::Rpc {
+    infra::blockchain::Rpc::new(urls, ethrpc_args)
         .await
         .expect("connect ethereum RPC")
 }
@@ -96,11 +97,14 @@ async fn ethrpc(url: &Url, ethrpc_args: &shared::ethrpc::Arguments) -> infra::bl
 /// Creates unbuffered Web3 transport.
 async fn unbuffered_ethrpc(url: &Url) -> infra::blockchain::Rpc {
     ethrpc(
-        url,
+        std::slice::from_ref(url),
         &shared::ethrpc::Arguments {
             ethrpc_max_batch_size: 0,
             ethrpc_max_concurrent_requests: 0,
             ethrpc_batch_delay: Default::default(),
+            ethrpc_quorum: NonZeroUsize::MIN,
+            ethrpc_quorum_methods: Default::default(),
+            ethrpc_failure_backoff: Default::default(),
         },
     )
     .await
@@ -112,9 +116,17 @@ async fn ethereum(
     chain: &Chain,
     url: Url,
     contracts: infra::blockchain::contracts::Addresses,
-    poll_interval: Duration,
+    current_block_args: &shared::current_block::Arguments,
 ) -> infra::Ethereum {
-    infra::Ethereum::new(web3, unbuffered_web3, chain, url, contracts, poll_interval).await
+    infra::Ethereum::new(
+        web3,
+        unbuffered_web3,
+        chain,
+        url,
+        contracts,
+        current_block_args,
+    )
+    .await
 }
 
 pub async fn start(args: impl Iterator<Item = String>) {
@@ -148,10 +160,10 @@ pub async fn run(args: Arguments) {
     crate::database::run_database_metrics_work(db.clone());
 
     let http_factory = HttpClientFactory::new(&args.http_client);
-    let web3 = shared::ethrpc::web3(
+    let web3 = shared::ethrpc::multi_web3(
         &args.shared.ethrpc,
         &http_factory,
-        &args.shared.node_url,
+        &args.shared.node_urls(),
         "base",
     );
     let simulation_web3 = args.shared.simulation_node_url.as_ref().map(|node_url| {
@@ -172,7 +184,7 @@ pub async fn run(args: Arguments) {
     }
 
     let unbuffered_ethrpc = unbuffered_ethrpc(&args.shared.node_url).await;
-    let ethrpc = ethrpc(&args.shared.node_url, &args.shared.ethrpc).await;
+    let ethrpc = ethrpc(&args.shared.node_urls(), &args.shared.ethrpc).await;
     let chain = ethrpc.chain();
     let web3 = ethrpc.web3().clone();
     let url = ethrpc.url().clone();
@@ -186,7 +198,7 @@ pub async fn run(args: Arguments) {
         &chain,
         url,
         contracts.clone(),
-        args.shared.current_block.block_stream_poll_interval,
+        &args.shared.current_block,
     )
     .await;
 
@@ -241,6 +253,7 @@ pub async fn run(args: Arguments) {
             &web3,
             args.shared.gas_estimators.as_slice(),
             args.shared.blocknative_api_key.clone(),
+            args.shared.fee_history(),
         )
         .await
         .expect("failed to create gas price estimator"),
@@ -417,7 +430,7 @@ pub async fn run(args: Arguments) {
     );
 
     let archive_node_web3 = args.archive_node_url.as_ref().map_or(web3.clone(), |url| {
-        boundary::web3_client(url, &args.shared.ethrpc)
+        boundary::web3_client(std::slice::from_ref(url), &args.shared.ethrpc)
     });
 
     let mut cow_amm_registry = cow_amm::Registry::new(archive_node_web3);
@@ -647,10 +660,10 @@ async fn shadow_mode(args: Arguments) -> ! {
         .collect();
 
     let trusted_tokens = {
-        let web3 = shared::ethrpc::web3(
+        let web3 = shared::ethrpc::multi_web3(
             &args.shared.ethrpc,
             &http_factory,
-            &args.shared.node_url,
+            &args.shared.node_urls(),
             "base",
         );
 
@@ -680,12 +693,12 @@ async fn shadow_mode(args: Arguments) -> ! {
     let liveness = Arc::new(Liveness::new(args.max_auction_age));
     observe::metrics::serve_metrics(liveness.clone(), args.metrics_address);
 
-    let current_block = ethrpc::block_stream::current_block_stream(
-        args.shared.node_url,
-        args.shared.current_block.block_stream_poll_interval,
-    )
-    .await
-    .expect("couldn't initialize current block stream");
+    let current_block = args
+        .shared
+        .current_block
+        .stream(args.shared.node_url)
+        .await
+        .expect("couldn't initialize current block stream");
 
     let shadow = shadow::RunLoop::new(
         orderbook,
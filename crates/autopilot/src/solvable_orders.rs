@@ -233,7 +233,7 @@ impl SolvableOrdersCache {
         let removed = counter.checkpoint("out_of_market", &orders);
         filtered_order_events.extend(removed);
 
-        let removed = counter.record(&orders);
+        let (removed, filter_reasons) = counter.record(&orders);
         filtered_order_events.extend(removed);
 
         // spawning a background task since `order_events` table insert operation takes
@@ -248,6 +248,11 @@ impl SolvableOrdersCache {
                 .map(|id| domain::OrderUid(id.0)),
             OrderEventLabel::Filtered,
         );
+        self.persistence.store_order_filter_reasons(
+            filter_reasons
+                .into_iter()
+                .map(|(id, reason)| (domain::OrderUid(id.0), reason)),
+        );
 
         let surplus_capturing_jit_order_owners = cow_amms
             .iter()
@@ -756,6 +761,8 @@ struct OrderFilterCounter {
     orders: HashMap<OrderUid, OrderClass>,
     /// Running tally for counts of filtered orders.
     counts: HashMap<Reason, usize>,
+    /// Why each of the filtered orders was filtered.
+    reasons: HashMap<OrderUid, Reason>,
 }
 
 type Reason = &'static str;
@@ -783,6 +790,7 @@ impl OrderFilterCounter {
                 .map(|order| (order.metadata.uid, order.metadata.class))
                 .collect(),
             counts: HashMap::new(),
+            reasons: HashMap::new(),
         }
     }
 
@@ -798,6 +806,7 @@ impl OrderFilterCounter {
         *self.counts.entry(reason).or_default() += filtered_orders.len();
         for order_uid in filtered_orders.keys() {
             self.orders.remove(order_uid).unwrap();
+            self.reasons.insert(*order_uid, reason);
         }
         if !filtered_orders.is_empty() {
             tracing::debug!(
@@ -818,6 +827,7 @@ impl OrderFilterCounter {
         let mut counter = 0;
         for order_uid in invalid_orders {
             if self.orders.remove(order_uid).is_some() {
+                self.reasons.insert(*order_uid, reason);
                 counter += 1;
             }
         }
@@ -834,8 +844,8 @@ impl OrderFilterCounter {
     /// Records the filter counter to metrics.
     /// If there are orders that have been filtered out since the last
     /// checkpoint these orders will get recorded with the readon "other".
-    /// Returns these catch-all orders.
-    fn record(mut self, orders: &[Order]) -> Vec<OrderUid> {
+    /// Returns these catch-all orders and the reasons of all filtered orders.
+    fn record(mut self, orders: &[Order]) -> (Vec<OrderUid>, HashMap<OrderUid, Reason>) {
         let removed = self.checkpoint("other", orders);
 
         self.metrics.auction_creations.inc();
@@ -856,7 +866,7 @@ impl OrderFilterCounter {
                 .set(i64::try_from(count).unwrap_or(i64::MAX));
         }
 
-        removed
+        (removed, self.reasons)
     }
 }
 
@@ -1173,6 +1183,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn records_filter_reasons() {
+        let orders = (0..4)
+            .map(|i| Order {
+                metadata: OrderMetadata {
+                    uid: OrderUid([i; 56]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let metrics = Metrics::instance(observe::metrics::get_storage_registry()).unwrap();
+
+        let mut counter = OrderFilterCounter::new(metrics, &orders);
+        counter.checkpoint_by_invalid_orders("banned_user", &[OrderUid([0; 56])]);
+        counter.checkpoint("insufficient_balance", &orders[2..]);
+        let (removed, reasons) = counter.record(&orders[3..]);
+
+        assert_eq!(removed, [OrderUid([2; 56])]);
+        assert_eq!(
+            reasons,
+            HashMap::from([
+                (OrderUid([0; 56]), "banned_user"),
+                (OrderUid([1; 56]), "insufficient_balance"),
+                (OrderUid([2; 56]), "other"),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn filters_invalidated_eip1271_signatures() {
         let orders = vec![
@@ -16,6 +16,7 @@ const GNOSIS: &str = "100";
 const SEPOLIA: &str = "11155111";
 const ARBITRUM_ONE: &str = "42161";
 const BASE: &str = "8453";
+const OPTIMISM: &str = "10";
 
 fn main() {
     // NOTE: This is a workaround for `rerun-if-changed` directives for
@@ -28,6 +29,10 @@ fn main() {
     println!("cargo:rerun-if-changed=build.rs");
 
     generate_contract("AaveFlashLoanSolverWrapper");
+    generate_contract_with_config("AerodromeRouter", |builder| {
+        // <https://aerodrome.finance/security#contracts>
+        builder.add_network_str(BASE, "0xcF77a3Ba9A5CA399B7c97c74d54e5b1Beb874E43")
+    });
     generate_contract_with_config("CoWSwapEthFlow", |builder| {
         builder
             .contract_mod_override("cowswap_eth_flow")
@@ -84,6 +89,16 @@ fn main() {
     generate_contract_with_config("CoWSwapOnchainOrders", |builder| {
         builder.contract_mod_override("cowswap_onchain_orders")
     });
+    generate_contract_with_config("ComposableCoW", |builder| {
+        // <https://github.com/cowprotocol/composable-cow/blob/main/networks.json>
+        builder
+            .contract_mod_override("composable_cow")
+            .add_network_str(MAINNET, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(GNOSIS, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(SEPOLIA, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(ARBITRUM_ONE, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+            .add_network_str(BASE, "0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74")
+    });
     generate_contract_with_config("BalancerV2Authorizer", |builder| {
         builder.contract_mod_override("balancer_v2_authorizer")
     });
@@ -678,6 +693,20 @@ fn main() {
                 },
             )
     });
+    generate_contract("BalancerV3StablePool");
+    generate_contract_with_config("BalancerV3Vault", |builder| {
+        // <https://docs.balancer.fi/developer-reference/contracts/deployment-addresses/mainnet.html>
+        builder
+            .contract_mod_override("balancer_v3_vault")
+            .add_network(
+                MAINNET,
+                Network {
+                    address: addr("0xbA1333333333a1BA1108E8412f11850A5C319bA9"),
+                    deployment_information: Some(DeploymentInformation::BlockNumber(21332121)),
+                },
+            )
+    });
+    generate_contract("BalancerV3WeightedPool");
     generate_contract_with_config("BaoswapRouter", |builder| {
         builder.add_network_str(GNOSIS, "0x6093AeBAC87d62b1A5a4cEec91204e35020E38bE")
     });
@@ -852,7 +881,11 @@ fn main() {
             .add_network_str(BASE, "0x01DcB88678aedD0C4cC9552B20F4718550250574")
     });
     generate_contract("IAavePool");
+    generate_contract("IERC4626");
     generate_contract("IFlashLoanSolverWrapper");
+    generate_contract("ISolidlyPool");
+    generate_contract("ISolidlyPoolFactory");
+    generate_contract("ISolidlyRouter");
     generate_contract("IUniswapLikeRouter");
     generate_contract("IUniswapLikePair");
     // EIP-1271 contract - SignatureValidator
@@ -863,6 +896,16 @@ fn main() {
             .add_network_str(ARBITRUM_ONE, "0x8cFe327CEc66d1C090Dd72bd0FF11d690C33a2Eb")
             .add_network_str(BASE, "0x8cFe327CEc66d1C090Dd72bd0FF11d690C33a2Eb")
     });
+    generate_contract_with_config("Permit2", |builder| {
+        // Permit2 is deployed at the same address on all chains.
+        // <https://github.com/Uniswap/permit2#deployment-addresses>
+        builder
+            .add_network_str(MAINNET, "0x000000000022D473030F116dDEE9F6B43aC78BA3")
+            .add_network_str(GNOSIS, "0x000000000022D473030F116dDEE9F6B43aC78BA3")
+            .add_network_str(SEPOLIA, "0x000000000022D473030F116dDEE9F6B43aC78BA3")
+            .add_network_str(ARBITRUM_ONE, "0x000000000022D473030F116dDEE9F6B43aC78BA3")
+            .add_network_str(BASE, "0x000000000022D473030F116dDEE9F6B43aC78BA3")
+    });
     generate_contract_with_config("SushiSwapRouter", |builder| {
         // <https://docs.sushi.com/docs/Products/Classic%20AMM/Deployment%20Addresses>
         builder
@@ -914,6 +957,24 @@ fn main() {
         // Not available on Gnosis Chain
     });
     generate_contract("UniswapV3Pool");
+    generate_contract_with_config("UniswapV4PoolManager", |builder| {
+        // <https://docs.uniswap.org/contracts/v4/deployments>
+        builder.add_network(
+            MAINNET,
+            Network {
+                address: addr("0x000000000004444c5dc75cB358380D2e3dE08A90"),
+                deployment_information: Some(DeploymentInformation::BlockNumber(21688329)),
+            },
+        )
+    });
+    generate_contract_with_config("UniswapV4UniversalRouter", |builder| {
+        // <https://docs.uniswap.org/contracts/v4/deployments>
+        builder.add_network_str(MAINNET, "0x66a9893cC07D91D95644AEDD05D03f95e1dBA8Af")
+    });
+    generate_contract_with_config("VelodromeRouter", |builder| {
+        // <https://velodrome.finance/security#contracts>
+        builder.add_network_str(OPTIMISM, "0xa062aE8A9c5e11aaA026fc2670B0D65cCc8B2858")
+    });
     generate_contract_with_config("WETH9", |builder| {
         // Note: the WETH address must be consistent with the one used by the ETH-flow
         // contract
@@ -1037,6 +1098,14 @@ fn main() {
     });
     generate_contract("CowAmmUniswapV2PriceOracle");
 
+    // Curve pools and the registry used for discovering them.
+    generate_contract_with_config("CurveRegistry", |builder| {
+        // <https://docs.curve.fi/references/deployed-contracts/#metaregistry>
+        builder.add_network_str(MAINNET, "0xF98B45FA17DE75FB1aD0e7aFD971b0ca00e379fC")
+    });
+    generate_contract("CurveStableSwapPool");
+    generate_contract("CurveCryptoSwapPool");
+
     // Support contracts used for trade and token simulations.
     generate_contract("Solver");
     generate_contract("Spardose");
@@ -205,6 +205,10 @@ fn run() -> Result<()> {
         .manual(
             "ChainalysisOracle",
             "Chainalysis does not publish its code",
+        )
+        .manual(
+            "ComposableCoW",
+            "Manually vendored ABI for indexing and polling conditional orders",
         );
 
     Ok(())
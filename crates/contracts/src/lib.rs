@@ -50,6 +50,7 @@ macro_rules! include_contracts {
 
 include_contracts! {
     AaveFlashLoanSolverWrapper;
+    AerodromeRouter;
     BalancerV2Authorizer;
     BalancerV2BasePool;
     BalancerV2BasePoolFactory;
@@ -71,6 +72,9 @@ include_contracts! {
     BalancerV2WeightedPoolFactoryV3;
     BalancerV2WeightedPoolFactoryV4;
     BalancerV3BatchRouter;
+    BalancerV3StablePool;
+    BalancerV3Vault;
+    BalancerV3WeightedPool;
     BaoswapRouter;
     CowAmm;
     CowAmmConstantProductFactory;
@@ -78,7 +82,11 @@ include_contracts! {
     CowAmmUniswapV2PriceOracle;
     CoWSwapEthFlow;
     CoWSwapOnchainOrders;
+    ComposableCoW;
     CowProtocolToken;
+    CurveCryptoSwapPool;
+    CurveRegistry;
+    CurveStableSwapPool;
     ERC1271SignatureValidator;
     ERC20;
     ERC20Mintable;
@@ -94,13 +102,18 @@ include_contracts! {
     HoneyswapRouter;
     HooksTrampoline;
     IAavePool;
+    IERC4626;
     IFlashLoanSolverWrapper;
+    ISolidlyPool;
+    ISolidlyPoolFactory;
+    ISolidlyRouter;
     ISwaprPair;
     IUniswapLikePair;
     IUniswapLikeRouter;
     IUniswapV3Factory;
     IZeroEx;
     PancakeRouter;
+    Permit2;
     ChainalysisOracle;
     SushiSwapRouter;
     SwaprRouter;
@@ -109,6 +122,9 @@ include_contracts! {
     UniswapV2Router02;
     UniswapV3Pool;
     UniswapV3SwapRouter;
+    UniswapV4PoolManager;
+    UniswapV4UniversalRouter;
+    VelodromeRouter;
     WETH9;
 }
 
@@ -140,6 +156,7 @@ mod tests {
     const GNOSIS: u64 = 100;
     const SEPOLIA: u64 = 11155111;
     const ARBITRUM_ONE: u64 = 42161;
+    const BASE: u64 = 8453;
 
     use {
         super::*,
@@ -246,6 +263,10 @@ mod tests {
             assert_has_deployment_address!(SwaprRouter for *network);
         }
 
+        for network in &[MAINNET, GNOSIS, SEPOLIA, ARBITRUM_ONE, BASE] {
+            assert_has_deployment_address!(ComposableCoW for *network);
+        }
+
         // only gnosis
         assert_has_deployment_address!(BaoswapRouter for GNOSIS);
         assert_has_deployment_address!(HoneyswapRouter for GNOSIS);
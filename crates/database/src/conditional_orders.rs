@@ -0,0 +1,219 @@
+//! Conditional orders created through ComposableCoW and the state of polling
+//! them for discrete orders.
+
+use {
+    crate::{Address, OrderUid, byte_array::ByteArray},
+    chrono::{DateTime, Utc},
+    sqlx::PgConnection,
+};
+
+pub type Hash = ByteArray<32>;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ConditionalOrder {
+    pub owner: Address,
+    pub hash: Hash,
+    pub handler: Address,
+    pub salt: ByteArray<32>,
+    pub static_input: Vec<u8>,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub active: bool,
+    pub next_poll_block: Option<i64>,
+    pub next_poll_timestamp: Option<DateTime<Utc>>,
+    pub last_order_uid: Option<OrderUid>,
+    pub last_error: Option<String>,
+    pub failed_polls: i32,
+}
+
+/// The outcome of polling a conditional order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PollResult {
+    pub active: bool,
+    pub next_poll_block: Option<i64>,
+    pub next_poll_timestamp: Option<DateTime<Utc>>,
+    pub last_order_uid: Option<OrderUid>,
+    pub last_error: Option<String>,
+    pub failed_polls: i32,
+}
+
+/// Stores a newly created conditional order. Creating the same conditional
+/// order again resets its polling state.
+pub async fn upsert(ex: &mut PgConnection, order: &ConditionalOrder) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO conditional_orders (owner, hash, handler, salt, static_input, block_number, log_index, active, next_poll_block, next_poll_timestamp, last_order_uid, last_error, failed_polls)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+ON CONFLICT (owner, hash) DO UPDATE
+SET block_number = $6, log_index = $7, active = $8, next_poll_block = $9, next_poll_timestamp = $10, last_order_uid = $11, last_error = $12, failed_polls = $13
+    ;"#;
+    sqlx::query(QUERY)
+        .bind(order.owner)
+        .bind(order.hash)
+        .bind(order.handler)
+        .bind(order.salt)
+        .bind(&order.static_input)
+        .bind(order.block_number)
+        .bind(order.log_index)
+        .bind(order.active)
+        .bind(order.next_poll_block)
+        .bind(order.next_poll_timestamp)
+        .bind(order.last_order_uid)
+        .bind(order.last_error.as_deref())
+        .bind(order.failed_polls)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Deletes the conditional orders created in or after the given block. Used in
+/// case of a reorg.
+pub async fn delete_from_block(ex: &mut PgConnection, from_block: i64) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "DELETE FROM conditional_orders WHERE block_number >= $1;";
+    sqlx::query(QUERY).bind(from_block).execute(ex).await?;
+    Ok(())
+}
+
+/// Returns the active conditional orders which are due to be polled at the
+/// given block.
+pub async fn due(
+    ex: &mut PgConnection,
+    block_number: i64,
+    timestamp: DateTime<Utc>,
+) -> Result<Vec<ConditionalOrder>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT * FROM conditional_orders
+WHERE
+    active AND
+    (next_poll_block IS NULL OR next_poll_block <= $1) AND
+    (next_poll_timestamp IS NULL OR next_poll_timestamp <= $2)
+ORDER BY block_number, log_index
+    ;"#;
+    sqlx::query_as(QUERY)
+        .bind(block_number)
+        .bind(timestamp)
+        .fetch_all(ex)
+        .await
+}
+
+pub async fn update_poll_result(
+    ex: &mut PgConnection,
+    owner: &Address,
+    hash: &Hash,
+    result: &PollResult,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE conditional_orders
+SET active = $3, next_poll_block = $4, next_poll_timestamp = $5, last_order_uid = $6, last_error = $7, failed_polls = $8
+WHERE owner = $1 AND hash = $2
+    ;"#;
+    sqlx::query(QUERY)
+        .bind(owner)
+        .bind(hash)
+        .bind(result.active)
+        .bind(result.next_poll_block)
+        .bind(result.next_poll_timestamp)
+        .bind(result.last_order_uid)
+        .bind(result.last_error.as_deref())
+        .bind(result.failed_polls)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch(
+    ex: &mut PgConnection,
+    owner: &Address,
+    hash: &Hash,
+) -> Result<Option<ConditionalOrder>, sqlx::Error> {
+    const QUERY: &str = "SELECT * FROM conditional_orders WHERE owner = $1 AND hash = $2;";
+    sqlx::query_as(QUERY)
+        .bind(owner)
+        .bind(hash)
+        .fetch_optional(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_conditional_orders_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = |hash: u8, block_number: i64| ConditionalOrder {
+            owner: ByteArray([1; 20]),
+            hash: ByteArray([hash; 32]),
+            handler: ByteArray([2; 20]),
+            block_number,
+            active: true,
+            ..Default::default()
+        };
+        upsert(&mut db, &order(1, 1)).await.unwrap();
+        upsert(&mut db, &order(2, 2)).await.unwrap();
+
+        let now = DateTime::from_timestamp(100, 0).unwrap();
+        assert_eq!(
+            due(&mut db, 10, now).await.unwrap(),
+            vec![order(1, 1), order(2, 2)]
+        );
+
+        // Orders are not polled before their next poll block or time.
+        let result = PollResult {
+            active: true,
+            next_poll_block: Some(11),
+            last_order_uid: Some(ByteArray([3; 56])),
+            ..Default::default()
+        };
+        update_poll_result(&mut db, &ByteArray([1; 20]), &ByteArray([1; 32]), &result)
+            .await
+            .unwrap();
+        let result = PollResult {
+            active: true,
+            next_poll_timestamp: Some(DateTime::from_timestamp(101, 0).unwrap()),
+            ..Default::default()
+        };
+        update_poll_result(&mut db, &ByteArray([1; 20]), &ByteArray([2; 32]), &result)
+            .await
+            .unwrap();
+        assert!(due(&mut db, 10, now).await.unwrap().is_empty());
+        assert_eq!(due(&mut db, 11, now).await.unwrap().len(), 1);
+        assert_eq!(
+            due(&mut db, 11, DateTime::from_timestamp(101, 0).unwrap())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // Inactive orders are never polled again.
+        let result = PollResult {
+            active: false,
+            last_error: Some("PollNever".to_string()),
+            failed_polls: 2,
+            ..Default::default()
+        };
+        update_poll_result(&mut db, &ByteArray([1; 20]), &ByteArray([1; 32]), &result)
+            .await
+            .unwrap();
+        let stored = fetch(&mut db, &ByteArray([1; 20]), &ByteArray([1; 32]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored.active);
+        assert_eq!(stored.last_error.as_deref(), Some("PollNever"));
+        assert_eq!(stored.failed_polls, 2);
+
+        // Reorgs delete the orders of the affected blocks.
+        delete_from_block(&mut db, 2).await.unwrap();
+        assert!(
+            fetch(&mut db, &ByteArray([1; 20]), &ByteArray([2; 32]))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
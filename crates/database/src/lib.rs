@@ -4,6 +4,7 @@ pub mod auction_orders;
 pub mod auction_participants;
 pub mod auction_prices;
 pub mod byte_array;
+pub mod conditional_orders;
 pub mod ethflow_orders;
 pub mod events;
 pub mod fee_policies;
@@ -13,6 +14,7 @@ pub mod onchain_broadcasted_orders;
 pub mod onchain_invalidations;
 pub mod order_events;
 pub mod order_execution;
+pub mod order_filter_reasons;
 pub mod order_history;
 pub mod orders;
 pub mod quotes;
@@ -55,6 +57,7 @@ pub const TABLES: &[&str] = &[
     "auction_orders",
     "auctions",
     "competition_auctions",
+    "conditional_orders",
     "ethflow_orders",
     "ethflow_refunds",
     "fee_policies",
@@ -65,6 +68,7 @@ pub const TABLES: &[&str] = &[
     "onchain_order_invalidations",
     "onchain_placed_orders",
     "order_execution",
+    "order_filter_reasons",
     "order_quotes",
     "orders",
     "presignature_events",
@@ -4,13 +4,22 @@
 use {
     crate::{OrderUid, byte_array::ByteArray},
     chrono::Utc,
-    sqlx::{PgConnection, PgPool, types::chrono::DateTime},
+    sqlx::{
+        PgConnection,
+        PgPool,
+        postgres::PgListener,
+        types::chrono::{DateTime, FixedOffset},
+    },
 };
 
+/// The channel on which every inserted order event gets published.
+pub const CHANNEL: &str = "order_events";
+
 /// Describes what kind of event was registered for an order.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type, strum::EnumString)]
 #[sqlx(type_name = "OrderEventLabel")]
 #[sqlx(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum OrderEventLabel {
     /// Order was added to the orderbook.
     Created,
@@ -104,6 +113,26 @@ pub async fn get_latest(
         .await
 }
 
+/// Subscribes to the order events inserted from now on. Notifications are
+/// missed while the listener reconnects after losing its connection.
+pub async fn listen(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(CHANNEL).await?;
+    Ok(listener)
+}
+
+/// Parses the payload of a notification published on [`CHANNEL`].
+pub fn parse_notification(payload: &str) -> Option<OrderEvent> {
+    let payload: serde_json::Value = serde_json::from_str(payload).ok()?;
+    let order_uid = hex::decode(payload["order_uid"].as_str()?).ok()?;
+    let timestamp = DateTime::<FixedOffset>::parse_from_rfc3339(payload["timestamp"].as_str()?);
+    Some(OrderEvent {
+        order_uid: ByteArray(order_uid.try_into().ok()?),
+        timestamp: timestamp.ok()?.with_timezone(&Utc),
+        label: payload["label"].as_str()?.parse().ok()?,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -172,6 +201,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_notification() {
+        let payload = format!(
+            r#"{{"order_uid": "{}", "timestamp": "2024-05-01T12:00:00.123456+00:00", "label": "executing"}}"#,
+            "01".repeat(56)
+        );
+        assert_eq!(
+            parse_notification(&payload),
+            Some(OrderEvent {
+                order_uid: ByteArray([1; 56]),
+                timestamp: "2024-05-01T12:00:00.123456Z".parse().unwrap(),
+                label: OrderEventLabel::Executing,
+            })
+        );
+        assert_eq!(parse_notification(r#"{"label": "executing"}"#), None);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_notifies_order_events() {
+        let pool = PgPool::connect("postgresql://").await.unwrap();
+        crate::clear_DANGER(&pool).await.unwrap();
+        let mut listener = listen(&pool).await.unwrap();
+
+        let event = OrderEvent {
+            order_uid: ByteArray([1; 56]),
+            timestamp: Utc::now(),
+            label: OrderEventLabel::Created,
+        };
+        let mut ex = pool.acquire().await.unwrap();
+        insert_order_event(&mut ex, &event).await.unwrap();
+        // Inserting the same label again doesn't insert a row, so there is no
+        // notification either.
+        insert_order_event(&mut ex, &event).await.unwrap();
+        let traded = OrderEvent {
+            label: OrderEventLabel::Traded,
+            ..event
+        };
+        insert_order_event(&mut ex, &traded).await.unwrap();
+
+        for expected in [event, traded] {
+            let notification = listener.recv().await.unwrap();
+            let received = parse_notification(notification.payload()).unwrap();
+            assert_eq!(received.order_uid, expected.order_uid);
+            assert_eq!(received.label, expected.label);
+            assert_eq!(
+                received.timestamp.timestamp_micros(),
+                expected.timestamp.timestamp_micros()
+            );
+        }
+    }
+
     async fn all_order_events(ex: &mut PgConnection) -> Vec<OrderEvent> {
         const QUERY: &str = r#"
                 SELECT *
@@ -0,0 +1,115 @@
+//! Stores the latest reason why an order was filtered from the auction.
+
+use {
+    crate::OrderUid,
+    chrono::{DateTime, Utc},
+    sqlx::{PgConnection, PgPool, QueryBuilder},
+};
+
+#[derive(Clone, Debug, Eq, PartialEq, sqlx::FromRow)]
+pub struct OrderFilterReason {
+    pub order_uid: OrderUid,
+    /// Since when the order is filtered for this reason.
+    pub timestamp: DateTime<Utc>,
+    pub reason: String,
+}
+
+/// Stores the reasons of the filtered orders. The timestamp of orders which
+/// are still filtered for the same reason is kept.
+pub async fn upsert_batch(
+    ex: &mut PgConnection,
+    reasons: impl IntoIterator<Item = (OrderUid, &str)>,
+    timestamp: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let mut reasons = reasons.into_iter().peekable();
+    if reasons.peek().is_none() {
+        return Ok(());
+    }
+
+    let mut query_builder =
+        QueryBuilder::new("INSERT INTO order_filter_reasons (order_uid, timestamp, reason)");
+    query_builder.push_values(reasons, |mut b, (order_uid, reason)| {
+        b.push_bind(order_uid)
+            .push_bind(timestamp)
+            .push_bind(reason);
+    });
+    query_builder.push(
+        " ON CONFLICT (order_uid) DO UPDATE SET timestamp = EXCLUDED.timestamp, reason = \
+         EXCLUDED.reason WHERE order_filter_reasons.reason <> EXCLUDED.reason",
+    );
+
+    query_builder.build().execute(ex).await.map(|_| ())
+}
+
+pub async fn get(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Option<OrderFilterReason>, sqlx::Error> {
+    const QUERY: &str = "SELECT * FROM order_filter_reasons WHERE order_uid = $1";
+    sqlx::query_as(QUERY)
+        .bind(order_uid)
+        .fetch_optional(ex)
+        .await
+}
+
+/// Deletes the reasons recorded before the provided timestamp.
+pub async fn delete_before(pool: &PgPool, timestamp: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    const QUERY: &str = "DELETE FROM order_filter_reasons WHERE timestamp < $1";
+    sqlx::query(QUERY)
+        .bind(timestamp)
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_filter_reasons_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut ex = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut ex).await.unwrap();
+
+        let uid_a = ByteArray([1; 56]);
+        let uid_b = ByteArray([2; 56]);
+        let first = DateTime::from_timestamp(1, 0).unwrap();
+        let second = DateTime::from_timestamp(2, 0).unwrap();
+
+        upsert_batch(
+            &mut ex,
+            [(uid_a, "insufficient_balance"), (uid_b, "missing_price")],
+            first,
+        )
+        .await
+        .unwrap();
+        upsert_batch(
+            &mut ex,
+            [(uid_a, "insufficient_balance"), (uid_b, "out_of_market")],
+            second,
+        )
+        .await
+        .unwrap();
+
+        // The timestamp is only updated when the reason changes.
+        assert_eq!(
+            get(&mut ex, &uid_a).await.unwrap(),
+            Some(OrderFilterReason {
+                order_uid: uid_a,
+                timestamp: first,
+                reason: "insufficient_balance".to_string(),
+            })
+        );
+        assert_eq!(
+            get(&mut ex, &uid_b).await.unwrap(),
+            Some(OrderFilterReason {
+                order_uid: uid_b,
+                timestamp: second,
+                reason: "out_of_market".to_string(),
+            })
+        );
+        assert_eq!(get(&mut ex, &ByteArray([3; 56])).await.unwrap(), None);
+    }
+}
@@ -1,42 +1,123 @@
 use {
-    crate::{Address, jit_orders, orders},
+    crate::{
+        Address,
+        AppId,
+        OrderUid,
+        jit_orders,
+        orders::{self, OrderClass},
+    },
+    chrono::{DateTime, Utc},
     futures::stream::BoxStream,
     sqlx::PgConnection,
 };
 
+/// The status of an order as computed from the executed amounts,
+/// invalidations, validity and presignatures of the order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OrderStatus {
+    PresignaturePending,
+    Open,
+    Fulfilled,
+    Cancelled,
+    Expired,
+}
+
+impl OrderStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::PresignaturePending => "presignature_pending",
+            Self::Open => "open",
+            Self::Fulfilled => "fulfilled",
+            Self::Cancelled => "cancelled",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// Any `None` value means that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserOrderFilter {
+    pub status: Option<OrderStatus>,
+    pub class: Option<OrderClass>,
+    /// Orders either selling or buying the token.
+    pub token: Option<Address>,
+    /// Orders created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Orders created before this time.
+    pub created_before: Option<DateTime<Utc>>,
+    pub app_data: Option<AppId>,
+}
+
+/// The last order of a page. The next page starts with the order created
+/// right before it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cursor {
+    pub creation_timestamp: DateTime<Utc>,
+    pub uid: OrderUid,
+}
+
+/// Filters the full orders selected by a subquery named `o`. The status is
+/// computed in the same way as when converting a `FullOrder` to an order.
+const FILTER: &str = r#"
+($4::text IS NULL OR $4 = CASE
+    WHEN (o.kind = 'sell' AND o.sum_sell <> 0 AND o.sum_sell - o.sum_fee = o.sell_amount)
+        OR (o.kind = 'buy' AND o.sum_buy <> 0 AND o.sum_buy = o.buy_amount) THEN 'fulfilled'
+    WHEN o.invalidated THEN 'cancelled'
+    WHEN COALESCE(
+        (SELECT eth_o.valid_to FROM ethflow_orders eth_o WHERE eth_o.uid = o.uid),
+        o.valid_to
+    ) < FLOOR(EXTRACT(EPOCH FROM now())) THEN 'expired'
+    WHEN o.presignature_pending THEN 'presignature_pending'
+    ELSE 'open'
+END)
+AND ($5::OrderClass IS NULL OR o.class = $5)
+AND ($6::bytea IS NULL OR o.sell_token = $6 OR o.buy_token = $6)
+AND ($7::timestamptz IS NULL OR o.creation_timestamp >= $7)
+AND ($8::timestamptz IS NULL OR o.creation_timestamp < $8)
+AND ($9::bytea IS NULL OR o.app_data = $9)
+AND ($10::timestamptz IS NULL OR (o.creation_timestamp, o.uid) < ($10, $11))
+"#;
+
+/// The orders of a user matching the filter ordered by creation date
+/// descending (newest orders first).
+///
+/// Pages can either be selected with an `offset` or, more efficiently, with
+/// the `cursor` of the last order of the previous page. With a cursor the
+/// database starts right at the next order through the index instead of
+/// enumerating all the orders before the offset.
 pub fn user_orders<'a>(
     ex: &'a mut PgConnection,
     owner: &'a Address,
+    filter: &'a UserOrderFilter,
+    cursor: Option<&'a Cursor>,
     offset: i64,
     limit: Option<i64>,
 ) -> BoxStream<'a, Result<orders::FullOrder, sqlx::Error>> {
-    // As a future consideration for this query we could move from offset to an
-    // approach called keyset pagination where the offset is identified by "key"
-    // of the previous query. In our case that would be the lowest
-    // creation_timestamp. This way the database can start immediately at the
-    // offset through the index without enumerating the first N elements
-    // before as is the case with OFFSET.
-    // On the other hand that approach is less flexible so we will consider if we
-    // see that these queries are taking too long in practice.
     #[rustfmt::skip]
     const QUERY: &str = const_format::concatcp!(
-"(SELECT ", orders::SELECT,
-" FROM ", orders::FROM,
-" LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
-" WHERE o.owner = $1",
-" ORDER BY creation_timestamp DESC LIMIT $2 + $3 ) ",
+"(SELECT o.* FROM (",
+    "SELECT ", orders::SELECT,
+    " FROM ", orders::FROM,
+    " LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
+    " WHERE o.owner = $1",
+" ) o WHERE ", FILTER,
+" ORDER BY o.creation_timestamp DESC, o.uid DESC LIMIT $2 + $3 ) ",
 " UNION ",
-" (SELECT ", orders::SELECT,
-" FROM ", orders::FROM,
-" LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
-" WHERE onchain_o.sender = $1 ",
-" ORDER BY creation_timestamp DESC LIMIT $2 + $3 ) ",
+" (SELECT o.* FROM (",
+    "SELECT ", orders::SELECT,
+    " FROM ", orders::FROM,
+    " LEFT OUTER JOIN onchain_placed_orders onchain_o on onchain_o.uid = o.uid",
+    " WHERE onchain_o.sender = $1 ",
+" ) o WHERE ", FILTER,
+" ORDER BY o.creation_timestamp DESC, o.uid DESC LIMIT $2 + $3 ) ",
 " UNION ",
-" (SELECT ", jit_orders::SELECT,
-" FROM ", jit_orders::FROM,
-" WHERE o.owner = $1 AND NOT EXISTS (SELECT 1 FROM orders ord WHERE o.uid = ord.uid)",
-" ORDER BY creation_timestamp DESC LIMIT $2 + $3 ) ",
-" ORDER BY creation_timestamp DESC ",
+" (SELECT o.* FROM (",
+    "SELECT ", jit_orders::SELECT,
+    " FROM ", jit_orders::FROM,
+    " WHERE o.owner = $1 AND NOT EXISTS (SELECT 1 FROM orders ord WHERE o.uid = ord.uid)",
+" ) o WHERE ", FILTER,
+" ORDER BY o.creation_timestamp DESC, o.uid DESC LIMIT $2 + $3 ) ",
+" ORDER BY creation_timestamp DESC, uid DESC ",
 " LIMIT $2 ",
 " OFFSET $3 ",
     );
@@ -44,6 +125,14 @@ pub fn user_orders<'a>(
         .bind(owner)
         .bind(limit)
         .bind(offset)
+        .bind(filter.status.map(|status| status.as_str()))
+        .bind(filter.class)
+        .bind(filter.token)
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.app_data)
+        .bind(cursor.map(|cursor| cursor.creation_timestamp))
+        .bind(cursor.map(|cursor| cursor.uid))
         .fetch(ex)
 }
 
@@ -68,7 +157,7 @@ mod tests {
         offset: i64,
         limit: Option<i64>,
     ) -> Vec<Data> {
-        super::user_orders(ex, owner, offset, limit)
+        super::user_orders(ex, owner, &Default::default(), None, offset, limit)
             .map(|o| {
                 let o = o.unwrap();
                 (o.uid.0, o.owner, o.creation_timestamp)
@@ -77,6 +166,127 @@ mod tests {
             .await
     }
 
+    async fn filtered_user_orders(
+        ex: &mut PgConnection,
+        owner: &Address,
+        filter: &UserOrderFilter,
+        cursor: Option<&Cursor>,
+        limit: Option<i64>,
+    ) -> Vec<OrderUid> {
+        super::user_orders(ex, owner, filter, cursor, 0, limit)
+            .map(|o| o.unwrap().uid)
+            .collect::<Vec<_>>()
+            .await
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_user_orders_filter_and_cursor() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        let order = |i: u8| orders::Order {
+            uid: ByteArray([i; 56]),
+            owner,
+            creation_timestamp: DateTime::from_timestamp(i.into(), 0).unwrap(),
+            valid_to: i64::MAX,
+            ..Default::default()
+        };
+        let market = order(1);
+        let limit = orders::Order {
+            class: OrderClass::Limit,
+            sell_token: ByteArray([2; 20]),
+            ..order(2)
+        };
+        let cancelled = orders::Order {
+            cancellation_timestamp: Some(Utc::now()),
+            ..order(3)
+        };
+        let expired = orders::Order {
+            valid_to: 0,
+            app_data: ByteArray([4; 32]),
+            ..order(4)
+        };
+        for order in [&market, &limit, &cancelled, &expired] {
+            orders::insert_order(&mut db, order).await.unwrap();
+        }
+
+        let all = UserOrderFilter::default();
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &all, None, None).await,
+            [expired.uid, cancelled.uid, limit.uid, market.uid]
+        );
+
+        // Paginate with the cursor of the last order of the previous page.
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &all, None, Some(2)).await,
+            [expired.uid, cancelled.uid]
+        );
+        let cursor = Cursor {
+            creation_timestamp: cancelled.creation_timestamp,
+            uid: cancelled.uid,
+        };
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &all, Some(&cursor), Some(2)).await,
+            [limit.uid, market.uid]
+        );
+
+        for (status, expected) in [
+            (OrderStatus::Open, vec![limit.uid, market.uid]),
+            (OrderStatus::Cancelled, vec![cancelled.uid]),
+            (OrderStatus::Expired, vec![expired.uid]),
+            (OrderStatus::Fulfilled, vec![]),
+        ] {
+            let filter = UserOrderFilter {
+                status: Some(status),
+                ..Default::default()
+            };
+            assert_eq!(
+                filtered_user_orders(&mut db, &owner, &filter, None, None).await,
+                expected
+            );
+        }
+
+        let filter = UserOrderFilter {
+            class: Some(OrderClass::Limit),
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &filter, None, None).await,
+            [limit.uid]
+        );
+
+        let filter = UserOrderFilter {
+            token: Some(ByteArray([2; 20])),
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &filter, None, None).await,
+            [limit.uid]
+        );
+
+        let filter = UserOrderFilter {
+            created_after: Some(limit.creation_timestamp),
+            created_before: Some(expired.creation_timestamp),
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &filter, None, None).await,
+            [cancelled.uid, limit.uid]
+        );
+
+        let filter = UserOrderFilter {
+            app_data: Some(ByteArray([4; 32])),
+            ..Default::default()
+        };
+        assert_eq!(
+            filtered_user_orders(&mut db, &owner, &filter, None, None).await,
+            [expired.uid]
+        );
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_user_orders_performance_many_users_with_some_orders() {
@@ -1,5 +1,13 @@
 use {
-    crate::{Address, OrderUid, TransactionHash, auction::AuctionId, events::EventIndex},
+    crate::{
+        Address,
+        AppId,
+        OrderUid,
+        TransactionHash,
+        auction::AuctionId,
+        events::EventIndex,
+        orders::OrderClass,
+    },
     bigdecimal::BigDecimal,
     futures::stream::BoxStream,
     sqlx::PgConnection,
@@ -20,10 +28,31 @@ pub struct TradesQueryRow {
     pub auction_id: Option<AuctionId>,
 }
 
+/// Any `None` value means that this field is unfiltered.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TradeFilter {
+    pub owner: Option<Address>,
+    pub order_uid: Option<OrderUid>,
+    pub class: Option<OrderClass>,
+    /// Trades of orders either selling or buying the token.
+    pub token: Option<Address>,
+    pub app_data: Option<AppId>,
+    /// Trades settled in or after this block.
+    pub from_block: Option<i64>,
+    /// Trades settled in or before this block.
+    pub to_block: Option<i64>,
+}
+
+/// The trades matching the filter ordered by their event index descending
+/// (most recent trades first).
+///
+/// The next page of trades starts right before the `cursor`, which is the
+/// event index of the last trade of the previous page.
 pub fn trades<'a>(
     ex: &'a mut PgConnection,
-    owner_filter: Option<&'a Address>,
-    order_uid_filter: Option<&'a OrderUid>,
+    filter: &'a TradeFilter,
+    cursor: Option<&'a EventIndex>,
+    limit: Option<i64>,
 ) -> BoxStream<'a, Result<TradesQueryRow, sqlx::Error>> {
     const COMMON_QUERY: &str = r#"
 SELECT
@@ -47,28 +76,54 @@ LEFT OUTER JOIN LATERAL (
     LIMIT 1
 ) AS settlement ON true"#;
 
+    const FILTER: &str = r#"
+    AND ($2 IS NULL OR o.uid = $2)
+    AND ($4::bytea IS NULL OR o.sell_token = $4 OR o.buy_token = $4)
+    AND ($5::bytea IS NULL OR o.app_data = $5)
+    AND ($6::bigint IS NULL OR t.block_number >= $6)
+    AND ($7::bigint IS NULL OR t.block_number <= $7)
+    AND ($8::bigint IS NULL OR (t.block_number, t.log_index) < ($8, $9))
+    ORDER BY t.block_number DESC, t.log_index DESC
+    LIMIT $10"#;
+
     const QUERY: &str = const_format::concatcp!(
+        "(",
         COMMON_QUERY,
         " JOIN orders o ON o.uid = t.order_uid",
         " WHERE ($1 IS NULL OR o.owner = $1)",
-        " AND ($2 IS NULL OR o.uid = $2)",
-        " UNION ",
+        " AND ($3::OrderClass IS NULL OR o.class = $3)",
+        FILTER,
+        ") UNION (",
         COMMON_QUERY,
         " JOIN orders o ON o.uid = t.order_uid",
         " LEFT OUTER JOIN onchain_placed_orders onchain_o",
         " ON onchain_o.uid = t.order_uid",
         " WHERE onchain_o.sender = $1",
-        " AND ($2 IS NULL OR o.uid = $2)",
-        " UNION ",
+        " AND ($3::OrderClass IS NULL OR o.class = $3)",
+        FILTER,
+        ") UNION (",
         COMMON_QUERY,
         " JOIN jit_orders o ON o.uid = t.order_uid",
         " WHERE ($1 IS NULL OR o.owner = $1)",
-        " AND ($2 IS NULL OR o.uid = $2)",
+        // JIT orders are always liquidity orders.
+        " AND ($3::OrderClass IS NULL OR $3 = 'liquidity')",
+        FILTER,
+        ")",
+        " ORDER BY block_number DESC, log_index DESC",
+        " LIMIT $10",
     );
 
     sqlx::query_as(QUERY)
-        .bind(owner_filter)
-        .bind(order_uid_filter)
+        .bind(filter.owner)
+        .bind(filter.order_uid)
+        .bind(filter.class)
+        .bind(filter.token)
+        .bind(filter.app_data)
+        .bind(filter.from_block)
+        .bind(filter.to_block)
+        .bind(cursor.map(|cursor| cursor.block_number))
+        .bind(cursor.map(|cursor| cursor.log_index))
+        .bind(limit)
         .fetch(ex)
 }
 
@@ -209,7 +264,12 @@ mod tests {
         order_uid_filter: Option<&OrderUid>,
         expected: &[TradesQueryRow],
     ) {
-        let mut filtered = trades(db, owner_filter, order_uid_filter)
+        let filter = TradeFilter {
+            owner: owner_filter.copied(),
+            order_uid: order_uid_filter.copied(),
+            ..Default::default()
+        };
+        let mut filtered = trades(db, &filter, None, None)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -282,7 +342,11 @@ mod tests {
         }
 
         let now = std::time::Instant::now();
-        trades(&mut db, Some(&ByteArray([2u8; 20])), None)
+        let filter = TradeFilter {
+            owner: Some(ByteArray([2u8; 20])),
+            ..Default::default()
+        };
+        trades(&mut db, &filter, None, None)
             .try_collect::<Vec<_>>()
             .await
             .unwrap();
@@ -605,6 +669,89 @@ mod tests {
         );
     }
 
+    async fn blocks(
+        db: &mut PgConnection,
+        filter: TradeFilter,
+        cursor: Option<EventIndex>,
+        limit: Option<i64>,
+    ) -> Vec<i64> {
+        trades(db, &filter, cursor.as_ref(), limit)
+            .map_ok(|trade| trade.block_number)
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_trades_with_filters_and_cursor() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let owner = ByteArray([1; 20]);
+        for block_number in 1..=3u8 {
+            let order = Order {
+                uid: ByteArray([block_number; 56]),
+                owner,
+                class: if block_number == 2 {
+                    OrderClass::Limit
+                } else {
+                    OrderClass::Market
+                },
+                buy_token: ByteArray([block_number; 20]),
+                app_data: ByteArray([block_number; 32]),
+                ..Default::default()
+            };
+            crate::orders::insert_order(&mut db, &order).await.unwrap();
+            let event_index = EventIndex {
+                block_number: block_number.into(),
+                log_index: 0,
+            };
+            add_trade(&mut db, owner, order.uid, event_index, None, None).await;
+        }
+
+        let owner_filter = TradeFilter {
+            owner: Some(owner),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            blocks(&mut db, owner_filter.clone(), None, Some(2)).await,
+            [3, 2]
+        );
+        let cursor = EventIndex {
+            block_number: 2,
+            log_index: 0,
+        };
+        assert_eq!(
+            blocks(&mut db, owner_filter.clone(), Some(cursor), Some(2)).await,
+            [1]
+        );
+
+        let filter = TradeFilter {
+            from_block: Some(2),
+            to_block: Some(2),
+            ..owner_filter.clone()
+        };
+        assert_eq!(blocks(&mut db, filter, None, None).await, [2]);
+        let filter = TradeFilter {
+            class: Some(OrderClass::Limit),
+            ..owner_filter.clone()
+        };
+        assert_eq!(blocks(&mut db, filter, None, None).await, [2]);
+        let filter = TradeFilter {
+            token: Some(ByteArray([3; 20])),
+            ..owner_filter.clone()
+        };
+        assert_eq!(blocks(&mut db, filter, None, None).await, [3]);
+        let filter = TradeFilter {
+            app_data: Some(ByteArray([1; 32])),
+            ..owner_filter
+        };
+        assert_eq!(blocks(&mut db, filter, None, None).await, [1]);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_token_first_trade_block() {
@@ -0,0 +1,173 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{self, balancer},
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::{Context, ensure},
+    contracts::{BalancerV3BatchRouter, Permit2},
+    ethrpc::block_stream::BlockRetrieving,
+    shared::{
+        baseline_solver::BaselineSolvable,
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::balancer_v3::{self as shared_balancer_v3, pool_fetching::BalancerV3PoolFetcher},
+    },
+    solver::{
+        interactions::allowances::Allowances,
+        liquidity::{
+            BalancerV3PoolOrder,
+            balancer_v3::{BalancerV3Liquidity, SettlementHandler},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+pub fn to_domain(id: liquidity::Id, pool: BalancerV3PoolOrder) -> Result<liquidity::Liquidity> {
+    let handler = pool
+        .settlement_handling
+        .as_any()
+        .downcast_ref::<SettlementHandler>()
+        .expect("downcast balancer v3 settlement handler");
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: (pool.pool.gas_cost() as u64).into(),
+        kind: liquidity::Kind::BalancerV3(balancer::v3::Pool {
+            batch_router: handler.batch_router().address().into(),
+            permit2: handler.permit2().address().into(),
+            address: pool.pool.address.into(),
+            reserves: balancer::v3::Reserves::try_new(
+                pool.pool
+                    .tokens
+                    .iter()
+                    .map(|token| {
+                        Ok(balancer::v3::Reserve {
+                            asset: eth::Asset {
+                                token: token.address.into(),
+                                amount: token.balance.into(),
+                            },
+                            scale: balancer::v2::ScalingFactor::from_raw(
+                                token.scaling_factor.as_uint256(),
+                            )?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            )?,
+            fee: balancer::v2::Fee::from_raw(pool.pool.swap_fee.as_uint256()),
+            kind: match &pool.pool.kind {
+                shared_balancer_v3::PoolKind::Weighted { weights } => {
+                    ensure!(
+                        weights.len() == pool.pool.tokens.len(),
+                        "mismatched Balancer V3 pool weights"
+                    );
+                    balancer::v3::Kind::Weighted(
+                        weights
+                            .iter()
+                            .map(|weight| {
+                                balancer::v2::weighted::Weight::from_raw(weight.as_uint256())
+                            })
+                            .collect(),
+                    )
+                }
+                shared_balancer_v3::PoolKind::Stable {
+                    amplification_parameter,
+                } => balancer::v3::Kind::Stable(balancer::v2::stable::AmplificationParameter::new(
+                    amplification_parameter.factor(),
+                    amplification_parameter.precision(),
+                )?),
+            },
+        }),
+    })
+}
+
+/// Encodes the interactions for swapping on a Balancer V3 pool.
+///
+/// Note that the batch router sends the output tokens to its caller, which is
+/// the receiver of the swap.
+pub fn to_interactions(
+    pool: &liquidity::balancer::v3::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+) -> Vec<eth::Interaction> {
+    let web3 = ethrpc::dummy::web3();
+    let handler = SettlementHandler::new(
+        pool.address.into(),
+        BalancerV3BatchRouter::at(&web3, pool.batch_router.into()),
+        Permit2::at(&web3, pool.permit2.into()),
+        // Token approvals of Permit2 are handled by the driver.
+        Allowances::empty(pool.permit2.into()),
+    );
+
+    let (permit2_approval, swap) = handler.swap(
+        TokenAmount::new(input.0.token.into(), input.0.amount),
+        TokenAmount::new(output.0.token.into(), output.0.amount),
+    );
+
+    [permit2_approval.encode(), swap.encode()]
+        .into_iter()
+        .map(|encoded| eth::Interaction {
+            target: eth::Address(encoded.0),
+            value: eth::Ether(encoded.1),
+            call_data: crate::util::Bytes(encoded.2.0),
+        })
+        .collect()
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::BalancerV3,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("balancerV3".into()));
+    let config = *config;
+    let init = move || {
+        let eth = eth.clone();
+        let block_retriever = block_retriever.clone();
+        async move { init_liquidity(&eth, block_retriever, &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "balancer-v3",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::BalancerV3,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+
+    let pool_fetcher = Arc::new(
+        BalancerV3PoolFetcher::new(
+            web3.clone(),
+            config.vault.into(),
+            config.deployment_block,
+            block_retriever,
+            boundary::liquidity::cache_config(),
+            eth.current_block().clone(),
+        )
+        .await
+        .context("failed to initialise Balancer V3 liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(BalancerV3Liquidity::new(
+        web3.clone(),
+        pool_fetcher,
+        eth.contracts().settlement().address(),
+        BalancerV3BatchRouter::at(&web3, config.batch_router.into()),
+        Permit2::at(&web3, config.permit2.into()),
+    ))
+}
@@ -0,0 +1,235 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{self, curve},
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    shared::{
+        baseline_solver::BaselineSolvable,
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::curve::{self as shared_curve, crypto, pool_fetching::CurvePoolFetcher, stable},
+        token_info::{CachedTokenInfoFetcher, TokenInfoFetcher},
+    },
+    solver::{
+        interactions::allowances::Allowances,
+        liquidity::{
+            CurvePoolOrder,
+            curve::{CurveLiquidity, SettlementHandler},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+pub fn to_domain(id: liquidity::Id, pool: CurvePoolOrder) -> Result<liquidity::Liquidity> {
+    Ok(liquidity::Liquidity {
+        id,
+        gas: (pool.pool.gas_cost() as u64).into(),
+        kind: liquidity::Kind::Curve(curve::Pool {
+            address: pool.pool.address.into(),
+            reserves: curve::Reserves::try_new(
+                pool.pool
+                    .tokens
+                    .iter()
+                    .map(|token| curve::Reserve {
+                        asset: eth::Asset {
+                            token: token.address.into(),
+                            amount: token.balance.into(),
+                        },
+                        rate: token.rate,
+                    })
+                    .collect(),
+            )?,
+            kind: match pool.pool.kind {
+                shared_curve::PoolKind::StableSwap(parameters) => {
+                    curve::Kind::StableSwap(curve::StableSwap {
+                        amplification_parameter: parameters.amplification_parameter,
+                        fee: parameters.fee,
+                        offpeg_fee_multiplier: parameters.offpeg_fee_multiplier,
+                    })
+                }
+                shared_curve::PoolKind::CryptoSwap(parameters) => {
+                    curve::Kind::CryptoSwap(curve::CryptoSwap {
+                        a: parameters.a,
+                        gamma: parameters.gamma,
+                        d: parameters.d,
+                        price_scale: parameters.price_scale,
+                        mid_fee: parameters.mid_fee,
+                        out_fee: parameters.out_fee,
+                        fee_gamma: parameters.fee_gamma,
+                    })
+                }
+            },
+        }),
+    })
+}
+
+/// Converts a domain Curve pool back into the `shared` representation.
+fn to_boundary_pool(pool: &curve::Pool) -> shared_curve::Pool {
+    shared_curve::Pool {
+        address: pool.address.into(),
+        tokens: pool
+            .reserves
+            .iter()
+            .map(|reserve| shared_curve::Token {
+                address: reserve.asset.token.into(),
+                balance: reserve.asset.amount.into(),
+                rate: reserve.rate,
+            })
+            .collect(),
+        kind: match &pool.kind {
+            curve::Kind::StableSwap(parameters) => {
+                shared_curve::PoolKind::StableSwap(stable::Parameters {
+                    amplification_parameter: parameters.amplification_parameter,
+                    fee: parameters.fee,
+                    offpeg_fee_multiplier: parameters.offpeg_fee_multiplier,
+                })
+            }
+            curve::Kind::CryptoSwap(parameters) => {
+                shared_curve::PoolKind::CryptoSwap(crypto::Parameters {
+                    a: parameters.a,
+                    gamma: parameters.gamma,
+                    d: parameters.d,
+                    price_scale: parameters.price_scale,
+                    mid_fee: parameters.mid_fee,
+                    out_fee: parameters.out_fee,
+                    fee_gamma: parameters.fee_gamma,
+                })
+            }
+        },
+    }
+}
+
+pub fn to_interaction(
+    pool: &liquidity::curve::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> eth::Interaction {
+    let handler = SettlementHandler::new(
+        &to_boundary_pool(pool),
+        &ethrpc::dummy::web3(),
+        Allowances::empty(receiver.0),
+    );
+
+    let interaction = handler
+        .swap(
+            TokenAmount::new(input.0.token.into(), input.0.amount),
+            TokenAmount::new(output.0.token.into(), output.0.amount),
+        )
+        .expect("tokens are part of the pool");
+
+    let encoded = interaction.encode();
+    eth::Interaction {
+        target: eth::Address(encoded.0),
+        value: eth::Ether(encoded.1),
+        call_data: crate::util::Bytes(encoded.2.0),
+    }
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    config: &infra::liquidity::config::Curve,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("curve".into()));
+    let config = Arc::new(config.clone());
+    let init = move || {
+        let eth = eth.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "curve",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    config: &infra::liquidity::config::Curve,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let token_info_fetcher = Arc::new(CachedTokenInfoFetcher::new(Arc::new(TokenInfoFetcher {
+        web3: web3.clone(),
+    })));
+
+    let pool_fetcher = Arc::new(
+        CurvePoolFetcher::new(
+            web3.clone(),
+            config.registry.into(),
+            token_info_fetcher,
+            boundary::liquidity::cache_config(),
+            eth.current_block().clone(),
+        )
+        .await
+        .context("failed to initialise Curve liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(CurveLiquidity::new(
+        web3,
+        pool_fetcher,
+        eth.contracts().settlement().address(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn encodes_swap_without_allowances() {
+        let token = |byte: u8| eth::TokenAddress::from(eth::H160([byte; 20]));
+        let asset = |byte: u8, amount: u64| eth::Asset {
+            token: token(byte),
+            amount: eth::TokenAmount(amount.into()),
+        };
+        let reserve = |byte: u8| curve::Reserve {
+            asset: asset(byte, 1_000_000),
+            rate: eth::U256::exp10(18),
+        };
+        let pool = curve::Pool {
+            address: eth::H160([0x42; 20]).into(),
+            reserves: curve::Reserves::try_new(vec![reserve(1), reserve(2), reserve(3)]).unwrap(),
+            kind: curve::Kind::StableSwap(curve::StableSwap {
+                amplification_parameter: 100_000.into(),
+                fee: 4_000_000.into(),
+                offpeg_fee_multiplier: 0.into(),
+            }),
+        };
+
+        // The settlement contract approves the pool in a separate interaction,
+        // so encoding the swap must not depend on any existing allowances.
+        let interaction = pool
+            .swap(
+                &liquidity::MaxInput(asset(2, 1_000)),
+                &liquidity::ExactOutput(asset(3, 999)),
+                &eth::Address(eth::H160([0x99; 20])),
+            )
+            .unwrap();
+
+        assert_eq!(interaction.target, eth::Address(eth::H160([0x42; 20])));
+        assert_eq!(interaction.value, eth::Ether(0.into()));
+        assert_eq!(
+            interaction.call_data.0,
+            hex!(
+                "3df02124
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000002
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 00000000000000000000000000000000000000000000000000000000000003e7"
+            )
+        );
+    }
+}
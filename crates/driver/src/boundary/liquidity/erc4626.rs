@@ -0,0 +1,122 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{self, erc4626},
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    shared::{
+        baseline_solver::BaselineSolvable,
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        sources::erc4626::{Vault, vault_fetching::Erc4626VaultFetcher},
+    },
+    solver::{
+        interactions::allowances::Allowances,
+        liquidity::{
+            Erc4626VaultOrder,
+            erc4626::{Erc4626Liquidity, SettlementHandler},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+pub fn to_domain(id: liquidity::Id, vault: Erc4626VaultOrder) -> Result<liquidity::Liquidity> {
+    Ok(liquidity::Liquidity {
+        id,
+        gas: (vault.vault.gas_cost() as u64).into(),
+        kind: liquidity::Kind::Erc4626(erc4626::Vault {
+            vault: vault.vault.address.into(),
+            asset: vault.vault.asset.into(),
+            assets_to_shares: vault.vault.assets_to_shares,
+            shares_to_assets: vault.vault.shares_to_assets,
+        }),
+    })
+}
+
+pub fn to_interaction(
+    vault: &liquidity::erc4626::Vault,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> eth::Interaction {
+    let handler = SettlementHandler::new(
+        &Vault {
+            address: vault.vault.into(),
+            asset: vault.asset.into(),
+            assets_to_shares: vault.assets_to_shares,
+            shares_to_assets: vault.shares_to_assets,
+        },
+        &ethrpc::dummy::web3(),
+        receiver.0,
+        Allowances::empty(receiver.0),
+    );
+
+    let interaction = handler
+        .swap(
+            TokenAmount::new(input.0.token.into(), input.0.amount),
+            TokenAmount::new(output.0.token.into(), output.0.amount),
+        )
+        .expect("tokens are the vault asset and shares");
+
+    let encoded = interaction.encode();
+    eth::Interaction {
+        target: eth::Address(encoded.0),
+        value: eth::Ether(encoded.1),
+        call_data: crate::util::Bytes(encoded.2.0),
+    }
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    config: &infra::liquidity::config::Erc4626,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("erc4626".into()));
+    let config = Arc::new(config.clone());
+    let init = move || {
+        let eth = eth.clone();
+        let config = config.clone();
+        async move { init_liquidity(&eth, &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "erc4626",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    config: &infra::liquidity::config::Erc4626,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let vaults = config
+        .vaults
+        .iter()
+        .map(|vault| vault.0)
+        .collect::<Vec<_>>();
+
+    // The vault cache updates itself on new blocks, so no maintenance task is
+    // needed.
+    let vault_fetcher = Arc::new(
+        Erc4626VaultFetcher::new(
+            web3.clone(),
+            &vaults,
+            boundary::liquidity::cache_config(),
+            eth.current_block().clone(),
+        )
+        .await
+        .context("failed to initialise ERC-4626 liquidity")?,
+    );
+
+    Ok(Erc4626Liquidity::new(
+        web3,
+        vault_fetcher,
+        eth.contracts().settlement().address(),
+    ))
+}
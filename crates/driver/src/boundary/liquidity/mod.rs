@@ -27,6 +27,9 @@ use {
 };
 
 pub mod balancer;
+pub mod curve;
+pub mod erc4626;
+pub mod solidly;
 pub mod swapr;
 pub mod uniswap;
 pub mod zeroex;
@@ -62,6 +65,7 @@ impl Fetcher {
     pub async fn try_new(eth: &Ethereum, config: &infra::liquidity::Config) -> Result<Self> {
         let blocks = current_block::Arguments {
             block_stream_poll_interval: BLOCK_POLL_INTERVAL,
+            node_ws_url: None,
         };
 
         let block_stream = eth.current_block();
@@ -98,6 +102,36 @@ impl Fetcher {
             .map(|config| uniswap::v3::collector(eth, block_retriever.clone(), config))
             .collect();
 
+        let uni_v4: Vec<_> = config
+            .uniswap_v4
+            .iter()
+            .map(|config| uniswap::v4::collector(eth, block_retriever.clone(), config))
+            .collect();
+
+        let bal_v3: Vec<_> = config
+            .balancer_v3
+            .iter()
+            .map(|config| balancer::v3::collector(eth, block_retriever.clone(), config))
+            .collect();
+
+        let curve: Vec<_> = config
+            .curve
+            .iter()
+            .map(|config| curve::collector(eth, config))
+            .collect();
+
+        let solidly: Vec<_> = config
+            .solidly
+            .iter()
+            .map(|config| solidly::collector(eth, config))
+            .collect();
+
+        let erc4626: Vec<_> = config
+            .erc4626
+            .iter()
+            .map(|config| erc4626::collector(eth, config))
+            .collect();
+
         let zeroex: Vec<_> = future::try_join_all(
             config
                 .zeroex
@@ -121,10 +155,12 @@ impl Fetcher {
         Ok(Self {
             blocks: block_stream.clone(),
             inner: LiquidityCollector {
-                liquidity_sources: [uni_v2, swapr, bal_v2, uni_v3, zeroex]
-                    .into_iter()
-                    .flatten()
-                    .collect(),
+                liquidity_sources: [
+                    uni_v2, swapr, bal_v2, bal_v3, uni_v3, uni_v4, curve, solidly, erc4626, zeroex,
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
                 base_tokens: Arc::new(base_tokens),
             },
             swapr_routers,
@@ -170,7 +206,17 @@ impl Fetcher {
                     Liquidity::BalancerWeighted(pool) => balancer::v2::weighted::to_domain(id, pool),
                     Liquidity::BalancerStable(pool) => balancer::v2::stable::to_domain(id, pool),
                     Liquidity::LimitOrder(pool) => zeroex::to_domain(id, pool),
-                    Liquidity::Concentrated(pool) => uniswap::v3::to_domain(id, pool),
+                    Liquidity::Concentrated(pool) => {
+                        if uniswap::v4::is_uniswap_v4(&pool) {
+                            uniswap::v4::to_domain(id, pool)
+                        } else {
+                            uniswap::v3::to_domain(id, pool)
+                        }
+                    }
+                    Liquidity::Curve(pool) => curve::to_domain(id, pool),
+                    Liquidity::BalancerV3(pool) => balancer::v3::to_domain(id, pool),
+                    Liquidity::Solidly(pool) => solidly::to_domain(id, pool),
+                    Liquidity::Erc4626(vault) => erc4626::to_domain(id, vault),
                 }
                 // Ignore "bad" liquidity - this allows the driver to continue
                 // solving with the other good stuff.
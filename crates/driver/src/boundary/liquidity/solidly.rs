@@ -0,0 +1,149 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{self, solidly},
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    contracts::ISolidlyRouter,
+    shared::{
+        baseline_solver::BaselineSolvable,
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        sources::solidly::pool_fetching::SolidlyPoolFetcher,
+    },
+    solver::{
+        interactions::allowances::Allowances,
+        liquidity::{
+            SolidlyPoolOrder,
+            solidly::{SettlementHandler, SolidlyLiquidity},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::sync::Arc,
+};
+
+/// The base unit for basis points, i.e. how many basis points in 100%.
+const BPS_BASE: u32 = 10_000;
+
+pub fn to_domain(id: liquidity::Id, pool: SolidlyPoolOrder) -> Result<liquidity::Liquidity> {
+    let handler = pool
+        .settlement_handling
+        .as_any()
+        .downcast_ref::<SettlementHandler>()
+        .expect("downcast solidly settlement handler");
+
+    let fee = pool.pool.fee;
+    anyhow::ensure!(
+        (fee.numer() * BPS_BASE) % fee.denom() == 0,
+        "invalid Solidly fee ratio; does not have exact BPS representation",
+    );
+    let (token0, token1) = pool.pool.tokens.get();
+    let reserve = |token: eth::H160, amount: eth::U256, decimals: u8| solidly::Reserve {
+        asset: eth::Asset {
+            token: token.into(),
+            amount: amount.into(),
+        },
+        decimals,
+    };
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: (pool.pool.gas_cost() as u64).into(),
+        kind: liquidity::Kind::Solidly(solidly::Pool {
+            address: pool.pool.address.into(),
+            router: handler.router().address().into(),
+            factory: handler.factory().into(),
+            reserves: solidly::Reserves::try_new(
+                reserve(token0, pool.pool.reserves.0, pool.pool.decimals.0),
+                reserve(token1, pool.pool.reserves.1, pool.pool.decimals.1),
+            )?,
+            fee: solidly::Fee::try_new((fee.numer() * BPS_BASE) / fee.denom())?,
+            stable: pool.pool.stable,
+        }),
+    })
+}
+
+pub fn to_interaction(
+    pool: &liquidity::solidly::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+    receiver: &eth::Address,
+) -> eth::Interaction {
+    let handler = SettlementHandler::new(
+        pool.stable,
+        ISolidlyRouter::at(&ethrpc::dummy::web3(), pool.router.into()),
+        pool.factory.into(),
+        receiver.0,
+        Allowances::empty(receiver.0),
+    );
+
+    let interaction = handler
+        .swap(
+            TokenAmount::new(input.0.token.into(), input.0.amount),
+            TokenAmount::new(output.0.token.into(), output.0.amount),
+        )
+        .expect("tokens are part of the pool");
+
+    let encoded = interaction.encode();
+    eth::Interaction {
+        target: eth::Address(encoded.0),
+        value: eth::Ether(encoded.1),
+        call_data: crate::util::Bytes(encoded.2.0),
+    }
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    config: &infra::liquidity::config::Solidly,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("solidly".into()));
+    let config = *config;
+    let init = move || {
+        let eth = eth.clone();
+        async move { init_liquidity(&eth, &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "solidly",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    config: &infra::liquidity::config::Solidly,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let router = ISolidlyRouter::at(&web3, config.router.into());
+    let factory = router
+        .default_factory()
+        .call()
+        .await
+        .context("failed to fetch Solidly factory")?;
+
+    // The pool cache updates itself on new blocks, so no maintenance task is
+    // needed.
+    let pool_fetcher = Arc::new(
+        SolidlyPoolFetcher::new(
+            web3.clone(),
+            factory,
+            boundary::liquidity::cache_config(),
+            eth.current_block().clone(),
+        )
+        .await
+        .context("failed to initialise Solidly liquidity")?,
+    );
+
+    Ok(SolidlyLiquidity::new(
+        web3,
+        pool_fetcher,
+        eth.contracts().settlement().address(),
+        router,
+        factory,
+    ))
+}
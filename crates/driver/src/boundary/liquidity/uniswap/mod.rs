@@ -1,2 +1,3 @@
 pub mod v2;
 pub mod v3;
+pub mod v4;
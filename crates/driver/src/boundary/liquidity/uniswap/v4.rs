@@ -0,0 +1,177 @@
+use {
+    crate::{
+        boundary::{self, Result},
+        domain::{
+            eth,
+            liquidity::{
+                self,
+                uniswap::{
+                    v3::{Fee, Liquidity, LiquidityNet, SqrtPrice, Tick},
+                    v4::Pool,
+                },
+            },
+        },
+        infra::{self, blockchain::Ethereum},
+    },
+    anyhow::Context,
+    contracts::UniswapV4UniversalRouter,
+    ethrpc::block_stream::BlockRetrieving,
+    num::rational::Ratio,
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::Interaction,
+        maintenance::ServiceMaintenance,
+        sources::uniswap_v4::{PoolKey, pool_fetching::UniswapV4PoolFetcher},
+        token_info::{CachedTokenInfoFetcher, TokenInfoFetcher},
+    },
+    solver::{
+        liquidity::{
+            ConcentratedLiquidity,
+            uniswap_v4::{Inner, UniswapV4Liquidity, UniswapV4SettlementHandler},
+        },
+        liquidity_collector::{BackgroundInitLiquiditySource, LiquidityCollecting},
+    },
+    std::{collections::BTreeMap, sync::Arc},
+};
+
+/// Returns whether the concentrated liquidity is a Uniswap V4 pool.
+pub fn is_uniswap_v4(pool: &ConcentratedLiquidity) -> bool {
+    pool.settlement_handling
+        .as_any()
+        .is::<UniswapV4SettlementHandler>()
+}
+
+pub fn to_domain(id: liquidity::Id, pool: ConcentratedLiquidity) -> Result<liquidity::Liquidity> {
+    let handler = pool
+        .settlement_handling
+        .as_any()
+        .downcast_ref::<UniswapV4SettlementHandler>()
+        .expect("downcast uniswap v4 settlement handler");
+
+    Ok(liquidity::Liquidity {
+        id,
+        gas: eth::Gas(pool.pool.gas_stats.mean_gas),
+        kind: liquidity::Kind::UniswapV4(Pool {
+            router: handler.inner.router.address().into(),
+            pool_manager: pool.pool.address.into(),
+            id: handler.id,
+            tokens: liquidity::TokenPair::try_new(
+                handler.key.currency0.into(),
+                handler.key.currency1.into(),
+            )?,
+            tick_spacing: handler.key.tick_spacing,
+            hooks: handler.key.hooks.into(),
+            sqrt_price: SqrtPrice(pool.pool.state.sqrt_price),
+            liquidity: Liquidity(pool.pool.state.liquidity.as_u128()),
+            tick: Tick(pool.pool.state.tick.try_into()?),
+            liquidity_net: pool
+                .pool
+                .state
+                .liquidity_net
+                .iter()
+                .map(|(key, value)| -> Result<_> {
+                    Ok((Tick(key.try_into()?), LiquidityNet(value.try_into()?)))
+                })
+                .collect::<Result<BTreeMap<_, _>>>()?,
+            fee: Fee(pool.pool.state.fee),
+        }),
+    })
+}
+
+/// Encodes the interactions for swapping on a Uniswap V4 pool.
+///
+/// Note that the router sends the output tokens to its caller, which is the
+/// receiver of the swap.
+pub fn to_interactions(
+    pool: &liquidity::uniswap::v4::Pool,
+    input: &liquidity::MaxInput,
+    output: &liquidity::ExactOutput,
+) -> Vec<eth::Interaction> {
+    let web3 = ethrpc::dummy::web3();
+    let (currency0, currency1) = pool.tokens.get();
+
+    let handler = UniswapV4SettlementHandler {
+        inner: Arc::new(Inner {
+            web3: web3.clone(),
+            router: UniswapV4UniversalRouter::at(&web3, pool.router.0),
+            pool_manager: pool.pool_manager.0,
+        }),
+        id: pool.id,
+        key: PoolKey {
+            currency0: currency0.into(),
+            currency1: currency1.into(),
+            // The fee is stored in hundredths of a bip.
+            fee: (pool.fee.0 * Ratio::from_integer(1_000_000)).to_integer(),
+            tick_spacing: pool.tick_spacing,
+            hooks: pool.hooks.0,
+        },
+    };
+
+    let (transfer, swap) = handler.settle(
+        TokenAmount::new(input.0.token.into(), input.0.amount),
+        TokenAmount::new(output.0.token.into(), output.0.amount),
+    );
+
+    [transfer.encode(), swap.encode()]
+        .into_iter()
+        .map(|encoded| eth::Interaction {
+            target: eth::Address(encoded.0),
+            value: eth::Ether(encoded.1),
+            call_data: crate::util::Bytes(encoded.2.0),
+        })
+        .collect()
+}
+
+pub fn collector(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::UniswapV4,
+) -> Box<dyn LiquidityCollecting> {
+    let eth = Arc::new(eth.with_metric_label("uniswapV4".into()));
+    let config = *config;
+    let init = move || {
+        let eth = eth.clone();
+        let block_retriever = block_retriever.clone();
+        async move { init_liquidity(&eth, block_retriever, &config).await }
+    };
+    const TEN_MINUTES: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+    Box::new(BackgroundInitLiquiditySource::new(
+        "uniswap-v4",
+        init,
+        TEN_MINUTES,
+    )) as Box<_>
+}
+
+async fn init_liquidity(
+    eth: &Ethereum,
+    block_retriever: Arc<dyn BlockRetrieving>,
+    config: &infra::liquidity::config::UniswapV4,
+) -> anyhow::Result<impl LiquidityCollecting + use<>> {
+    let web3 = boundary::web3(eth);
+    let token_info_fetcher = Arc::new(CachedTokenInfoFetcher::new(Arc::new(TokenInfoFetcher {
+        web3: web3.clone(),
+    })));
+
+    let pool_fetcher = Arc::new(
+        UniswapV4PoolFetcher::new(
+            web3.clone(),
+            config.pool_manager.0,
+            config.deployment_block,
+            block_retriever,
+        )
+        .await
+        .context("failed to initialise Uniswap V4 liquidity")?,
+    );
+
+    let update_task = ServiceMaintenance::new(vec![pool_fetcher.clone()])
+        .run_maintenance_on_new_block(eth.current_block().clone());
+    tokio::task::spawn(update_task);
+
+    Ok(UniswapV4Liquidity::new(
+        web3.clone(),
+        UniswapV4UniversalRouter::at(&web3, config.router.0),
+        config.pool_manager.0,
+        pool_fetcher,
+        token_info_fetcher,
+    ))
+}
@@ -27,7 +27,7 @@ pub mod liquidity;
 
 // The [`anyhow::Error`] type is re-exported because the legacy code mostly
 // returns that error. This will change as the legacy code gets refactored away.
-use {crate::infra::blockchain::Ethereum, url::Url};
+use {crate::infra::blockchain::Ethereum, std::num::NonZeroUsize, url::Url};
 pub use {
     anyhow::{Error, Result},
     contracts,
@@ -59,6 +59,9 @@ fn web3_client(ethrpc: &Url, max_batch_size: usize, max_concurrent_requests: usi
         ethrpc_max_batch_size: max_batch_size,
         ethrpc_max_concurrent_requests: max_concurrent_requests,
         ethrpc_batch_delay: Default::default(),
+        ethrpc_quorum: NonZeroUsize::MIN,
+        ethrpc_quorum_methods: Default::default(),
+        ethrpc_failure_backoff: Default::default(),
     };
     let http_factory =
         shared::http_client::HttpClientFactory::new(&shared::http_client::Arguments {
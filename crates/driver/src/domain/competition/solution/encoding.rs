@@ -269,16 +269,18 @@ pub fn tx(
             continue;
         }
 
-        interactions.push(match interaction {
-            competition::solution::Interaction::Custom(interaction) => eth::Interaction {
-                value: interaction.value,
-                target: interaction.target.into(),
-                call_data: interaction.call_data.clone(),
-            },
-            competition::solution::Interaction::Liquidity(liquidity) => {
-                liquidity_interaction(liquidity, &slippage, contracts.settlement())?
+        match interaction {
+            competition::solution::Interaction::Custom(interaction) => {
+                interactions.push(eth::Interaction {
+                    value: interaction.value,
+                    target: interaction.target.into(),
+                    call_data: interaction.call_data.clone(),
+                })
             }
-        })
+            competition::solution::Interaction::Liquidity(liquidity) => interactions.extend(
+                liquidity_interaction(liquidity, &slippage, contracts.settlement())?,
+            ),
+        }
     }
 
     // Encode WETH unwrap
@@ -329,7 +331,7 @@ pub fn liquidity_interaction(
     liquidity: &Liquidity,
     slippage: &slippage::Parameters,
     settlement: &contracts::GPv2Settlement,
-) -> Result<eth::Interaction, Error> {
+) -> Result<Vec<eth::Interaction>, Error> {
     let (input, output) = slippage.apply_to(&slippage::Interaction {
         input: liquidity.input,
         output: liquidity.output,
@@ -338,20 +340,42 @@ pub fn liquidity_interaction(
     match liquidity.liquidity.kind.clone() {
         liquidity::Kind::UniswapV2(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
         liquidity::Kind::UniswapV3(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
         liquidity::Kind::BalancerV2Stable(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
         liquidity::Kind::BalancerV2Weighted(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::BalancerV3(pool) => pool.swap(&input, &output).ok(),
         liquidity::Kind::Swapr(pool) => pool
             .swap(&input, &output, &settlement.address().into())
-            .ok(),
-        liquidity::Kind::ZeroEx(limit_order) => limit_order.to_interaction(&input).ok(),
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::UniswapV4(pool) => pool.swap(&input, &output).ok(),
+        liquidity::Kind::ZeroEx(limit_order) => limit_order
+            .to_interaction(&input)
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::Curve(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::Solidly(pool) => pool
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
+        liquidity::Kind::Erc4626(vault) => vault
+            .swap(&input, &output, &settlement.address().into())
+            .ok()
+            .map(|interaction| vec![interaction]),
     }
     .ok_or(Error::InvalidInteractionExecution(liquidity.clone()))
 }
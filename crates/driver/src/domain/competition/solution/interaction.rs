@@ -39,10 +39,24 @@ impl Interaction {
                 let address = match &interaction.liquidity.kind {
                     liquidity::Kind::UniswapV2(pool) => pool.router.into(),
                     liquidity::Kind::UniswapV3(pool) => pool.router.into(),
+                    // Uniswap V4 swaps are paid by transferring the input
+                    // tokens to the router, so no approval is needed.
+                    liquidity::Kind::UniswapV4(_) => return Vec::new(),
                     liquidity::Kind::BalancerV2Stable(pool) => pool.vault.into(),
                     liquidity::Kind::BalancerV2Weighted(pool) => pool.vault.into(),
+                    // The Balancer V3 batch router pulls the input tokens
+                    // through Permit2.
+                    liquidity::Kind::BalancerV3(pool) => pool.permit2.into(),
                     liquidity::Kind::Swapr(pool) => pool.base.router.into(),
                     liquidity::Kind::ZeroEx(pool) => pool.zeroex.address().into(),
+                    liquidity::Kind::Curve(pool) => pool.address.into(),
+                    liquidity::Kind::Solidly(pool) => pool.router.into(),
+                    // Only deposits pull tokens, shares are redeemed by the
+                    // settlement contract as their owner.
+                    liquidity::Kind::Erc4626(vault) if interaction.input.token == vault.vault => {
+                        return Vec::new();
+                    }
+                    liquidity::Kind::Erc4626(vault) => vault.vault.into(),
                 };
                 // As a gas optimization, we always approve the max amount possible. This
                 // minimizes the number of approvals necessary, and therefore
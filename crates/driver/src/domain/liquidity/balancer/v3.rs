@@ -0,0 +1,112 @@
+use {
+    super::v2::{Fee, ScalingFactor, stable::AmplificationParameter, weighted::Weight},
+    crate::{
+        boundary,
+        domain::{eth, liquidity},
+    },
+    itertools::Itertools,
+};
+
+/// Liquidity data tied to a Balancer V3 pool.
+///
+/// All Balancer V3 pools are held by a single vault and are swapped on through
+/// the batch router, which pulls the input tokens from the settlement contract
+/// with Permit2 [^1]. Only weighted and stable pools without swap altering
+/// hooks are supported.
+///
+/// [^1]: <https://docs.balancer.fi/concepts/router/overview.html>
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub batch_router: eth::ContractAddress,
+    pub permit2: eth::ContractAddress,
+    pub address: eth::ContractAddress,
+    pub reserves: Reserves,
+    pub fee: Fee,
+    pub kind: Kind,
+}
+
+impl Pool {
+    /// Encodes a pool swap as interactions. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens do not belong to the pool.
+    ///
+    /// The swap is encoded as a Permit2 approval of the batch router for the
+    /// input amount followed by an exact output swap through the router. The
+    /// router sends the output tokens to its caller, so they can only be
+    /// received by the settlement contract executing the interactions.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+    ) -> Result<Vec<eth::Interaction>, liquidity::InvalidSwap> {
+        let (Some(i), Some(j)) = (
+            self.reserves.index(&input.0.token),
+            self.reserves.index(&output.0.token),
+        ) else {
+            return Err(liquidity::InvalidSwap);
+        };
+        if i == j {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::balancer::v3::to_interactions(
+            self, input, output,
+        ))
+    }
+}
+
+/// Balancer V3 pool reserves.
+///
+/// This is an ordered collection of tokens with their balance and scaling
+/// factor, where the order corresponds to the token order of the vault.
+#[derive(Clone, Debug)]
+pub struct Reserves(Vec<Reserve>);
+
+impl Reserves {
+    /// Creates new Balancer V3 token reserves, returns `Err` if the specified
+    /// token reserves are invalid, specifically, if there are fewer than 2
+    /// tokens or duplicate tokens.
+    pub fn try_new(reserves: Vec<Reserve>) -> Result<Self, InvalidReserves> {
+        if reserves.len() < 2 || !reserves.iter().map(|r| r.asset.token).all_unique() {
+            return Err(InvalidReserves);
+        }
+
+        Ok(Self(reserves))
+    }
+
+    /// Returns the index of the token in the pool.
+    pub fn index(&self, token: &eth::TokenAddress) -> Option<usize> {
+        self.0.iter().position(|r| r.asset.token == *token)
+    }
+
+    /// Returns an iterator over the reserve tokens.
+    pub fn tokens(&self) -> impl Iterator<Item = eth::TokenAddress> + '_ {
+        self.iter().map(|r| r.asset.token)
+    }
+
+    /// Returns an iterator over the reserve assets.
+    pub fn iter(&self) -> impl Iterator<Item = Reserve> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid Balancer V3 token reserves; fewer than 2 tokens or duplicate token address")]
+pub struct InvalidReserves;
+
+/// Balancer V3 pool reserve for a single token.
+#[derive(Clone, Copy, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The factor scaling raw token amounts to the 18 decimal amounts used by
+    /// the pool math. Unlike Balancer V2, this includes the token rate.
+    pub scale: ScalingFactor,
+}
+
+/// The kind of Balancer V3 pool along with its invariant parameters.
+#[derive(Clone, Debug)]
+pub enum Kind {
+    /// A weighted pool with the weights of its reserves in reserve order.
+    Weighted(Vec<Weight>),
+    Stable(AmplificationParameter),
+}
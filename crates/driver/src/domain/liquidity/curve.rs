@@ -0,0 +1,126 @@
+use {
+    crate::{
+        boundary,
+        domain::{eth, liquidity},
+    },
+    itertools::Itertools,
+};
+
+/// Liquidity data tied to a Curve pool.
+///
+/// Curve pools come in two flavours: StableSwap pools [^1] for assets that are
+/// pegged to each other, and CryptoSwap pools [^2] for volatile assets.
+///
+/// [^1]: <https://docs.curve.fi/stableswap-exchange/overview/>
+/// [^2]: <https://docs.curve.fi/cryptoswap-exchange/overview/>
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub address: eth::ContractAddress,
+    pub reserves: Reserves,
+    pub kind: Kind,
+}
+
+impl Pool {
+    /// Encodes a pool swap as an interaction. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens do not belong to the pool.
+    ///
+    /// Note that Curve pools only support swapping exact input amounts, so the
+    /// whole input amount is sold for at least the output amount.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<eth::Interaction, liquidity::InvalidSwap> {
+        let (Some(i), Some(j)) = (
+            self.reserves.index(&input.0.token),
+            self.reserves.index(&output.0.token),
+        ) else {
+            return Err(liquidity::InvalidSwap);
+        };
+        if i == j {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::curve::to_interaction(
+            self, input, output, receiver,
+        ))
+    }
+}
+
+/// Curve pool reserves.
+///
+/// This is an ordered collection of tokens with their balance and rates, where
+/// the order corresponds to the coin indices of the pool.
+#[derive(Clone, Debug)]
+pub struct Reserves(Vec<Reserve>);
+
+impl Reserves {
+    /// Creates new Curve token reserves, returns `Err` if the specified token
+    /// reserves are invalid, specifically, if there are fewer than 2 tokens or
+    /// duplicate tokens.
+    pub fn try_new(reserves: Vec<Reserve>) -> Result<Self, InvalidReserves> {
+        if reserves.len() < 2 || !reserves.iter().map(|r| r.asset.token).all_unique() {
+            return Err(InvalidReserves);
+        }
+
+        Ok(Self(reserves))
+    }
+
+    /// Returns the coin index of the token in the pool.
+    pub fn index(&self, token: &eth::TokenAddress) -> Option<usize> {
+        self.0.iter().position(|r| r.asset.token == *token)
+    }
+
+    /// Returns an iterator over the reserve tokens.
+    pub fn tokens(&self) -> impl Iterator<Item = eth::TokenAddress> + '_ {
+        self.iter().map(|r| r.asset.token)
+    }
+
+    /// Returns an iterator over the reserve assets.
+    pub fn iter(&self) -> impl Iterator<Item = Reserve> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid Curve token reserves; fewer than 2 tokens or duplicate token address")]
+pub struct InvalidReserves;
+
+/// Curve pool reserve for a single token.
+#[derive(Clone, Copy, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The rate scaled by 1e18 that normalizes the token balance to 18
+    /// decimals.
+    pub rate: eth::U256,
+}
+
+/// The kind of Curve pool along with its invariant parameters.
+#[derive(Clone, Debug)]
+pub enum Kind {
+    StableSwap(StableSwap),
+    CryptoSwap(CryptoSwap),
+}
+
+/// The parameters of a StableSwap pool. Fees are expressed over `1e10`.
+#[derive(Clone, Debug)]
+pub struct StableSwap {
+    /// The amplification parameter scaled by `A_PRECISION` (100).
+    pub amplification_parameter: eth::U256,
+    pub fee: eth::U256,
+    pub offpeg_fee_multiplier: eth::U256,
+}
+
+/// The parameters of a CryptoSwap pool. Fees are expressed over `1e10`.
+#[derive(Clone, Debug)]
+pub struct CryptoSwap {
+    pub a: eth::U256,
+    pub gamma: eth::U256,
+    pub d: eth::U256,
+    pub price_scale: eth::U256,
+    pub mid_fee: eth::U256,
+    pub out_fee: eth::U256,
+    pub fee_gamma: eth::U256,
+}
@@ -0,0 +1,54 @@
+use crate::{
+    boundary,
+    domain::{eth, liquidity},
+};
+
+/// Liquidity data tied to an ERC-4626 tokenized vault [^1].
+///
+/// Vaults deterministically convert between their underlying asset and vault
+/// shares, which are themselves an ERC-20 token, by depositing and redeeming.
+///
+/// [^1]: <https://eips.ethereum.org/EIPS/eip-4626>
+#[derive(Clone, Debug)]
+pub struct Vault {
+    /// The vault address, which is also the address of the share token.
+    pub vault: eth::TokenAddress,
+    /// The underlying asset of the vault.
+    pub asset: eth::TokenAddress,
+    /// The shares received for `1e18` assets, as returned by
+    /// `previewDeposit` and discounted by a safety margin.
+    pub assets_to_shares: eth::U256,
+    /// The assets received for `1e18` shares, as returned by `previewRedeem`
+    /// and discounted by a safety margin.
+    pub shares_to_assets: eth::U256,
+}
+
+impl Vault {
+    /// Encodes a vault conversion as an interaction. Returns `Err` if the swap
+    /// parameters are invalid for the vault, specifically if the input and
+    /// output tokens aren't the vault asset and shares.
+    ///
+    /// Note that conversions are executed with exact input amounts, so the
+    /// whole input amount is deposited or redeemed. Vaults don't take a minimum
+    /// output amount, so the output amount isn't enforced on-chain.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<eth::Interaction, liquidity::InvalidSwap> {
+        let tokens = (input.0.token, output.0.token);
+        if tokens != (self.asset, self.vault) && tokens != (self.vault, self.asset) {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::erc4626::to_interaction(
+            self, input, output, receiver,
+        ))
+    }
+
+    /// Returns the vault asset and share tokens.
+    pub fn tokens(&self) -> [eth::TokenAddress; 2] {
+        [self.asset, self.vault]
+    }
+}
@@ -8,6 +8,9 @@ use {
 };
 
 pub mod balancer;
+pub mod curve;
+pub mod erc4626;
+pub mod solidly;
 pub mod swapr;
 pub mod uniswap;
 pub mod zeroex;
@@ -47,10 +50,15 @@ pub struct ExactOutput(pub eth::Asset);
 pub enum Kind {
     UniswapV2(uniswap::v2::Pool),
     UniswapV3(uniswap::v3::Pool),
+    UniswapV4(uniswap::v4::Pool),
     BalancerV2Stable(balancer::v2::stable::Pool),
     BalancerV2Weighted(balancer::v2::weighted::Pool),
+    BalancerV3(balancer::v3::Pool),
     Swapr(swapr::Pool),
     ZeroEx(zeroex::LimitOrder),
+    Curve(curve::Pool),
+    Solidly(solidly::Pool),
+    Erc4626(erc4626::Vault),
 }
 
 impl From<&Kind> for &'static str {
@@ -58,10 +66,15 @@ impl From<&Kind> for &'static str {
         match *val {
             Kind::UniswapV2(_) => "UniswapV2",
             Kind::UniswapV3(_) => "UniswapV3",
+            Kind::UniswapV4(_) => "UniswapV4",
             Kind::BalancerV2Stable(_) => "BalancerV2Stable",
             Kind::BalancerV2Weighted(_) => "BalancerV2Weighted",
+            Kind::BalancerV3(_) => "BalancerV3",
             Kind::Swapr(_) => "Swapr",
             Kind::ZeroEx(_) => "ZeroExLimitOrder",
+            Kind::Curve(_) => "Curve",
+            Kind::Solidly(_) => "Solidly",
+            Kind::Erc4626(_) => "Erc4626",
         }
     }
 }
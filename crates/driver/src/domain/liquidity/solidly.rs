@@ -0,0 +1,123 @@
+use {
+    crate::{
+        boundary,
+        domain::{eth, liquidity},
+    },
+    std::cmp::Ordering,
+};
+
+/// Liquidity data tied to a Solidly pool, as used by Velodrome V2 and
+/// Aerodrome [^1].
+///
+/// Solidly factories deploy two pools per token pair: a volatile pool using
+/// the constant product invariant `x · y = k` and a stable pool using the
+/// `x³y + y³x = k` invariant for correlated assets. The swap fee is configured
+/// per pool in the factory.
+///
+/// [^1]: <https://github.com/aerodrome-finance/contracts>
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub address: eth::Address,
+    pub router: eth::ContractAddress,
+    /// The factory that deployed the pool, which the router needs for
+    /// computing the pool address.
+    pub factory: eth::ContractAddress,
+    pub reserves: Reserves,
+    pub fee: Fee,
+    pub stable: bool,
+}
+
+impl Pool {
+    /// Encodes a pool swap as an interaction. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens don't correspond to the pool's token pair.
+    ///
+    /// Note that swaps are routed through the router with exact input amounts,
+    /// so the whole input amount is sold for at least the output amount.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+        receiver: &eth::Address,
+    ) -> Result<eth::Interaction, liquidity::InvalidSwap> {
+        if !self.reserves.has_tokens(&input.0.token, &output.0.token) {
+            return Err(liquidity::InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::solidly::to_interaction(
+            self, input, output, receiver,
+        ))
+    }
+}
+
+/// The reserves of a Solidly pool. These reserves are ordered by token address
+/// and are guaranteed to be for distinct tokens.
+#[derive(Clone, Copy, Debug)]
+pub struct Reserves(Reserve, Reserve);
+
+impl Reserves {
+    /// Creates new Solidly token reserves, returns `Err` if the specified
+    /// token addresses are equal.
+    pub fn try_new(a: Reserve, b: Reserve) -> Result<Self, InvalidReserves> {
+        match a.asset.token.cmp(&b.asset.token) {
+            Ordering::Less => Ok(Self(a, b)),
+            Ordering::Equal => Err(InvalidReserves),
+            Ordering::Greater => Ok(Self(b, a)),
+        }
+    }
+
+    /// Returns `true` if the reserves correspond to the specified tokens.
+    fn has_tokens(&self, a: &eth::TokenAddress, b: &eth::TokenAddress) -> bool {
+        let (token0, token1) = (&self.0.asset.token, &self.1.asset.token);
+        (token0 == a && token1 == b) || (token1 == a && token0 == b)
+    }
+
+    /// Returns the reserves as a tuple ordered by token address.
+    pub fn get(&self) -> (Reserve, Reserve) {
+        (self.0, self.1)
+    }
+
+    /// Returns an iterator over the reserve tokens.
+    pub fn tokens(&self) -> impl Iterator<Item = eth::TokenAddress> + use<> {
+        [self.0.asset.token, self.1.asset.token].into_iter()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid Solidly token reserves; assets cannot have the same token address")]
+pub struct InvalidReserves;
+
+/// Solidly pool reserve for a single token.
+#[derive(Clone, Copy, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The token decimals, used by stable pools for normalizing the reserves
+    /// to 18 decimals.
+    pub decimals: u8,
+}
+
+/// A swap fee.
+///
+/// Internally, it is represented in basis points.
+#[derive(Clone, Copy, Debug)]
+pub struct Fee(u32);
+
+impl Fee {
+    /// Creates a new fee from the specified basis points. Returns `Err` for
+    /// fee values above the factory maximum of 3%.
+    pub fn try_new(bps: u32) -> Result<Self, InvalidFee> {
+        if bps > 300 {
+            return Err(InvalidFee);
+        }
+        Ok(Self(bps))
+    }
+
+    /// Returns the fee in basis points.
+    pub fn bps(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid Solidly fee outside of 0%-3% range")]
+pub struct InvalidFee;
@@ -0,0 +1,67 @@
+use {
+    super::v3::{Fee, Liquidity, LiquidityNet, SqrtPrice, Tick},
+    crate::{
+        boundary,
+        domain::{
+            eth,
+            liquidity::{self, InvalidSwap},
+        },
+    },
+    derive_more::Debug,
+    std::collections::BTreeMap,
+};
+
+/// A Uniswap V4 concentrated liquidity pool.
+///
+/// Uniswap V4 pools all live in a single `PoolManager` contract and behave like
+/// Uniswap V3 pools, as long as their hooks don't interfere with swaps. Only
+/// such pools are indexed, so their state is represented with the Uniswap V3
+/// types.
+///
+/// [^1]: <https://docs.uniswap.org/contracts/v4/overview>
+#[derive(Clone, Debug)]
+pub struct Pool {
+    /// The Universal Router used for swapping on the pool.
+    pub router: eth::ContractAddress,
+    pub pool_manager: eth::ContractAddress,
+    /// The ID of the pool within the pool manager, which is the hash of its
+    /// pool key.
+    pub id: eth::H256,
+    pub tokens: liquidity::TokenPair,
+    pub tick_spacing: i32,
+    pub hooks: eth::ContractAddress,
+    pub sqrt_price: SqrtPrice,
+    pub liquidity: Liquidity,
+    pub tick: Tick,
+    #[debug(ignore)]
+    pub liquidity_net: BTreeMap<Tick, LiquidityNet>,
+    pub fee: Fee,
+}
+
+impl Pool {
+    /// Encodes a pool swap as interactions. Returns `Err` if the swap
+    /// parameters are invalid for the pool, specifically if the input and
+    /// output tokens don't correspond to the pool's token pair.
+    ///
+    /// The swap is encoded as a transfer of the input tokens to the router
+    /// followed by an exact input swap through the router, so the whole input
+    /// amount is sold for at least the output amount. The router always sends
+    /// the output tokens to its caller, so they can only be received by the
+    /// settlement contract executing the interactions.
+    pub fn swap(
+        &self,
+        input: &liquidity::MaxInput,
+        output: &liquidity::ExactOutput,
+    ) -> Result<Vec<eth::Interaction>, InvalidSwap> {
+        let tokens_match = (input.0.token == self.tokens.0 && output.0.token == self.tokens.1)
+            || (input.0.token == self.tokens.1 && output.0.token == self.tokens.0);
+
+        if !tokens_match {
+            return Err(InvalidSwap);
+        }
+
+        Ok(boundary::liquidity::uniswap::v4::to_interactions(
+            self, input, output,
+        ))
+    }
+}
@@ -0,0 +1,224 @@
+//! Strategies for escalating the gas price of pending settlements. For every
+//! block a settlement does not get included in, the configured strategy
+//! decides the fees it gets resubmitted with at the same nonce.
+
+use {super::GAS_PRICE_BUMP, crate::domain::eth, std::fmt::Debug};
+
+/// The maximum increase of the base fee from one block to the next (12.5%)
+/// as defined by EIP-1559.
+const MAX_BASE_FEE_INCREASE: f64 = 1.125;
+
+/// A strategy deciding the gas price of a settlement's resubmissions.
+pub trait Strategy: Debug + Send + Sync {
+    /// The name of the strategy, used for metrics.
+    fn name(&self) -> &'static str;
+
+    /// Computes the gas price to resubmit the pending settlement with. The
+    /// settlement only gets resubmitted if the gas price is high enough to
+    /// replace the pending transaction, otherwise it stays pending.
+    fn gas_price(&self, pending: &Pending) -> eth::GasPrice;
+}
+
+/// A settlement that was submitted but did not get included yet.
+#[derive(Debug, Clone, Copy)]
+pub struct Pending {
+    /// The gas price the settlement was first submitted with.
+    pub initial: eth::GasPrice,
+    /// The gas price of the currently pending submission.
+    pub current: eth::GasPrice,
+    /// The number of blocks since the settlement was first submitted.
+    pub blocks_elapsed: u64,
+    /// The number of blocks left until the submission deadline.
+    pub blocks_remaining: u64,
+    /// The latest block.
+    pub block: Block,
+}
+
+/// The fee market state of a block.
+#[derive(Debug, Clone, Copy)]
+pub struct Block {
+    pub base_fee: eth::U256,
+    pub gas_used: eth::U256,
+    pub gas_limit: eth::U256,
+}
+
+impl Block {
+    /// Computes the base fee of the next block as defined by EIP-1559.
+    fn next_base_fee(&self) -> f64 {
+        let base_fee = self.base_fee.to_f64_lossy();
+        let target = self.gas_limit.to_f64_lossy() / 2.;
+        if target == 0. {
+            return base_fee;
+        }
+        let delta = (self.gas_used.to_f64_lossy() - target) / target;
+        base_fee * (1. + delta / 8.)
+    }
+}
+
+/// Increases the fees of the initial submission by a fixed fraction for every
+/// elapsed block.
+#[derive(Debug, Clone, Copy)]
+pub struct Linear {
+    pub step: f64,
+}
+
+impl Strategy for Linear {
+    fn name(&self) -> &'static str {
+        "Linear"
+    }
+
+    fn gas_price(&self, pending: &Pending) -> eth::GasPrice {
+        pending.initial * (1. + self.step * pending.blocks_elapsed as f64)
+    }
+}
+
+/// Multiplies the fees of the initial submission by a fixed factor for every
+/// elapsed block.
+#[derive(Debug, Clone, Copy)]
+pub struct Exponential {
+    pub factor: f64,
+}
+
+impl Strategy for Exponential {
+    fn name(&self) -> &'static str {
+        "Exponential"
+    }
+
+    fn gas_price(&self, pending: &Pending) -> eth::GasPrice {
+        pending.initial * self.factor.powf(pending.blocks_elapsed as f64)
+    }
+}
+
+/// Spreads the escalation of the fees over the blocks until the submission
+/// deadline, such that the fees of the initial submission are multiplied by
+/// `max_factor` in the last block before the deadline.
+#[derive(Debug, Clone, Copy)]
+pub struct DeadlineAware {
+    pub max_factor: f64,
+}
+
+impl Strategy for DeadlineAware {
+    fn name(&self) -> &'static str {
+        "DeadlineAware"
+    }
+
+    fn gas_price(&self, pending: &Pending) -> eth::GasPrice {
+        let elapsed = pending.blocks_elapsed as f64;
+        let total = elapsed + pending.blocks_remaining as f64;
+        let progress = if total == 0. { 1. } else { elapsed / total };
+        pending.initial * (1. + (self.max_factor - 1.) * progress)
+    }
+}
+
+/// Only escalates the fees once the predicted base fee could exceed the max
+/// fee of the pending submission before the submission deadline. The
+/// prediction assumes that the base fee keeps increasing at the maximal rate
+/// after the next block.
+#[derive(Debug, Clone, Copy)]
+pub struct BaseFeePrediction;
+
+impl Strategy for BaseFeePrediction {
+    fn name(&self) -> &'static str {
+        "BaseFeePrediction"
+    }
+
+    fn gas_price(&self, pending: &Pending) -> eth::GasPrice {
+        let next_base_fee = pending.block.next_base_fee();
+        let exponent = pending.blocks_remaining.saturating_sub(1) as f64;
+        let base_fee = next_base_fee * MAX_BASE_FEE_INCREASE.powf(exponent);
+        let required =
+            eth::U256::from_f64_lossy(base_fee.ceil()).saturating_add(pending.current.tip().into());
+        if required <= pending.current.max().into() {
+            return pending.current;
+        }
+        let bumped = pending.current * GAS_PRICE_BUMP;
+        eth::GasPrice::new(
+            std::cmp::max(required, bumped.max().into()).into(),
+            bumped.tip(),
+            eth::U256::from_f64_lossy(next_base_fee.ceil()).into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas_price(max: u64, tip: u64, base: u64) -> eth::GasPrice {
+        eth::GasPrice::new(
+            eth::U256::from(max).into(),
+            eth::U256::from(tip).into(),
+            eth::U256::from(base).into(),
+        )
+    }
+
+    fn pending(blocks_elapsed: u64, blocks_remaining: u64) -> Pending {
+        let initial = gas_price(100, 10, 50);
+        Pending {
+            initial,
+            current: initial,
+            blocks_elapsed,
+            blocks_remaining,
+            block: Block {
+                base_fee: 50.into(),
+                gas_used: 15_000_000.into(),
+                gas_limit: 30_000_000.into(),
+            },
+        }
+    }
+
+    fn fees(gas_price: eth::GasPrice) -> (eth::U256, eth::U256) {
+        (gas_price.max().into(), gas_price.tip().into())
+    }
+
+    #[test]
+    fn linear() {
+        let strategy = Linear { step: 0.5 };
+        assert_eq!(
+            fees(strategy.gas_price(&pending(2, 3))),
+            (200.into(), 20.into())
+        );
+    }
+
+    #[test]
+    fn exponential() {
+        let strategy = Exponential { factor: 2. };
+        assert_eq!(
+            fees(strategy.gas_price(&pending(3, 3))),
+            (800.into(), 80.into())
+        );
+    }
+
+    #[test]
+    fn deadline_aware() {
+        let strategy = DeadlineAware { max_factor: 3. };
+        assert_eq!(
+            fees(strategy.gas_price(&pending(1, 3))),
+            (150.into(), 15.into())
+        );
+        assert_eq!(
+            fees(strategy.gas_price(&pending(4, 0))),
+            (300.into(), 30.into())
+        );
+    }
+
+    #[test]
+    fn base_fee_prediction() {
+        let strategy = BaseFeePrediction;
+
+        // The base fee stays at 50 for a block at its gas target, so the max
+        // fee of 100 covers it for the remaining blocks.
+        assert_eq!(
+            fees(strategy.gas_price(&pending(1, 3))),
+            (100.into(), 10.into())
+        );
+
+        // A full block increases the next base fee to 56.25 and the max fee
+        // needs to cover it growing for 4 more blocks: 56.25 * 1.125^4 + 10,
+        // so 101. The fees get bumped enough to replace the pending
+        // transaction.
+        let mut full = pending(1, 5);
+        full.block.gas_used = 30_000_000.into();
+        assert_eq!(fees(strategy.gas_price(&full)), (113.into(), 12.into()));
+    }
+}
@@ -19,6 +19,8 @@ use {
     tracing::Instrument,
 };
 
+pub mod escalation;
+
 /// Factor by how much a transaction fee needs to be increased to override a
 /// pending transaction at the same nonce.
 const GAS_PRICE_BUMP: f64 = 1.125;
@@ -130,22 +132,25 @@ impl Mempools {
             }
         }
 
-        let hash = mempool.submit(tx.clone(), settlement.gas, solver).await?;
+        let submission = mempool.submit(tx.clone(), settlement.gas, solver).await?;
+        let hash = submission.hash;
+        // Bundles keep getting re-sent for as long as this is held, so it gets
+        // dropped as soon as the settlement was confirmed or cancelled.
+        let mut resender = submission.resender;
         let submitted_at_block = self.ethereum.current_block().borrow().number;
         tracing::debug!(?hash, current_block = ?submitted_at_block, "submitted tx to the mempool");
 
+        // Escalating the gas price replaces the pending transaction, but any of
+        // the submitted transactions might still end up getting included.
+        let mut hashes = vec![hash];
+        let mut gas_price = settlement.gas.price;
+        let mut resubmitted_at_block = submitted_at_block;
+
         // Wait for the transaction to be mined, expired or failing.
         let result = async {
             while let Some(block) = block_stream.next().await {
-                tracing::debug!(?hash, current_block = ?block.number, "checking if tx is confirmed");
-                let receipt = self
-                    .ethereum
-                    .transaction_status(&hash)
-                    .await
-                    .unwrap_or_else(|err| {
-                        tracing::warn!(?hash, ?err, "failed to get transaction status",);
-                        TxStatus::Pending
-                    });
+                tracing::debug!(hash = ?hashes.last(), current_block = ?block.number, "checking if tx is confirmed");
+                let (hash, receipt) = self.transaction_status(mempool, &hashes).await;
                 match receipt {
                     TxStatus::Executed { block_number } => return Ok(SubmissionSuccess {
                         tx_hash: hash.clone(),
@@ -164,8 +169,14 @@ impl Mempools {
 
                         // Check if the current block reached the submission deadline block number
                         if block.number >= submission_deadline {
+                            resender = None;
                             let cancellation_tx_hash = self
-                                .cancel(mempool, settlement.gas.price, solver, blocks_elapsed)
+                                .cancel(
+                                    mempool,
+                                    gas_price,
+                                    solver,
+                                    block.number.sub(resubmitted_at_block),
+                                )
                                 .await
                                 .context("cancellation tx due to deadline failed")?;
                             tracing::info!(
@@ -184,8 +195,14 @@ impl Mempools {
                         // Check if transaction still simulates
                         if let Err(err) = self.ethereum.estimate_gas(tx).await {
                             if err.is_revert() {
+                                resender = None;
                                 let cancellation_tx_hash = self
-                                    .cancel(mempool, settlement.gas.price, solver, blocks_elapsed)
+                                    .cancel(
+                                        mempool,
+                                        gas_price,
+                                        solver,
+                                        block.number.sub(resubmitted_at_block),
+                                    )
                                     .await
                                     .context("cancellation tx due to revert failed")?;
                                 tracing::info!(
@@ -202,6 +219,44 @@ impl Mempools {
                                 tracing::warn!(?hash, ?err, "couldn't re-simulate tx");
                             }
                         }
+                        // Resubmit the transaction with an escalated gas price
+                        let Some(strategy) = &mempool.config().escalation else {
+                            continue;
+                        };
+                        let pending = escalation::Pending {
+                            initial: settlement.gas.price,
+                            current: gas_price,
+                            blocks_elapsed,
+                            blocks_remaining: submission_deadline.saturating_sub(block.number),
+                            block: escalation::Block {
+                                base_fee: block.gas_price,
+                                gas_used: block.gas_used,
+                                gas_limit: block.gas_limit,
+                            },
+                        };
+                        let Some(escalated) = self.escalate(mempool, strategy.as_ref(), &pending)
+                        else {
+                            continue;
+                        };
+                        let gas = settlement::Gas {
+                            price: escalated,
+                            ..settlement.gas
+                        };
+                        let result = mempool.submit(tx.clone(), gas, solver).await;
+                        observe::gas_price_escalated(
+                            mempool,
+                            strategy.as_ref(),
+                            &pending,
+                            escalated,
+                            &result,
+                        );
+                        if let Ok(submission) = result {
+                            hashes.push(submission.hash);
+                            // Stop re-sending the replaced bundle.
+                            resender = submission.resender;
+                            gas_price = escalated;
+                            resubmitted_at_block = block.number;
+                        }
                     }
                 }
             }
@@ -210,12 +265,13 @@ impl Mempools {
             )))
         }
         .await;
+        drop(resender);
 
         if result.is_err() {
             // Do one last attempt to see if the transaction was confirmed (in case of race
             // conditions or misclassified errors like `OrderFilled` simulation failures).
-            if let Ok(TxStatus::Executed { block_number }) =
-                self.ethereum.transaction_status(&hash).await
+            if let (hash, TxStatus::Executed { block_number }) =
+                self.transaction_status(mempool, &hashes).await
             {
                 tracing::info!(
                     ?hash,
@@ -232,15 +288,73 @@ impl Mempools {
         result
     }
 
+    /// Returns the inclusion status of a settlement that was submitted as the
+    /// specified transactions. Since at most one of them can get included,
+    /// the status of the first transaction that is no longer pending is
+    /// returned. If all of them are pending, the last one is returned.
+    async fn transaction_status(
+        &self,
+        mempool: &infra::mempool::Mempool,
+        hashes: &[TxId],
+    ) -> (TxId, TxStatus) {
+        for hash in hashes.iter().rev() {
+            let status = mempool
+                .transaction_status(&self.ethereum, hash)
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::warn!(?hash, ?err, "failed to get transaction status",);
+                    TxStatus::Pending
+                });
+            if !matches!(status, TxStatus::Pending) {
+                return (hash.clone(), status);
+            }
+        }
+        let hash = hashes
+            .last()
+            .expect("at least one transaction was submitted");
+        (hash.clone(), TxStatus::Pending)
+    }
+
+    /// Computes the gas price to resubmit a pending settlement with according
+    /// to the escalation strategy. Returns `None` if the gas price is not high
+    /// enough to replace the pending transaction or exceeds the gas price cap.
+    fn escalate(
+        &self,
+        mempool: &infra::mempool::Mempool,
+        strategy: &dyn escalation::Strategy,
+        pending: &escalation::Pending,
+    ) -> Option<eth::GasPrice> {
+        let escalated = strategy.gas_price(pending);
+        let replacement = pending.current * GAS_PRICE_BUMP;
+        if escalated.max() < replacement.max() || escalated.tip() < replacement.tip() {
+            observe::gas_price_escalation_skipped(strategy, pending, escalated, false);
+            return None;
+        }
+        if eth::U256::from(escalated.max()) > mempool.config().gas_price_cap {
+            observe::gas_price_escalation_skipped(strategy, pending, escalated, true);
+            return None;
+        }
+        Some(escalated)
+    }
+
     /// Cancel a pending settlement by sending a transaction to self with a
-    /// slightly higher gas price than the existing one.
+    /// slightly higher gas price than the existing one. Unlike the settlement,
+    /// the cancellation keeps being re-sent until it got included or timed out.
+    ///
+    /// Bundles only target a few upcoming blocks and are cancelled by no
+    /// longer re-sending them, so no cancellation is submitted for them and
+    /// `None` is returned.
     async fn cancel(
         &self,
         mempool: &infra::mempool::Mempool,
         pending: eth::GasPrice,
         solver: &Solver,
         blocks_elapsed: u64,
-    ) -> Result<TxId, Error> {
+    ) -> Result<Option<TxId>, Error> {
+        if matches!(mempool.config().kind, infra::mempool::Kind::Bundle { .. }) {
+            return Ok(None);
+        }
+
         let cancellation = eth::Tx {
             from: solver.address(),
             to: solver.address(),
@@ -263,7 +377,11 @@ impl Mempools {
             "Cancelling transaction with adjusted gas price"
         );
 
-        mempool.submit(cancellation, gas, solver).await
+        let submission = mempool.submit(cancellation, gas, solver).await?;
+        if let Some(resender) = submission.resender {
+            resender.detach();
+        }
+        Ok(Some(submission.hash))
     }
 }
 
@@ -285,11 +285,11 @@ mod encode {
         };
 
         let encoded = match interaction {
-            solution::Interaction::Custom(interaction) => eth::Interaction {
+            solution::Interaction::Custom(interaction) => vec![eth::Interaction {
                 value: interaction.value,
                 target: interaction.target.0.into(),
                 call_data: interaction.call_data.clone(),
-            },
+            }],
             solution::Interaction::Liquidity(liquidity) => {
                 solution::encoding::liquidity_interaction(liquidity, &slippage, settlement)?
             }
@@ -314,7 +314,7 @@ mod encode {
                     solution::encoding::approve(&approval.max().0),
                 ]
             })
-            .chain(std::iter::once(encoded))
+            .chain(encoded)
             .collect())
     }
 }
@@ -15,6 +15,7 @@ use {
         GasPriceEstimating,
         nativegasestimator::{NativeGasEstimator, Params},
     },
+    shared::gas_price_estimation::fee_history::{self, FeeHistoryGasEstimator},
     std::{sync::Arc, time::Duration},
 };
 
@@ -54,6 +55,18 @@ impl GasPriceEstimator {
                 .await
                 .map_err(Error::GasPrice)?,
             ),
+            GasEstimatorType::FeeHistory {
+                block_count,
+                reward_percentile,
+                blocks_ahead,
+            } => Arc::new(FeeHistoryGasEstimator::new(
+                web3.clone(),
+                fee_history::Params {
+                    block_count: *block_count,
+                    reward_percentile: *reward_percentile,
+                    blocks_ahead: *blocks_ahead,
+                },
+            )),
             GasEstimatorType::Web3 => Arc::new(web3.clone()),
         };
         let additional_tip = mempools
@@ -64,6 +77,16 @@ impl GasPriceEstimator {
                     additional_tip_percentage,
                     ..
                 } => (max_additional_tip, additional_tip_percentage),
+                mempool::Kind::Bundle {
+                    max_additional_tip,
+                    additional_tip_percentage,
+                    ..
+                } => (max_additional_tip, additional_tip_percentage),
+                mempool::Kind::UserOperation {
+                    max_additional_tip,
+                    additional_tip_percentage,
+                    ..
+                } => (max_additional_tip, additional_tip_percentage),
                 mempool::Kind::Public {
                     max_additional_tip,
                     additional_tip_percentage,
@@ -21,12 +21,14 @@ pub struct Rpc {
     web3: DynWeb3,
     chain: Chain,
     url: Url,
+    ws_url: Option<Url>,
 }
 
 impl Rpc {
     /// Instantiate an RPC client to an Ethereum (or Ethereum-compatible) node
-    /// at the specifed URL.
-    pub async fn try_new(url: &url::Url) -> Result<Self, RpcError> {
+    /// at the specifed URL. If a WebSocket URL is specified, it is used for
+    /// subscribing to new blocks.
+    pub async fn try_new(url: &url::Url, ws_url: Option<&url::Url>) -> Result<Self, RpcError> {
         let web3 = boundary::buffered_web3_client(url);
         let chain = Chain::try_from(web3.eth().chain_id().await?)?;
 
@@ -34,6 +36,7 @@ impl Rpc {
             web3,
             chain,
             url: url.clone(),
+            ws_url: ws_url.cloned(),
         })
     }
 
@@ -83,12 +86,21 @@ impl Ethereum {
         gas: Arc<GasPriceEstimator>,
         archive_node_url: Option<&Url>,
     ) -> Self {
-        let Rpc { web3, chain, url } = rpc;
-
-        let current_block_stream =
-            ethrpc::block_stream::current_block_stream(url, std::time::Duration::from_millis(500))
-                .await
-                .expect("couldn't initialize current block stream");
+        let Rpc {
+            web3,
+            chain,
+            url,
+            ws_url,
+        } = rpc;
+
+        let poll_interval = std::time::Duration::from_millis(500);
+        let current_block_stream = match ws_url {
+            Some(ws_url) => {
+                ethrpc::block_stream::current_block_ws_stream(url, ws_url, poll_interval).await
+            }
+            None => ethrpc::block_stream::current_block_stream(url, poll_interval).await,
+        }
+        .expect("couldn't initialize current block stream");
 
         let contracts = Contracts::new(
             &web3,
@@ -21,6 +21,11 @@ pub struct Args {
     #[clap(long, env)]
     pub ethrpc: Url,
 
+    /// The node WebSocket RPC API endpoint. If specified, new blocks are
+    /// observed with a `newHeads` subscription instead of polling.
+    #[clap(long, env)]
+    pub ethrpc_ws: Option<Url>,
+
     /// Path to the driver configuration file. This file should be in TOML
     /// format. For an example see
     /// https://github.com/cowprotocol/services/blob/main/crates/driver/example.toml.
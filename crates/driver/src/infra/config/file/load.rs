@@ -1,6 +1,6 @@
 use {
     crate::{
-        domain::{competition::bad_tokens, eth},
+        domain::{competition::bad_tokens, eth, mempools::escalation},
         infra::{
             self,
             blockchain,
@@ -14,7 +14,7 @@ use {
     chain::Chain,
     futures::future::join_all,
     number::conversions::big_decimal_to_big_rational,
-    std::path::Path,
+    std::{path::Path, sync::Arc},
     tokio::fs,
 };
 
@@ -48,8 +48,29 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
         chain,
         "The configured chain ID does not match the connected Ethereum node"
     );
+
+    // Smart accounts can't sign transactions, so their settlements can only be
+    // submitted as user operations, which in turn require a smart account.
+    for solver in &config.solvers {
+        let smart_account = matches!(solver.account, file::Account::SmartAccount { .. });
+        for mempool in &config.submission.mempools {
+            let user_operation = matches!(mempool, file::Mempool::UserOperation { .. });
+            assert!(
+                !smart_account || user_operation,
+                "smart account solver {} can only submit settlements to user-operation mempools",
+                solver.name,
+            );
+            assert!(
+                smart_account || !user_operation,
+                "user-operation mempools require smart account solvers, but {} is not one",
+                solver.name,
+            );
+        }
+    }
+
     infra::Config {
         solvers: join_all(config.solvers.into_iter().map(|solver_config| async move {
+            let mut smart_account = None;
             let account = match solver_config.account {
                 file::Account::PrivateKey(private_key) => ethcontract::Account::Offline(
                     ethcontract::PrivateKey::from_raw(private_key.0).unwrap(),
@@ -64,6 +85,17 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     ethcontract::Account::Kms(account, None)
                 }
                 file::Account::Address(address) => ethcontract::Account::Local(address, None),
+                file::Account::SmartAccount {
+                    address,
+                    session_key,
+                } => {
+                    smart_account = Some(solver::SmartAccount {
+                        address: address.into(),
+                        session_key: ethcontract::PrivateKey::from_raw(session_key.0)
+                            .expect("invalid smart account session key"),
+                    });
+                    ethcontract::Account::Local(address, None)
+                }
             };
             solver::Config {
                 endpoint: solver_config.endpoint,
@@ -78,6 +110,7 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     solver::Liquidity::Fetch
                 },
                 account,
+                smart_account,
                 timeouts: solver::Timeouts {
                     http_delay: chrono::Duration::from_std(solver_config.timeouts.http_time_buffer)
                         .unwrap(),
@@ -237,6 +270,29 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     },
                 })
                 .collect(),
+            uniswap_v4: config
+                .liquidity
+                .uniswap_v4
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::UniswapV4Config::Preset { preset } => match preset {
+                        file::UniswapV4Preset::UniswapV4 => {
+                            liquidity::config::UniswapV4::uniswap_v4(chain)
+                        }
+                    }
+                    .expect("no Uniswap V4 preset for current network"),
+                    file::UniswapV4Config::Manual {
+                        pool_manager,
+                        router,
+                        deployment_block,
+                    } => liquidity::config::UniswapV4 {
+                        pool_manager: pool_manager.into(),
+                        router: router.into(),
+                        deployment_block,
+                    },
+                })
+                .collect(),
             balancer_v2: config
                 .liquidity
                 .balancer_v2
@@ -289,6 +345,72 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     },
                 })
                 .collect(),
+            balancer_v3: config
+                .liquidity
+                .balancer_v3
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::BalancerV3Config::Preset { preset } => match preset {
+                        file::BalancerV3Preset::BalancerV3 => {
+                            liquidity::config::BalancerV3::balancer_v3(chain)
+                        }
+                    }
+                    .expect("no Balancer V3 preset for current network"),
+                    file::BalancerV3Config::Manual {
+                        vault,
+                        batch_router,
+                        permit2,
+                        deployment_block,
+                    } => liquidity::config::BalancerV3 {
+                        vault: vault.into(),
+                        batch_router: batch_router.into(),
+                        permit2: permit2.into(),
+                        deployment_block,
+                    },
+                })
+                .collect(),
+            curve: config
+                .liquidity
+                .curve
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::CurveConfig::Preset { preset } => match preset {
+                        file::CurvePreset::Curve => liquidity::config::Curve::curve(chain),
+                    }
+                    .expect("no Curve preset for current network"),
+                    file::CurveConfig::Manual { registry } => liquidity::config::Curve {
+                        registry: registry.into(),
+                    },
+                })
+                .collect(),
+            solidly: config
+                .liquidity
+                .solidly
+                .iter()
+                .cloned()
+                .map(|config| match config {
+                    file::SolidlyConfig::Preset { preset } => match preset {
+                        file::SolidlyPreset::Aerodrome => {
+                            liquidity::config::Solidly::aerodrome(chain)
+                        }
+                        file::SolidlyPreset::Velodrome => {
+                            liquidity::config::Solidly::velodrome(chain)
+                        }
+                    }
+                    .expect("no Solidly preset for current network"),
+                    file::SolidlyConfig::Manual { router } => liquidity::config::Solidly {
+                        router: router.into(),
+                    },
+                })
+                .collect(),
+            erc4626: config
+                .liquidity
+                .erc4626
+                .map(|config| liquidity::config::Erc4626 {
+                    vaults: config.vaults.into_iter().map(Into::into).collect(),
+                }),
             zeroex: config
                 .liquidity
                 .zeroex
@@ -307,6 +429,24 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                 gas_price_cap: config.submission.gas_price_cap,
                 target_confirm_time: config.submission.target_confirm_time,
                 retry_interval: config.submission.retry_interval,
+                escalation: config.submission.gas_price_escalation.map(
+                    |escalation| -> Arc<dyn escalation::Strategy> {
+                        match escalation {
+                            file::GasPriceEscalation::Linear { step } => {
+                                Arc::new(escalation::Linear { step })
+                            }
+                            file::GasPriceEscalation::Exponential { factor } => {
+                                Arc::new(escalation::Exponential { factor })
+                            }
+                            file::GasPriceEscalation::DeadlineAware { max_factor } => {
+                                Arc::new(escalation::DeadlineAware { max_factor })
+                            }
+                            file::GasPriceEscalation::BaseFeePrediction => {
+                                Arc::new(escalation::BaseFeePrediction)
+                            }
+                        }
+                    },
+                ),
                 kind: match mempool {
                     file::Mempool::Public {
                         max_additional_tip,
@@ -315,12 +455,12 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         // If there is no private mempool, revert protection is
                         // disabled, otherwise driver would not even try to settle revertable
                         // settlements
-                        let revert_protection = if config
-                            .submission
-                            .mempools
-                            .iter()
-                            .any(|pool| matches!(pool, file::Mempool::MevBlocker { .. }))
-                        {
+                        let revert_protection = if config.submission.mempools.iter().any(|pool| {
+                            matches!(
+                                pool,
+                                file::Mempool::MevBlocker { .. } | file::Mempool::Bundle { .. }
+                            )
+                        }) {
                             mempool::RevertProtection::Enabled
                         } else {
                             mempool::RevertProtection::Disabled
@@ -343,11 +483,36 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                         additional_tip_percentage: *additional_tip_percentage,
                         use_soft_cancellations: *use_soft_cancellations,
                     },
+                    file::Mempool::Bundle {
+                        relays,
+                        auth_key,
+                        target_blocks,
+                        max_additional_tip,
+                        additional_tip_percentage,
+                    } => mempool::Kind::Bundle {
+                        relays: relays.to_owned(),
+                        auth_key: ethcontract::PrivateKey::from_raw(auth_key.0)
+                            .expect("invalid bundle auth key"),
+                        target_blocks: *target_blocks,
+                        max_additional_tip: *max_additional_tip,
+                        additional_tip_percentage: *additional_tip_percentage,
+                    },
+                    file::Mempool::UserOperation {
+                        bundler,
+                        entry_point,
+                        max_additional_tip,
+                        additional_tip_percentage,
+                    } => mempool::Kind::UserOperation {
+                        bundler: bundler.to_owned(),
+                        entry_point: (*entry_point).into(),
+                        max_additional_tip: *max_additional_tip,
+                        additional_tip_percentage: *additional_tip_percentage,
+                    },
                 },
             })
             .collect(),
-        simulator: match (config.tenderly, config.enso) {
-            (Some(config), None) => {
+        simulator: match (config.tenderly, config.enso, config.revm) {
+            (Some(config), None, None) => {
                 Some(simulator::Config::Tenderly(simulator::tenderly::Config {
                     url: config.url,
                     api_key: config.api_key,
@@ -357,12 +522,13 @@ pub async fn load(chain: Chain, path: &Path) -> infra::Config {
                     save_if_fails: config.save_if_fails,
                 }))
             }
-            (None, Some(config)) => Some(simulator::Config::Enso(simulator::enso::Config {
+            (None, Some(config), None) => Some(simulator::Config::Enso(simulator::enso::Config {
                 url: config.url,
                 network_block_interval: config.network_block_interval,
             })),
-            (None, None) => None,
-            (Some(_), Some(_)) => panic!("Cannot configure both Tenderly and Enso"),
+            (None, None, Some(_)) => Some(simulator::Config::Revm),
+            (None, None, None) => None,
+            _ => panic!("Cannot configure more than one of Tenderly, Enso and revm"),
         },
         contracts: blockchain::contracts::Addresses {
             settlement: config.contracts.gp_v2_settlement.map(Into::into),
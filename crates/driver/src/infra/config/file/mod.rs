@@ -4,6 +4,7 @@ use {
     reqwest::Url,
     serde::{Deserialize, Deserializer, Serialize},
     serde_with::serde_as,
+    shared::gas_price_estimation::fee_history,
     solver::solver::Arn,
     std::{collections::HashMap, time::Duration},
 };
@@ -49,6 +50,9 @@ struct Config {
     /// Use Enso for transaction simulation.
     enso: Option<EnsoConfig>,
 
+    /// Use an in-process EVM for transaction simulation.
+    revm: Option<RevmConfig>,
+
     #[serde(rename = "solver")]
     solvers: Vec<SolverConfig>,
 
@@ -109,12 +113,42 @@ struct SubmissionConfig {
     #[serde(with = "humantime_serde", default = "default_retry_interval")]
     retry_interval: Duration,
 
+    /// The strategy for escalating the gas price of pending settlements. If
+    /// not specified, settlements are not resubmitted while pending.
+    #[serde(default)]
+    gas_price_escalation: Option<GasPriceEscalation>,
+
     /// The mempools to submit settlement transactions to. Can be the public
-    /// mempool of a node or the private MEVBlocker mempool.
+    /// mempool of a node, the private MEVBlocker mempool or block builder
+    /// relays accepting bundles.
     #[serde(rename = "mempool", default)]
     mempools: Vec<Mempool>,
 }
 
+/// Strategies for escalating the gas price of pending settlements. Every block
+/// a settlement does not get included in, it gets resubmitted with the gas
+/// price computed by the strategy if it is high enough to replace the pending
+/// transaction (i.e. at least 12.5% higher).
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "strategy", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum GasPriceEscalation {
+    /// Increases the fees of the initial submission by `step` times the fees
+    /// for every elapsed block.
+    Linear { step: f64 },
+    /// Multiplies the fees of the initial submission by `factor` for every
+    /// elapsed block.
+    Exponential { factor: f64 },
+    /// Increases the fees of the initial submission gradually such that they
+    /// are multiplied by `max-factor` in the last block before the
+    /// submission deadline.
+    #[serde(rename_all = "kebab-case")]
+    DeadlineAware { max_factor: f64 },
+    /// Only increases the fees once the base fee, predicted to rise at the
+    /// maximal rate until the submission deadline, could exceed the max fee
+    /// of the pending transaction.
+    BaseFeePrediction,
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize)]
 #[serde(tag = "mempool")]
@@ -155,6 +189,52 @@ enum Mempool {
         #[serde(default = "default_soft_cancellations_flag")]
         use_soft_cancellations: bool,
     },
+    #[serde(rename_all = "kebab-case")]
+    Bundle {
+        /// The URLs of the block builder relays to send bundles to with
+        /// `eth_sendBundle`.
+        relays: Vec<Url>,
+        /// The private key used for signing the bundle requests (in the
+        /// `X-Flashbots-Signature` header). Relays use it to authenticate the
+        /// sender and build up its reputation, so it does not need to hold any
+        /// funds and should be different from the solver account.
+        auth_key: eth::H256,
+        /// The number of upcoming blocks each bundle targets. Bundles are
+        /// re-sent for every new block until the target confirmation time
+        /// elapses.
+        #[serde(default = "default_bundle_target_blocks")]
+        target_blocks: u64,
+        /// Maximum additional tip in Gwei that we are willing to give to
+        /// builders above regular gas price estimation.
+        #[serde(default = "default_max_additional_tip")]
+        #[serde_as(as = "serialize::U256")]
+        max_additional_tip: eth::U256,
+        /// Additional tip in percentage of max_fee_per_gas we are giving to
+        /// builders above regular gas price estimation. Expects a
+        /// floating point value between 0 and 1.
+        #[serde(default = "default_additional_tip_percentage")]
+        additional_tip_percentage: f64,
+    },
+    #[serde(rename_all = "kebab-case")]
+    UserOperation {
+        /// The URL of the ERC-4337 bundler to send user operations to with
+        /// `eth_sendUserOperation`.
+        bundler: Url,
+        /// The address of the `EntryPoint` contract. Defaults to the canonical
+        /// v0.6 deployment.
+        #[serde(default = "default_entry_point")]
+        entry_point: eth::H160,
+        /// Maximum additional tip in Gwei that we are willing to give to
+        /// the bundler above regular gas price estimation.
+        #[serde(default = "default_max_additional_tip")]
+        #[serde_as(as = "serialize::U256")]
+        max_additional_tip: eth::U256,
+        /// Additional tip in percentage of max_fee_per_gas we are giving to
+        /// the bundler above regular gas price estimation. Expects a
+        /// floating point value between 0 and 1.
+        #[serde(default = "default_additional_tip_percentage")]
+        additional_tip_percentage: f64,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -210,6 +290,17 @@ fn default_soft_cancellations_flag() -> bool {
     false
 }
 
+fn default_bundle_target_blocks() -> u64 {
+    1
+}
+
+/// The canonical ERC-4337 v0.6 `EntryPoint` deployment.
+fn default_entry_point() -> eth::H160 {
+    eth::H160(hex_literal::hex!(
+        "5FF137D4b0FDCD49DcA30c7CF57E578a026d2789"
+    ))
+}
+
 pub fn default_http_time_buffer() -> Duration {
     Duration::from_millis(500)
 }
@@ -323,6 +414,16 @@ enum Account {
     /// connected node's account management features. This can also be used to
     /// start the driver in a dry-run mode.
     Address(eth::H160),
+    /// A smart account settles on behalf of the solver. Settlements are
+    /// submitted as ERC-4337 user operations signed with the session key, so
+    /// this requires a `user-operation` mempool.
+    #[serde(rename_all = "kebab-case")]
+    SmartAccount {
+        /// The address of the smart account.
+        address: eth::H160,
+        /// The private key signing the user operations.
+        session_key: eth::H256,
+    },
 }
 
 #[serde_as]
@@ -442,6 +543,12 @@ struct EnsoConfig {
     network_block_interval: Option<Duration>,
 }
 
+/// The in-process EVM fetches the state it needs from the node, so it doesn't
+/// need any configuration yet.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RevmConfig {}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct LiquidityConfig {
@@ -462,10 +569,31 @@ struct LiquidityConfig {
     #[serde(default)]
     uniswap_v3: Vec<UniswapV3Config>,
 
+    /// Liquidity provided by Uniswap V4 pools.
+    #[serde(default)]
+    uniswap_v4: Vec<UniswapV4Config>,
+
     /// Liquidity provided by a Balancer V2 compatible contract.
     #[serde(default)]
     balancer_v2: Vec<BalancerV2Config>,
 
+    /// Liquidity provided by Balancer V3 pools.
+    #[serde(default)]
+    balancer_v3: Vec<BalancerV3Config>,
+
+    /// Liquidity provided by Curve pools.
+    #[serde(default)]
+    curve: Vec<CurveConfig>,
+
+    /// Liquidity provided by Solidly compatible pools.
+    #[serde(default)]
+    solidly: Vec<SolidlyConfig>,
+
+    /// Liquidity provided by converting between ERC-4626 vault assets and
+    /// shares.
+    #[serde(default)]
+    erc4626: Option<Erc4626Config>,
+
     /// Liquidity provided by 0x API.
     #[serde(default)]
     zeroex: Option<ZeroExConfig>,
@@ -572,6 +700,31 @@ mod uniswap_v3 {
     }
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum UniswapV4Config {
+    #[serde(rename_all = "kebab-case")]
+    Preset { preset: UniswapV4Preset },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the Uniswap V4 pool manager contract.
+        pool_manager: eth::H160,
+
+        /// The address of the Universal Router used for swapping.
+        router: eth::H160,
+
+        /// The block at which the pool manager was deployed.
+        deployment_block: u64,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum UniswapV4Preset {
+    UniswapV4,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
 enum BalancerV2Config {
@@ -630,6 +783,80 @@ enum BalancerV2Preset {
     BalancerV2,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum BalancerV3Config {
+    #[serde(rename_all = "kebab-case")]
+    Preset { preset: BalancerV3Preset },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the Balancer V3 vault contract.
+        vault: eth::H160,
+
+        /// The address of the batch router used for swapping.
+        batch_router: eth::H160,
+
+        /// The address of the Permit2 contract.
+        permit2: eth::H160,
+
+        /// The block at which the vault was deployed.
+        deployment_block: u64,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum BalancerV3Preset {
+    BalancerV3,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum CurveConfig {
+    #[serde(rename_all = "kebab-case")]
+    Preset { preset: CurvePreset },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the Curve meta registry used for discovering pools.
+        registry: eth::H160,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum CurvePreset {
+    Curve,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum SolidlyConfig {
+    #[serde(rename_all = "kebab-case")]
+    Preset { preset: SolidlyPreset },
+
+    #[serde(rename_all = "kebab-case")]
+    Manual {
+        /// The address of the Solidly router contract.
+        router: eth::H160,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+enum SolidlyPreset {
+    Aerodrome,
+    Velodrome,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Erc4626Config {
+    /// The addresses of the vaults to use.
+    vaults: Vec<eth::H160>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct ZeroExConfig {
@@ -675,6 +902,21 @@ pub enum GasEstimatorType {
         #[serde(default = "default_max_block_percentile")]
         max_block_percentile: f64,
     },
+    /// Derives the gas price from the fee market of recent blocks as reported
+    /// by `eth_feeHistory`, predicting the base fee a few blocks ahead.
+    #[serde(rename_all = "kebab-case")]
+    FeeHistory {
+        /// The number of recent blocks the estimate is derived from.
+        #[serde(default = "default_fee_history_block_count")]
+        block_count: u64,
+        /// The percentile of the priority fees paid in each block that is
+        /// considered to be that block's priority fee.
+        #[serde(default = "default_fee_history_reward_percentile")]
+        reward_percentile: f64,
+        /// The number of blocks ahead the base fee is predicted for.
+        #[serde(default = "default_fee_history_blocks_ahead")]
+        blocks_ahead: u64,
+    },
     Web3,
 }
 
@@ -700,6 +942,18 @@ fn default_max_block_percentile() -> f64 {
     60.
 }
 
+fn default_fee_history_block_count() -> u64 {
+    fee_history::Params::default().block_count
+}
+
+fn default_fee_history_reward_percentile() -> f64 {
+    fee_history::Params::default().reward_percentile
+}
+
+fn default_fee_history_blocks_ahead() -> u64 {
+    fee_history::Params::default().blocks_ahead
+}
+
 /// Defines various strategies to prioritize orders.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case", tag = "strategy")]
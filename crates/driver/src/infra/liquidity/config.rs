@@ -26,10 +26,25 @@ pub struct Config {
     /// for.
     pub uniswap_v3: Vec<UniswapV3>,
 
+    /// The collection of Uniswap V4 pool managers to fetch liquidity for.
+    pub uniswap_v4: Vec<UniswapV4>,
+
     /// The collection of Balancer V2 compatible exchanges to fetch liquidity
     /// for.
     pub balancer_v2: Vec<BalancerV2>,
 
+    /// The collection of Balancer V3 vaults to fetch liquidity for.
+    pub balancer_v3: Vec<BalancerV3>,
+
+    /// The collection of Curve registries to fetch liquidity for.
+    pub curve: Vec<Curve>,
+
+    /// The collection of Solidly compatible exchanges to fetch liquidity for.
+    pub solidly: Vec<Solidly>,
+
+    /// ERC-4626 vaults to convert between assets and shares with.
+    pub erc4626: Option<Erc4626>,
+
     /// 0x liquidity fetcher.
     pub zeroex: Option<ZeroEx>,
 }
@@ -162,6 +177,33 @@ impl UniswapV3 {
     }
 }
 
+/// Uniswap V4 liquidity fetching options.
+#[derive(Clone, Copy, Debug)]
+pub struct UniswapV4 {
+    /// The address of the singleton pool manager contract holding all pools.
+    pub pool_manager: eth::ContractAddress,
+
+    /// The address of the Universal Router used for swapping.
+    pub router: eth::ContractAddress,
+
+    /// The block at which the pool manager was deployed, indexing of pool
+    /// events starts from there.
+    pub deployment_block: u64,
+}
+
+impl UniswapV4 {
+    /// Returns the liquidity configuration for Uniswap V4.
+    #[allow(clippy::self_named_constructors)]
+    pub fn uniswap_v4(chain: Chain) -> Option<Self> {
+        let pool_manager = contracts::UniswapV4PoolManager::raw_contract();
+        Some(Self {
+            pool_manager: deployment_address(pool_manager, chain)?,
+            router: deployment_address(contracts::UniswapV4UniversalRouter::raw_contract(), chain)?,
+            deployment_block: contracts::deployment_block(pool_manager, chain.id()).ok()?,
+        })
+    }
+}
+
 /// Balancer V2 liquidity fetching options.
 #[derive(Clone, Debug)]
 pub struct BalancerV2 {
@@ -235,6 +277,89 @@ impl BalancerV2 {
     }
 }
 
+/// Balancer V3 liquidity fetching options.
+#[derive(Clone, Copy, Debug)]
+pub struct BalancerV3 {
+    /// The address of the vault contract holding all pools.
+    pub vault: eth::ContractAddress,
+
+    /// The address of the batch router used for swapping.
+    pub batch_router: eth::ContractAddress,
+
+    /// The address of the Permit2 contract the batch router pulls tokens
+    /// with.
+    pub permit2: eth::ContractAddress,
+
+    /// The block at which the vault was deployed, indexing of pool
+    /// registration events starts from there.
+    pub deployment_block: u64,
+}
+
+impl BalancerV3 {
+    /// Returns the liquidity configuration for Balancer V3.
+    #[allow(clippy::self_named_constructors)]
+    pub fn balancer_v3(chain: Chain) -> Option<Self> {
+        let vault = contracts::BalancerV3Vault::raw_contract();
+        Some(Self {
+            vault: deployment_address(vault, chain)?,
+            batch_router: deployment_address(
+                contracts::BalancerV3BatchRouter::raw_contract(),
+                chain,
+            )?,
+            permit2: deployment_address(contracts::Permit2::raw_contract(), chain)?,
+            deployment_block: contracts::deployment_block(vault, chain.id()).ok()?,
+        })
+    }
+}
+
+/// Curve liquidity fetching options.
+#[derive(Clone, Copy, Debug)]
+pub struct Curve {
+    /// The address of the Curve meta registry used for discovering pools.
+    pub registry: eth::ContractAddress,
+}
+
+impl Curve {
+    /// Returns the liquidity configuration for Curve.
+    #[allow(clippy::self_named_constructors)]
+    pub fn curve(chain: Chain) -> Option<Self> {
+        Some(Self {
+            registry: deployment_address(contracts::CurveRegistry::raw_contract(), chain)?,
+        })
+    }
+}
+
+/// Solidly (Velodrome V2 and Aerodrome) liquidity fetching options.
+#[derive(Clone, Copy, Debug)]
+pub struct Solidly {
+    /// The address of the Solidly router. The pools of its default factory
+    /// are used.
+    pub router: eth::ContractAddress,
+}
+
+impl Solidly {
+    /// Returns the liquidity configuration for Aerodrome.
+    pub fn aerodrome(chain: Chain) -> Option<Self> {
+        Some(Self {
+            router: deployment_address(contracts::AerodromeRouter::raw_contract(), chain)?,
+        })
+    }
+
+    /// Returns the liquidity configuration for Velodrome.
+    pub fn velodrome(chain: Chain) -> Option<Self> {
+        Some(Self {
+            router: deployment_address(contracts::VelodromeRouter::raw_contract(), chain)?,
+        })
+    }
+}
+
+/// ERC-4626 vault liquidity options.
+#[derive(Clone, Debug)]
+pub struct Erc4626 {
+    /// The addresses of the vaults to use.
+    pub vaults: Vec<eth::ContractAddress>,
+}
+
 /// ZeroEx liquidity fetching options.
 #[derive(Clone, Debug)]
 pub struct ZeroEx {
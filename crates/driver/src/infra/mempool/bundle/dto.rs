@@ -0,0 +1,50 @@
+//! Data transfer objects for the `eth_sendBundle` JSON-RPC method of block
+//! builder relays.
+
+use {
+    crate::util::serialize,
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+};
+
+#[derive(Debug, Serialize)]
+pub struct Request {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: [Bundle; 1],
+}
+
+impl Request {
+    pub fn new(txs: Vec<Vec<u8>>, block_number: u64) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: 1,
+            method: "eth_sendBundle",
+            params: [Bundle {
+                txs,
+                block_number: block_number.into(),
+            }],
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bundle {
+    #[serde_as(as = "Vec<serialize::Hex>")]
+    txs: Vec<Vec<u8>>,
+    block_number: web3::types::U64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Response {
+    pub error: Option<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Error {
+    pub code: i64,
+    pub message: String,
+}
@@ -0,0 +1,111 @@
+//! Submission of settlements to block builders as bundles via the
+//! `eth_sendBundle` JSON-RPC method pioneered by Flashbots.
+
+use {
+    crate::domain::eth,
+    futures::future::join_all,
+    std::ops::RangeInclusive,
+    thiserror::Error,
+    web3::signing::{self, Key, SecretKeyRef},
+};
+
+mod dto;
+
+/// The header relays use to authenticate the sender of a bundle.
+const SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+/// The block builder relays bundles get sent to.
+#[derive(Debug, Clone)]
+pub struct Relays {
+    client: reqwest::Client,
+    urls: Vec<reqwest::Url>,
+    auth_key: ethcontract::PrivateKey,
+}
+
+impl Relays {
+    pub fn new(urls: Vec<reqwest::Url>, auth_key: ethcontract::PrivateKey) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            urls,
+            auth_key,
+        }
+    }
+
+    /// Sends a bundle consisting of the signed transaction to all relays,
+    /// targeting each of the specified blocks. Succeeds if at least one relay
+    /// accepted the bundle.
+    pub async fn send(&self, tx: &[u8], blocks: RangeInclusive<u64>) -> Result<(), Error> {
+        let requests = self
+            .urls
+            .iter()
+            .flat_map(|url| blocks.clone().map(move |block| (url, block)));
+        let results = join_all(requests.map(|(url, block)| async move {
+            let result = self.send_to(url, tx, block).await;
+            if let Err(err) = &result {
+                tracing::warn!(%url, %block, ?err, "relay did not accept bundle");
+            }
+            result
+        }))
+        .await;
+
+        if results.iter().any(Result::is_ok) {
+            Ok(())
+        } else {
+            Err(Error::NotAccepted)
+        }
+    }
+
+    async fn send_to(&self, url: &reqwest::Url, tx: &[u8], block: u64) -> Result<(), Error> {
+        let body = serde_json::to_vec(&dto::Request::new(vec![tx.to_vec()], block))?;
+        let response: dto::Response = self
+            .client
+            .post(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, self.sign(&body))
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        match response.error {
+            Some(err) => Err(Error::Rpc {
+                code: err.code,
+                message: err.message,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Computes the value of the [`SIGNATURE_HEADER`] for the request body:
+    /// the address of the auth key followed by an EIP-191 signature of the
+    /// hex encoded hash of the body.
+    fn sign(&self, body: &[u8]) -> String {
+        let message = format!("{:?}", eth::H256(signing::keccak256(body)));
+        let hash = signing::hash_message(message.as_bytes());
+        // Unwrap because the only error is for invalid messages which we don't create.
+        let signature = SecretKeyRef::new(&self.auth_key)
+            .sign(hash.as_bytes(), None)
+            .unwrap();
+        format!(
+            "{:?}:0x{}{}{:02x}",
+            self.auth_key.public_address(),
+            hex::encode(signature.r),
+            hex::encode(signature.s),
+            signature.v,
+        )
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to serialize bundle: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("relay request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("relay rejected bundle with error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("no relay accepted the bundle")]
+    NotAccepted,
+}
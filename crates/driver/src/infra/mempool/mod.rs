@@ -1,18 +1,34 @@
 use {
     crate::{
         boundary::unbuffered_web3_client,
-        domain::{competition, eth, mempools},
+        domain::{
+            competition,
+            eth,
+            mempools::{self, escalation},
+        },
         infra,
     },
-    ethcontract::dyns::DynWeb3,
+    anyhow::anyhow,
+    ethcontract::{
+        dyns::{DynTransport, DynWeb3},
+        transaction::{Transaction, TransactionBuilder},
+    },
+    std::{sync::Arc, time::Instant},
+    tracing::Instrument,
 };
 
+pub mod bundle;
+pub mod user_operation;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub min_priority_fee: eth::U256,
     pub gas_price_cap: eth::U256,
     pub target_confirm_time: std::time::Duration,
     pub retry_interval: std::time::Duration,
+    /// Decides the gas price for resubmitting pending settlements, if they
+    /// should be resubmitted.
+    pub escalation: Option<Arc<dyn escalation::Strategy>>,
     pub kind: Kind,
 }
 
@@ -31,6 +47,25 @@ pub enum Kind {
         additional_tip_percentage: f64,
         use_soft_cancellations: bool,
     },
+    /// Bundles sent to block builder relays with `eth_sendBundle`.
+    Bundle {
+        relays: Vec<reqwest::Url>,
+        /// Signs the bundle requests so relays can authenticate the sender.
+        auth_key: ethcontract::PrivateKey,
+        /// The number of upcoming blocks each bundle targets.
+        target_blocks: u64,
+        max_additional_tip: eth::U256,
+        additional_tip_percentage: f64,
+    },
+    /// User operations of the solver's smart account sent to an ERC-4337
+    /// bundler with `eth_sendUserOperation`.
+    UserOperation {
+        bundler: reqwest::Url,
+        /// The `EntryPoint` contract the bundler submits user operations to.
+        entry_point: eth::Address,
+        max_additional_tip: eth::U256,
+        additional_tip_percentage: f64,
+    },
 }
 
 impl Kind {
@@ -39,6 +74,8 @@ impl Kind {
         match self {
             Kind::Public { .. } => "PublicMempool",
             Kind::MEVBlocker { .. } => "MEVBlocker",
+            Kind::Bundle { .. } => "Bundle",
+            Kind::UserOperation { .. } => "UserOperation",
         }
     }
 }
@@ -54,10 +91,45 @@ pub enum RevertProtection {
     Disabled,
 }
 
+/// A transaction that was submitted to a mempool.
+#[derive(Debug)]
+pub struct Submission {
+    pub hash: eth::TxId,
+    /// Keeps re-sending the transaction in the background for mempools that
+    /// need it, re-sending stops when this is dropped.
+    pub resender: Option<Resender>,
+}
+
+/// Handle to the background task re-sending a bundle. The task is stopped
+/// when the handle is dropped, unless it was detached.
+#[derive(Debug)]
+pub struct Resender(Option<tokio::task::AbortHandle>);
+
+impl Resender {
+    /// Keeps re-sending the bundle until its nonce was used or the target
+    /// confirmation time elapsed.
+    pub fn detach(mut self) {
+        self.0.take();
+    }
+}
+
+impl Drop for Resender {
+    fn drop(&mut self) {
+        if let Some(task) = self.0.take() {
+            task.abort();
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Mempool {
     transport: DynWeb3,
     config: Config,
+    /// The relays to send bundles to, if this mempool submits bundles.
+    relays: Option<bundle::Relays>,
+    /// The bundler to send user operations to, if this mempool submits user
+    /// operations.
+    bundler: Option<user_operation::Bundler>,
 }
 
 impl std::fmt::Display for Mempool {
@@ -69,22 +141,62 @@ impl std::fmt::Display for Mempool {
 impl Mempool {
     pub fn new(config: Config, transport: DynWeb3) -> Self {
         let transport = match &config.kind {
-            Kind::Public { .. } => transport,
+            // Bundles and user operations are sent to the relays and bundlers
+            // directly, the node is only used for preparing them.
+            Kind::Public { .. } | Kind::Bundle { .. } | Kind::UserOperation { .. } => transport,
             // Flashbots Protect RPC fallback doesn't support buffered transport
             Kind::MEVBlocker { url, .. } => unbuffered_web3_client(url),
         };
-        Self { config, transport }
+        let relays = match &config.kind {
+            Kind::Bundle {
+                relays, auth_key, ..
+            } => Some(bundle::Relays::new(relays.clone(), *auth_key)),
+            Kind::Public { .. } | Kind::MEVBlocker { .. } | Kind::UserOperation { .. } => None,
+        };
+        let bundler = match &config.kind {
+            Kind::UserOperation {
+                bundler,
+                entry_point,
+                ..
+            } => Some(user_operation::Bundler::new(bundler.clone(), *entry_point)),
+            Kind::Public { .. } | Kind::MEVBlocker { .. } | Kind::Bundle { .. } => None,
+        };
+        Self {
+            config,
+            transport,
+            relays,
+            bundler,
+        }
     }
 
     /// Submits a transaction to the mempool. Returns optimistically as soon as
     /// the transaction is pending.
+    ///
+    /// For user operation mempools, the transaction is executed by the solver's
+    /// smart account and the returned ID is the user operation hash.
     pub async fn submit(
         &self,
         tx: eth::Tx,
         gas: competition::solution::settlement::Gas,
         solver: &infra::Solver,
-    ) -> Result<eth::TxId, mempools::Error> {
-        ethcontract::transaction::TransactionBuilder::new(self.transport.clone())
+    ) -> Result<Submission, mempools::Error> {
+        if let Some(bundler) = &self.bundler {
+            let account = solver.smart_account().ok_or_else(|| {
+                mempools::Error::Other(anyhow!(
+                    "user operations can only be submitted for smart account solvers"
+                ))
+            })?;
+            return bundler
+                .submit(&self.transport, account, &tx, &gas)
+                .await
+                .map(|hash| Submission {
+                    hash,
+                    resender: None,
+                })
+                .map_err(|err| mempools::Error::Other(err.into()));
+        }
+
+        let builder = TransactionBuilder::new(self.transport.clone())
             .from(solver.account().clone())
             .to(tx.to.into())
             .gas_price(ethcontract::GasPrice::Eip1559 {
@@ -94,12 +206,118 @@ impl Mempool {
             .data(tx.input.into())
             .value(tx.value.0)
             .gas(gas.limit.0)
-            .access_list(web3::types::AccessList::from(tx.access_list))
-            .resolve(ethcontract::transaction::ResolveCondition::Pending)
-            .send()
+            .access_list(web3::types::AccessList::from(tx.access_list));
+
+        match &self.relays {
+            Some(relays) => self.submit_bundle(relays, builder, solver.address()).await,
+            None => builder
+                .resolve(ethcontract::transaction::ResolveCondition::Pending)
+                .send()
+                .await
+                .map(|result| Submission {
+                    hash: eth::TxId(result.hash()),
+                    resender: None,
+                })
+                .map_err(|err| mempools::Error::Other(anyhow::Error::from(err))),
+        }
+    }
+
+    /// Signs the transaction and sends it as a bundle to the relays. Returns as
+    /// soon as the first bundle was accepted and keeps re-sending it for every
+    /// new block in the background until the transaction's nonce was used, the
+    /// target confirmation time elapsed or the returned resender was dropped.
+    async fn submit_bundle(
+        &self,
+        relays: &bundle::Relays,
+        builder: TransactionBuilder<DynTransport>,
+        from: eth::Address,
+    ) -> Result<Submission, mempools::Error> {
+        let Kind::Bundle { target_blocks, .. } = self.config.kind else {
+            unreachable!("relays are only configured for bundle mempools");
+        };
+        let target_blocks = target_blocks.max(1);
+        let eth = self.transport.eth();
+
+        // Bundles only get included at the nonce they were signed with, so we
+        // set it explicitly in order to know when the bundle became obsolete.
+        let nonce = eth
+            .transaction_count(from.into(), None)
             .await
-            .map(|result| eth::TxId(result.hash()))
-            .map_err(|err| mempools::Error::Other(anyhow::Error::from(err)))
+            .map_err(anyhow::Error::from)?;
+        let (tx, hash) = match builder
+            .nonce(nonce)
+            .build()
+            .await
+            .map_err(anyhow::Error::from)?
+        {
+            Transaction::Raw { bytes, hash } => (bytes.0, hash),
+            Transaction::Request(_) => {
+                return Err(mempools::Error::Other(anyhow!(
+                    "bundles can only be submitted for accounts that sign transactions locally"
+                )));
+            }
+        };
+
+        let mut block = eth
+            .block_number()
+            .await
+            .map_err(anyhow::Error::from)?
+            .as_u64();
+        relays
+            .send(&tx, block + 1..=block + target_blocks)
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        let relays = relays.clone();
+        let deadline = Instant::now() + self.config.target_confirm_time;
+        let retry_interval = self.config.retry_interval;
+        let resubmit = async move {
+            while Instant::now() < deadline {
+                tokio::time::sleep(retry_interval).await;
+                match eth.transaction_count(from.into(), None).await {
+                    Ok(current) if current > nonce => {
+                        tracing::debug!(?hash, "bundle nonce was used");
+                        return;
+                    }
+                    Ok(_) => {}
+                    Err(err) => tracing::warn!(?err, "failed to get account nonce"),
+                }
+                let current = match eth.block_number().await {
+                    Ok(current) => current.as_u64(),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to get current block");
+                        continue;
+                    }
+                };
+                if current <= block {
+                    continue;
+                }
+                block = current;
+                if let Err(err) = relays.send(&tx, block + 1..=block + target_blocks).await {
+                    tracing::warn!(?hash, ?err, "failed to re-send bundle");
+                }
+            }
+            tracing::debug!(?hash, "stopped re-sending bundle");
+        };
+        let task = tokio::spawn(resubmit.instrument(tracing::Span::current()));
+
+        Ok(Submission {
+            hash: eth::TxId(hash),
+            resender: Some(Resender(Some(task.abort_handle()))),
+        })
+    }
+
+    /// Returns the on-chain inclusion status of a transaction submitted to
+    /// this mempool. User operations are looked up with the bundler.
+    pub async fn transaction_status(
+        &self,
+        ethereum: &infra::Ethereum,
+        id: &eth::TxId,
+    ) -> anyhow::Result<eth::TxStatus> {
+        match &self.bundler {
+            Some(bundler) => Ok(bundler.status(id).await?),
+            None => Ok(ethereum.transaction_status(id).await?),
+        }
     }
 
     pub fn config(&self) -> &Config {
@@ -108,8 +326,10 @@ impl Mempool {
 
     pub fn may_revert(&self) -> bool {
         match &self.config.kind {
-            Kind::Public { .. } => true,
-            Kind::MEVBlocker { .. } => false,
+            // Bundlers only simulate the validation of user operations, their
+            // execution can still revert on-chain.
+            Kind::Public { .. } | Kind::UserOperation { .. } => true,
+            Kind::MEVBlocker { .. } | Kind::Bundle { .. } => false,
         }
     }
 }
@@ -0,0 +1,87 @@
+//! Data transfer objects for the ERC-4337 bundler JSON-RPC methods.
+
+use {
+    crate::{domain::eth, util::serialize},
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+};
+
+#[derive(Debug, Serialize)]
+pub struct Request<P> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: P,
+}
+
+impl<P> Request<P> {
+    pub fn new(method: &'static str, params: P) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC response. The error variant comes first so that responses
+/// without a `result` don't get mistaken for a `null` result.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Response<T> {
+    Error { error: Error },
+    Result { result: T },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Error {
+    pub code: i64,
+    pub message: String,
+}
+
+/// An ERC-4337 v0.6 user operation.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    pub sender: eth::H160,
+    pub nonce: eth::U256,
+    #[serde_as(as = "serialize::Hex")]
+    pub init_code: Vec<u8>,
+    #[serde_as(as = "serialize::Hex")]
+    pub call_data: Vec<u8>,
+    pub call_gas_limit: eth::U256,
+    pub verification_gas_limit: eth::U256,
+    pub pre_verification_gas: eth::U256,
+    pub max_fee_per_gas: eth::U256,
+    pub max_priority_fee_per_gas: eth::U256,
+    #[serde_as(as = "serialize::Hex")]
+    pub paymaster_and_data: Vec<u8>,
+    #[serde_as(as = "serialize::Hex")]
+    pub signature: Vec<u8>,
+}
+
+/// The result of `eth_estimateUserOperationGas`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimate {
+    pub pre_verification_gas: eth::U256,
+    pub verification_gas_limit: eth::U256,
+    pub call_gas_limit: eth::U256,
+}
+
+/// The result of `eth_getUserOperationReceipt`.
+#[derive(Debug, Deserialize)]
+pub struct Receipt {
+    /// Whether the execution of the user operation's call data succeeded.
+    pub success: bool,
+    /// The receipt of the bundle transaction including the user operation.
+    pub receipt: TransactionReceipt,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceipt {
+    pub block_number: web3::types::U64,
+}
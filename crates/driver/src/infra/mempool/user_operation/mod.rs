@@ -0,0 +1,270 @@
+//! Submission of settlements as ERC-4337 user operations of a solver's smart
+//! account via the `eth_sendUserOperation` JSON-RPC method of a bundler.
+//!
+//! The smart account is expected to be compatible with the v0.6 `EntryPoint`
+//! and the `SimpleAccount` reference implementation, i.e. it executes calls
+//! with `execute(address,uint256,bytes)` and accepts EIP-191 signatures of the
+//! user operation hash.
+
+use {
+    crate::{
+        domain::{competition::solution::settlement, eth},
+        infra::solver,
+    },
+    ethabi::Token,
+    ethcontract::dyns::DynWeb3,
+    serde::{Serialize, de::DeserializeOwned},
+    thiserror::Error,
+    web3::{
+        signing::{self, Key, SecretKeyRef},
+        types::{Bytes, CallRequest},
+    },
+};
+
+mod dto;
+
+/// The key of the nonce sequence used for user operations. The `EntryPoint`
+/// supports parallel nonce sequences but settlements are always submitted
+/// sequentially.
+const NONCE_KEY: u64 = 0;
+
+/// A bundler accepting user operations for an `EntryPoint`.
+#[derive(Debug, Clone)]
+pub struct Bundler {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    entry_point: eth::Address,
+}
+
+impl Bundler {
+    pub fn new(url: reqwest::Url, entry_point: eth::Address) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            entry_point,
+        }
+    }
+
+    /// Wraps the transaction in a user operation of the smart account and
+    /// sends it to the bundler. The gas limits for verifying the user
+    /// operation are estimated by the bundler. Returns the user operation
+    /// hash.
+    pub async fn submit(
+        &self,
+        web3: &DynWeb3,
+        account: &solver::SmartAccount,
+        tx: &eth::Tx,
+        gas: &settlement::Gas,
+    ) -> Result<eth::TxId, Error> {
+        let nonce = self.nonce(web3, account.address).await?;
+        let chain_id = web3.eth().chain_id().await?;
+
+        let mut operation = dto::UserOperation {
+            sender: account.address.0,
+            nonce,
+            init_code: Default::default(),
+            call_data: ethabi::short_signature(
+                "execute",
+                &[
+                    ethabi::ParamType::Address,
+                    ethabi::ParamType::Uint(256),
+                    ethabi::ParamType::Bytes,
+                ],
+            )
+            .into_iter()
+            .chain(ethabi::encode(&[
+                Token::Address(tx.to.0),
+                Token::Uint(tx.value.0),
+                Token::Bytes(tx.input.clone().into()),
+            ]))
+            .collect(),
+            call_gas_limit: gas.limit.0,
+            verification_gas_limit: Default::default(),
+            pre_verification_gas: Default::default(),
+            max_fee_per_gas: gas.price.max().0.0,
+            max_priority_fee_per_gas: gas.price.tip().0.0,
+            paymaster_and_data: Default::default(),
+            signature: Default::default(),
+        };
+
+        // Bundlers simulate the signature validation during the estimation,
+        // so the operation already needs to be signed at this point.
+        operation.signature = self.sign(&operation, account, chain_id);
+        let estimate: dto::GasEstimate = self
+            .call(
+                "eth_estimateUserOperationGas",
+                (&operation, self.entry_point.0),
+            )
+            .await?;
+        operation.pre_verification_gas = estimate.pre_verification_gas;
+        operation.verification_gas_limit = estimate.verification_gas_limit;
+        operation.call_gas_limit = operation.call_gas_limit.max(estimate.call_gas_limit);
+
+        operation.signature = self.sign(&operation, account, chain_id);
+        let hash: eth::H256 = self
+            .call("eth_sendUserOperation", (&operation, self.entry_point.0))
+            .await?;
+        Ok(eth::TxId(hash))
+    }
+
+    /// Returns the on-chain inclusion status of the user operation with the
+    /// specified hash.
+    pub async fn status(&self, hash: &eth::TxId) -> Result<eth::TxStatus, Error> {
+        let receipt: Option<dto::Receipt> =
+            self.call("eth_getUserOperationReceipt", [hash.0]).await?;
+        Ok(match receipt {
+            Some(receipt) => {
+                let block_number = eth::BlockNo(receipt.receipt.block_number.as_u64());
+                if receipt.success {
+                    eth::TxStatus::Executed { block_number }
+                } else {
+                    eth::TxStatus::Reverted { block_number }
+                }
+            }
+            None => eth::TxStatus::Pending,
+        })
+    }
+
+    /// Fetches the next nonce of the smart account from the `EntryPoint`.
+    async fn nonce(&self, web3: &DynWeb3, account: eth::Address) -> Result<eth::U256, Error> {
+        let data = ethabi::short_signature(
+            "getNonce",
+            &[ethabi::ParamType::Address, ethabi::ParamType::Uint(192)],
+        )
+        .into_iter()
+        .chain(ethabi::encode(&[
+            Token::Address(account.0),
+            Token::Uint(NONCE_KEY.into()),
+        ]))
+        .collect::<Vec<_>>();
+        let result = web3
+            .eth()
+            .call(
+                CallRequest {
+                    to: Some(self.entry_point.0),
+                    data: Some(Bytes(data)),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await?;
+        match ethabi::decode(&[ethabi::ParamType::Uint(256)], &result.0)?.as_slice() {
+            [Token::Uint(nonce)] => Ok(*nonce),
+            _ => unreachable!("decoded tokens match the requested types"),
+        }
+    }
+
+    /// Computes the signature of the user operation for `SimpleAccount`
+    /// compatible smart accounts: an EIP-191 signature of the user operation
+    /// hash by the session key.
+    fn sign(
+        &self,
+        operation: &dto::UserOperation,
+        account: &solver::SmartAccount,
+        chain_id: eth::U256,
+    ) -> Vec<u8> {
+        let hash = signing::hash_message(self.hash(operation, chain_id));
+        // Unwrap because the only error is for invalid messages which we don't create.
+        let signature = SecretKeyRef::new(&account.session_key)
+            .sign(hash.as_bytes(), None)
+            .unwrap();
+        [
+            signature.r.as_bytes(),
+            signature.s.as_bytes(),
+            &[u8::try_from(signature.v).expect("signature v is 27 or 28")],
+        ]
+        .concat()
+    }
+
+    /// Computes the user operation hash as the `EntryPoint` does in
+    /// `getUserOpHash`.
+    fn hash(&self, operation: &dto::UserOperation, chain_id: eth::U256) -> [u8; 32] {
+        let keccak = |bytes: &[u8]| Token::FixedBytes(signing::keccak256(bytes).to_vec());
+        let packed = ethabi::encode(&[
+            Token::Address(operation.sender),
+            Token::Uint(operation.nonce),
+            keccak(&operation.init_code),
+            keccak(&operation.call_data),
+            Token::Uint(operation.call_gas_limit),
+            Token::Uint(operation.verification_gas_limit),
+            Token::Uint(operation.pre_verification_gas),
+            Token::Uint(operation.max_fee_per_gas),
+            Token::Uint(operation.max_priority_fee_per_gas),
+            keccak(&operation.paymaster_and_data),
+        ]);
+        signing::keccak256(&ethabi::encode(&[
+            keccak(&packed),
+            Token::Address(self.entry_point.0),
+            Token::Uint(chain_id),
+        ]))
+    }
+
+    async fn call<P, T>(&self, method: &'static str, params: P) -> Result<T, Error>
+    where
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let response: dto::Response<T> = self
+            .client
+            .post(self.url.clone())
+            .json(&dto::Request::new(method, params))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match response {
+            dto::Response::Result { result } => Ok(result),
+            dto::Response::Error { error } => Err(Error::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("node request failed: {0:?}")]
+    Web3(#[from] web3::error::Error),
+    #[error("failed to decode account nonce: {0}")]
+    Nonce(#[from] ethabi::Error),
+    #[error("bundler request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("bundler returned error {code}: {message}")]
+    Rpc { code: i64, message: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn user_operation_hash() {
+        let bundler = Bundler::new(
+            "http://localhost:4337".parse().unwrap(),
+            eth::Address(eth::H160(hex!("5FF137D4b0FDCD49DcA30c7CF57E578a026d2789"))),
+        );
+        let operation = dto::UserOperation {
+            sender: eth::H160(hex!("1234567890123456789012345678901234567890")),
+            nonce: 1.into(),
+            init_code: Default::default(),
+            call_data: hex!("deadbeef").to_vec(),
+            call_gas_limit: 6942069.into(),
+            verification_gas_limit: 6942069.into(),
+            pre_verification_gas: 6942069.into(),
+            max_fee_per_gas: 69420.into(),
+            max_priority_fee_per_gas: 69.into(),
+            paymaster_and_data: Default::default(),
+            signature: hex!("0badc0de").to_vec(),
+        };
+
+        // `keccak256(abi.encode(keccak256(pack(op)), entryPoint, chainId))` as
+        // computed by `getUserOpHash` of the v0.6 `EntryPoint`. The signature is
+        // not part of the hash.
+        assert_eq!(
+            bundler.hash(&operation, 1.into()),
+            hex!("67c55df4690fe0ca8b16aaf16512fcb78ec64ff4f91e1d2234dab3cd42475c3d"),
+        );
+    }
+}
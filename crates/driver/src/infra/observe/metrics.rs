@@ -23,6 +23,16 @@ pub struct Metrics {
     /// atempted and the error detection.
     #[metric(labels("mempool", "result"))]
     pub mempool_submission_results_blocks_passed: prometheus::IntCounterVec,
+    /// The results of escalating the gas price of pending settlements.
+    #[metric(labels("strategy", "result"))]
+    pub gas_price_escalations: prometheus::IntCounterVec,
+    /// The tip of resubmitted settlements relative to the tip of their initial
+    /// submission.
+    #[metric(
+        labels("strategy"),
+        buckets(1.0, 1.125, 1.25, 1.5, 2.0, 3.0, 5.0, 10.0)
+    )]
+    pub gas_price_escalation_factor: prometheus::HistogramVec,
     /// How many tokens detected by specific solver and strategy.
     #[metric(labels("solver", "strategy"))]
     pub bad_tokens_detected: prometheus::IntCounterVec,
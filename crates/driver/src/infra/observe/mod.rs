@@ -4,7 +4,7 @@
 //! and update the metrics, if the event is worth measuring.
 
 use {
-    super::{Ethereum, Mempool, simulator, solver::Timeouts},
+    super::{Ethereum, Mempool, mempool::Submission, simulator, solver::Timeouts},
     crate::{
         boundary,
         domain::{
@@ -16,7 +16,7 @@ use {
                 solution::{self, Settlement},
             },
             eth::{self, Gas},
-            mempools::{self, SubmissionSuccess},
+            mempools::{self, SubmissionSuccess, escalation},
             quote::{self, Quote},
             time::{Deadline, Remaining},
         },
@@ -384,6 +384,77 @@ pub fn mempool_executed(
     }
 }
 
+/// Observe the resubmission of a pending settlement with an escalated gas
+/// price.
+pub fn gas_price_escalated(
+    mempool: &Mempool,
+    strategy: &dyn escalation::Strategy,
+    pending: &escalation::Pending,
+    gas_price: eth::GasPrice,
+    res: &Result<Submission, mempools::Error>,
+) {
+    let result = match res {
+        Ok(submission) => {
+            tracing::debug!(
+                txid = ?submission.hash,
+                %mempool,
+                strategy = strategy.name(),
+                ?pending,
+                ?gas_price,
+                "resubmitted settlement with escalated gas price",
+            );
+            "Resubmitted"
+        }
+        Err(err) => {
+            tracing::warn!(
+                ?err,
+                %mempool,
+                strategy = strategy.name(),
+                ?pending,
+                ?gas_price,
+                "failed to resubmit settlement with escalated gas price",
+            );
+            "Failed"
+        }
+    };
+    metrics::get()
+        .gas_price_escalations
+        .with_label_values(&[strategy.name(), result])
+        .inc();
+    if res.is_ok() {
+        let initial = eth::U256::from(pending.initial.tip()).to_f64_lossy();
+        let escalated = eth::U256::from(gas_price.tip()).to_f64_lossy();
+        if initial > 0. {
+            metrics::get()
+                .gas_price_escalation_factor
+                .with_label_values(&[strategy.name()])
+                .observe(escalated / initial);
+        }
+    }
+}
+
+/// Observe that the gas price of a pending settlement was not escalated, either
+/// because the strategy did not increase it enough to replace the pending
+/// transaction or because it exceeds the gas price cap.
+pub fn gas_price_escalation_skipped(
+    strategy: &dyn escalation::Strategy,
+    pending: &escalation::Pending,
+    gas_price: eth::GasPrice,
+    capped: bool,
+) {
+    tracing::trace!(
+        strategy = strategy.name(),
+        ?pending,
+        ?gas_price,
+        capped,
+        "not escalating gas price of pending settlement",
+    );
+    metrics::get()
+        .gas_price_escalations
+        .with_label_values(&[strategy.name(), if capped { "Capped" } else { "Unchanged" }])
+        .inc();
+}
+
 /// Observe that an invalid DTO was received.
 pub fn invalid_dto(err: &impl std::error::Error, dto: &str) {
     tracing::warn!(?err, ?dto, "received invalid dto");
@@ -7,6 +7,7 @@ use {
 };
 
 pub mod enso;
+pub mod revm;
 pub mod tenderly;
 
 /// Ethereum transaction simulator.
@@ -25,6 +26,7 @@ pub struct Simulator {
 pub enum Config {
     Tenderly(tenderly::Config),
     Enso(enso::Config),
+    Revm,
 }
 
 impl Simulator {
@@ -66,6 +68,18 @@ impl Simulator {
         }
     }
 
+    /// Simulate transactions in-process with an EVM running on top of the
+    /// state of the current block which is lazily fetched from the node.
+    pub fn revm(eth: Ethereum) -> Self {
+        let eth = eth.with_metric_label("revmSimulator".into());
+        Self {
+            inner: Inner::Revm(revm::Revm::new(eth.clone())),
+            eth,
+            disable_access_lists: false,
+            disable_gas: None,
+        }
+    }
+
     /// Disable access list simulation. Some environments, such as less popular
     /// blockchains, don't support access list simulation.
     pub fn disable_access_lists(&mut self) {
@@ -104,6 +118,13 @@ impl Simulator {
                 .create_access_list(tx.clone())
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Revm(revm) => {
+                revm.simulate(tx)
+                    .measure("revm_simulate_access_list")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .access_list
+            }
         };
         Ok(tx.access_list.clone().merge(access_list))
     }
@@ -133,6 +154,13 @@ impl Simulator {
                 .measure("enso_simulate_gas")
                 .await
                 .map_err(with(tx.clone(), block))?,
+            Inner::Revm(revm) => {
+                revm.simulate(tx)
+                    .measure("revm_simulate_gas")
+                    .await
+                    .map_err(with(tx.clone(), block))?
+                    .gas
+            }
         })
     }
 }
@@ -142,6 +170,7 @@ enum Inner {
     Tenderly(tenderly::Tenderly),
     Ethereum,
     Enso(enso::Enso),
+    Revm(revm::Revm),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -152,6 +181,8 @@ pub enum SimulatorError {
     Blockchain(#[from] blockchain::Error),
     #[error("enso error: {0:?}")]
     Enso(#[from] enso::Error),
+    #[error("revm error: {0:?}")]
+    Revm(#[from] revm::Error),
     #[error("the simulated gas {0} exceeded the gas limit {1} provided in the solution")]
     GasExceeded(eth::Gas, eth::Gas),
 }
@@ -194,6 +225,8 @@ where
             }
             SimulatorError::Enso(enso::Error::Http(_)) => None,
             SimulatorError::Enso(enso::Error::Revert(_)) => Some(tx),
+            SimulatorError::Revm(revm::Error::Revert(_)) => Some(tx),
+            SimulatorError::Revm(_) => None,
             SimulatorError::GasExceeded(..) => Some(tx),
         };
         match tx {
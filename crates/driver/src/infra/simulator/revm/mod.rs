@@ -0,0 +1,314 @@
+//! Simulates transactions in-process with [revm](https://github.com/bluealloy/revm)
+//! on top of the state of the current block.
+//!
+//! The state is lazily fetched from the node and cached until the next block,
+//! so the many solutions of an auction can be simulated in parallel without
+//! fetching the same accounts and storage slots over and over again.
+
+use {
+    crate::{domain::eth, infra::blockchain::Ethereum},
+    ::revm::{
+        Database,
+        DatabaseRef,
+        Evm,
+        db::CacheDB,
+        precompile::{PrecompileSpecId, Precompiles},
+        primitives::{
+            AccountInfo,
+            Address,
+            B256,
+            Bytecode,
+            EVMError,
+            Env,
+            ExecutionResult,
+            ResultAndState,
+            SpecId,
+            TxKind,
+            U256,
+        },
+    },
+    dashmap::DashMap,
+    ethcontract::dyns::DynWeb3,
+    ethrpc::block_stream::BlockInfo,
+    std::sync::{Arc, Mutex},
+    thiserror::Error,
+    tokio::runtime::Handle,
+    web3::types::{BlockId, BlockNumber},
+};
+
+/// The hard fork the transactions are executed with.
+const SPEC_ID: SpecId = SpecId::CANCUN;
+
+/// The maximum gas limit of simulated transactions. Some networks, such as
+/// Arbitrum, have an exceptionally high block gas limit which settlements
+/// never come close to using.
+const MAX_GAS_LIMIT: u64 = 100_000_000;
+
+#[derive(Debug, Clone)]
+pub(super) struct Revm {
+    eth: Ethereum,
+    state: Arc<Mutex<Arc<State>>>,
+}
+
+/// The result of a successful simulation.
+#[derive(Debug)]
+pub(super) struct Simulation {
+    pub gas: eth::Gas,
+    pub access_list: eth::AccessList,
+}
+
+impl Revm {
+    pub(super) fn new(eth: Ethereum) -> Self {
+        let block = eth.current_block().borrow().hash;
+        Self {
+            eth,
+            state: Arc::new(Mutex::new(Arc::new(State::new(block)))),
+        }
+    }
+
+    /// Executes the transaction on top of the current block. Returns the gas
+    /// used and the accounts and storage slots accessed by the transaction.
+    pub(super) async fn simulate(&self, tx: &eth::Tx) -> Result<Simulation, Error> {
+        let block = *self.eth.current_block().borrow();
+        let fork = Fork {
+            web3: self.eth.web3().clone(),
+            block: BlockNumber::Number(block.number.into()),
+            state: self.state(&block),
+            runtime: Handle::current(),
+        };
+        let env = env(self.eth.chain().id(), &block, tx);
+        // The EVM fetches state synchronously, so the simulation runs on a
+        // blocking thread which drives the node requests to completion.
+        tokio::task::spawn_blocking(move || transact(fork, env)).await?
+    }
+
+    /// Returns the cached state of the block, discarding the cache of the
+    /// previous block.
+    fn state(&self, block: &BlockInfo) -> Arc<State> {
+        let mut state = self.state.lock().unwrap();
+        if state.block != block.hash {
+            *state = Arc::new(State::new(block.hash));
+        }
+        state.clone()
+    }
+}
+
+/// Builds the environment for executing the transaction in the context of
+/// the block.
+fn env(chain_id: u64, block: &BlockInfo, tx: &eth::Tx) -> Box<Env> {
+    let gas_limit = block.gas_limit.min(MAX_GAS_LIMIT.into()).as_u64();
+    let mut env = Env::default();
+    env.cfg.chain_id = chain_id;
+    env.block.number = U256::from(block.number);
+    env.block.timestamp = U256::from(block.timestamp);
+    env.block.gas_limit = U256::from(gas_limit);
+    env.block.basefee = u256(block.gas_price);
+    env.block.prevrandao = Some(B256::ZERO);
+    env.tx.caller = Address::from(tx.from.0.0);
+    env.tx.transact_to = TxKind::Call(Address::from(tx.to.0.0));
+    env.tx.value = u256(tx.value.0);
+    env.tx.data = tx.input.0.clone().into();
+    env.tx.gas_limit = gas_limit;
+    // Simulate with a realistic gas price since some tokens behave differently
+    // when the gas price is 0. The caller gets funded for the gas in `transact`.
+    env.tx.gas_price = env.block.basefee;
+    env.tx.chain_id = Some(chain_id);
+    env.tx.access_list = web3::types::AccessList::from(tx.access_list.clone())
+        .into_iter()
+        .map(|item| {
+            (
+                Address::from(item.address.0),
+                item.storage_keys
+                    .into_iter()
+                    .map(|key| U256::from_be_bytes(key.0))
+                    .collect(),
+            )
+        })
+        .collect();
+    Box::new(env)
+}
+
+fn transact(fork: Fork, env: Box<Env>) -> Result<Simulation, Error> {
+    let caller = env.tx.caller;
+    let coinbase = env.block.coinbase;
+    let to = match env.tx.transact_to {
+        TxKind::Call(to) => Some(to),
+        TxKind::Create => None,
+    };
+
+    // Like `eth_estimateGas`, don't require the caller to hold enough ETH to
+    // pay for the whole gas limit of the simulation.
+    let mut db = CacheDB::new(fork);
+    let mut account = db.basic(caller)?.unwrap_or_default();
+    account.balance = account
+        .balance
+        .saturating_add(U256::from(env.tx.gas_limit).saturating_mul(env.tx.gas_price));
+    db.insert_account_info(caller, account);
+
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_env(env)
+        .with_spec_id(SPEC_ID)
+        .build();
+    let ResultAndState { result, state } = evm.transact().map_err(|err| match err {
+        EVMError::Database(err) => Error::Web3(err),
+        err => Error::Evm(err.to_string()),
+    })?;
+    // The gas used is net of refunds, but refunds are only paid out after the
+    // execution, so the transaction needs a gas limit covering the gross gas.
+    let gas = match result {
+        ExecutionResult::Success {
+            gas_used,
+            gas_refunded,
+            ..
+        } => gas_used + gas_refunded,
+        ExecutionResult::Revert { output, .. } => {
+            return Err(Error::Revert(format!("reverted: {}", hex::encode(output))));
+        }
+        ExecutionResult::Halt { reason, .. } => {
+            return Err(Error::Revert(format!("halted: {reason:?}")));
+        }
+    };
+
+    // Like `eth_createAccessList`, leave out the accounts that are warm
+    // regardless of the access list.
+    let precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(SPEC_ID));
+    let access_list = state
+        .into_iter()
+        .filter(|(address, _)| {
+            *address != caller && *address != coinbase && !precompiles.contains(address)
+        })
+        .filter(|(address, account)| Some(*address) != to || !account.storage.is_empty())
+        .map(|(address, account)| web3::types::AccessListItem {
+            address: eth::H160(address.0.0),
+            storage_keys: account
+                .storage
+                .into_keys()
+                .map(|key| eth::H256(key.to_be_bytes()))
+                .collect(),
+        })
+        .collect::<web3::types::AccessList>();
+
+    Ok(Simulation {
+        gas: gas.into(),
+        access_list: access_list.into(),
+    })
+}
+
+/// The state of a block fetched from the node so far.
+#[derive(Debug)]
+struct State {
+    block: eth::H256,
+    accounts: DashMap<Address, Option<AccountInfo>>,
+    code: DashMap<B256, Bytecode>,
+    storage: DashMap<(Address, U256), U256>,
+    block_hashes: DashMap<u64, B256>,
+}
+
+impl State {
+    fn new(block: eth::H256) -> Self {
+        Self {
+            block,
+            accounts: Default::default(),
+            code: Default::default(),
+            storage: Default::default(),
+            block_hashes: Default::default(),
+        }
+    }
+}
+
+/// The state of a block which is lazily fetched from the node.
+struct Fork {
+    web3: DynWeb3,
+    block: BlockNumber,
+    state: Arc<State>,
+    runtime: Handle,
+}
+
+impl DatabaseRef for Fork {
+    type Error = web3::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(account) = self.state.accounts.get(&address) {
+            return Ok(account.clone());
+        }
+        let node = self.web3.eth();
+        let h160 = eth::H160(address.0.0);
+        let (balance, nonce, code) = self.runtime.block_on(futures::future::try_join3(
+            node.balance(h160, Some(self.block)),
+            node.transaction_count(h160, Some(self.block)),
+            node.code(h160, Some(self.block)),
+        ))?;
+        // Empty accounts are reported as non-existent since that affects the gas
+        // costs of calls sending ETH to them.
+        let account = (!balance.is_zero() || !nonce.is_zero() || !code.0.is_empty()).then(|| {
+            let code = Bytecode::new_raw(code.0.into());
+            let code_hash = code.hash_slow();
+            self.state.code.insert(code_hash, code.clone());
+            AccountInfo::new(u256(balance), nonce.as_u64(), code_hash, code)
+        });
+        self.state.accounts.insert(address, account.clone());
+        Ok(account)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // The code is always fetched together with its account, so there is
+        // no code to fetch for unknown hashes.
+        Ok(self
+            .state
+            .code
+            .get(&code_hash)
+            .map(|code| code.clone())
+            .unwrap_or_default())
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if let Some(value) = self.state.storage.get(&(address, index)) {
+            return Ok(*value);
+        }
+        let value = self.runtime.block_on(self.web3.eth().storage(
+            eth::H160(address.0.0),
+            eth::U256::from_big_endian(&index.to_be_bytes::<32>()),
+            Some(self.block),
+        ))?;
+        let value = U256::from_be_bytes(value.0);
+        self.state.storage.insert((address, index), value);
+        Ok(value)
+    }
+
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        let number = number.saturating_to::<u64>();
+        if let Some(hash) = self.state.block_hashes.get(&number) {
+            return Ok(*hash);
+        }
+        let block = self.runtime.block_on(
+            self.web3
+                .eth()
+                .block(BlockId::Number(BlockNumber::Number(number.into()))),
+        )?;
+        let hash = block
+            .and_then(|block| block.hash)
+            .map(|hash| B256::from(hash.0))
+            .unwrap_or_default();
+        self.state.block_hashes.insert(number, hash);
+        Ok(hash)
+    }
+}
+
+fn u256(value: eth::U256) -> U256 {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    U256::from_be_bytes(bytes)
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("node request failed: {0:?}")]
+    Web3(#[from] web3::Error),
+    #[error("evm error: {0}")]
+    Evm(String),
+    #[error("transaction reverted: {0}")]
+    Revert(String),
+    #[error("simulation task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
@@ -50,8 +50,10 @@ pub fn new(
         .flat_map(|liquidity| match &liquidity.kind {
             liquidity::Kind::UniswapV2(pool) => pool.reserves.iter().map(|r| r.token).collect(),
             liquidity::Kind::UniswapV3(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
+            liquidity::Kind::UniswapV4(pool) => vec![pool.tokens.get().0, pool.tokens.get().1],
             liquidity::Kind::BalancerV2Stable(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::BalancerV2Weighted(pool) => pool.reserves.tokens().collect(),
+            liquidity::Kind::BalancerV3(pool) => pool.reserves.tokens().collect(),
             liquidity::Kind::Swapr(pool) => pool.base.reserves.iter().map(|r| r.token).collect(),
             liquidity::Kind::ZeroEx(limit_order) => {
                 vec![
@@ -59,6 +61,9 @@ pub fn new(
                     limit_order.order.taker_token.into(),
                 ]
             }
+            liquidity::Kind::Curve(pool) => pool.reserves.tokens().collect(),
+            liquidity::Kind::Solidly(pool) => pool.reserves.tokens().collect(),
+            liquidity::Kind::Erc4626(vault) => vault.tokens().to_vec(),
         })
     {
         tokens.entry(token.into()).or_insert_with(Default::default);
@@ -209,6 +214,28 @@ pub fn new(
                         solvers_dto::auction::ConcentratedLiquidityPool {
                             id: liquidity.id.0.to_string(),
                             address: pool.address.0,
+                            pool_id: None,
+                            router: pool.router.into(),
+                            gas_estimate: liquidity.gas.0,
+                            tokens: vec![pool.tokens.get().0.into(), pool.tokens.get().1.into()],
+                            sqrt_price: pool.sqrt_price.0,
+                            liquidity: pool.liquidity.0,
+                            tick: pool.tick.0,
+                            liquidity_net: pool
+                                .liquidity_net
+                                .iter()
+                                .map(|(key, value)| (key.0, value.0))
+                                .collect(),
+                            fee: rational_to_big_decimal(&pool.fee.0),
+                        },
+                    )
+                }
+                liquidity::Kind::UniswapV4(pool) => {
+                    solvers_dto::auction::Liquidity::ConcentratedLiquidity(
+                        solvers_dto::auction::ConcentratedLiquidityPool {
+                            id: liquidity.id.0.to_string(),
+                            address: pool.pool_manager.0,
+                            pool_id: Some(pool.id),
                             router: pool.router.into(),
                             gas_estimate: liquidity.gas.0,
                             tokens: vec![pool.tokens.get().0.into(), pool.tokens.get().1.into()],
@@ -283,6 +310,67 @@ pub fn new(
                         },
                     )
                 }
+                // Balancer V3 pools use the same math as their Balancer V2
+                // counterparts, with the token rates folded into the scaling
+                // factors. They don't have pool IDs, so the pool address is
+                // used instead.
+                liquidity::Kind::BalancerV3(pool) => match &pool.kind {
+                    liquidity::balancer::v3::Kind::Weighted(weights) => {
+                        solvers_dto::auction::Liquidity::WeightedProduct(
+                            solvers_dto::auction::WeightedProductPool {
+                                id: liquidity.id.0.to_string(),
+                                address: pool.address.into(),
+                                balancer_pool_id: eth::H256::from(pool.address.0),
+                                gas_estimate: liquidity.gas.into(),
+                                tokens: pool
+                                    .reserves
+                                    .iter()
+                                    .zip(weights)
+                                    .map(|(r, weight)| {
+                                        (
+                                            r.asset.token.into(),
+                                            solvers_dto::auction::WeightedProductReserve {
+                                                balance: r.asset.amount.into(),
+                                                scaling_factor: scaling_factor_to_decimal(r.scale),
+                                                weight: weight_to_decimal(*weight),
+                                            },
+                                        )
+                                    })
+                                    .collect(),
+                                fee: fee_to_decimal(pool.fee),
+                                version: solvers_dto::auction::WeightedProductVersion::V3Plus,
+                            },
+                        )
+                    }
+                    liquidity::balancer::v3::Kind::Stable(amplification_parameter) => {
+                        solvers_dto::auction::Liquidity::Stable(solvers_dto::auction::StablePool {
+                            id: liquidity.id.0.to_string(),
+                            address: pool.address.into(),
+                            balancer_pool_id: eth::H256::from(pool.address.0),
+                            gas_estimate: liquidity.gas.into(),
+                            tokens: pool
+                                .reserves
+                                .iter()
+                                .map(|r| {
+                                    (
+                                        r.asset.token.into(),
+                                        solvers_dto::auction::StableReserve {
+                                            balance: r.asset.amount.into(),
+                                            scaling_factor: scaling_factor_to_decimal(r.scale),
+                                        },
+                                    )
+                                })
+                                .collect(),
+                            amplification_parameter: rational_to_big_decimal(
+                                &num::BigRational::new(
+                                    amplification_parameter.factor().to_big_int(),
+                                    amplification_parameter.precision().to_big_int(),
+                                ),
+                            ),
+                            fee: fee_to_decimal(pool.fee),
+                        })
+                    }
+                },
                 liquidity::Kind::Swapr(pool) => solvers_dto::auction::Liquidity::ConstantProduct(
                     solvers_dto::auction::ConstantProductPool {
                         id: liquidity.id.0.to_string(),
@@ -320,6 +408,74 @@ pub fn new(
                         },
                     )
                 }
+                liquidity::Kind::Curve(pool) => {
+                    solvers_dto::auction::Liquidity::Curve(solvers_dto::auction::CurvePool {
+                        id: liquidity.id.0.to_string(),
+                        address: pool.address.into(),
+                        gas_estimate: liquidity.gas.into(),
+                        tokens: pool
+                            .reserves
+                            .iter()
+                            .map(|r| solvers_dto::auction::CurveReserve {
+                                address: r.asset.token.into(),
+                                balance: r.asset.amount.into(),
+                                rate: r.rate,
+                            })
+                            .collect(),
+                        parameters: match &pool.kind {
+                            liquidity::curve::Kind::StableSwap(parameters) => {
+                                solvers_dto::auction::CurveParameters::StableSwap {
+                                    amplification_parameter: parameters.amplification_parameter,
+                                    fee: parameters.fee,
+                                    offpeg_fee_multiplier: parameters.offpeg_fee_multiplier,
+                                }
+                            }
+                            liquidity::curve::Kind::CryptoSwap(parameters) => {
+                                solvers_dto::auction::CurveParameters::CryptoSwap {
+                                    a: parameters.a,
+                                    gamma: parameters.gamma,
+                                    d: parameters.d,
+                                    price_scale: parameters.price_scale,
+                                    mid_fee: parameters.mid_fee,
+                                    out_fee: parameters.out_fee,
+                                    fee_gamma: parameters.fee_gamma,
+                                }
+                            }
+                        },
+                    })
+                }
+                liquidity::Kind::Solidly(pool) => {
+                    solvers_dto::auction::Liquidity::Solidly(solvers_dto::auction::SolidlyPool {
+                        id: liquidity.id.0.to_string(),
+                        address: pool.address.into(),
+                        router: pool.router.into(),
+                        gas_estimate: liquidity.gas.into(),
+                        tokens: [pool.reserves.get().0, pool.reserves.get().1]
+                            .into_iter()
+                            .map(|reserve| {
+                                (
+                                    reserve.asset.token.into(),
+                                    solvers_dto::auction::SolidlyReserve {
+                                        balance: reserve.asset.amount.into(),
+                                        decimals: reserve.decimals,
+                                    },
+                                )
+                            })
+                            .collect(),
+                        fee: bigdecimal::BigDecimal::new(pool.fee.bps().into(), 4),
+                        stable: pool.stable,
+                    })
+                }
+                liquidity::Kind::Erc4626(vault) => {
+                    solvers_dto::auction::Liquidity::Erc4626(solvers_dto::auction::Erc4626Vault {
+                        id: liquidity.id.0.to_string(),
+                        address: vault.vault.into(),
+                        gas_estimate: liquidity.gas.into(),
+                        asset: vault.asset.into(),
+                        assets_to_shares: vault.assets_to_shares,
+                        shares_to_assets: vault.shares_to_assets,
+                    })
+                }
             })
             .collect(),
         tokens,
@@ -84,6 +84,16 @@ pub struct ManageNativeToken {
     pub insert_unwraps: bool,
 }
 
+/// A smart account settling on behalf of a solver, for example an ERC-4337
+/// `SimpleAccount`.
+#[derive(Debug, Clone)]
+pub struct SmartAccount {
+    pub address: eth::Address,
+    /// The key signing the user operations of the smart account. It can be a
+    /// session key with restricted permissions instead of the account owner.
+    pub session_key: ethcontract::PrivateKey,
+}
+
 /// Solvers are controlled by the driver. Their job is to search for solutions
 /// to auctions. They do this in various ways, often by analyzing different AMMs
 /// on the Ethereum blockchain.
@@ -106,6 +116,9 @@ pub struct Config {
     pub liquidity: Liquidity,
     /// The private key of this solver, used for settlement submission.
     pub account: ethcontract::Account,
+    /// The smart account of this solver, used for settlement submission with
+    /// user operations. The `account` is the smart account's address then.
+    pub smart_account: Option<SmartAccount>,
     /// How much time to spend for each step of the solving and competition.
     pub timeouts: Timeouts,
     /// HTTP headers that should be added to every request.
@@ -189,6 +202,12 @@ impl Solver {
         self.config.account.clone()
     }
 
+    /// The smart account which should be used for submitting settlements as
+    /// user operations, if this solver has one.
+    pub fn smart_account(&self) -> Option<&SmartAccount> {
+        self.config.smart_account.as_ref()
+    }
+
     /// Timeout configuration for this solver.
     pub fn timeouts(&self) -> Timeouts {
         self.config.timeouts
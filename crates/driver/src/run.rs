@@ -127,6 +127,7 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
             },
             eth.to_owned(),
         ),
+        Some(infra::simulator::Config::Revm) => Simulator::revm(eth.to_owned()),
         None => Simulator::ethereum(eth.to_owned()),
     };
     if config.disable_access_list_simulation {
@@ -139,7 +140,7 @@ fn simulator(config: &infra::Config, eth: &Ethereum) -> Simulator {
 }
 
 async fn ethrpc(args: &cli::Args) -> blockchain::Rpc {
-    blockchain::Rpc::try_new(&args.ethrpc)
+    blockchain::Rpc::try_new(&args.ethrpc, args.ethrpc_ws.as_ref())
         .await
         .expect("connect ethereum RPC")
 }
@@ -21,6 +21,7 @@ pub mod order_prioritization;
 pub mod parallel_auctions;
 pub mod protocol_fees;
 pub mod quote;
+pub mod revm_simulation;
 pub mod settle;
 pub mod solver_balance;
 
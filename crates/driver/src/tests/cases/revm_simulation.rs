@@ -0,0 +1,41 @@
+use crate::tests::{
+    setup,
+    setup::{ab_order, ab_pool, ab_solution},
+};
+
+/// Test that solutions simulated with the in-process EVM get scored and
+/// settled.
+#[tokio::test]
+#[ignore]
+async fn settle() {
+    let test = setup()
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .revm_simulation()
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
+
+/// Test that solutions reverting in the in-process EVM are discarded.
+#[tokio::test]
+#[ignore]
+async fn revert() {
+    let test = setup()
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution().invalid())
+        .revm_simulation()
+        .done()
+        .await;
+
+    test.solve().await.ok().empty();
+}
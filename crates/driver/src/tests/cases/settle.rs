@@ -82,6 +82,93 @@ async fn private_rpc_with_high_risk_solution() {
     test.settle(id).await.err().kind("FailedToSubmit");
 }
 
+/// Checks that settlements can be submitted as bundles to block builder
+/// relays.
+#[tokio::test]
+#[ignore]
+async fn bundle_relay() {
+    let test = tests::setup()
+        .name("bundle relay")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::Bundle {
+            forward: true,
+            received: Default::default(),
+        }])
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
+
+/// Checks that bundles which don't get included in time are cancelled by no
+/// longer re-sending them, without submitting a cancellation transaction.
+#[tokio::test]
+#[ignore]
+async fn bundle_relay_expired() {
+    let received = tests::setup::relay::Received::default();
+    let test = tests::setup()
+        .name("bundle relay expired")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::Bundle {
+            forward: false,
+            received: received.clone(),
+        }])
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+
+    // The relay drops all bundles, so blocks have to be mined without the
+    // settlement for the submission deadline to pass.
+    let web3 = test.web3().clone();
+    let miner = tokio::spawn(async move {
+        loop {
+            web3.transport().execute("evm_mine", vec![]).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    });
+    test.settle(id).await.err().kind("FailedToSubmit");
+    miner.abort();
+
+    // Only the settlement was ever sent to the relay.
+    assert_eq!(received.transactions().len(), 1);
+}
+
+/// Checks that settlements can be submitted as user operations of a smart
+/// account to an ERC-4337 bundler. The stand-in bundler checks that the user
+/// operation executes a call to the settlement contract, but doesn't verify its
+/// signature.
+#[tokio::test]
+#[ignore]
+async fn user_operation() {
+    let test = tests::setup()
+        .name("user operation")
+        .pool(ab_pool())
+        .order(ab_order())
+        .solution(ab_solution())
+        .mempools(vec![tests::setup::Mempool::UserOperation])
+        .done()
+        .await;
+
+    let id = test.solve().await.ok().id();
+    test.settle(id)
+        .await
+        .ok()
+        .await
+        .ab_order_executed(&test)
+        .await;
+}
+
 #[tokio::test]
 #[ignore]
 async fn too_much_gas() {
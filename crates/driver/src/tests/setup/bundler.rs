@@ -0,0 +1,179 @@
+use {
+    super::blockchain,
+    axum::{Extension, Json, Router, response::IntoResponse, routing::post},
+    ethcontract::dyns::{DynTransport, DynWeb3},
+    serde_json::json,
+    std::net::SocketAddr,
+    web3::{Transport, Web3, types::H160},
+};
+
+/// The `EntryPoint` address the bundler accepts user operations for.
+pub const ENTRY_POINT: H160 = H160([0xe4; 20]);
+
+/// Code of the stand-in `EntryPoint` which returns 0 for every call, i.e. the
+/// nonce of every smart account is 0.
+const ENTRY_POINT_CODE: [u8; 5] = [
+    0x60, 0x20, // PUSH1 32
+    0x60, 0x00, // PUSH1 0
+    0xf3, // RETURN
+];
+
+/// A stand-in ERC-4337 bundler. Instead of submitting user operations via the
+/// `EntryPoint`, it impersonates the smart account on the test node and sends
+/// the call of the user operation as a transaction from the account. The
+/// transaction hash doubles as the user operation hash.
+///
+/// The bundler checks that user operations execute a call to the settlement
+/// contract, but it doesn't verify their signatures.
+pub struct Bundler {
+    pub addr: SocketAddr,
+}
+
+#[derive(Clone)]
+struct State {
+    web3: Web3<DynTransport>,
+    settlement: H160,
+}
+
+impl Bundler {
+    /// Starts the bundler server listening on a random port, accepting user
+    /// operations that call the specified settlement contract.
+    pub async fn start(web3: Web3<DynTransport>, settlement: H160) -> Self {
+        blockchain::set_code(&web3, ENTRY_POINT, &ENTRY_POINT_CODE).await;
+
+        let app = Router::new()
+            .route("/", post(Self::rpc))
+            .layer(Extension(State { web3, settlement }));
+        let server =
+            axum::Server::bind(&"0.0.0.0:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+
+        tracing::info!("Bundler mock server listening on {}", addr);
+
+        tokio::spawn(server);
+
+        Bundler { addr }
+    }
+
+    async fn rpc(
+        Extension(state): Extension<State>,
+        Json(request): Json<serde_json::Value>,
+    ) -> impl IntoResponse {
+        tracing::debug!("Bundler received a request: {}", request);
+
+        let response = match request["method"].as_str() {
+            Some("eth_estimateUserOperationGas") => Self::estimate(&request["params"]),
+            Some("eth_sendUserOperation") => Self::send(&state, &request["params"]).await,
+            Some("eth_getUserOperationReceipt") => {
+                Self::receipt(&state.web3, &request["params"]).await
+            }
+            _ => Err("unsupported method"),
+        };
+        Json(match response {
+            Ok(result) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": result,
+            }),
+            Err(message) => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32602, "message": message },
+            }),
+        })
+    }
+
+    fn estimate(params: &serde_json::Value) -> Result<serde_json::Value, &'static str> {
+        if params[1] != json!(ENTRY_POINT) {
+            return Err("unsupported entry point");
+        }
+        Ok(json!({
+            "preVerificationGas": "0xc350",
+            "verificationGasLimit": "0x186a0",
+            "callGasLimit": params[0]["callGasLimit"],
+        }))
+    }
+
+    async fn send(
+        state: &State,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value, &'static str> {
+        if params[1] != json!(ENTRY_POINT) {
+            return Err("unsupported entry point");
+        }
+        let operation = &params[0];
+        let signature = operation["signature"].as_str().unwrap_or_default();
+        if signature.len() != 2 + 2 * 65 {
+            return Err("invalid signature");
+        }
+        let call_data = operation["callData"]
+            .as_str()
+            .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok())
+            .filter(|data| data.len() >= 4)
+            .ok_or("invalid call data")?;
+        let params = [
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+            ethabi::ParamType::Bytes,
+        ];
+        if call_data[..4] != ethabi::short_signature("execute", &params) {
+            return Err("call data is not an execute call");
+        }
+        let tokens = ethabi::decode(&params, &call_data[4..])
+            .map_err(|_| "call data is not an execute call")?;
+        let [
+            ethabi::Token::Address(to),
+            ethabi::Token::Uint(value),
+            ethabi::Token::Bytes(data),
+        ] = tokens.as_slice()
+        else {
+            return Err("call data is not an execute call");
+        };
+        if *to != state.settlement {
+            return Err("user operation does not call the settlement contract");
+        }
+
+        let web3 = &state.web3;
+        web3.transport()
+            .execute(
+                "anvil_impersonateAccount",
+                vec![operation["sender"].clone()],
+            )
+            .await
+            .map_err(|_| "failed to impersonate smart account")?;
+        web3.transport()
+            .execute(
+                "eth_sendTransaction",
+                vec![json!({
+                    "from": operation["sender"],
+                    "to": to,
+                    "value": value,
+                    "data": format!("0x{}", hex::encode(data)),
+                    "gas": operation["callGasLimit"],
+                    "maxFeePerGas": operation["maxFeePerGas"],
+                    "maxPriorityFeePerGas": operation["maxPriorityFeePerGas"],
+                })],
+            )
+            .await
+            .map_err(|_| "failed to execute user operation")
+    }
+
+    async fn receipt(
+        web3: &DynWeb3,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value, &'static str> {
+        let receipt = web3
+            .transport()
+            .execute("eth_getTransactionReceipt", vec![params[0].clone()])
+            .await
+            .map_err(|_| "failed to get receipt")?;
+        if receipt.is_null() {
+            return Ok(receipt);
+        }
+        Ok(json!({
+            "userOpHash": params[0],
+            "success": receipt["status"] == "0x1",
+            "receipt": receipt,
+        }))
+    }
+}
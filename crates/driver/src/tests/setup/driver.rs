@@ -5,7 +5,12 @@ use {
         infra::config::file::OrderPriorityStrategy,
         tests::{
             hex_address,
-            setup::{blockchain::Trade, orderbook::Orderbook},
+            setup::{
+                blockchain::Trade,
+                bundler::{self, Bundler},
+                orderbook::Orderbook,
+                relay::Relay,
+            },
         },
     },
     rand::seq::SliceRandom,
@@ -19,6 +24,7 @@ pub struct Config {
     /// temporary file will be created with reasonable values.
     pub config_file: Option<PathBuf>,
     pub enable_simulation: bool,
+    pub revm_simulation: bool,
     pub mempools: Vec<Mempool>,
     pub order_priority_strategies: Vec<OrderPriorityStrategy>,
     pub orderbook: Orderbook,
@@ -220,6 +226,9 @@ async fn create_config_file(
     )
     .unwrap();
     writeln!(file, "flashloans-enabled = true").unwrap();
+    if config.revm_simulation {
+        writeln!(file, "[revm]").unwrap();
+    }
     write!(
         file,
         r#"[contracts]
@@ -265,6 +274,37 @@ async fn create_config_file(
                 )
                 .unwrap();
             }
+            Mempool::Bundle { forward, received } => {
+                let relay = Relay::start(blockchain.web3.clone(), received.clone(), *forward);
+                write!(
+                    file,
+                    r#"[[submission.mempool]]
+                    mempool = "bundle"
+                    relays = ["http://{}"]
+                    auth-key = "0x{}"
+                    additional-tip-percentage = 0.0
+                    "#,
+                    relay.addr,
+                    hex::encode([0x42; 32]),
+                )
+                .unwrap();
+            }
+            Mempool::UserOperation => {
+                let bundler =
+                    Bundler::start(blockchain.web3.clone(), blockchain.settlement.address()).await;
+                write!(
+                    file,
+                    r#"[[submission.mempool]]
+                    mempool = "user-operation"
+                    bundler = "http://{}"
+                    entry-point = "{}"
+                    additional-tip-percentage = 0.0
+                    "#,
+                    bundler.addr,
+                    hex_address(bundler::ENTRY_POINT),
+                )
+                .unwrap();
+            }
         }
     }
 
@@ -308,7 +348,22 @@ async fn create_config_file(
         }
     }
 
+    let smart_accounts = config
+        .mempools
+        .iter()
+        .any(|mempool| matches!(mempool, Mempool::UserOperation));
     for (solver, addr) in solvers {
+        // The stand-in bundler impersonates the smart accounts, so the solver
+        // addresses can act as smart accounts.
+        let account = if smart_accounts {
+            format!(
+                r#"{{ address = "{}", session-key = "0x{}" }}"#,
+                hex_address(solver.address()),
+                hex::encode([0x43; 32]),
+            )
+        } else {
+            format!(r#""0x{}""#, hex::encode(solver.private_key.secret_bytes()))
+        };
         write!(
             file,
             r#"[[solver]]
@@ -316,7 +371,7 @@ async fn create_config_file(
                endpoint = "http://{}"
                absolute-slippage = "{}"
                relative-slippage = "{}"
-               account = "0x{}"
+               account = {}
                solving-share-of-deadline = {}
                http-time-buffer = "{}ms"
                fee-handler = {}
@@ -330,7 +385,7 @@ async fn create_config_file(
                 .map(|abs| abs.0)
                 .unwrap_or_default(),
             solver.slippage.relative,
-            hex::encode(solver.private_key.secret_bytes()),
+            account,
             solver.timeouts.solving_share_of_deadline.get(),
             solver.timeouts.http_delay.num_milliseconds(),
             serde_json::to_string(&solver.fee_handler).unwrap(),
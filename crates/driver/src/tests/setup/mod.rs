@@ -56,9 +56,11 @@ use {
 };
 
 pub mod blockchain;
+mod bundler;
 mod driver;
 pub mod fee;
 mod orderbook;
+pub mod relay;
 mod solver;
 
 #[derive(Debug, Clone, Copy)]
@@ -507,6 +509,16 @@ pub enum Mempool {
         /// Uses ethrpc node if None
         url: Option<String>,
     },
+    /// Submits bundles to a mocked block builder relay.
+    Bundle {
+        /// Whether the relay forwards bundles to the node. Otherwise, they
+        /// never get included.
+        forward: bool,
+        received: relay::Received,
+    },
+    /// Submits user operations from the solvers' accounts, acting as smart
+    /// accounts, to a stand-in bundler.
+    UserOperation,
 }
 
 /// Create a builder for the setup process.
@@ -538,6 +550,9 @@ pub struct Setup {
     solvers: Vec<Solver>,
     /// Should simulation be enabled? True by default.
     enable_simulation: bool,
+    /// Should transactions be simulated with the in-process EVM instead of
+    /// the node?
+    revm_simulation: bool,
     /// Ensure the settlement contract is deployed on a specific address?
     settlement_address: Option<eth::H160>,
     /// Via which mempool the solutions should be submitted
@@ -847,6 +862,12 @@ impl Setup {
         self
     }
 
+    /// Simulate transactions with the in-process EVM instead of the node.
+    pub fn revm_simulation(mut self) -> Self {
+        self.revm_simulation = true;
+        self
+    }
+
     pub fn mempools(mut self, mempools: Vec<Mempool>) -> Self {
         self.mempools = mempools;
         self
@@ -986,6 +1007,7 @@ impl Setup {
             &driver::Config {
                 config_file,
                 enable_simulation: self.enable_simulation,
+                revm_simulation: self.revm_simulation,
                 mempools: self.mempools,
                 order_priority_strategies: self.order_priority_strategies,
                 orderbook,
@@ -0,0 +1,109 @@
+use {
+    axum::{Extension, Json, Router, http::HeaderMap, response::IntoResponse, routing::post},
+    ethcontract::dyns::DynTransport,
+    serde_json::json,
+    std::{
+        collections::HashSet,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    },
+    web3::Web3,
+};
+
+/// A mocked block builder relay that accepts `eth_sendBundle` requests and
+/// forwards the bundled transactions to the test node, so that they get
+/// included in the next block.
+pub struct Relay {
+    pub addr: SocketAddr,
+}
+
+/// The raw transactions of all bundles received by a relay.
+#[derive(Clone, Debug, Default)]
+pub struct Received(Arc<Mutex<HashSet<String>>>);
+
+impl Received {
+    /// Returns the distinct transactions the relay received.
+    pub fn transactions(&self) -> HashSet<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[derive(Clone)]
+struct State {
+    web3: Web3<DynTransport>,
+    received: Received,
+    forward: bool,
+}
+
+impl Relay {
+    /// Starts the relay server listening on a random port. Bundles are only
+    /// forwarded to the node if `forward` is set, otherwise they never get
+    /// included.
+    pub fn start(web3: Web3<DynTransport>, received: Received, forward: bool) -> Self {
+        let app = Router::new()
+            .route("/", post(Self::send_bundle))
+            .layer(Extension(State {
+                web3,
+                received,
+                forward,
+            }));
+        let server =
+            axum::Server::bind(&"0.0.0.0:0".parse().unwrap()).serve(app.into_make_service());
+        let addr = server.local_addr();
+
+        tracing::info!("Relay mock server listening on {}", addr);
+
+        tokio::spawn(server);
+
+        Relay { addr }
+    }
+
+    async fn send_bundle(
+        Extension(state): Extension<State>,
+        headers: HeaderMap,
+        Json(request): Json<serde_json::Value>,
+    ) -> impl IntoResponse {
+        tracing::debug!("Relay received a request: {}", request);
+
+        let error = |message: &str| {
+            Json(json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32600, "message": message },
+            }))
+        };
+        if !headers.contains_key("X-Flashbots-Signature") {
+            return error("missing signature");
+        }
+        if request["method"] != "eth_sendBundle" {
+            return error("unsupported method");
+        }
+        let Some(txs) = request["params"][0]["txs"].as_array() else {
+            return error("missing transactions");
+        };
+
+        for tx in txs {
+            let Some(tx) = tx.as_str() else {
+                return error("invalid transaction");
+            };
+            state.received.0.lock().unwrap().insert(tx.to_owned());
+            let Ok(tx) = hex::decode(tx.trim_start_matches("0x")) else {
+                return error("invalid transaction");
+            };
+            if !state.forward {
+                continue;
+            }
+            // Bundles get re-sent for every block, so the node might already
+            // know about the transaction.
+            if let Err(err) = state.web3.eth().send_raw_transaction(tx.into()).await {
+                tracing::debug!(?err, "relay failed to forward transaction");
+            }
+        }
+
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {},
+        }))
+    }
+}
@@ -431,7 +431,7 @@ impl Solver {
             .collect::<HashMap<_, _>>();
 
         let url = config.blockchain.web3_url.parse().unwrap();
-        let rpc = infra::blockchain::Rpc::try_new(&url).await.unwrap();
+        let rpc = infra::blockchain::Rpc::try_new(&url, None).await.unwrap();
         let gas = Arc::new(
             infra::blockchain::GasPriceEstimator::new(
                 rpc.web3(),
@@ -441,6 +441,7 @@ impl Solver {
                     gas_price_cap: eth::U256::MAX,
                     target_confirm_time: Default::default(),
                     retry_interval: Default::default(),
+                    escalation: None,
                     kind: infra::mempool::Kind::Public {
                         max_additional_tip: 0.into(),
                         additional_tip_percentage: 0.,
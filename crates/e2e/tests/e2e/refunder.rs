@@ -123,11 +123,12 @@ async fn refunder_tx(web3: Web3) {
     let pg_pool = PgPool::connect_lazy("postgresql://").expect("failed to create database");
     let mut refunder = RefundService::new(
         pg_pool,
-        web3,
+        web3.clone(),
         vec![ethflow_contract.clone(), ethflow_contract_2.clone()],
         validity_duration as i64 / 2,
         10i64,
         refunder.account().clone(),
+        Box::new(web3),
     );
 
     assert_ne!(
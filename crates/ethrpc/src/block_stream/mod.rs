@@ -1,9 +1,16 @@
 use {
-    crate::{Web3, Web3Transport, http::HttpTransport, instrumented::instrument_with_label},
+    crate::{
+        Web3,
+        Web3Transport,
+        http::HttpTransport,
+        instrumented::instrument_with_label,
+        ws::WsTransport,
+    },
     anyhow::{Context as _, Result, anyhow, ensure},
     futures::StreamExt,
     primitive_types::{H256, U256},
     std::{
+        collections::VecDeque,
         fmt::Debug,
         num::NonZeroU64,
         time::{Duration, Instant},
@@ -16,7 +23,7 @@ use {
         BatchTransport,
         Transport,
         helpers,
-        types::{Block, BlockId, BlockNumber, U64},
+        types::{Block, BlockHeader, BlockId, BlockNumber, U64},
     },
 };
 
@@ -55,9 +62,60 @@ pub struct BlockInfo {
     pub parent_hash: H256,
     pub timestamp: u64,
     pub gas_limit: U256,
+    pub gas_used: U256,
     pub gas_price: U256,
     /// When the system noticed the new block.
     pub observed_at: Instant,
+    /// The most recent reorg the stream observed, if it happened within the
+    /// last [`MAX_REORG_DEPTH`] blocks.
+    ///
+    /// The reorg stays attached to the blocks following it, so that consumers
+    /// which don't read every block still notice it. Use a [`ReorgTracker`] to
+    /// only handle each reorg once.
+    pub reorg: Option<Reorg>,
+}
+
+/// A chain reorganization observed by the block stream.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Reorg {
+    /// The most recent block that is part of both the old and the new chain.
+    /// Note that this can be older than the actual fork point if the stream
+    /// skipped some blocks.
+    pub common_ancestor: BlockNumberHash,
+    /// The first block of the new chain the reorg was noticed at.
+    pub head: BlockNumberHash,
+    /// The number of blocks of the old chain that got replaced.
+    pub depth: u64,
+}
+
+impl Reorg {
+    /// Combines the reorg with an earlier one, so that the result covers the
+    /// blocks replaced by both of them.
+    fn merge(self, earlier: Reorg) -> Self {
+        if earlier.common_ancestor.0 >= self.common_ancestor.0 {
+            return self;
+        }
+        Self {
+            common_ancestor: earlier.common_ancestor,
+            depth: self.depth + (self.common_ancestor.0 - earlier.common_ancestor.0),
+            head: self.head,
+        }
+    }
+}
+
+/// Keeps track of the reorgs a consumer of the block stream already handled.
+/// Since reorgs stay attached to the blocks following them, the same reorg is
+/// usually observed several times.
+#[derive(Debug, Default)]
+pub struct ReorgTracker(Option<Reorg>);
+
+impl ReorgTracker {
+    /// Returns the reorg attached to the block if it wasn't handled yet.
+    pub fn unhandled(&mut self, block: &BlockInfo) -> Option<Reorg> {
+        let reorg = block.reorg.filter(|reorg| self.0 != Some(*reorg))?;
+        self.0 = Some(reorg);
+        Some(reorg)
+    }
 }
 
 impl Default for BlockInfo {
@@ -68,8 +126,10 @@ impl Default for BlockInfo {
             parent_hash: Default::default(),
             timestamp: Default::default(),
             gas_limit: Default::default(),
+            gas_used: Default::default(),
             gas_price: Default::default(),
             observed_at: Instant::now(),
+            reorg: None,
         }
     }
 }
@@ -81,6 +141,7 @@ impl PartialEq<Self> for BlockInfo {
             && self.parent_hash == other.parent_hash
             && self.timestamp == other.timestamp
             && self.gas_limit == other.gas_limit
+            && self.gas_used == other.gas_used
             && self.gas_price == other.gas_price
     }
 }
@@ -95,12 +156,40 @@ impl TryFrom<Block<H256>> for BlockInfo {
             parent_hash: value.parent_hash,
             timestamp: value.timestamp.as_u64(),
             gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
             gas_price: value.base_fee_per_gas.context("no gas price")?,
             observed_at: Instant::now(),
+            reorg: None,
         })
     }
 }
 
+impl TryFrom<BlockHeader> for BlockInfo {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BlockHeader) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            number: value.number.context("block missing number")?.as_u64(),
+            hash: value.hash.context("block missing hash")?,
+            parent_hash: value.parent_hash,
+            timestamp: value.timestamp.as_u64(),
+            gas_limit: value.gas_limit,
+            gas_used: value.gas_used,
+            gas_price: value.base_fee_per_gas.context("no gas price")?,
+            observed_at: Instant::now(),
+            reorg: None,
+        })
+    }
+}
+
+/// How long to fall back to polling after the `newHeads` subscription failed
+/// before trying to subscribe again.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(10);
+
+/// How many blocks of history the block stream keeps around for finding the
+/// common ancestor of a reorg.
+const MAX_REORG_DEPTH: u64 = 64;
+
 /// Creates a cloneable stream that yields the current block whenever it
 /// changes.
 ///
@@ -110,6 +199,10 @@ impl TryFrom<Block<H256>> for BlockInfo {
 /// in quick succession we might only observe the last block, skipping some
 /// blocks in between.
 ///
+/// Whenever the new block is not a descendant of the previous one, the stream
+/// looks up the common ancestor of the old and the new chain and yields the
+/// block with [`BlockInfo::reorg`] set.
+///
 /// The stream is cloneable so that we only have to poll the node once while
 /// being able to share the result with several consumers. Calling this function
 /// again would create a new poller so it is preferable to clone an existing
@@ -131,48 +224,238 @@ pub async fn current_block_stream(
 
     let (sender, receiver) = watch::channel(first_block);
     let update_future = async move {
-        let mut previous_block = first_block;
+        let mut updater = Updater::new(sender);
         loop {
             tokio::time::sleep(poll_interval).await;
-            let block = match web3.current_block().await {
-                Ok(block) => block,
-                Err(err) => {
-                    tracing::warn!("failed to get current block: {:?}", err);
-                    continue;
+            if updater.poll(&web3).await.is_err() {
+                break;
+            }
+        }
+    };
+
+    tokio::task::spawn(update_future.instrument(tracing::info_span!("current_block_stream")));
+    Ok(receiver)
+}
+
+/// Like [`current_block_stream`] but gets notified of new blocks with a
+/// `newHeads` subscription over the node's WebSocket endpoint instead of
+/// polling. This allows new blocks to be observed with lower latency.
+///
+/// Whenever no new block was received within `poll_interval`, the current
+/// block is polled over HTTP instead. If the subscription fails, for example
+/// because the connection was closed, the stream falls back to polling and
+/// periodically tries to subscribe again.
+pub async fn current_block_ws_stream(
+    url: Url,
+    ws_url: Url,
+    poll_interval: Duration,
+) -> Result<CurrentBlockWatcher> {
+    let web3 = Web3::new(Web3Transport::new(HttpTransport::new(
+        Default::default(),
+        url,
+        "block_stream".into(),
+    )));
+    let web3 = instrument_with_label(&web3, "base_currentBlockStream".into());
+    let first_block = web3.current_block().await?;
+    tracing::debug!(number=%first_block.number, hash=?first_block.hash, "polled block");
+
+    let (sender, receiver) = watch::channel(first_block);
+    let update_future = async move {
+        let mut updater = Updater::new(sender);
+        loop {
+            match subscribe_new_heads(ws_url.clone()).await {
+                Ok(mut heads) => loop {
+                    let update = match tokio::time::timeout(poll_interval, heads.next()).await {
+                        Ok(Some(Ok(block))) => updater.update(block, &web3).await,
+                        Ok(Some(Err(err))) => {
+                            tracing::warn!(?err, "new heads subscription failed");
+                            break;
+                        }
+                        Ok(None) => {
+                            tracing::warn!("new heads subscription closed");
+                            break;
+                        }
+                        Err(_) => updater.poll(&web3).await,
+                    };
+                    if update.is_err() {
+                        return;
+                    }
+                },
+                Err(err) => tracing::warn!(?err, "failed to subscribe to new heads"),
+            }
+
+            let resubscribe = Instant::now() + RESUBSCRIBE_DELAY;
+            while Instant::now() < resubscribe {
+                tokio::time::sleep(poll_interval).await;
+                if updater.poll(&web3).await.is_err() {
+                    return;
                 }
-            };
+            }
+        }
+    };
 
-            // If the block is exactly the same, ignore it.
-            if previous_block.hash == block.hash {
-                continue;
+    tokio::task::spawn(update_future.instrument(tracing::info_span!("current_block_ws_stream")));
+    Ok(receiver)
+}
+
+async fn subscribe_new_heads(
+    ws_url: Url,
+) -> Result<impl futures::Stream<Item = Result<BlockInfo>> + Unpin> {
+    let ws = WsTransport::connect(ws_url, "block_stream".into()).await?;
+    let heads = ws.new_heads().await?;
+    Ok(heads.map(|header| header.and_then(BlockInfo::try_from)))
+}
+
+/// Keeps track of the current block and pushes new blocks into the block
+/// stream.
+struct Updater {
+    sender: watch::Sender<BlockInfo>,
+    previous_block: BlockInfo,
+    /// Recently observed blocks of the current chain, oldest first. Used for
+    /// finding the common ancestor when a reorg happens.
+    recent_blocks: VecDeque<BlockNumberHash>,
+}
+
+/// Error indicating that all receivers of the block stream were dropped.
+struct Closed;
+
+impl Updater {
+    fn new(sender: watch::Sender<BlockInfo>) -> Self {
+        let previous_block = *sender.borrow();
+        Self {
+            sender,
+            previous_block,
+            recent_blocks: VecDeque::from([(previous_block.number, previous_block.hash)]),
+        }
+    }
+
+    /// Polls the current block from the node and updates the stream with it.
+    async fn poll(&mut self, web3: &dyn BlockRetrieving) -> Result<(), Closed> {
+        match web3.current_block().await {
+            Ok(block) => self.update(block, web3).await,
+            Err(err) => {
+                tracing::warn!("failed to get current block: {:?}", err);
+                Ok(())
             }
+        }
+    }
 
-            // The new block is different but might still have the same number.
+    /// Updates the stream with the specified block if it is newer than the
+    /// current one. The node is used for finding the common ancestor in case
+    /// the block is not a descendant of the current one.
+    async fn update(&mut self, block: BlockInfo, web3: &dyn BlockRetrieving) -> Result<(), Closed> {
+        // If the block is exactly the same, ignore it.
+        if self.previous_block.hash == block.hash {
+            return Ok(());
+        }
 
-            tracing::debug!(number=%block.number, hash=?block.hash, "polled block");
-            update_block_metrics(previous_block.number, block.number);
+        // The new block is different but might still have the same number.
 
-            // Only update the stream if the number has increased.
-            if block.number <= previous_block.number {
-                continue;
+        tracing::debug!(number=%block.number, hash=?block.hash, "observed block");
+        update_block_metrics(self.previous_block.number, block.number);
+
+        // Only update the stream if the number has increased.
+        if block.number <= self.previous_block.number {
+            return Ok(());
+        }
+
+        let reorg = self.detect_reorg(&block, web3).await;
+        if let Some(reorg) = reorg {
+            tracing::warn!(
+                number = %block.number,
+                hash = ?block.hash,
+                common_ancestor = ?reorg.common_ancestor,
+                depth = %reorg.depth,
+                "noticed a reorg",
+            );
+            update_reorg_metrics(reorg.depth);
+        }
+
+        // Keep the previous reorg attached until it is older than the tracked
+        // history, so that consumers which skip blocks don't miss it.
+        let block = BlockInfo {
+            reorg: match (reorg, self.previous_block.reorg) {
+                (Some(reorg), Some(earlier)) => Some(reorg.merge(earlier)),
+                (reorg, earlier) => reorg.or(earlier),
             }
+            .filter(|reorg| reorg.common_ancestor.0 + MAX_REORG_DEPTH >= block.number),
+            ..block
+        };
+
+        tracing::info!(number=%block.number, hash=?block.hash, "noticed a new block");
+        if self.sender.send(block).is_err() {
+            tracing::debug!("exiting polling loop");
+            return Err(Closed);
+        }
 
-            tracing::info!(number=%block.number, hash=?block.hash, "noticed a new block");
-            if sender.send(block).is_err() {
-                tracing::debug!("exiting polling loop");
-                break;
+        if let Some(reorg) = reorg {
+            self.recent_blocks
+                .retain(|(number, _)| *number <= reorg.common_ancestor.0);
+        }
+        self.recent_blocks.push_back((block.number, block.hash));
+        while self
+            .recent_blocks
+            .front()
+            .is_some_and(|(number, _)| number + MAX_REORG_DEPTH < block.number)
+        {
+            self.recent_blocks.pop_front();
+        }
+        self.previous_block = block;
+        Ok(())
+    }
+
+    /// Checks whether the new block replaced the previously observed chain
+    /// and if so, finds the most recent block both chains have in common.
+    async fn detect_reorg(&self, block: &BlockInfo, web3: &dyn BlockRetrieving) -> Option<Reorg> {
+        // Most of the time the new block is a direct child of the previous one.
+        if block.parent_hash == self.previous_block.hash {
+            return None;
+        }
+
+        // Either we skipped some blocks or the previous block got reorged out,
+        // so we need to ask the node which of the blocks we've seen are still
+        // part of the chain.
+        let common_ancestor = match self.find_canonical_block(web3).await {
+            Ok(block) => block,
+            Err(err) => {
+                tracing::warn!(?err, "failed to find common ancestor of new block");
+                return None;
             }
+        };
+
+        let previous = (self.previous_block.number, self.previous_block.hash);
+        (common_ancestor != previous).then(|| Reorg {
+            common_ancestor,
+            head: (block.number, block.hash),
+            depth: self.previous_block.number.saturating_sub(common_ancestor.0),
+        })
+    }
 
-            previous_block = block;
+    /// Returns the most recent observed block that is still part of the
+    /// node's canonical chain. If none of them are, the reorg is deeper than
+    /// the tracked history and the block right before it is returned.
+    async fn find_canonical_block(&self, web3: &dyn BlockRetrieving) -> Result<BlockNumberHash> {
+        for (number, hash) in self.recent_blocks.iter().rev() {
+            let canonical = web3.block(*number).await?;
+            if canonical.1 == *hash {
+                return Ok(canonical);
+            }
         }
-    };
 
-    tokio::task::spawn(update_future.instrument(tracing::info_span!("current_block_stream")));
-    Ok(receiver)
+        let oldest = self
+            .recent_blocks
+            .front()
+            .context("no recent blocks")?
+            .0
+            .saturating_sub(1);
+        tracing::warn!(%oldest, "reorg is deeper than the tracked block history");
+        web3.block(oldest).await
+    }
 }
 
 /// Returns a stream that is synchronized to the passed in stream by only yields
-/// every nth update of the original stream.
+/// every nth update of the original stream. Reorgs in skipped updates are not
+/// lost, since they stay attached to the following blocks.
 pub fn throttle(blocks: CurrentBlockWatcher, updates_to_skip: NonZeroU64) -> CurrentBlockWatcher {
     let first_block = *blocks.borrow();
 
@@ -342,6 +625,10 @@ pub struct Metrics {
     /// How much a new block number differs from the current block number.
     #[metric(buckets(0., 1., 2., 4., 8., 25.), labels("sign"))]
     block_stream_update_delta: prometheus::HistogramVec,
+
+    /// How many blocks got replaced by a reorg.
+    #[metric(buckets(1., 2., 4., 8., 16., 32., 64.))]
+    block_stream_reorg_depth: prometheus::Histogram,
 }
 
 /// Updates metrics about the difference of the new block number compared to the
@@ -359,6 +646,14 @@ fn update_block_metrics(current_block: u64, new_block: u64) {
     }
 }
 
+/// Records the depth of an observed reorg.
+fn update_reorg_metrics(depth: u64) {
+    Metrics::instance(observe::metrics::get_storage_registry())
+        .unwrap()
+        .block_stream_reorg_depth
+        .observe(depth as f64);
+}
+
 /// Awaits and returns the next block that will be pushed into the stream.
 pub async fn next_block(current_block: &CurrentBlockWatcher) -> BlockInfo {
     let mut stream = into_stream(current_block.clone());
@@ -479,6 +774,181 @@ mod tests {
         assert_eq!(block.number, 4);
     }
 
+    /// A node whose canonical chain consists of the specified block hashes.
+    #[derive(Debug)]
+    struct FakeChain(Vec<H256>);
+
+    #[async_trait::async_trait]
+    impl BlockRetrieving for FakeChain {
+        async fn current_block(&self) -> Result<BlockInfo> {
+            let number = self
+                .0
+                .len()
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("empty chain"))?;
+            Ok(BlockInfo {
+                number: number as u64,
+                hash: self.0[number],
+                ..Default::default()
+            })
+        }
+
+        async fn block(&self, number: u64) -> Result<BlockNumberHash> {
+            let hash = self
+                .0
+                .get(number as usize)
+                .ok_or_else(|| anyhow!("unknown block"))?;
+            Ok((number, *hash))
+        }
+
+        async fn blocks(&self, range: RangeInclusive<u64>) -> Result<Vec<BlockNumberHash>> {
+            let mut blocks = Vec::new();
+            for number in range {
+                blocks.push(self.block(number).await?);
+            }
+            Ok(blocks)
+        }
+    }
+
+    #[tokio::test]
+    async fn updater_only_forwards_newer_blocks() {
+        let block = |number: u64, hash: u8| BlockInfo {
+            number,
+            hash: H256::repeat_byte(hash),
+            ..Default::default()
+        };
+        let chain = FakeChain([0, 1, 3].map(H256::repeat_byte).to_vec());
+        let (sender, receiver) = watch::channel(block(1, 1));
+        let mut updater = Updater::new(sender);
+
+        // Same block and a sibling block at the same height are ignored.
+        assert!(updater.update(block(1, 1), &chain).await.is_ok());
+        assert!(updater.update(block(1, 2), &chain).await.is_ok());
+        assert_eq!(*receiver.borrow(), block(1, 1));
+
+        assert!(updater.update(block(2, 3), &chain).await.is_ok());
+        assert_eq!(*receiver.borrow(), block(2, 3));
+        assert_eq!(receiver.borrow().reorg, None);
+
+        // Older blocks, for example from a lagging node, are ignored.
+        assert!(updater.update(block(1, 4), &chain).await.is_ok());
+        assert_eq!(*receiver.borrow(), block(2, 3));
+
+        drop(receiver);
+        assert!(updater.update(block(3, 5), &chain).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn updater_detects_reorgs() {
+        let block = |number: u64, hash: u8, parent: u8| BlockInfo {
+            number,
+            hash: H256::repeat_byte(hash),
+            parent_hash: H256::repeat_byte(parent),
+            ..Default::default()
+        };
+        let (sender, receiver) = watch::channel(block(1, 1, 0));
+        let mut updater = Updater::new(sender);
+
+        // Extending the chain is not a reorg.
+        let chain = FakeChain([0, 1, 2, 3, 4, 5].map(H256::repeat_byte).to_vec());
+        updater.update(block(2, 2, 1), &chain).await.ok();
+        updater.update(block(3, 3, 2), &chain).await.ok();
+        assert_eq!(receiver.borrow().reorg, None);
+
+        // Skipping blocks is not a reorg either.
+        updater.update(block(5, 5, 4), &chain).await.ok();
+        assert_eq!(*receiver.borrow(), block(5, 5, 4));
+        assert_eq!(receiver.borrow().reorg, None);
+
+        // Blocks 4 and 5 got replaced.
+        let chain = FakeChain([0, 1, 2, 3, 14, 15, 16, 17].map(H256::repeat_byte).to_vec());
+        updater.update(block(6, 16, 15), &chain).await.ok();
+        assert_eq!(*receiver.borrow(), block(6, 16, 15));
+        assert_eq!(
+            receiver.borrow().reorg,
+            Some(Reorg {
+                common_ancestor: (3, H256::repeat_byte(3)),
+                head: (6, H256::repeat_byte(16)),
+                depth: 2,
+            })
+        );
+
+        // Blocks are still forwarded if the common ancestor can't be found.
+        let chain = FakeChain(vec![]);
+        let (sender, receiver) = watch::channel(block(1, 1, 0));
+        let mut updater = Updater::new(sender);
+        updater.update(block(2, 12, 99), &chain).await.ok();
+        assert_eq!(*receiver.borrow(), block(2, 12, 99));
+        assert_eq!(receiver.borrow().reorg, None);
+    }
+
+    #[tokio::test]
+    async fn reorgs_stay_attached_to_following_blocks() {
+        let block = |number: u64, hash: u8, parent: u8| BlockInfo {
+            number,
+            hash: H256::repeat_byte(hash),
+            parent_hash: H256::repeat_byte(parent),
+            ..Default::default()
+        };
+        let (sender, mut receiver) = watch::channel(block(3, 3, 2));
+        let mut updater = Updater::new(sender);
+        let mut tracker = ReorgTracker::default();
+
+        // Block 3 gets replaced, and the new chain gets extended before the
+        // consumer reads the stream.
+        let chain = FakeChain([0, 1, 2, 13, 14, 15].map(H256::repeat_byte).to_vec());
+        updater.update(block(4, 14, 13), &chain).await.ok();
+        updater.update(block(5, 15, 14), &chain).await.ok();
+        let first = Reorg {
+            common_ancestor: (2, H256::repeat_byte(2)),
+            head: (4, H256::repeat_byte(14)),
+            depth: 1,
+        };
+        assert_eq!(*receiver.borrow_and_update(), block(5, 15, 14));
+        assert_eq!(receiver.borrow().reorg, Some(first));
+        assert_eq!(tracker.unhandled(&receiver.borrow()), Some(first));
+
+        // The consumer only handles the reorg once.
+        let chain = FakeChain([0, 1, 2, 13, 14, 15, 16].map(H256::repeat_byte).to_vec());
+        updater.update(block(6, 16, 15), &chain).await.ok();
+        assert_eq!(tracker.unhandled(&receiver.borrow_and_update()), None);
+
+        // A second reorg gets merged with the one that is still attached, so
+        // that consumers which missed either of them notice both.
+        let chain = FakeChain(
+            [0, 1, 2, 23, 24, 25, 26, 27]
+                .map(H256::repeat_byte)
+                .to_vec(),
+        );
+        updater.update(block(7, 27, 26), &chain).await.ok();
+        let merged = Reorg {
+            common_ancestor: (2, H256::repeat_byte(2)),
+            head: (7, H256::repeat_byte(27)),
+            depth: 4,
+        };
+        assert_eq!(receiver.borrow().reorg, Some(merged));
+        assert_eq!(
+            tracker.unhandled(&receiver.borrow_and_update()),
+            Some(merged)
+        );
+
+        // The reorg gets dropped once it is older than the tracked history.
+        let mut parent = H256::repeat_byte(27);
+        for number in 8..=2 + MAX_REORG_DEPTH + 1 {
+            let hash = H256::from_low_u64_be(number);
+            let next = BlockInfo {
+                number,
+                hash,
+                parent_hash: parent,
+                ..Default::default()
+            };
+            updater.update(next, &chain).await.ok();
+            parent = hash;
+        }
+        assert_eq!(receiver.borrow().number, 2 + MAX_REORG_DEPTH + 1);
+        assert_eq!(receiver.borrow().reorg, None);
+    }
+
     #[tokio::test]
     async fn test_next_block() {
         let (sender, receiver) = watch::channel(new_block(0));
@@ -5,10 +5,12 @@ pub mod extensions;
 pub mod http;
 pub mod instrumented;
 pub mod mock;
+pub mod multi;
 pub mod multicall;
+pub mod ws;
 
 use {
-    self::{buffered::BufferedTransport, http::HttpTransport},
+    self::{buffered::BufferedTransport, http::HttpTransport, multi::MultiTransport},
     ethcontract::{batch::CallBatch, dyns::DynWeb3, transport::DynTransport},
     reqwest::{Client, Url},
     std::{num::NonZeroUsize, time::Duration},
@@ -33,12 +35,23 @@ pub struct Config {
     /// Buffering "nagle" delay to wait for additional requests before sending
     /// out an incomplete batch.
     pub ethrpc_batch_delay: Duration,
+
+    /// Number of endpoints that need to agree on the result of calls to one of
+    /// the `ethrpc_quorum_methods` when multiple endpoints are configured.
+    pub ethrpc_quorum: NonZeroUsize,
+
+    /// JSON RPC methods that require a quorum of endpoints to agree.
+    pub ethrpc_quorum_methods: Vec<String>,
+
+    /// How long to avoid an endpoint after it failed when multiple endpoints
+    /// are configured.
+    pub ethrpc_failure_backoff: Duration,
 }
 
 impl Config {
     /// Returns the buffered transport configuration or `None` if batching is
     /// disabled.
-    fn into_buffered_configuration(self) -> Option<buffered::Configuration> {
+    fn buffered_configuration(&self) -> Option<buffered::Configuration> {
         match (
             self.ethrpc_max_batch_size,
             self.ethrpc_max_concurrent_requests,
@@ -51,14 +64,27 @@ impl Config {
             }),
         }
     }
+
+    /// Returns the multi-endpoint transport configuration.
+    fn multi_configuration(&self) -> multi::Configuration {
+        multi::Configuration {
+            quorum: self.ethrpc_quorum,
+            quorum_methods: self.ethrpc_quorum_methods.iter().cloned().collect(),
+            failure_backoff: self.ethrpc_failure_backoff,
+        }
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
+        let multi = multi::Configuration::default();
         Self {
             ethrpc_max_batch_size: 20,
             ethrpc_max_concurrent_requests: 10,
             ethrpc_batch_delay: Default::default(),
+            ethrpc_quorum: multi.quorum,
+            ethrpc_quorum_methods: multi.quorum_methods.into_iter().collect(),
+            ethrpc_failure_backoff: multi.failure_backoff,
         }
     }
 }
@@ -72,11 +98,56 @@ pub fn web3(
 ) -> Web3 {
     let http = http_factory.cookie_store(true).build().unwrap();
     let http = HttpTransport::new(http, url.clone(), name.to_string());
-    let transport = match args.into_buffered_configuration() {
-        Some(config) => Web3Transport::new(BufferedTransport::with_config(http, config)),
-        None => Web3Transport::new(http),
+    wrap_transport(&args, Web3Transport::new(http), name.to_string())
+}
+
+/// Create a Web3 instance that load-balances requests across multiple node
+/// endpoints, failing over to other endpoints on errors. See [`multi`] for more
+/// details.
+///
+/// # Panics
+///
+/// Panics if no URLs are specified or if the configured quorum exceeds the
+/// number of URLs.
+pub fn multi_web3(
+    args: Config,
+    http_factory: reqwest::ClientBuilder,
+    urls: &[Url],
+    name: impl ToString,
+) -> Web3 {
+    assert!(
+        args.ethrpc_quorum.get() <= urls.len(),
+        "ethrpc quorum of {} exceeds the {} configured node URLs",
+        args.ethrpc_quorum,
+        urls.len(),
+    );
+    let name = name.to_string();
+    let http = http_factory.cookie_store(true).build().unwrap();
+    let transport = match urls {
+        [url] => Web3Transport::new(HttpTransport::new(http, url.clone(), name.clone())),
+        urls => {
+            let endpoints = urls
+                .iter()
+                .enumerate()
+                .map(|(i, url)| {
+                    let name = format!("{name}_{i}");
+                    let http = HttpTransport::new(http.clone(), url.clone(), name.clone());
+                    (name, Web3Transport::new(http))
+                })
+                .collect();
+            Web3Transport::new(MultiTransport::new(endpoints, args.multi_configuration()))
+        }
+    };
+    wrap_transport(&args, transport, name)
+}
+
+/// Wraps a transport with request batching (if enabled) and instrumentation.
+fn wrap_transport(args: &Config, transport: Web3Transport, name: String) -> Web3 {
+    let transport = match args.buffered_configuration() {
+        Some(config) => Web3Transport::new(BufferedTransport::with_config(transport, config)),
+        None => transport,
     };
-    let instrumented = instrumented::InstrumentedTransport::new(name.to_string(), transport);
+    let instrumented = instrumented::InstrumentedTransport::new(name, transport);
     Web3::new(Web3Transport::new(instrumented))
 }
 
@@ -0,0 +1,589 @@
+//! A `Transport` implementation that load-balances JSON RPC requests across
+//! multiple node endpoints.
+//!
+//! Requests are sent to the endpoint with the best score, which is computed
+//! from its observed latency and the number of requests that are currently in
+//! flight. If an endpoint fails with a transport error or is rate limited, the
+//! request fails over to the next endpoint and the failing endpoint is avoided
+//! for some time. Calls to configurable methods can additionally require a
+//! quorum of endpoints to agree on the result.
+
+use {
+    ethcontract::{
+        jsonrpc::Call,
+        transport::DynTransport,
+        web3::{BatchTransport, Error as Web3Error, RequestId, Transport, helpers},
+    },
+    futures::{
+        FutureExt as _,
+        future::{self, BoxFuture},
+    },
+    serde_json::{Value, json},
+    std::{
+        collections::HashSet,
+        fmt::{self, Debug, Formatter},
+        num::NonZeroUsize,
+        sync::{
+            Arc,
+            Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// Multi-endpoint transport configuration.
+#[derive(Clone, Debug)]
+pub struct Configuration {
+    /// The number of endpoints that need to agree on the result of calls to
+    /// one of the `quorum_methods`.
+    pub quorum: NonZeroUsize,
+    /// The JSON RPC methods that require a quorum.
+    pub quorum_methods: HashSet<String>,
+    /// How long to avoid an endpoint after it failed. This doubles with every
+    /// consecutive failure up to `8 * failure_backoff`.
+    pub failure_backoff: Duration,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            quorum: NonZeroUsize::MIN,
+            quorum_methods: HashSet::from(["eth_getLogs".to_string()]),
+            failure_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+/// `Transport` implementation that distributes requests across multiple
+/// endpoints. See the module documentation for more details.
+#[derive(Clone)]
+pub struct MultiTransport(Arc<Inner>);
+
+struct Inner {
+    endpoints: Vec<Endpoint>,
+    config: Configuration,
+    id: AtomicUsize,
+    metrics: &'static Metrics,
+}
+
+struct Endpoint {
+    name: String,
+    transport: DynTransport,
+    inflight: AtomicUsize,
+    health: Mutex<Health>,
+}
+
+#[derive(Default)]
+struct Health {
+    /// Exponentially weighted moving average of the request latency. `None` if
+    /// no request completed yet.
+    latency: Option<Duration>,
+    consecutive_failures: u32,
+    unavailable_until: Option<Instant>,
+}
+
+#[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
+#[metric(subsystem = "rpc")]
+struct Metrics {
+    /// Number of requests sent to an endpoint by result.
+    #[metric(labels("endpoint", "result"))]
+    endpoint_requests: prometheus::IntCounterVec,
+
+    /// Moving average of the request latency of an endpoint.
+    #[metric(labels("endpoint"))]
+    endpoint_latency_seconds: prometheus::GaugeVec,
+
+    /// Whether an endpoint is currently considered available.
+    #[metric(labels("endpoint"))]
+    endpoint_available: prometheus::IntGaugeVec,
+}
+
+type RpcResult = Result<Value, Web3Error>;
+
+impl MultiTransport {
+    /// Creates a new transport for the specified named endpoints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no endpoints are specified or if the quorum exceeds the
+    /// number of endpoints.
+    pub fn new(endpoints: Vec<(String, DynTransport)>, config: Configuration) -> Self {
+        assert!(!endpoints.is_empty(), "no endpoints specified");
+        assert!(
+            config.quorum.get() <= endpoints.len(),
+            "quorum of {} exceeds the {} specified endpoints",
+            config.quorum,
+            endpoints.len(),
+        );
+        let metrics = Metrics::instance(observe::metrics::get_storage_registry()).unwrap();
+        let endpoints = endpoints
+            .into_iter()
+            .map(|(name, transport)| {
+                metrics
+                    .endpoint_available
+                    .with_label_values(&[&name])
+                    .set(1);
+                Endpoint {
+                    name,
+                    transport,
+                    inflight: AtomicUsize::new(0),
+                    health: Default::default(),
+                }
+            })
+            .collect();
+        Self(Arc::new(Inner {
+            endpoints,
+            config,
+            id: AtomicUsize::new(0),
+            metrics,
+        }))
+    }
+}
+
+impl Inner {
+    /// Returns the endpoint indices in the order in which they should be
+    /// tried. Available endpoints are ordered by their score, followed by the
+    /// unavailable ones ordered by when they become available again.
+    fn ranked(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut available = Vec::new();
+        let mut unavailable = Vec::new();
+        for (i, endpoint) in self.endpoints.iter().enumerate() {
+            let health = endpoint.health.lock().unwrap();
+            match health.unavailable_until {
+                Some(until) if until > now => unavailable.push((until, i)),
+                _ => {
+                    let inflight = endpoint.inflight.load(Ordering::Relaxed);
+                    let latency = health.latency.unwrap_or_default();
+                    let score =
+                        latency.saturating_mul(u32::try_from(inflight + 1).unwrap_or(u32::MAX));
+                    available.push((score, i));
+                }
+            }
+        }
+        available.sort();
+        unavailable.sort();
+        available
+            .into_iter()
+            .map(|(_, i)| i)
+            .chain(unavailable.into_iter().map(|(_, i)| i))
+            .collect()
+    }
+
+    fn requires_quorum(&self, call: &Call) -> bool {
+        let Call::MethodCall(call) = call else {
+            return false;
+        };
+        self.config.quorum.get() > 1 && self.config.quorum_methods.contains(&call.method)
+    }
+
+    fn record_success(&self, endpoint: &Endpoint, latency: Duration) {
+        let mut health = endpoint.health.lock().unwrap();
+        let latency = match health.latency {
+            Some(average) => (average * 4 + latency) / 5,
+            None => latency,
+        };
+        health.latency = Some(latency);
+        health.consecutive_failures = 0;
+        health.unavailable_until = None;
+
+        self.metrics
+            .endpoint_requests
+            .with_label_values(&[&endpoint.name, "success"])
+            .inc();
+        self.metrics
+            .endpoint_latency_seconds
+            .with_label_values(&[&endpoint.name])
+            .set(latency.as_secs_f64());
+        self.metrics
+            .endpoint_available
+            .with_label_values(&[&endpoint.name])
+            .set(1);
+    }
+
+    fn record_failure(&self, endpoint: &Endpoint, err: &Web3Error) {
+        let mut health = endpoint.health.lock().unwrap();
+        let backoff = self.config.failure_backoff * (1 << health.consecutive_failures.min(3));
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        health.unavailable_until = Some(Instant::now() + backoff);
+        tracing::warn!(endpoint = %endpoint.name, ?err, ?backoff, "endpoint failed");
+
+        self.metrics
+            .endpoint_requests
+            .with_label_values(&[&endpoint.name, "failure"])
+            .inc();
+        self.metrics
+            .endpoint_available
+            .with_label_values(&[&endpoint.name])
+            .set(0);
+    }
+
+    /// Sends a request to the endpoint with the specified index, keeping track
+    /// of its health.
+    async fn send_to<T>(
+        &self,
+        index: usize,
+        request: impl Fn(&DynTransport) -> BoxFuture<'static, Result<T, Web3Error>>,
+    ) -> Result<T, Web3Error> {
+        let endpoint = &self.endpoints[index];
+        endpoint.inflight.fetch_add(1, Ordering::Relaxed);
+        let _inflight = scopeguard::guard((), |_| {
+            endpoint.inflight.fetch_sub(1, Ordering::Relaxed);
+        });
+
+        let start = Instant::now();
+        let result = request(&endpoint.transport).await;
+        match &result {
+            Err(err) if should_fail_over(err) => self.record_failure(endpoint, err),
+            _ => self.record_success(endpoint, start.elapsed()),
+        }
+        result
+    }
+
+    /// Sends a request to the best endpoint, failing over to the next one on
+    /// errors.
+    async fn failover<T>(
+        &self,
+        request: impl Fn(&DynTransport) -> BoxFuture<'static, Result<T, Web3Error>>,
+    ) -> Result<T, Web3Error> {
+        let mut last_err = None;
+        for index in self.ranked() {
+            match self.send_to(index, &request).await {
+                Err(err) if should_fail_over(&err) => last_err = Some(err),
+                result => return result,
+            }
+        }
+        Err(last_err.expect("at least one endpoint"))
+    }
+
+    /// Sends a call to the best endpoints until `quorum` of them agree on the
+    /// result.
+    async fn quorum(&self, id: RequestId, call: Call) -> RpcResult {
+        let quorum = self.config.quorum.get();
+        let mut ranked = self.ranked().into_iter();
+        let send = |index: usize| {
+            let call = call.clone();
+            self.send_to(index, move |transport| transport.send(id, call.clone()))
+        };
+
+        // Query the minimum number of endpoints needed concurrently, and only
+        // query additional endpoints one by one if they don't agree.
+        let mut results = future::join_all(ranked.by_ref().take(quorum).map(&send)).await;
+        // Responses are tallied by their normalized form, along with the first
+        // response in that form which gets returned.
+        let mut tally = Vec::<(Value, Value, usize)>::new();
+        let mut last_err = None;
+        loop {
+            for result in results.drain(..) {
+                match result {
+                    Ok(value) => {
+                        let key = normalize(&call, &value);
+                        match tally.iter_mut().find(|(k, _, _)| *k == key) {
+                            Some((_, _, count)) => *count += 1,
+                            None => tally.push((key, value, 1)),
+                        }
+                    }
+                    // The call failed for reasons other than the endpoint, so
+                    // there is no point in asking other endpoints.
+                    Err(err) if !should_fail_over(&err) => return Err(err),
+                    Err(err) => last_err = Some(err),
+                }
+            }
+            if let Some(position) = tally.iter().position(|(_, _, count)| *count >= quorum) {
+                return Ok(tally.swap_remove(position).1);
+            }
+
+            let Some(index) = ranked.next() else {
+                break;
+            };
+            results.push(send(index).await);
+        }
+
+        tracing::warn!(
+            ?call,
+            responses = tally.len(),
+            "endpoints did not reach quorum"
+        );
+        Err(match (tally.is_empty(), last_err) {
+            (true, Some(err)) => err,
+            _ => {
+                Web3Error::InvalidResponse(format!("endpoints did not reach a quorum of {quorum}"))
+            }
+        })
+    }
+}
+
+/// Returns the form of a response that endpoints need to agree on for a quorum.
+/// Nodes differ in how they format numbers and in the additional fields they
+/// include in logs (like `blockTimestamp` or `removed`), so logs are compared
+/// by their canonical fields only.
+fn normalize(call: &Call, value: &Value) -> Value {
+    let Call::MethodCall(call) = call else {
+        return value.clone();
+    };
+    if call.method != "eth_getLogs" {
+        return value.clone();
+    }
+    match serde_json::from_value::<Vec<web3::types::Log>>(value.clone()) {
+        Ok(logs) => logs
+            .into_iter()
+            .map(|log| {
+                json!([
+                    log.address,
+                    log.topics,
+                    log.data,
+                    log.block_hash,
+                    log.block_number,
+                    log.transaction_hash,
+                    log.transaction_index,
+                    log.log_index,
+                ])
+            })
+            .collect(),
+        Err(_) => value.clone(),
+    }
+}
+
+/// Returns whether a request that failed with the specified error should be
+/// retried with another endpoint. This is the case for errors indicating that
+/// the endpoint is unhealthy or rate limited, but not for errors returned by
+/// the node when executing the request (like a reverting `eth_call`).
+fn should_fail_over(err: &Web3Error) -> bool {
+    match err {
+        Web3Error::Unreachable
+        | Web3Error::Transport(_)
+        | Web3Error::Decoder(_)
+        | Web3Error::InvalidResponse(_) => true,
+        Web3Error::Rpc(err) => {
+            // Common error codes and messages that providers use for rate
+            // limiting.
+            let message = err.message.to_lowercase();
+            matches!(err.code.code(), -32005 | 429)
+                || message.contains("rate limit")
+                || message.contains("too many requests")
+        }
+        _ => false,
+    }
+}
+
+impl Debug for MultiTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiTransport")
+            .field(
+                "endpoints",
+                &self.0.endpoints.iter().map(|e| &e.name).collect::<Vec<_>>(),
+            )
+            .field("config", &self.0.config)
+            .finish()
+    }
+}
+
+impl Transport for MultiTransport {
+    type Out = BoxFuture<'static, RpcResult>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        let id = self.0.id.fetch_add(1, Ordering::SeqCst);
+        let request = helpers::build_request(id, method, params);
+        (id, request)
+    }
+
+    fn send(&self, id: RequestId, call: Call) -> Self::Out {
+        let inner = self.0.clone();
+        async move {
+            if inner.requires_quorum(&call) {
+                inner.quorum(id, call).await
+            } else {
+                inner
+                    .failover(|transport| transport.send(id, call.clone()))
+                    .await
+            }
+        }
+        .boxed()
+    }
+}
+
+impl BatchTransport for MultiTransport {
+    type Batch = BoxFuture<'static, Result<Vec<RpcResult>, Web3Error>>;
+
+    fn send_batch<T>(&self, requests: T) -> Self::Batch
+    where
+        T: IntoIterator<Item = (RequestId, Call)>,
+    {
+        let inner = self.0.clone();
+        let requests = requests.into_iter().collect::<Vec<_>>();
+        async move {
+            // Batches containing calls that require a quorum are split up, so
+            // that only those calls get sent to multiple endpoints.
+            if requests.iter().any(|(_, call)| inner.requires_quorum(call)) {
+                let this = MultiTransport(inner);
+                return Ok(future::join_all(
+                    requests.into_iter().map(|(id, call)| this.send(id, call)),
+                )
+                .await);
+            }
+
+            inner
+                .failover(|transport| transport.send_batch(requests.clone()))
+                .await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::mock::MockTransport,
+        ethcontract::jsonrpc::{Error as RpcError, ErrorCode},
+    };
+
+    fn multi(endpoints: Vec<MockTransport>, config: Configuration) -> MultiTransport {
+        MultiTransport::new(
+            endpoints
+                .into_iter()
+                .enumerate()
+                .map(|(i, endpoint)| (format!("test_{i}"), DynTransport::new(endpoint)))
+                .collect(),
+            config,
+        )
+    }
+
+    fn unreachable() -> MockTransport {
+        let transport = MockTransport::new();
+        transport
+            .mock()
+            .expect_execute()
+            .returning(|_, _| Err(Web3Error::Unreachable));
+        transport
+    }
+
+    fn responding(value: Value) -> MockTransport {
+        let transport = MockTransport::new();
+        transport
+            .mock()
+            .expect_execute()
+            .returning(move |_, _| Ok(value.clone()));
+        transport
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_endpoint() {
+        let transport = multi(
+            vec![unreachable(), responding(json!("0x1"))],
+            Default::default(),
+        );
+
+        for _ in 0..3 {
+            let result = transport.execute("eth_blockNumber", vec![]).await;
+            assert_eq!(result.unwrap(), json!("0x1"));
+        }
+
+        // The failing endpoint is no longer tried first.
+        assert_eq!(transport.0.ranked(), vec![1, 0]);
+    }
+
+    #[tokio::test]
+    async fn does_not_fail_over_on_execution_errors() {
+        let reverting = MockTransport::new();
+        reverting.mock().expect_execute().returning(|_, _| {
+            Err(Web3Error::Rpc(RpcError {
+                code: ErrorCode::ServerError(3),
+                message: "execution reverted".to_string(),
+                data: None,
+            }))
+        });
+        let other = MockTransport::new();
+        other.mock().expect_execute().never();
+
+        let transport = multi(vec![reverting, other], Default::default());
+        let result = transport.execute("eth_call", vec![]).await;
+        assert!(matches!(result, Err(Web3Error::Rpc(_))));
+    }
+
+    #[tokio::test]
+    async fn requires_quorum() {
+        let config = Configuration {
+            quorum: NonZeroUsize::new(2).unwrap(),
+            ..Default::default()
+        };
+
+        let transport = multi(
+            vec![
+                responding(json!(["a"])),
+                responding(json!(["b"])),
+                responding(json!(["a"])),
+            ],
+            config.clone(),
+        );
+        let result = transport.execute("eth_getLogs", vec![]).await;
+        assert_eq!(result.unwrap(), json!(["a"]));
+
+        let transport = multi(
+            vec![
+                responding(json!(["a"])),
+                responding(json!(["b"])),
+                unreachable(),
+            ],
+            config,
+        );
+        let result = transport.execute("eth_getLogs", vec![]).await;
+        assert!(matches!(result, Err(Web3Error::InvalidResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn quorum_ignores_log_formatting() {
+        let config = Configuration {
+            quorum: NonZeroUsize::new(2).unwrap(),
+            ..Default::default()
+        };
+        let log = json!({
+            "address": "0x9008d19f58aabd9ed0d60971565aa8510560ab41",
+            "topics": [
+                "0xa07a543ab8a018198e99ca0184c93fe9050a79400a0a723441f84de1d972cc17",
+            ],
+            "data": "0x",
+            "blockHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "blockNumber": "0x10",
+            "transactionHash": "0x2222222222222222222222222222222222222222222222222222222222222222",
+            "transactionIndex": "0x1",
+            "logIndex": "0x2",
+            "removed": false,
+        });
+        let reformatted = json!({
+            "address": "0x9008D19f58AAbD9eD0D60971565AA8510560ab41",
+            "topics": [
+                "0xa07a543ab8a018198e99ca0184c93fe9050a79400a0a723441f84de1d972cc17",
+            ],
+            "data": "0x",
+            "blockHash": "0x1111111111111111111111111111111111111111111111111111111111111111",
+            "blockNumber": "0x0010",
+            "blockTimestamp": "0x6553f100",
+            "transactionHash": "0x2222222222222222222222222222222222222222222222222222222222222222",
+            "transactionIndex": "0x01",
+            "logIndex": "0x02",
+        });
+
+        let transport = multi(
+            vec![
+                responding(json!([log])),
+                responding(json!([reformatted])),
+                unreachable(),
+            ],
+            config,
+        );
+        let result = transport.execute("eth_getLogs", vec![]).await;
+        assert_eq!(result.unwrap(), json!([log]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_unreachable_quorum() {
+        let config = Configuration {
+            quorum: NonZeroUsize::new(3).unwrap(),
+            ..Default::default()
+        };
+        multi(vec![unreachable(), unreachable()], config);
+    }
+}
@@ -0,0 +1,86 @@
+use {
+    anyhow::{Context as _, Result},
+    futures::{StreamExt, stream::BoxStream},
+    std::fmt::{Debug, Formatter},
+    url::Url,
+    web3::{
+        Web3,
+        transports::WebSocket,
+        types::{BlockHeader, Filter, Log},
+    },
+};
+
+/// A WebSocket connection to a node. In addition to regular RPC requests, it
+/// supports `eth_subscribe` subscriptions for new block headers and logs.
+#[derive(Clone)]
+pub struct WsTransport {
+    web3: Web3<WebSocket>,
+    url: Url,
+    /// Name of the transport used in logs to distinguish different transports.
+    name: String,
+}
+
+impl WsTransport {
+    /// Connects to the WebSocket RPC endpoint at the specified URL.
+    pub async fn connect(url: Url, name: String) -> Result<Self> {
+        let transport = WebSocket::new(url.as_str())
+            .await
+            .with_context(|| format!("failed to connect to {name} websocket"))?;
+        tracing::debug!(%name, "connected to websocket");
+        Ok(Self {
+            web3: Web3::new(transport),
+            url,
+            name,
+        })
+    }
+
+    /// Returns the underlying transport. This can be used for sending regular
+    /// RPC requests over the same connection.
+    pub fn transport(&self) -> &WebSocket {
+        self.web3.transport()
+    }
+
+    /// Subscribes to new block headers (`newHeads`). A header is emitted every
+    /// time the node's chain head changes, including on reorgs.
+    ///
+    /// The stream ends when the connection is closed.
+    pub async fn new_heads(&self) -> Result<BoxStream<'static, Result<BlockHeader>>> {
+        let subscription = self
+            .web3
+            .eth_subscribe()
+            .subscribe_new_heads()
+            .await
+            .context("failed to subscribe to new heads")?;
+        tracing::debug!(name = %self.name, id = ?subscription.id(), "subscribed to new heads");
+        Ok(subscription
+            .map(|header| header.context("invalid new heads notification"))
+            .boxed())
+    }
+
+    /// Subscribes to logs matching the specified filter (`logs`). Note that
+    /// logs of blocks that get reorged out are emitted again with `removed`
+    /// set.
+    ///
+    /// The stream ends when the connection is closed.
+    pub async fn logs(&self, filter: Filter) -> Result<BoxStream<'static, Result<Log>>> {
+        let subscription = self
+            .web3
+            .eth_subscribe()
+            .subscribe_logs(filter)
+            .await
+            .context("failed to subscribe to logs")?;
+        tracing::debug!(name = %self.name, id = ?subscription.id(), "subscribed to logs");
+        Ok(subscription
+            .map(|log| log.context("invalid logs notification"))
+            .boxed())
+    }
+}
+
+impl Debug for WsTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsTransport")
+            .field("url", &self.url)
+            .field("name", &self.name)
+            .finish()
+    }
+}
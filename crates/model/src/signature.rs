@@ -8,6 +8,7 @@ use {
         fmt::{self, Debug, Formatter},
     },
     web3::{
+        ethabi::{self, ParamType, Token},
         signing::{self, Key, SecretKeyRef},
         types::Recovery,
     },
@@ -199,6 +200,59 @@ pub struct Recovered {
     pub signer: H160,
 }
 
+/// The suffix identifying EIP-6492 wrapped signatures.
+pub const EIP6492_MAGIC_SUFFIX: [u8; 32] =
+    hex_literal::hex!("6492649264926492649264926492649264926492649264926492649264926492");
+
+/// An EIP-1271 signature of a smart contract wallet which is not deployed yet,
+/// wrapped together with the call deploying the wallet as specified in
+/// <https://eips.ethereum.org/EIPS/eip-6492>.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Eip6492Signature {
+    /// The factory deploying the wallet.
+    pub factory: H160,
+    /// The call to the factory deploying the wallet.
+    pub factory_calldata: Vec<u8>,
+    /// The EIP-1271 signature of the deployed wallet.
+    pub signature: Vec<u8>,
+}
+
+impl Eip6492Signature {
+    /// Unwraps an EIP-6492 signature, i.e.
+    /// `abi.encode(factory, factoryCalldata, signature) ++ magicSuffix`.
+    /// Returns `None` if the signature is not wrapped.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let encoded = bytes.strip_suffix(&EIP6492_MAGIC_SUFFIX)?;
+        let tokens = ethabi::decode(
+            &[ParamType::Address, ParamType::Bytes, ParamType::Bytes],
+            encoded,
+        )
+        .ok()?;
+        match tokens.as_slice() {
+            [
+                Token::Address(factory),
+                Token::Bytes(factory_calldata),
+                Token::Bytes(signature),
+            ] => Some(Self {
+                factory: *factory,
+                factory_calldata: factory_calldata.clone(),
+                signature: signature.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = ethabi::encode(&[
+            Token::Address(self.factory),
+            Token::Bytes(self.factory_calldata.clone()),
+            Token::Bytes(self.signature.clone()),
+        ]);
+        bytes.extend(EIP6492_MAGIC_SUFFIX);
+        bytes
+    }
+}
+
 /// An internal type used for deriving `serde` implementations for the
 /// `Signature` type.
 #[derive(Deserialize, Serialize)]
@@ -488,6 +542,26 @@ mod tests {
         assert_eq!(Signature::Eip1271(vec![1, 2, 3]).to_bytes(), vec![1, 2, 3]);
     }
 
+    #[test]
+    fn eip6492_signature_roundtrip() {
+        let signature = Eip6492Signature {
+            factory: H160([1; 20]),
+            factory_calldata: vec![2; 36],
+            signature: vec![3; 65],
+        };
+        let encoded = signature.encode();
+        assert!(encoded.ends_with(&EIP6492_MAGIC_SUFFIX));
+        assert_eq!(Eip6492Signature::parse(&encoded), Some(signature));
+
+        // Regular EIP-1271 signatures are not wrapped.
+        assert_eq!(Eip6492Signature::parse(&[3; 65]), None);
+        // Wrapped signatures must be valid ABI encoded data.
+        assert_eq!(
+            Eip6492Signature::parse(&[[0; 32], EIP6492_MAGIC_SUFFIX].concat()),
+            None
+        );
+    }
+
     #[test]
     fn ecdsa_scheme_conversion() {
         for ecdsa_scheme in [EcdsaSigningScheme::Eip712, EcdsaSigningScheme::EthSign] {
@@ -2,3 +2,4 @@
 //! validation.
 
 pub mod banned;
+pub mod permit;
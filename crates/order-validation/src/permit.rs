@@ -0,0 +1,479 @@
+//! Decoding of token approval permits. Orders commonly carry a permit as a
+//! pre-hook so that the owner doesn't need to approve the vault relayer
+//! on-chain before trading.
+
+use ethcontract::{
+    H160,
+    U256,
+    common::abi::{self, ParamType, Token},
+    web3::signing::keccak256,
+};
+
+/// The Uniswap Permit2 contract which is deployed at the same address on all
+/// chains.
+pub const PERMIT2: H160 = H160([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x22, 0xd4, 0x73, 0x03, 0x0f, 0x11, 0x6d, 0xde, 0xe9, 0xf6, 0xb4,
+    0x3a, 0xc7, 0x8b, 0xa3,
+]);
+
+const EIP2612_PERMIT: &str = "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)";
+const EIP2612_PERMIT_TYPE: &str =
+    "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+const DAI_PERMIT: &str = "permit(address,address,uint256,uint256,bool,uint8,bytes32,bytes32)";
+const DAI_PERMIT_TYPE: &str =
+    "Permit(address holder,address spender,uint256 nonce,uint256 expiry,bool allowed)";
+const PERMIT2_PERMIT: &str =
+    "permit(address,((address,uint160,uint48,uint48),address,uint256),bytes)";
+const PERMIT2_DETAILS_TYPE: &str =
+    "PermitDetails(address token,uint160 amount,uint48 expiration,uint48 nonce)";
+const PERMIT2_SINGLE_TYPE: &str = "PermitSingle(PermitDetails details,address spender,uint256 \
+                                   sigDeadline)PermitDetails(address token,uint160 amount,uint48 \
+                                   expiration,uint48 nonce)";
+
+/// A permit call approving a spender to transfer tokens of the permit's owner.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Permit {
+    /// `permit(owner, spender, value, deadline, v, r, s)` as specified by
+    /// EIP-2612.
+    Eip2612 {
+        token: H160,
+        owner: H160,
+        spender: H160,
+        value: U256,
+        deadline: U256,
+        signature: [u8; 65],
+    },
+    /// `permit(holder, spender, nonce, expiry, allowed, v, r, s)` of DAI and
+    /// the tokens copying its implementation.
+    Dai {
+        token: H160,
+        holder: H160,
+        spender: H160,
+        nonce: U256,
+        expiry: U256,
+        allowed: bool,
+        signature: [u8; 65],
+    },
+    /// `permit(owner, permitSingle, signature)` of Uniswap Permit2.
+    Permit2 {
+        owner: H160,
+        token: H160,
+        amount: U256,
+        expiration: U256,
+        nonce: U256,
+        spender: H160,
+        sig_deadline: U256,
+        signature: Vec<u8>,
+    },
+}
+
+impl Permit {
+    /// Decodes a call to `target` as a permit. Returns `None` if it is not a
+    /// permit call.
+    pub fn decode(target: H160, call_data: &[u8]) -> Option<Self> {
+        let (function, params) = call_data.split_at_checked(4)?;
+        if function == selector(EIP2612_PERMIT) {
+            let tokens = abi::decode(
+                &[
+                    ParamType::Address,
+                    ParamType::Address,
+                    ParamType::Uint(256),
+                    ParamType::Uint(256),
+                    ParamType::Uint(8),
+                    ParamType::FixedBytes(32),
+                    ParamType::FixedBytes(32),
+                ],
+                params,
+            )
+            .ok()?;
+            match tokens.as_slice() {
+                [
+                    Token::Address(owner),
+                    Token::Address(spender),
+                    Token::Uint(value),
+                    Token::Uint(deadline),
+                    v,
+                    r,
+                    s,
+                ] => Some(Self::Eip2612 {
+                    token: target,
+                    owner: *owner,
+                    spender: *spender,
+                    value: *value,
+                    deadline: *deadline,
+                    signature: ecdsa_signature(v, r, s)?,
+                }),
+                _ => None,
+            }
+        } else if function == selector(DAI_PERMIT) {
+            let tokens = abi::decode(
+                &[
+                    ParamType::Address,
+                    ParamType::Address,
+                    ParamType::Uint(256),
+                    ParamType::Uint(256),
+                    ParamType::Bool,
+                    ParamType::Uint(8),
+                    ParamType::FixedBytes(32),
+                    ParamType::FixedBytes(32),
+                ],
+                params,
+            )
+            .ok()?;
+            match tokens.as_slice() {
+                [
+                    Token::Address(holder),
+                    Token::Address(spender),
+                    Token::Uint(nonce),
+                    Token::Uint(expiry),
+                    Token::Bool(allowed),
+                    v,
+                    r,
+                    s,
+                ] => Some(Self::Dai {
+                    token: target,
+                    holder: *holder,
+                    spender: *spender,
+                    nonce: *nonce,
+                    expiry: *expiry,
+                    allowed: *allowed,
+                    signature: ecdsa_signature(v, r, s)?,
+                }),
+                _ => None,
+            }
+        } else if function == selector(PERMIT2_PERMIT) && target == PERMIT2 {
+            let tokens = abi::decode(
+                &[
+                    ParamType::Address,
+                    ParamType::Tuple(vec![
+                        ParamType::Tuple(vec![
+                            ParamType::Address,
+                            ParamType::Uint(160),
+                            ParamType::Uint(48),
+                            ParamType::Uint(48),
+                        ]),
+                        ParamType::Address,
+                        ParamType::Uint(256),
+                    ]),
+                    ParamType::Bytes,
+                ],
+                params,
+            )
+            .ok()?;
+            let [
+                Token::Address(owner),
+                Token::Tuple(permit),
+                Token::Bytes(signature),
+            ] = tokens.as_slice()
+            else {
+                return None;
+            };
+            let [
+                Token::Tuple(details),
+                Token::Address(spender),
+                Token::Uint(sig_deadline),
+            ] = permit.as_slice()
+            else {
+                return None;
+            };
+            match details.as_slice() {
+                [
+                    Token::Address(token),
+                    Token::Uint(amount),
+                    Token::Uint(expiration),
+                    Token::Uint(nonce),
+                ] => Some(Self::Permit2 {
+                    owner: *owner,
+                    token: *token,
+                    amount: *amount,
+                    expiration: *expiration,
+                    nonce: *nonce,
+                    spender: *spender,
+                    sig_deadline: *sig_deadline,
+                    signature: signature.clone(),
+                }),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// The contract verifying the permit's signature and keeping track of its
+    /// nonces.
+    pub fn verifying_contract(&self) -> H160 {
+        match self {
+            Self::Eip2612 { token, .. } | Self::Dai { token, .. } => *token,
+            Self::Permit2 { .. } => PERMIT2,
+        }
+    }
+
+    pub fn token(&self) -> H160 {
+        match self {
+            Self::Eip2612 { token, .. } | Self::Dai { token, .. } | Self::Permit2 { token, .. } => {
+                *token
+            }
+        }
+    }
+
+    pub fn owner(&self) -> H160 {
+        match self {
+            Self::Eip2612 { owner, .. } | Self::Permit2 { owner, .. } => *owner,
+            Self::Dai { holder, .. } => *holder,
+        }
+    }
+
+    pub fn spender(&self) -> H160 {
+        match self {
+            Self::Eip2612 { spender, .. }
+            | Self::Dai { spender, .. }
+            | Self::Permit2 { spender, .. } => *spender,
+        }
+    }
+
+    /// The allowance the permit sets for the spender.
+    pub fn allowance(&self) -> U256 {
+        match self {
+            Self::Eip2612 { value, .. } => *value,
+            Self::Dai { allowed: true, .. } => U256::MAX,
+            Self::Dai { allowed: false, .. } => U256::zero(),
+            Self::Permit2 { amount, .. } => *amount,
+        }
+    }
+
+    /// The timestamp after which the permit can no longer be used. `None` if
+    /// it never expires.
+    pub fn deadline(&self) -> Option<U256> {
+        match self {
+            Self::Eip2612 { deadline, .. } => Some(*deadline),
+            // DAI permits without expiry are valid forever.
+            Self::Dai { expiry, .. } => (!expiry.is_zero()).then_some(*expiry),
+            Self::Permit2 { sig_deadline, .. } => Some(*sig_deadline),
+        }
+    }
+
+    /// The nonce the permit was signed with. `None` for EIP-2612 permits which
+    /// are implicitly signed with the owner's current nonce.
+    pub fn nonce(&self) -> Option<U256> {
+        match self {
+            Self::Eip2612 { .. } => None,
+            Self::Dai { nonce, .. } | Self::Permit2 { nonce, .. } => Some(*nonce),
+        }
+    }
+
+    /// The ECDSA signature (`r || s || v`) of the permit. `None` if the
+    /// permit is signed in a different way, e.g. by a smart contract.
+    pub fn ecdsa_signature(&self) -> Option<[u8; 65]> {
+        match self {
+            Self::Eip2612 { signature, .. } | Self::Dai { signature, .. } => Some(*signature),
+            Self::Permit2 { signature, .. } => signature.as_slice().try_into().ok(),
+        }
+    }
+
+    /// The EIP-712 struct hash of the permit when signed with the given nonce.
+    pub fn struct_hash(&self, nonce: U256) -> [u8; 32] {
+        let encoded = match self {
+            Self::Eip2612 {
+                owner,
+                spender,
+                value,
+                deadline,
+                ..
+            } => abi::encode(&[
+                Token::FixedBytes(keccak256(EIP2612_PERMIT_TYPE.as_bytes()).to_vec()),
+                Token::Address(*owner),
+                Token::Address(*spender),
+                Token::Uint(*value),
+                Token::Uint(nonce),
+                Token::Uint(*deadline),
+            ]),
+            Self::Dai {
+                holder,
+                spender,
+                expiry,
+                allowed,
+                ..
+            } => abi::encode(&[
+                Token::FixedBytes(keccak256(DAI_PERMIT_TYPE.as_bytes()).to_vec()),
+                Token::Address(*holder),
+                Token::Address(*spender),
+                Token::Uint(nonce),
+                Token::Uint(*expiry),
+                Token::Bool(*allowed),
+            ]),
+            Self::Permit2 {
+                token,
+                amount,
+                expiration,
+                spender,
+                sig_deadline,
+                ..
+            } => {
+                let details = keccak256(&abi::encode(&[
+                    Token::FixedBytes(keccak256(PERMIT2_DETAILS_TYPE.as_bytes()).to_vec()),
+                    Token::Address(*token),
+                    Token::Uint(*amount),
+                    Token::Uint(*expiration),
+                    Token::Uint(nonce),
+                ]));
+                abi::encode(&[
+                    Token::FixedBytes(keccak256(PERMIT2_SINGLE_TYPE.as_bytes()).to_vec()),
+                    Token::FixedBytes(details.to_vec()),
+                    Token::Address(*spender),
+                    Token::Uint(*sig_deadline),
+                ])
+            }
+        };
+        keccak256(&encoded)
+    }
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn ecdsa_signature(v: &Token, r: &Token, s: &Token) -> Option<[u8; 65]> {
+    let (Token::Uint(v), Token::FixedBytes(r), Token::FixedBytes(s)) = (v, r, s) else {
+        return None;
+    };
+    let mut signature = [0; 65];
+    signature[..32].copy_from_slice(r);
+    signature[32..64].copy_from_slice(s);
+    signature[64] = u8::try_from(*v).ok()?;
+    Some(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(signature: &str, params: &[Token]) -> Vec<u8> {
+        [selector(signature).as_slice(), &abi::encode(params)].concat()
+    }
+
+    #[test]
+    fn selectors() {
+        assert_eq!(selector(EIP2612_PERMIT), [0xd5, 0x05, 0xac, 0xcf]);
+        assert_eq!(selector(DAI_PERMIT), [0x8f, 0xcb, 0xaf, 0x0c]);
+        assert_eq!(selector(PERMIT2_PERMIT), [0x2b, 0x67, 0xb5, 0x70]);
+    }
+
+    #[test]
+    fn decodes_eip2612_permit() {
+        let call_data = call(
+            EIP2612_PERMIT,
+            &[
+                Token::Address(H160([1; 20])),
+                Token::Address(H160([2; 20])),
+                Token::Uint(1000.into()),
+                Token::Uint(2000.into()),
+                Token::Uint(27.into()),
+                Token::FixedBytes(vec![3; 32]),
+                Token::FixedBytes(vec![4; 32]),
+            ],
+        );
+        let permit = Permit::decode(H160([5; 20]), &call_data).unwrap();
+        let mut signature = [3; 65];
+        signature[32..64].fill(4);
+        signature[64] = 27;
+        assert_eq!(
+            permit,
+            Permit::Eip2612 {
+                token: H160([5; 20]),
+                owner: H160([1; 20]),
+                spender: H160([2; 20]),
+                value: 1000.into(),
+                deadline: 2000.into(),
+                signature,
+            }
+        );
+        assert_eq!(permit.verifying_contract(), H160([5; 20]));
+        assert_eq!(permit.allowance(), 1000.into());
+        assert_eq!(permit.nonce(), None);
+
+        // Truncated calls are not permits.
+        assert_eq!(
+            Permit::decode(H160([5; 20]), &call_data[..call_data.len() - 1]),
+            None
+        );
+    }
+
+    #[test]
+    fn decodes_dai_permit() {
+        let call_data = call(
+            DAI_PERMIT,
+            &[
+                Token::Address(H160([1; 20])),
+                Token::Address(H160([2; 20])),
+                Token::Uint(7.into()),
+                Token::Uint(0.into()),
+                Token::Bool(true),
+                Token::Uint(28.into()),
+                Token::FixedBytes(vec![3; 32]),
+                Token::FixedBytes(vec![4; 32]),
+            ],
+        );
+        let permit = Permit::decode(H160([5; 20]), &call_data).unwrap();
+        assert_eq!(permit.owner(), H160([1; 20]));
+        assert_eq!(permit.spender(), H160([2; 20]));
+        assert_eq!(permit.nonce(), Some(7.into()));
+        assert_eq!(permit.allowance(), U256::MAX);
+        // An expiry of zero means the permit never expires.
+        assert_eq!(permit.deadline(), None);
+    }
+
+    #[test]
+    fn decodes_permit2_permit() {
+        let call_data = call(
+            PERMIT2_PERMIT,
+            &[
+                Token::Address(H160([1; 20])),
+                Token::Tuple(vec![
+                    Token::Tuple(vec![
+                        Token::Address(H160([5; 20])),
+                        Token::Uint(1000.into()),
+                        Token::Uint(3000.into()),
+                        Token::Uint(7.into()),
+                    ]),
+                    Token::Address(H160([2; 20])),
+                    Token::Uint(2000.into()),
+                ]),
+                Token::Bytes(vec![3; 64]),
+            ],
+        );
+        let permit = Permit::decode(PERMIT2, &call_data).unwrap();
+        assert_eq!(
+            permit,
+            Permit::Permit2 {
+                owner: H160([1; 20]),
+                token: H160([5; 20]),
+                amount: 1000.into(),
+                expiration: 3000.into(),
+                nonce: 7.into(),
+                spender: H160([2; 20]),
+                sig_deadline: 2000.into(),
+                signature: vec![3; 64],
+            }
+        );
+        assert_eq!(permit.verifying_contract(), PERMIT2);
+        assert_eq!(permit.deadline(), Some(2000.into()));
+        // Compact signatures are not supported.
+        assert_eq!(permit.ecdsa_signature(), None);
+
+        // Only calls to the canonical Permit2 deployment are recognized.
+        assert_eq!(Permit::decode(H160([5; 20]), &call_data), None);
+    }
+
+    #[test]
+    fn eip2612_type_hash() {
+        assert_eq!(
+            keccak256(EIP2612_PERMIT_TYPE.as_bytes()),
+            [
+                0x6e, 0x71, 0xed, 0xae, 0x12, 0xb1, 0xb9, 0x7f, 0x4d, 0x1f, 0x60, 0x37, 0x0f, 0xef,
+                0x10, 0x10, 0x5f, 0xa2, 0xfa, 0xae, 0x01, 0x26, 0x11, 0x4a, 0x16, 0x9c, 0x64, 0x84,
+                0x5d, 0x61, 0x26, 0xc9,
+            ]
+        );
+    }
+}
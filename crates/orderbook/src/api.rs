@@ -1,5 +1,11 @@
 use {
-    crate::{app_data, database::Postgres, orderbook::Orderbook, quoter::QuoteHandler},
+    crate::{
+        app_data,
+        database::Postgres,
+        order_events::OrderEvents,
+        orderbook::Orderbook,
+        quoter::QuoteHandler,
+    },
     anyhow::Result,
     serde::{Serialize, de::DeserializeOwned},
     shared::price_estimation::{PriceEstimationError, native::NativePriceEstimating},
@@ -20,6 +26,7 @@ mod get_app_data;
 mod get_auction;
 mod get_native_price;
 mod get_order_by_uid;
+mod get_order_events;
 mod get_order_status;
 mod get_orders_by_tx;
 mod get_solver_competition;
@@ -28,8 +35,10 @@ mod get_total_surplus;
 mod get_trades;
 mod get_user_orders;
 mod post_order;
+mod post_order_batch;
 mod post_quote;
 mod put_app_data;
+mod validate_order;
 mod version;
 
 pub fn handle_all_routes(
@@ -38,6 +47,7 @@ pub fn handle_all_routes(
     quotes: Arc<QuoteHandler>,
     app_data: Arc<app_data::Registry>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_events: Arc<OrderEvents>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
@@ -48,6 +58,14 @@ pub fn handle_all_routes(
             "v1/create_order",
             box_filter(post_order::post_order(orderbook.clone())),
         ),
+        (
+            "v1/create_orders",
+            box_filter(post_order_batch::post_order_batch(orderbook.clone())),
+        ),
+        (
+            "v1/validate_order",
+            box_filter(validate_order::validate_order(orderbook.clone())),
+        ),
         (
             "v1/get_order",
             box_filter(get_order_by_uid::get_order_by_uid(orderbook.clone())),
@@ -56,6 +74,14 @@ pub fn handle_all_routes(
             "v1/get_order_status",
             box_filter(get_order_status::get_status(orderbook.clone())),
         ),
+        (
+            "v1/get_order_events",
+            box_filter(get_order_events::get_order_events(order_events.clone())),
+        ),
+        (
+            "v1/get_owner_order_events",
+            box_filter(get_order_events::get_owner_order_events(order_events)),
+        ),
         (
             "v1/get_trades",
             box_filter(get_trades::get_trades(database.clone())),
@@ -119,6 +145,10 @@ pub fn handle_all_routes(
 
 pub type ApiReply = WithStatus<Json>;
 
+/// Response header of paginated routes containing the cursor to request the
+/// next page with.
+pub const NEXT_CURSOR_HEADER: &str = "X-Next-Cursor";
+
 // We turn Rejection into Reply to workaround warp not setting CORS headers on
 // rejections.
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
@@ -322,7 +352,8 @@ pub fn finalize_router(
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS", "PUT", "PATCH"])
-        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"]);
+        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"])
+        .expose_headers(vec![NEXT_CURSOR_HEADER]);
 
     warp::path!("api" / ..)
         .and(instrumented)
@@ -0,0 +1,73 @@
+use {
+    crate::{
+        dto::order_event::Event,
+        order_events::{self, OrderEvents},
+    },
+    futures::{Stream, StreamExt},
+    model::order::OrderUid,
+    primitive_types::H160,
+    std::sync::Arc,
+    warp::{Filter, Rejection, Reply, sse},
+};
+
+fn order_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid / "events").and(warp::get())
+}
+
+fn owner_request() -> impl Filter<Extract = (H160,), Error = Rejection> + Clone {
+    warp::path!("v1" / "account" / H160 / "orders" / "events").and(warp::get())
+}
+
+/// Streams the lifecycle events of a single order.
+pub fn get_order_events(
+    events: Arc<OrderEvents>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    order_request().map(move |uid| reply(events.subscribe(order_events::Filter::Order(uid))))
+}
+
+/// Streams the lifecycle events of all orders of an owner.
+pub fn get_owner_order_events(
+    events: Arc<OrderEvents>,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    owner_request().map(move |owner| reply(events.subscribe(order_events::Filter::Owner(owner))))
+}
+
+/// Sends the events as server-sent events named after the kind of event.
+fn reply(events: impl Stream<Item = Event> + Send + 'static) -> impl Reply {
+    let events = events.map(|event| {
+        sse::Event::default()
+            .event(<&'static str>::from(event.kind))
+            .json_data(&event)
+    });
+    sse::reply(sse::keep_alive().stream(events))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, shared::addr, warp::test::request};
+
+    #[tokio::test]
+    async fn order_request_ok() {
+        let uid = OrderUid::default();
+        let path = format!("/v1/orders/{uid}/events");
+        let result = request()
+            .path(&path)
+            .method("GET")
+            .filter(&order_request())
+            .await
+            .unwrap();
+        assert_eq!(result, uid);
+    }
+
+    #[tokio::test]
+    async fn owner_request_ok() {
+        let path = "/v1/account/0x0000000000000000000000000000000000000001/orders/events";
+        let result = request()
+            .path(path)
+            .method("GET")
+            .filter(&owner_request())
+            .await
+            .unwrap();
+        assert_eq!(result, addr!("0000000000000000000000000000000000000001"));
+    }
+}
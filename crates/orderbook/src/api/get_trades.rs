@@ -1,29 +1,61 @@
 use {
     crate::{
-        api::{ApiReply, error},
+        api::{NEXT_CURSOR_HEADER, error},
         database::{
             Postgres,
-            trades::{TradeFilter, TradeRetrieving},
+            trades::{TradeCursor, TradeFilter, TradeRetrieving},
         },
     },
     anyhow::{Context, Result},
-    model::order::OrderUid,
+    app_data::AppDataHash,
+    model::order::{OrderClass, OrderUid},
     primitive_types::H160,
     serde::Deserialize,
+    serde_with::{DisplayFromStr, serde_as},
     std::convert::Infallible,
-    warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        hyper::StatusCode,
+        reply::{Response, with_header, with_status},
+    },
 };
 
+const MIN_LIMIT: u64 = 1;
+const MAX_LIMIT: u64 = 1000;
+
+#[serde_as]
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Query {
     pub order_uid: Option<OrderUid>,
     pub owner: Option<H160>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub class: Option<OrderClass>,
+    pub token: Option<H160>,
+    pub app_data: Option<AppDataHash>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub cursor: Option<TradeCursor>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct TradesRequest {
+    filter: TradeFilter,
+    cursor: Option<TradeCursor>,
+    /// All trades are returned if no limit is specified.
+    limit: Option<u64>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 enum TradeFilterError {
     InvalidFilter(String),
+    LimitOutOfBounds,
 }
 
 impl Query {
@@ -31,12 +63,27 @@ impl Query {
         TradeFilter {
             order_uid: self.order_uid,
             owner: self.owner,
+            class: self.class,
+            token: self.token,
+            app_data: self.app_data,
+            from_block: self.from_block,
+            to_block: self.to_block,
         }
     }
 
-    fn validate(&self) -> Result<TradeFilter, TradeFilterError> {
+    fn validate(&self) -> Result<TradesRequest, TradeFilterError> {
+        if self
+            .limit
+            .is_some_and(|limit| !(MIN_LIMIT..=MAX_LIMIT).contains(&limit))
+        {
+            return Err(TradeFilterError::LimitOutOfBounds);
+        }
         match (self.order_uid.as_ref(), self.owner.as_ref()) {
-            (Some(_), None) | (None, Some(_)) => Ok(self.trade_filter()),
+            (Some(_), None) | (None, Some(_)) => Ok(TradesRequest {
+                filter: self.trade_filter(),
+                cursor: self.cursor,
+                limit: self.limit,
+            }),
             _ => Err(TradeFilterError::InvalidFilter(
                 "Must specify exactly one of owner or orderUid.".to_owned(),
             )),
@@ -45,31 +92,59 @@ impl Query {
 }
 
 fn get_trades_request()
--> impl Filter<Extract = (Result<TradeFilter, TradeFilterError>,), Error = Rejection> + Clone {
+-> impl Filter<Extract = (Result<TradesRequest, TradeFilterError>,), Error = Rejection> + Clone {
     warp::path!("v1" / "trades")
         .and(warp::get())
         .and(warp::query::<Query>())
         .map(|query: Query| query.validate())
 }
 
-pub fn get_trades(db: Postgres) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+pub fn get_trades(db: Postgres) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
     get_trades_request().and_then(move |request_result| {
         let database = db.clone();
         async move {
             Result::<_, Infallible>::Ok(match request_result {
-                Ok(trade_filter) => {
-                    let result = database.trades(&trade_filter).await.context("get_trades");
+                Ok(TradesRequest {
+                    filter,
+                    cursor,
+                    limit,
+                }) => {
+                    let result = database
+                        .trades(&filter, cursor, limit)
+                        .await
+                        .context("get_trades");
                     match result {
-                        Ok(reply) => with_status(warp::reply::json(&reply), StatusCode::OK),
+                        Ok(trades) => {
+                            let reply = with_status(warp::reply::json(&trades), StatusCode::OK);
+                            // A full page indicates that there might be more trades.
+                            match (trades.last(), limit) {
+                                (Some(last), Some(limit)) if trades.len() as u64 == limit => {
+                                    with_header(
+                                        reply,
+                                        NEXT_CURSOR_HEADER,
+                                        TradeCursor::after(last).to_string(),
+                                    )
+                                    .into_response()
+                                }
+                                _ => reply.into_response(),
+                            }
+                        }
                         Err(err) => {
                             tracing::error!(?err, "get_trades");
-                            crate::api::internal_error_reply()
+                            crate::api::internal_error_reply().into_response()
                         }
                     }
                 }
                 Err(TradeFilterError::InvalidFilter(msg)) => {
                     let err = error("InvalidTradeFilter", msg);
-                    with_status(err, StatusCode::BAD_REQUEST)
+                    with_status(err, StatusCode::BAD_REQUEST).into_response()
+                }
+                Err(TradeFilterError::LimitOutOfBounds) => {
+                    let err = error(
+                        "LIMIT_OUT_OF_BOUNDS",
+                        format!("The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."),
+                    );
+                    with_status(err, StatusCode::BAD_REQUEST).into_response()
                 }
             })
         }
@@ -98,8 +173,8 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        assert_eq!(result.owner, Some(owner));
-        assert_eq!(result.order_uid, None);
+        assert_eq!(result.filter.owner, Some(owner));
+        assert_eq!(result.filter.order_uid, None);
 
         let uid = OrderUid([1u8; 56]);
         let order_uid_path = format!("/v1/trades?orderUid={uid}");
@@ -107,8 +182,8 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
-        assert_eq!(result.owner, None);
-        assert_eq!(result.order_uid, Some(uid));
+        assert_eq!(result.filter.owner, None);
+        assert_eq!(result.filter.order_uid, Some(uid));
     }
 
     #[tokio::test]
@@ -128,5 +203,45 @@ mod tests {
         let path = "/v1/trades";
         let result = trade_filter(request().path(path)).await.unwrap();
         assert!(result.is_err());
+
+        let path = format!("/v1/trades?owner=0x{owner:x}&limit=0");
+        let result = trade_filter(request().path(path.as_str())).await.unwrap();
+        assert_eq!(result, Err(TradeFilterError::LimitOutOfBounds));
+    }
+
+    #[tokio::test]
+    async fn get_trades_request_with_cursor_and_filters() {
+        let filter = get_trades_request();
+        let owner = H160::from_slice(&hex!("0000000000000000000000000000000000000001"));
+        let token = H160::from_slice(&hex!("0000000000000000000000000000000000000002"));
+        let path = format!(
+            "/v1/trades?owner=0x{owner:x}&class=limit&token=0x{token:x}&fromBlock=1&toBlock=2&\
+             cursor=2_3&limit=10"
+        );
+        let result = request()
+            .path(path.as_str())
+            .method("GET")
+            .filter(&filter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            TradesRequest {
+                filter: TradeFilter {
+                    owner: Some(owner),
+                    class: Some(OrderClass::Limit),
+                    token: Some(token),
+                    from_block: Some(1),
+                    to_block: Some(2),
+                    ..Default::default()
+                },
+                cursor: Some(TradeCursor {
+                    block_number: 2,
+                    log_index: 3,
+                }),
+                limit: Some(10),
+            }
+        );
     }
 }
@@ -1,16 +1,56 @@
 use {
-    crate::{api::ApiReply, orderbook::Orderbook},
+    crate::{
+        api::NEXT_CURSOR_HEADER,
+        database::orders::{UserOrderCursor, UserOrderFilter},
+        orderbook::Orderbook,
+    },
     anyhow::Result,
+    app_data::AppDataHash,
+    chrono::{DateTime, Utc},
+    model::order::{OrderClass, OrderStatus},
     primitive_types::H160,
     serde::Deserialize,
+    serde_with::{DisplayFromStr, serde_as},
     std::{convert::Infallible, sync::Arc},
-    warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
+    warp::{
+        Filter,
+        Rejection,
+        Reply,
+        hyper::StatusCode,
+        reply::{Response, with_header, with_status},
+    },
 };
 
+#[serde_as]
 #[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct Query {
     offset: Option<u64>,
     limit: Option<u64>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    cursor: Option<UserOrderCursor>,
+    status: Option<OrderStatus>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    class: Option<OrderClass>,
+    token: Option<H160>,
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    app_data: Option<AppDataHash>,
+}
+
+impl Query {
+    fn filter(&self) -> UserOrderFilter {
+        UserOrderFilter {
+            status: self.status,
+            class: self.class,
+            token: self.token,
+            created_after: self.created_after,
+            created_before: self.created_before,
+            app_data: self.app_data,
+        }
+    }
 }
 
 fn request() -> impl Filter<Extract = (H160, Query), Error = Rejection> + Clone {
@@ -21,7 +61,7 @@ fn request() -> impl Filter<Extract = (H160, Query), Error = Rejection> + Clone
 
 pub fn get_user_orders(
     orderbook: Arc<Orderbook>,
-) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
     request().and_then(move |owner: H160, query: Query| {
         let orderbook = orderbook.clone();
         async move {
@@ -38,14 +78,29 @@ pub fn get_user_orders(
                         format!("The pagination limit is [{MIN_LIMIT},{MAX_LIMIT}]."),
                     ),
                     StatusCode::BAD_REQUEST,
-                ));
+                )
+                .into_response());
             }
-            let result = orderbook.get_user_orders(&owner, offset, limit).await;
+            let result = orderbook
+                .get_user_orders(&owner, &query.filter(), query.cursor, offset, limit)
+                .await;
             Result::<_, Infallible>::Ok(match result {
-                Ok(reply) => with_status(warp::reply::json(&reply), StatusCode::OK),
+                Ok(orders) => {
+                    let reply = with_status(warp::reply::json(&orders), StatusCode::OK);
+                    // A full page indicates that there might be more orders.
+                    match orders.last() {
+                        Some(last) if orders.len() as u64 == limit => with_header(
+                            reply,
+                            NEXT_CURSOR_HEADER,
+                            UserOrderCursor::after(last).to_string(),
+                        )
+                        .into_response(),
+                        _ => reply.into_response(),
+                    }
+                }
                 Err(err) => {
                     tracing::error!(?err, "get_user_orders");
-                    crate::api::internal_error_reply()
+                    crate::api::internal_error_reply().into_response()
                 }
             })
         }
@@ -68,6 +123,8 @@ mod tests {
         assert_eq!(result.0, addr!("0000000000000000000000000000000000000001"));
         assert_eq!(result.1.offset, None);
         assert_eq!(result.1.limit, None);
+        assert_eq!(result.1.cursor, None);
+        assert_eq!(result.1.filter(), UserOrderFilter::default());
 
         let path = "/v1/account/0x0000000000000000000000000000000000000001/orders?offset=1&limit=2";
         let result = warp::test::request()
@@ -79,4 +136,49 @@ mod tests {
         assert_eq!(result.1.offset, Some(1));
         assert_eq!(result.1.limit, Some(2));
     }
+
+    #[tokio::test]
+    async fn request_with_cursor_and_filters() {
+        let uid = model::order::OrderUid([1; 56]);
+        let app_data = AppDataHash([3; 32]);
+        let path = format!(
+            "/v1/account/0x0000000000000000000000000000000000000001/orders?\
+             cursor=1700000000000000_{uid}&status=fulfilled&class=limit&\
+             token=0x0000000000000000000000000000000000000002&createdAfter=2024-01-01T00:00:00Z&\
+             createdBefore=2024-02-01T00:00:00Z&appData=0x{}",
+            hex::encode(app_data.0)
+        );
+        let result = warp::test::request()
+            .path(&path)
+            .method("GET")
+            .filter(&request())
+            .await
+            .unwrap();
+        assert_eq!(
+            result.1.cursor,
+            Some(UserOrderCursor {
+                creation_date: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+                uid,
+            })
+        );
+        assert_eq!(
+            result.1.filter(),
+            UserOrderFilter {
+                status: Some(OrderStatus::Fulfilled),
+                class: Some(OrderClass::Limit),
+                token: Some(addr!("0000000000000000000000000000000000000002")),
+                created_after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+                created_before: Some("2024-02-01T00:00:00Z".parse().unwrap()),
+                app_data: Some(app_data),
+            }
+        );
+
+        let path = "/v1/account/0x0000000000000000000000000000000000000001/orders?cursor=invalid";
+        let result = warp::test::request()
+            .path(path)
+            .method("GET")
+            .filter(&request())
+            .await;
+        assert!(result.is_err());
+    }
 }
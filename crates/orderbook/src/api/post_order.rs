@@ -116,7 +116,7 @@ impl IntoWarpReply for AppDataValidationErrorWrapper {
     }
 }
 
-pub struct ValidationErrorWrapper(ValidationError);
+pub struct ValidationErrorWrapper(pub ValidationError);
 impl IntoWarpReply for ValidationErrorWrapper {
     fn into_warp_reply(self) -> ApiReply {
         match self.0 {
@@ -160,6 +160,13 @@ impl IntoWarpReply for ValidationErrorWrapper {
                 ),
                 StatusCode::BAD_REQUEST,
             ),
+            ValidationError::InvalidPermit(err) => with_status(
+                error(
+                    "InvalidPermit",
+                    format!("permit pre-hook is invalid: {err}"),
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
             ValidationError::InsufficientBalance => with_status(
                 error(
                     "InsufficientBalance",
@@ -263,6 +270,20 @@ impl IntoWarpReply for AddOrderError {
                 super::error("MetadataSerializationFailed", err.to_string()),
                 StatusCode::INTERNAL_SERVER_ERROR,
             ),
+            AddOrderError::ReplacementInBatch => with_status(
+                error(
+                    "ReplacementInBatch",
+                    "order replacements can't be placed in a batch",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            AddOrderError::BatchRejected => with_status(
+                error(
+                    "BatchRejected",
+                    "order was not placed because another order of the batch was rejected",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
         }
     }
 }
@@ -0,0 +1,176 @@
+use {
+    crate::{
+        api::{
+            ApiReply,
+            IntoWarpReply,
+            MAX_JSON_BODY_PAYLOAD,
+            error,
+            extract_payload_with_max_size,
+            response_body,
+        },
+        orderbook::{AddOrderError, BatchMode, Orderbook, QuoteMetadata},
+    },
+    futures::future,
+    model::{
+        order::{OrderCreation, OrderUid},
+        quote::QuoteId,
+    },
+    serde::{Deserialize, Serialize},
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, Reply, hyper::StatusCode, reply::with_status},
+};
+
+/// The maximum number of orders which can be placed in a single batch.
+const MAX_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct OrderBatch {
+    orders: Vec<OrderCreation>,
+    #[serde(default)]
+    mode: BatchMode,
+}
+
+fn create_orders_request() -> impl Filter<Extract = (OrderBatch,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "batch")
+        .and(warp::post())
+        .and(extract_payload_with_max_size(
+            MAX_JSON_BODY_PAYLOAD * MAX_BATCH_SIZE as u64,
+        ))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+enum OrderStatus {
+    Created {
+        uid: OrderUid,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        quote_id: Option<QuoteId>,
+    },
+    Rejected {
+        /// The error in the same format as when placing a single order.
+        error: serde_json::Value,
+    },
+}
+
+async fn order_status(
+    result: Result<(OrderUid, Option<QuoteMetadata>), AddOrderError>,
+) -> OrderStatus {
+    match result {
+        Ok((uid, quote)) => OrderStatus::Created {
+            uid,
+            quote_id: quote.and_then(|quote| quote.id),
+        },
+        Err(err) => {
+            let response = err.into_warp_reply().into_response();
+            OrderStatus::Rejected {
+                error: serde_json::from_slice(&response_body(response).await).unwrap_or_default(),
+            }
+        }
+    }
+}
+
+async fn create_orders_response(
+    results: Vec<Result<(OrderUid, Option<QuoteMetadata>), AddOrderError>>,
+) -> ApiReply {
+    let statuses = future::join_all(results.into_iter().map(order_status)).await;
+    with_status(warp::reply::json(&statuses), StatusCode::OK)
+}
+
+pub fn post_order_batch(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    create_orders_request().and_then(move |batch: OrderBatch| {
+        let orderbook = orderbook.clone();
+        async move {
+            if batch.orders.len() > MAX_BATCH_SIZE {
+                return Result::<_, Infallible>::Ok(with_status(
+                    error(
+                        "TooManyOrders",
+                        format!("a batch can contain at most {MAX_BATCH_SIZE} orders"),
+                    ),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+
+            let results = orderbook.add_orders(batch.orders, batch.mode).await;
+            for result in &results {
+                match result {
+                    Ok((order_uid, _)) => tracing::debug!(%order_uid, "order created"),
+                    Err(err) => tracing::debug!(?err, "error creating order"),
+                }
+            }
+            Ok(create_orders_response(results).await)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json, warp::test::request};
+
+    #[tokio::test]
+    async fn create_orders_request_ok() {
+        let filter = create_orders_request();
+        let batch = request()
+            .path("/v1/orders/batch")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&json!({
+                "orders": [OrderCreation::default(), OrderCreation::default()],
+                "mode": "bestEffort",
+            }));
+        let result = batch.filter(&filter).await.unwrap();
+        assert_eq!(
+            result,
+            OrderBatch {
+                orders: vec![OrderCreation::default(), OrderCreation::default()],
+                mode: BatchMode::BestEffort,
+            }
+        );
+
+        // All or nothing by default.
+        let batch = request()
+            .path("/v1/orders/batch")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&json!({ "orders": [] }));
+        let result = batch.filter(&filter).await.unwrap();
+        assert_eq!(result.mode, BatchMode::AllOrNothing);
+    }
+
+    #[tokio::test]
+    async fn create_orders_response_reports_each_order() {
+        let response = create_orders_response(vec![
+            Ok((OrderUid([1; 56]), None)),
+            Err(AddOrderError::DuplicatedOrder),
+            Err(AddOrderError::BatchRejected),
+        ])
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response_body(response).await).unwrap();
+        assert_eq!(
+            body,
+            json!([
+                { "status": "created", "uid": OrderUid([1; 56]) },
+                {
+                    "status": "rejected",
+                    "error": {
+                        "errorType": "DuplicatedOrder",
+                        "description": "order already exists",
+                    },
+                },
+                {
+                    "status": "rejected",
+                    "error": {
+                        "errorType": "BatchRejected",
+                        "description": "order was not placed because another order of the batch \
+                                        was rejected",
+                    },
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use {
+    crate::{
+        api::{
+            ApiReply,
+            IntoWarpReply,
+            extract_payload,
+            post_order::ValidationErrorWrapper,
+            response_body,
+        },
+        orderbook::Orderbook,
+    },
+    futures::future,
+    model::order::{OrderClass, OrderCreation, OrderUid},
+    primitive_types::H160,
+    serde::Serialize,
+    shared::order_validation::{OrderDiagnosis, ValidationError, ValidationWarning},
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, Reply, hyper::StatusCode, reply::with_status},
+};
+
+fn validate_order_request() -> impl Filter<Extract = (OrderCreation,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "validate")
+        .and(warp::post())
+        .and(extract_payload())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Diagnosis {
+    valid: bool,
+    owner: Option<H160>,
+    uid: Option<OrderUid>,
+    #[serde(flatten)]
+    class: Option<OrderClass>,
+    /// The errors in the same format as when placing the order.
+    errors: Vec<serde_json::Value>,
+    warnings: Vec<Warning>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Warning {
+    warning_type: &'static str,
+    description: &'static str,
+}
+
+impl From<ValidationWarning> for Warning {
+    fn from(warning: ValidationWarning) -> Self {
+        let (warning_type, description) = match warning {
+            ValidationWarning::UnknownOwner => (
+                "UnknownOwner",
+                "the order owner could not be determined, so checks depending on it were skipped",
+            ),
+            ValidationWarning::Eip1271SignatureNotVerified => (
+                "Eip1271SignatureNotVerified",
+                "EIP-1271 signatures are not verified when placing orders",
+            ),
+            ValidationWarning::PresignedWithoutFunds => (
+                "PresignedWithoutFunds",
+                "the sell token can't be transferred yet, the pre-signed order only gets executed \
+                 once the owner has sufficient balance and allowance",
+            ),
+            ValidationWarning::QuoteNotFound => (
+                "QuoteNotFound",
+                "the referenced quote doesn't exist or doesn't match the order, a fresh quote was \
+                 used instead",
+            ),
+            ValidationWarning::NoLiquidity => (
+                "NoLiquidity",
+                "no liquidity was found for the order, it can only be placed as a limit order",
+            ),
+            ValidationWarning::OutsideMarketPrice => (
+                "OutsideMarketPrice",
+                "the limit price is outside the market price, the order is placed as a limit order",
+            ),
+        };
+        Self {
+            warning_type,
+            description,
+        }
+    }
+}
+
+/// Renders the error like the order creation endpoint does.
+async fn error_body(err: ValidationError) -> serde_json::Value {
+    let response = ValidationErrorWrapper(err)
+        .into_warp_reply()
+        .into_response();
+    serde_json::from_slice(&response_body(response).await).unwrap_or_default()
+}
+
+async fn diagnosis_reply(diagnosis: OrderDiagnosis) -> ApiReply {
+    let valid = diagnosis.is_valid();
+    let errors = future::join_all(diagnosis.errors.into_iter().map(error_body)).await;
+    let diagnosis = Diagnosis {
+        valid,
+        owner: diagnosis.owner,
+        uid: diagnosis.uid,
+        class: diagnosis.class,
+        errors,
+        warnings: diagnosis.warnings.into_iter().map(Warning::from).collect(),
+    };
+    with_status(warp::reply::json(&diagnosis), StatusCode::OK)
+}
+
+pub fn validate_order(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    validate_order_request().and_then(move |order: OrderCreation| {
+        let orderbook = orderbook.clone();
+        async move {
+            let reply = match orderbook.validate_order(order).await {
+                Ok(diagnosis) => diagnosis_reply(diagnosis).await,
+                Err(err) => {
+                    tracing::error!(?err, "validate_order");
+                    crate::api::internal_error_reply()
+                }
+            };
+            Result::<_, Infallible>::Ok(reply)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json, warp::test::request};
+
+    #[tokio::test]
+    async fn validate_order_request_ok() {
+        let filter = validate_order_request();
+        let order_payload = OrderCreation::default();
+        let request = request()
+            .path("/v1/orders/validate")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&order_payload);
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, order_payload);
+    }
+
+    #[tokio::test]
+    async fn diagnosis_reply_lists_errors_and_warnings() {
+        let diagnosis = OrderDiagnosis {
+            owner: Some(H160([1; 20])),
+            uid: Some(OrderUid([2; 56])),
+            class: Some(OrderClass::Limit),
+            errors: vec![ValidationError::ZeroAmount],
+            warnings: vec![ValidationWarning::QuoteNotFound],
+        };
+        let response = diagnosis_reply(diagnosis).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response_body(response).await).unwrap();
+        assert_eq!(body["valid"], json!(false));
+        assert_eq!(body["class"], json!("limit"));
+        assert_eq!(
+            body["owner"],
+            json!("0x0101010101010101010101010101010101010101")
+        );
+        assert_eq!(body["errors"][0]["errorType"], json!("ZeroAmount"));
+        assert_eq!(body["warnings"][0]["warningType"], json!("QuoteNotFound"));
+    }
+}
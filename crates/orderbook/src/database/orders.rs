@@ -9,6 +9,8 @@ use {
     database::{
         byte_array::ByteArray,
         order_events::{OrderEvent, OrderEventLabel, insert_order_event},
+        order_filter_reasons::{self, OrderFilterReason},
+        order_history,
         orders::{self, FullOrder, OrderKind as DbOrderKind},
     },
     ethcontract::H256,
@@ -51,13 +53,25 @@ use {
         order_validation::{Amounts, LimitOrderCounting, is_order_outside_market_price},
     },
     sqlx::{Connection, PgConnection, types::BigDecimal},
-    std::convert::TryInto,
+    std::{
+        convert::TryInto,
+        fmt::{self, Display},
+        str::FromStr,
+    },
 };
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait OrderStoring: Send + Sync {
     async fn insert_order(&self, order: &Order) -> Result<(), InsertionError>;
+    /// Inserts the orders in a single transaction and returns the result of
+    /// each insertion. If `all_or_nothing` is set, no order gets inserted if
+    /// any of the insertions fails.
+    async fn insert_orders(
+        &self,
+        orders: &[Order],
+        all_or_nothing: bool,
+    ) -> Result<Vec<Result<(), InsertionError>>, InsertionError>;
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()>;
     async fn cancel_order(&self, order_uid: &OrderUid, now: DateTime<Utc>) -> Result<()>;
     async fn replace_order(
@@ -66,15 +80,21 @@ pub trait OrderStoring: Send + Sync {
         new_order: &Order,
     ) -> Result<(), InsertionError>;
     async fn orders_for_tx(&self, tx_hash: &H256) -> Result<Vec<Order>>;
-    /// All orders of a single user ordered by creation date descending (newest
-    /// orders first).
+    /// All orders of a single user matching the filter ordered by creation
+    /// date descending (newest orders first). Pages start after the `cursor`
+    /// and skip `offset` orders.
     async fn user_orders(
         &self,
         owner: &H160,
+        filter: &UserOrderFilter,
+        cursor: Option<UserOrderCursor>,
         offset: u64,
         limit: Option<u64>,
     ) -> Result<Vec<Order>>;
     async fn latest_order_event(&self, order_uid: &OrderUid) -> Result<Option<OrderEvent>>;
+    /// The latest reason why the order was filtered from the auction.
+    async fn latest_filter_reason(&self, order_uid: &OrderUid)
+    -> Result<Option<OrderFilterReason>>;
     async fn single_order(&self, uid: &OrderUid) -> Result<Option<Order>>;
 }
 
@@ -93,6 +113,57 @@ impl From<sqlx::Error> for InsertionError {
     }
 }
 
+/// Any default value means that this field is unfiltered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UserOrderFilter {
+    pub status: Option<OrderStatus>,
+    pub class: Option<OrderClass>,
+    /// Orders either selling or buying the token.
+    pub token: Option<H160>,
+    /// Orders created at or after this time.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Orders created before this time.
+    pub created_before: Option<DateTime<Utc>>,
+    pub app_data: Option<AppDataHash>,
+}
+
+/// Points at the last order of a page of user orders, so that the next page
+/// starts right after it. It is encoded as the creation timestamp of the
+/// order in microseconds and its uid separated by `_`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UserOrderCursor {
+    pub creation_date: DateTime<Utc>,
+    pub uid: OrderUid,
+}
+
+impl UserOrderCursor {
+    pub fn after(order: &Order) -> Self {
+        Self {
+            creation_date: order.metadata.creation_date,
+            uid: order.metadata.uid,
+        }
+    }
+}
+
+impl Display for UserOrderCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.creation_date.timestamp_micros(), self.uid)
+    }
+}
+
+impl FromStr for UserOrderCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (timestamp, uid) = s.split_once('_').context("missing separator")?;
+        Ok(Self {
+            creation_date: DateTime::from_timestamp_micros(timestamp.parse()?)
+                .context("timestamp out of range")?,
+            uid: uid.parse()?,
+        })
+    }
+}
+
 /// Applies the needed DB modification to cancel a single order.
 async fn cancel_order(
     ex: &mut PgConnection,
@@ -229,6 +300,44 @@ impl OrderStoring for Postgres {
         Ok(())
     }
 
+    async fn insert_orders(
+        &self,
+        orders: &[Order],
+        all_or_nothing: bool,
+    ) -> Result<Vec<Result<(), InsertionError>>, InsertionError> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["insert_orders"])
+            .start_timer();
+
+        let mut connection = self.pool.acquire().await?;
+        let mut ex = connection.begin().await?;
+
+        let mut results = Vec::with_capacity(orders.len());
+        for order in orders {
+            // Every order is inserted in its own savepoint, so that a failed
+            // insertion doesn't abort the whole transaction.
+            let mut savepoint = ex.begin().await?;
+            let result = async {
+                insert_order(order, &mut savepoint).await?;
+                Self::insert_order_app_data(order, &mut savepoint).await
+            }
+            .await;
+            match result {
+                Ok(()) => savepoint.commit().await?,
+                Err(_) => savepoint.rollback().await?,
+            }
+            results.push(result);
+        }
+
+        if all_or_nothing && results.iter().any(Result::is_err) {
+            ex.rollback().await?;
+        } else {
+            ex.commit().await?;
+        }
+        Ok(results)
+    }
+
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()> {
         let _timer = super::Metrics::get()
             .database_queries
@@ -328,6 +437,8 @@ impl OrderStoring for Postgres {
     async fn user_orders(
         &self,
         owner: &H160,
+        filter: &UserOrderFilter,
+        cursor: Option<UserOrderCursor>,
         offset: u64,
         limit: Option<u64>,
     ) -> Result<Vec<Order>> {
@@ -336,10 +447,24 @@ impl OrderStoring for Postgres {
             .with_label_values(&["user_orders"])
             .start_timer();
 
+        let filter = order_history::UserOrderFilter {
+            status: filter.status.map(order_status_into),
+            class: filter.class.as_ref().map(order_class_into),
+            token: filter.token.map(|token| ByteArray(token.0)),
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+            app_data: filter.app_data.map(|app_data| ByteArray(app_data.0)),
+        };
+        let cursor = cursor.map(|cursor| order_history::Cursor {
+            creation_timestamp: cursor.creation_date,
+            uid: ByteArray(cursor.uid.0),
+        });
         let mut ex = self.pool.acquire().await?;
-        database::order_history::user_orders(
+        order_history::user_orders(
             &mut ex,
             &ByteArray(owner.0),
+            &filter,
+            cursor.as_ref(),
             i64::try_from(offset).unwrap_or(i64::MAX),
             limit.map(|l| i64::try_from(l).unwrap_or(i64::MAX)),
         )
@@ -362,6 +487,21 @@ impl OrderStoring for Postgres {
             .await
             .context("order_events::get_latest")
     }
+
+    async fn latest_filter_reason(
+        &self,
+        order_uid: &OrderUid,
+    ) -> Result<Option<OrderFilterReason>> {
+        let mut ex = self.pool.acquire().await?;
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["latest_filter_reason"])
+            .start_timer();
+
+        order_filter_reasons::get(&mut ex, &ByteArray(order_uid.0))
+            .await
+            .context("order_filter_reasons::get")
+    }
 }
 
 impl Postgres {
@@ -512,6 +652,16 @@ fn calculate_status(order: &FullOrder) -> OrderStatus {
     OrderStatus::Open
 }
 
+fn order_status_into(status: OrderStatus) -> order_history::OrderStatus {
+    match status {
+        OrderStatus::PresignaturePending => order_history::OrderStatus::PresignaturePending,
+        OrderStatus::Open => order_history::OrderStatus::Open,
+        OrderStatus::Fulfilled => order_history::OrderStatus::Fulfilled,
+        OrderStatus::Cancelled => order_history::OrderStatus::Cancelled,
+        OrderStatus::Expired => order_history::OrderStatus::Expired,
+    }
+}
+
 fn full_order_into_model_order(order: FullOrder) -> Result<Order> {
     full_order_with_quote_into_model_order(order, None)
 }
@@ -644,6 +794,24 @@ mod tests {
         std::sync::atomic::{AtomicI64, Ordering},
     };
 
+    #[test]
+    fn user_order_cursor_roundtrip() {
+        let cursor = UserOrderCursor {
+            creation_date: DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            uid: OrderUid([1; 56]),
+        };
+        assert_eq!(
+            cursor.to_string().parse::<UserOrderCursor>().unwrap(),
+            cursor
+        );
+        assert!("1700000000123456".parse::<UserOrderCursor>().is_err());
+        assert!(
+            format!("x_{}", cursor.uid)
+                .parse::<UserOrderCursor>()
+                .is_err()
+        );
+    }
+
     #[test]
     fn order_status() {
         let valid_to_timestamp = Utc::now() + Duration::days(1);
@@ -857,6 +1025,37 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_insert_orders() {
+        let db = Postgres::try_new("postgresql://").unwrap();
+        database::clear_DANGER(&db.pool).await.unwrap();
+
+        let order = |uid: u8| Order {
+            metadata: OrderMetadata {
+                uid: OrderUid([uid; 56]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        db.insert_order(&order(1)).await.unwrap();
+
+        // The duplicated order prevents inserting the other one.
+        let results = db.insert_orders(&[order(2), order(1)], true).await.unwrap();
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(InsertionError::DuplicatedRecord)));
+        assert!(db.single_order(&OrderUid([2; 56])).await.unwrap().is_none());
+
+        // The other order gets inserted regardless of the duplicate.
+        let results = db
+            .insert_orders(&[order(2), order(1)], false)
+            .await
+            .unwrap();
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(InsertionError::DuplicatedRecord)));
+        assert!(db.single_order(&OrderUid([2; 56])).await.unwrap().is_some());
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_replace_order() {
@@ -897,7 +1096,7 @@ mod tests {
             .unwrap();
 
         let order_statuses = db
-            .user_orders(&owner, 0, None)
+            .user_orders(&owner, &Default::default(), None, 0, None)
             .await
             .unwrap()
             .iter()
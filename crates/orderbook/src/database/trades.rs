@@ -1,18 +1,35 @@
 use {
     crate::database::Postgres,
     anyhow::{Context, Result},
-    database::{byte_array::ByteArray, trades::TradesQueryRow},
+    app_data::AppDataHash,
+    database::{byte_array::ByteArray, events::EventIndex, trades::TradesQueryRow},
     ethcontract::H160,
     futures::stream::TryStreamExt,
-    model::{fee_policy::ExecutedProtocolFee, order::OrderUid, trade::Trade},
+    model::{
+        fee_policy::ExecutedProtocolFee,
+        order::{OrderClass, OrderUid},
+        trade::Trade,
+    },
     number::conversions::big_decimal_to_big_uint,
     primitive_types::H256,
-    std::convert::TryInto,
+    shared::db_order_conversions::order_class_into,
+    std::{
+        convert::TryInto,
+        fmt::{self, Display},
+        str::FromStr,
+    },
 };
 
 #[async_trait::async_trait]
 pub trait TradeRetrieving: Send + Sync {
-    async fn trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>>;
+    /// The trades matching the filter, most recent trades first. Pages start
+    /// after the `cursor`.
+    async fn trades(
+        &self,
+        filter: &TradeFilter,
+        cursor: Option<TradeCursor>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Trade>>;
 }
 
 /// Any default value means that this field is unfiltered.
@@ -20,25 +37,83 @@ pub trait TradeRetrieving: Send + Sync {
 pub struct TradeFilter {
     pub owner: Option<H160>,
     pub order_uid: Option<OrderUid>,
+    pub class: Option<OrderClass>,
+    /// Trades of orders either selling or buying the token.
+    pub token: Option<H160>,
+    pub app_data: Option<AppDataHash>,
+    /// Trades settled in or after this block.
+    pub from_block: Option<u64>,
+    /// Trades settled in or before this block.
+    pub to_block: Option<u64>,
+}
+
+/// Points at the last trade of a page, so that the next page starts right
+/// after it. It is encoded as the block number and log index of the trade
+/// separated by `_`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TradeCursor {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+impl TradeCursor {
+    pub fn after(trade: &Trade) -> Self {
+        Self {
+            block_number: trade.block_number,
+            log_index: trade.log_index,
+        }
+    }
+}
+
+impl Display for TradeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.block_number, self.log_index)
+    }
+}
+
+impl FromStr for TradeCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (block_number, log_index) = s.split_once('_').context("missing separator")?;
+        Ok(Self {
+            block_number: block_number.parse()?,
+            log_index: log_index.parse()?,
+        })
+    }
 }
 
 #[async_trait::async_trait]
 impl TradeRetrieving for Postgres {
-    async fn trades(&self, filter: &TradeFilter) -> Result<Vec<Trade>> {
+    async fn trades(
+        &self,
+        filter: &TradeFilter,
+        cursor: Option<TradeCursor>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Trade>> {
         let timer = super::Metrics::get()
             .database_queries
             .with_label_values(&["trades"])
             .start_timer();
 
+        let filter = database::trades::TradeFilter {
+            owner: filter.owner.map(|owner| ByteArray(owner.0)),
+            order_uid: filter.order_uid.map(|uid| ByteArray(uid.0)),
+            class: filter.class.as_ref().map(order_class_into),
+            token: filter.token.map(|token| ByteArray(token.0)),
+            app_data: filter.app_data.map(|app_data| ByteArray(app_data.0)),
+            from_block: filter.from_block.map(to_i64),
+            to_block: filter.to_block.map(to_i64),
+        };
+        let cursor = cursor.map(|cursor| EventIndex {
+            block_number: to_i64(cursor.block_number),
+            log_index: to_i64(cursor.log_index),
+        });
         let mut ex = self.pool.acquire().await?;
-        let trades = database::trades::trades(
-            &mut ex,
-            filter.owner.map(|owner| ByteArray(owner.0)).as_ref(),
-            filter.order_uid.map(|uid| ByteArray(uid.0)).as_ref(),
-        )
-        .map_err(anyhow::Error::from)
-        .try_collect::<Vec<TradesQueryRow>>()
-        .await?;
+        let trades = database::trades::trades(&mut ex, &filter, cursor.as_ref(), limit.map(to_i64))
+            .map_err(anyhow::Error::from)
+            .try_collect::<Vec<TradesQueryRow>>()
+            .await?;
         timer.stop_and_record();
 
         let auction_order_uids = trades
@@ -67,6 +142,10 @@ impl TradeRetrieving for Postgres {
     }
 }
 
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
 fn trade_from(
     row: TradesQueryRow,
     executed_protocol_fees: Vec<ExecutedProtocolFee>,
@@ -110,4 +189,15 @@ mod tests {
     fn convert_trade() {
         trade_from(TradesQueryRow::default(), vec![]).unwrap();
     }
+
+    #[test]
+    fn trade_cursor_roundtrip() {
+        let cursor = TradeCursor {
+            block_number: 19_000_000,
+            log_index: 42,
+        };
+        assert_eq!(cursor.to_string().parse::<TradeCursor>().unwrap(), cursor);
+        assert!("19000000".parse::<TradeCursor>().is_err());
+        assert!("19000000_-1".parse::<TradeCursor>().is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use {
     app_data::AppDataHash,
+    chrono::{DateTime, Utc},
     model::{
         interaction::InteractionData,
         order::{BuyTokenDestination, OrderClass, OrderKind, OrderUid, SellTokenSource},
@@ -83,6 +84,17 @@ pub struct ExecutedAmounts {
     pub buy: U256,
 }
 
+/// Why an order was filtered from the auction.
+#[derive(Serialize, PartialEq, Debug, Clone)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct FilterReason {
+    /// For example `insufficient_balance` or `missing_price`.
+    pub reason: String,
+    /// Since when the order is filtered for this reason.
+    pub since: DateTime<Utc>,
+}
+
 /// Indicates that a solver has provided a solution, with `executed_amounts`
 /// determining whether the solution was provided for the desired order.
 #[derive(Serialize, PartialEq, Debug, Clone)]
@@ -102,8 +114,9 @@ pub struct SolutionInclusion {
 pub enum Status {
     /// Order is part of the orderbook but not actively being worked on. This
     /// can for example happen if the necessary balances are missing or if
-    /// the order's signature check fails.
-    Open,
+    /// the order's signature check fails. Contains why the order was filtered
+    /// from the auction if known.
+    Open(Option<FilterReason>),
     /// Order awaits being put into the current auction.
     Scheduled,
     /// Order is part of the current and solvers are computing solutions for it.
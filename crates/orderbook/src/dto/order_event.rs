@@ -0,0 +1,63 @@
+use {
+    chrono::{DateTime, Utc},
+    database::order_events::{OrderEvent, OrderEventLabel},
+    model::order::OrderUid,
+    serde::Serialize,
+};
+
+/// An event in the lifecycle of an order.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub order_uid: OrderUid,
+    pub timestamp: DateTime<Utc>,
+    pub kind: Kind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, strum_macros::IntoStaticStr)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum Kind {
+    /// The order was added to the orderbook.
+    Created,
+    /// The order was included in an auction and got sent to the solvers.
+    Active,
+    /// The order was filtered from the auction and did not get sent to the
+    /// solvers.
+    Filtered,
+    /// The order can not be settled on-chain, e.g. because the owner is
+    /// missing funds.
+    Invalid,
+    /// A solver proposed a valid solution including the order.
+    Solved,
+    /// The order was included in the winning solution which the solver
+    /// currently tries to submit on-chain.
+    Executing,
+    /// The order was settled on-chain.
+    Traded,
+    /// The user cancelled the order.
+    Cancelled,
+    /// The order expired without being fully executed.
+    Expired,
+}
+
+impl From<OrderEvent> for Event {
+    fn from(event: OrderEvent) -> Self {
+        Self {
+            order_uid: OrderUid(event.order_uid.0),
+            timestamp: event.timestamp,
+            kind: match event.label {
+                OrderEventLabel::Created => Kind::Created,
+                OrderEventLabel::Ready => Kind::Active,
+                OrderEventLabel::Filtered => Kind::Filtered,
+                OrderEventLabel::Invalid => Kind::Invalid,
+                OrderEventLabel::Considered => Kind::Solved,
+                OrderEventLabel::Executing => Kind::Executing,
+                OrderEventLabel::Traded => Kind::Traded,
+                OrderEventLabel::Cancelled => Kind::Cancelled,
+            },
+        }
+    }
+}
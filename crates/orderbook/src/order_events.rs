@@ -0,0 +1,312 @@
+//! Streams the lifecycle events of orders to API clients as they get stored in
+//! the `order_events` table, so that they don't need to poll the order status.
+
+use {
+    crate::{
+        database::{Postgres, orders::OrderStoring},
+        dto::order_event::{Event, Kind},
+    },
+    chrono::{DateTime, Utc},
+    database::order_events,
+    futures::{Stream, stream},
+    model::{
+        order::{OrderStatus, OrderUid},
+        time::now_in_epoch_seconds,
+    },
+    primitive_types::H160,
+    std::{collections::HashMap, sync::Arc, time::Duration},
+    tokio::sync::broadcast::{self, error::RecvError},
+};
+
+/// How many events a subscriber can fall behind before it starts missing
+/// events.
+const CAPACITY: usize = 10_000;
+
+/// How long to wait before listening for events again after the connection
+/// to the database failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Which orders a subscriber receives events for.
+#[derive(Clone, Copy, Debug)]
+pub enum Filter {
+    Order(OrderUid),
+    Owner(H160),
+}
+
+impl Filter {
+    fn matches(&self, uid: &OrderUid) -> bool {
+        match self {
+            Filter::Order(order) => order == uid,
+            Filter::Owner(owner) => uid.parts().1 == *owner,
+        }
+    }
+}
+
+pub struct OrderEvents {
+    sender: broadcast::Sender<Event>,
+    database: Arc<dyn OrderStoring>,
+}
+
+impl OrderEvents {
+    /// Starts forwarding the order events inserted into the database to the
+    /// subscribers.
+    pub fn new(database: Postgres) -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        tokio::spawn(forward(database.clone(), sender.clone()));
+        Self {
+            sender,
+            database: Arc::new(database),
+        }
+    }
+
+    /// Streams the events of the orders matching the filter. When subscribing
+    /// to a single order, the stream starts with the latest event of the
+    /// order.
+    ///
+    /// The expiration of an order is not recorded in the database, so an
+    /// `expired` event is emitted once an order with events since
+    /// subscribing reaches its `validTo` without being fully executed.
+    pub fn subscribe(&self, filter: Filter) -> impl Stream<Item = Event> + Send + 'static {
+        let subscription = Subscription {
+            events: self.sender.subscribe(),
+            database: self.database.clone(),
+            filter,
+            expirations: Default::default(),
+            initialized: false,
+        };
+        stream::unfold(subscription, |mut subscription| async move {
+            let event = subscription.next().await?;
+            Some((event, subscription))
+        })
+    }
+}
+
+/// Publishes the order events inserted into the database on the channel.
+async fn forward(database: Postgres, sender: broadcast::Sender<Event>) {
+    loop {
+        match order_events::listen(&database.pool).await {
+            Ok(mut listener) => loop {
+                let notification = match listener.recv().await {
+                    Ok(notification) => notification,
+                    Err(err) => {
+                        tracing::warn!(?err, "lost connection to order events");
+                        break;
+                    }
+                };
+                match order_events::parse_notification(notification.payload()) {
+                    Some(event) => {
+                        // Sending only fails if there are no subscribers.
+                        let _ = sender.send(event.into());
+                    }
+                    None => tracing::warn!(
+                        payload = notification.payload(),
+                        "invalid order event notification"
+                    ),
+                }
+            },
+            Err(err) => tracing::warn!(?err, "failed to listen for order events"),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+struct Subscription {
+    events: broadcast::Receiver<Event>,
+    database: Arc<dyn OrderStoring>,
+    filter: Filter,
+    /// The `validTo` of the orders that may still expire.
+    expirations: HashMap<OrderUid, u32>,
+    /// Whether the latest event of the subscribed order was emitted.
+    initialized: bool,
+}
+
+impl Subscription {
+    async fn next(&mut self) -> Option<Event> {
+        if !self.initialized {
+            self.initialized = true;
+            if let Some(event) = self.latest_event().await {
+                self.track(&event);
+                return Some(event);
+            }
+        }
+
+        loop {
+            let expiration = self
+                .expirations
+                .iter()
+                .min_by_key(|(_, valid_to)| **valid_to)
+                .map(|(uid, valid_to)| (*uid, *valid_to));
+            let expired = async {
+                match expiration {
+                    // Orders are still valid in the second of their `validTo`.
+                    Some((_, valid_to)) => {
+                        let remaining = valid_to.saturating_sub(now_in_epoch_seconds()) + 1;
+                        tokio::time::sleep(Duration::from_secs(remaining.into())).await
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                event = self.events.recv() => match event {
+                    Ok(event) if self.filter.matches(&event.order_uid) => {
+                        self.track(&event);
+                        return Some(event);
+                    }
+                    Ok(_) => (),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "order event subscriber fell behind");
+                    }
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = expired => {
+                    let (uid, valid_to) = expiration.expect("only completes with an expiration");
+                    self.expirations.remove(&uid);
+                    if self.is_expired(&uid).await {
+                        return Some(Event {
+                            order_uid: uid,
+                            timestamp: DateTime::from_timestamp(valid_to.into(), 0)
+                                .unwrap_or_else(Utc::now),
+                            kind: Kind::Expired,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fetches the latest event of the subscribed order.
+    async fn latest_event(&self) -> Option<Event> {
+        let Filter::Order(uid) = self.filter else {
+            return None;
+        };
+        match self.database.latest_order_event(&uid).await {
+            Ok(event) => event.map(Into::into),
+            Err(err) => {
+                tracing::warn!(?err, %uid, "failed to fetch latest order event");
+                None
+            }
+        }
+    }
+
+    /// Keeps track of when the order of the event expires.
+    fn track(&mut self, event: &Event) {
+        match event.kind {
+            Kind::Cancelled | Kind::Expired => {
+                self.expirations.remove(&event.order_uid);
+            }
+            _ => {
+                let (_, _, valid_to) = event.order_uid.parts();
+                self.expirations.insert(event.order_uid, valid_to);
+            }
+        }
+    }
+
+    async fn is_expired(&self, uid: &OrderUid) -> bool {
+        match self.database.single_order(uid).await {
+            Ok(order) => order.is_some_and(|order| order.metadata.status == OrderStatus::Expired),
+            Err(err) => {
+                tracing::warn!(?err, %uid, "failed to fetch expired order");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::database::orders::MockOrderStoring,
+        database::{
+            byte_array::ByteArray,
+            order_events::{OrderEvent, OrderEventLabel},
+        },
+        futures::StreamExt,
+        model::order::{Order, OrderMetadata},
+        primitive_types::H256,
+    };
+
+    fn event(uid: OrderUid, kind: Kind) -> Event {
+        Event {
+            order_uid: uid,
+            timestamp: DateTime::from_timestamp(1, 0).unwrap(),
+            kind,
+        }
+    }
+
+    fn order_events(database: MockOrderStoring) -> (OrderEvents, broadcast::Sender<Event>) {
+        let (sender, _) = broadcast::channel(CAPACITY);
+        let order_events = OrderEvents {
+            sender: sender.clone(),
+            database: Arc::new(database),
+        };
+        (order_events, sender)
+    }
+
+    #[tokio::test]
+    async fn streams_events_of_owner() {
+        let owner = H160([1; 20]);
+        let uid = OrderUid::from_parts(H256([1; 32]), owner, u32::MAX);
+        let other = OrderUid::from_parts(H256([2; 32]), H160([2; 20]), u32::MAX);
+        let (order_events, sender) = order_events(MockOrderStoring::new());
+
+        let mut stream = Box::pin(order_events.subscribe(Filter::Owner(owner)));
+        sender.send(event(other, Kind::Created)).unwrap();
+        sender.send(event(uid, Kind::Created)).unwrap();
+        sender.send(event(uid, Kind::Traded)).unwrap();
+
+        assert_eq!(stream.next().await, Some(event(uid, Kind::Created)));
+        assert_eq!(stream.next().await, Some(event(uid, Kind::Traded)));
+    }
+
+    #[tokio::test]
+    async fn starts_with_latest_event_of_order() {
+        let uid = OrderUid::from_parts(H256([1; 32]), H160([1; 20]), u32::MAX);
+        let mut database = MockOrderStoring::new();
+        database.expect_latest_order_event().returning(move |_| {
+            Ok(Some(OrderEvent {
+                order_uid: ByteArray(uid.0),
+                timestamp: DateTime::from_timestamp(1, 0).unwrap(),
+                label: OrderEventLabel::Ready,
+            }))
+        });
+        let (order_events, sender) = order_events(database);
+
+        let mut stream = Box::pin(order_events.subscribe(Filter::Order(uid)));
+        assert_eq!(stream.next().await, Some(event(uid, Kind::Active)));
+        sender.send(event(uid, Kind::Solved)).unwrap();
+        assert_eq!(stream.next().await, Some(event(uid, Kind::Solved)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn emits_expiration() {
+        let valid_to = now_in_epoch_seconds() + 10;
+        let uid = OrderUid::from_parts(H256([1; 32]), H160([1; 20]), valid_to);
+        let mut database = MockOrderStoring::new();
+        database.expect_latest_order_event().returning(|_| Ok(None));
+        database.expect_single_order().returning(move |_| {
+            Ok(Some(Order {
+                metadata: OrderMetadata {
+                    uid,
+                    status: OrderStatus::Expired,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }))
+        });
+        let (order_events, sender) = order_events(database);
+
+        let mut stream = Box::pin(order_events.subscribe(Filter::Order(uid)));
+        sender.send(event(uid, Kind::Created)).unwrap();
+        assert_eq!(stream.next().await, Some(event(uid, Kind::Created)));
+        assert_eq!(
+            stream.next().await,
+            Some(Event {
+                order_uid: uid,
+                timestamp: DateTime::from_timestamp(valid_to.into(), 0).unwrap(),
+                kind: Kind::Expired,
+            })
+        );
+    }
+}
@@ -1,18 +1,19 @@
 use {
     crate::{
         database::{
-            orders::{InsertionError, OrderStoring},
+            orders::{InsertionError, OrderStoring, UserOrderCursor, UserOrderFilter},
             trades::{TradeFilter, TradeRetrieving},
         },
         dto,
         solver_competition::{Identifier, LoadSolverCompetitionError, SolverCompetitionStoring},
     },
-    anyhow::{Context, Result},
+    anyhow::{Context, Result, anyhow},
     app_data::{AppDataHash, Validator},
     bigdecimal::ToPrimitive,
     chrono::Utc,
     database::order_events::OrderEventLabel,
     ethcontract::H256,
+    futures::future,
     model::{
         DomainSeparator,
         order::{
@@ -29,17 +30,20 @@ use {
     },
     observe::metrics::LivenessChecking,
     primitive_types::H160,
+    serde::Deserialize,
     shared::{
         fee::FeeParameters,
         order_quoting::Quote,
         order_validation::{
             Amounts,
+            LimitOrderCounting,
+            OrderDiagnosis,
             OrderValidating,
             ValidationError,
             is_order_outside_market_price,
         },
     },
-    std::{borrow::Cow, sync::Arc},
+    std::{borrow::Cow, collections::HashMap, sync::Arc},
     strum_macros::Display,
     thiserror::Error,
 };
@@ -73,28 +77,7 @@ impl Metrics {
     }
 
     fn on_order_operation(order: &Order, operation: OrderOperation) {
-        let class = if order.metadata.quote.as_ref().is_some_and(|quote| {
-            // Check if the order at the submission time was "in market"
-            !is_order_outside_market_price(
-                &Amounts {
-                    sell: order.data.sell_amount,
-                    buy: order.data.buy_amount,
-                    fee: order.data.fee_amount,
-                },
-                &Amounts {
-                    sell: quote.sell_amount,
-                    buy: quote.buy_amount,
-                    fee: FeeParameters {
-                        // safe to unwrap as these values were converted from f64 previously
-                        gas_amount: quote.gas_amount.to_f64().unwrap(),
-                        gas_price: quote.gas_price.to_f64().unwrap(),
-                        sell_token_price: quote.sell_token_price.to_f64().unwrap(),
-                    }
-                    .fee(),
-                },
-                order.data.kind,
-            )
-        }) {
+        let class = if is_in_market(order) == Some(true) {
             OrderClass::Market
         } else {
             OrderClass::Limit
@@ -119,6 +102,42 @@ impl Metrics {
     }
 }
 
+/// Whether the order was in market at the time it was placed according to its
+/// quote. Orders without quote are neither.
+fn is_in_market(order: &Order) -> Option<bool> {
+    let quote = order.metadata.quote.as_ref()?;
+    Some(!is_order_outside_market_price(
+        &Amounts {
+            sell: order.data.sell_amount,
+            buy: order.data.buy_amount,
+            fee: order.data.fee_amount,
+        },
+        &Amounts {
+            sell: quote.sell_amount,
+            buy: quote.buy_amount,
+            fee: FeeParameters {
+                // safe to unwrap as these values were converted from f64 previously
+                gas_amount: quote.gas_amount.to_f64().unwrap(),
+                gas_price: quote.gas_price.to_f64().unwrap(),
+                sell_token_price: quote.sell_token_price.to_f64().unwrap(),
+            }
+            .fee(),
+        },
+        order.data.kind,
+    ))
+}
+
+/// How invalid orders are handled when placing a batch of orders.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchMode {
+    /// Only place the orders if all of them are valid.
+    #[default]
+    AllOrNothing,
+    /// Place all the valid orders.
+    BestEffort,
+}
+
 #[derive(Debug, Error)]
 pub enum AddOrderError {
     #[error("unable to find an existing order: {0}")]
@@ -144,6 +163,10 @@ pub enum AddOrderError {
     },
     #[error("quote metadata failed to serialize as json, error: {0}")]
     MetadataSerializationFailed(serde_json::Error),
+    #[error("order replacements are not supported in batches")]
+    ReplacementInBatch,
+    #[error("another order of the batch was rejected")]
+    BatchRejected,
 }
 
 impl AddOrderError {
@@ -234,6 +257,7 @@ pub struct Orderbook {
     order_validator: Arc<dyn OrderValidating>,
     app_data: Arc<crate::app_data::Registry>,
     active_order_competition_threshold: u32,
+    max_limit_orders_per_user: u64,
 }
 
 impl Orderbook {
@@ -245,6 +269,7 @@ impl Orderbook {
         order_validator: Arc<dyn OrderValidating>,
         app_data: Arc<crate::app_data::Registry>,
         active_order_competition_threshold: u32,
+        max_limit_orders_per_user: u64,
     ) -> Self {
         Metrics::initialize();
         Self {
@@ -254,6 +279,7 @@ impl Orderbook {
             order_validator,
             app_data,
             active_order_competition_threshold,
+            max_limit_orders_per_user,
         }
     }
 
@@ -296,6 +322,165 @@ impl Orderbook {
         Ok((order_uid, quote.as_ref().map(QuoteMetadata::from)))
     }
 
+    /// Places a batch of orders. The orders are validated concurrently and the
+    /// valid ones are inserted in a single database transaction. Returns the
+    /// result of each order in the order of the payloads.
+    pub async fn add_orders(
+        &self,
+        payloads: Vec<OrderCreation>,
+        mode: BatchMode,
+    ) -> Vec<Result<(OrderUid, Option<QuoteMetadata>), AddOrderError>> {
+        let results = future::join_all(
+            payloads
+                .into_iter()
+                .map(|payload| self.validate_batch_order(payload)),
+        )
+        .await;
+        let results = self.check_batch_limit_orders(results).await;
+
+        let all_or_nothing = mode == BatchMode::AllOrNothing;
+        if all_or_nothing && results.iter().any(Result::is_err) {
+            return results
+                .into_iter()
+                .map(|result| match result {
+                    Ok(_) => Err(AddOrderError::BatchRejected),
+                    Err(err) => Err(err),
+                })
+                .collect();
+        }
+
+        let orders = results
+            .iter()
+            .filter_map(|result| Some(result.as_ref().ok()?.0.clone()))
+            .collect::<Vec<_>>();
+        let insertions = match self.database.insert_orders(&orders, all_or_nothing).await {
+            Ok(insertions) => insertions,
+            Err(err) => {
+                tracing::warn!(?err, "failed to insert batch of orders");
+                return results
+                    .into_iter()
+                    .map(|result| {
+                        result?;
+                        Err(AddOrderError::Database(anyhow!(
+                            "failed to insert batch of orders"
+                        )))
+                    })
+                    .collect();
+            }
+        };
+        let rolled_back = all_or_nothing && insertions.iter().any(Result::is_err);
+
+        let mut insertions = insertions.into_iter();
+        results
+            .into_iter()
+            .map(|result| {
+                let (order, quote) = result?;
+                match insertions.next().expect("one insertion per valid order") {
+                    Ok(()) if rolled_back => Err(AddOrderError::BatchRejected),
+                    Ok(()) => {
+                        Metrics::on_order_operation(&order, OrderOperation::Created);
+                        Ok((order.metadata.uid, quote))
+                    }
+                    Err(err) => Err(AddOrderError::from_insertion(err, &order)),
+                }
+            })
+            .collect()
+    }
+
+    async fn validate_batch_order(
+        &self,
+        payload: OrderCreation,
+    ) -> Result<(Order, Option<QuoteMetadata>), AddOrderError> {
+        let full_app_data_override = match payload.app_data {
+            OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
+            _ => None,
+        };
+
+        if self
+            .get_replaced_order(&payload, full_app_data_override.as_deref())
+            .await?
+            .is_some()
+        {
+            return Err(AddOrderError::ReplacementInBatch);
+        }
+
+        let (order, quote) = self
+            .order_validator
+            .validate_and_construct_order(
+                payload,
+                &self.domain_separator,
+                self.settlement_contract,
+                full_app_data_override,
+            )
+            .await?;
+        Ok((order, quote.as_ref().map(QuoteMetadata::from)))
+    }
+
+    /// Every order was checked against the limit orders of its owner on its
+    /// own, so this makes sure that the orders of the batch don't exceed the
+    /// limit together either.
+    async fn check_batch_limit_orders(
+        &self,
+        mut results: Vec<Result<(Order, Option<QuoteMetadata>), AddOrderError>>,
+    ) -> Vec<Result<(Order, Option<QuoteMetadata>), AddOrderError>> {
+        let mut limit_orders = HashMap::<H160, Vec<usize>>::new();
+        let valid_orders = results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, result)| Some((i, &result.as_ref().ok()?.0)));
+        for (i, order) in valid_orders {
+            // Like the limit order counter, only orders outside the market price
+            // of their quote count.
+            if is_in_market(order) == Some(false) {
+                limit_orders
+                    .entry(order.metadata.owner)
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        for (owner, indices) in limit_orders {
+            // Single limit orders were already checked during validation.
+            if indices.len() < 2 {
+                continue;
+            }
+            let count = match LimitOrderCounting::count(&self.database, owner).await {
+                Ok(count) => count,
+                Err(err) => {
+                    tracing::warn!(?err, ?owner, "failed to count limit orders");
+                    for i in indices {
+                        results[i] = Err(AddOrderError::Database(anyhow!(
+                            "failed to count limit orders"
+                        )));
+                    }
+                    continue;
+                }
+            };
+            let allowed = self.max_limit_orders_per_user.saturating_sub(count);
+            for i in indices
+                .into_iter()
+                .skip(usize::try_from(allowed).unwrap_or(usize::MAX))
+            {
+                results[i] = Err(ValidationError::TooManyLimitOrders.into());
+            }
+        }
+
+        results
+    }
+
+    /// Runs all order placement checks without placing the order.
+    pub async fn validate_order(&self, payload: OrderCreation) -> Result<OrderDiagnosis> {
+        let full_app_data_override = match payload.app_data {
+            OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
+            _ => None,
+        };
+
+        Ok(self
+            .order_validator
+            .diagnose_order(payload, &self.domain_separator, full_app_data_override)
+            .await)
+    }
+
     /// Finds an order for cancellation.
     ///
     /// Returns an error if the order cannot be found or cannot be cancelled.
@@ -496,11 +681,13 @@ impl Orderbook {
     pub async fn get_user_orders(
         &self,
         owner: &H160,
+        filter: &UserOrderFilter,
+        cursor: Option<UserOrderCursor>,
         offset: u64,
         limit: u64,
     ) -> Result<Vec<Order>> {
         self.database
-            .user_orders(owner, offset, Some(limit))
+            .user_orders(owner, filter, cursor, offset, Some(limit))
             .await
             .context("get_user_orders error")
     }
@@ -550,13 +737,18 @@ impl Orderbook {
         // table and return the appropriate competition data.
         let trades = self
             .database
-            .trades(&TradeFilter {
-                owner: None,
-                order_uid: Some(*uid),
-            })
+            .trades(
+                &TradeFilter {
+                    order_uid: Some(*uid),
+                    ..Default::default()
+                },
+                None,
+                None,
+            )
             .await?;
 
-        match trades.first().map(|trade| trade.tx_hash) {
+        // Trades are sorted from the most recent to the oldest.
+        match trades.last().map(|trade| trade.tx_hash) {
             Some(Some(tx_hash)) => {
                 let competition = self
                     .database
@@ -580,8 +772,13 @@ impl Orderbook {
             // order executed but not fully indexed and processed
             OrderEventLabel::Traded => dto::order::Status::Traded(latest_competition.await?),
             OrderEventLabel::Cancelled => dto::order::Status::Cancelled,
-            OrderEventLabel::Filtered => dto::order::Status::Open,
-            OrderEventLabel::Invalid => dto::order::Status::Open,
+            OrderEventLabel::Filtered | OrderEventLabel::Invalid => {
+                let reason = self.database.latest_filter_reason(uid).await?;
+                dto::order::Status::Open(reason.map(|reason| dto::order::FilterReason {
+                    reason: reason.reason,
+                    since: reason.timestamp,
+                }))
+            }
         };
         Ok(status)
     }
@@ -686,6 +883,7 @@ mod tests {
             settlement_contract: H160([0xba; 20]),
             app_data,
             active_order_competition_threshold: Default::default(),
+            max_limit_orders_per_user: Default::default(),
         };
 
         // Different owner
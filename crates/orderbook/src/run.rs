@@ -5,6 +5,7 @@ use {
         database::Postgres,
         ipfs::Ipfs,
         ipfs_app_data::IpfsAppData,
+        order_events::OrderEvents,
         orderbook::Orderbook,
         quoter::QuoteHandler,
     },
@@ -33,6 +34,7 @@ use {
         http_client::HttpClientFactory,
         order_quoting::{self, OrderQuoter},
         order_validation::{OrderValidPeriodConfiguration, OrderValidator},
+        permit_validator,
         price_estimation::{
             PriceEstimating,
             QuoteVerificationMode,
@@ -63,10 +65,10 @@ pub async fn start(args: impl Iterator<Item = String>) {
 pub async fn run(args: Arguments) {
     let http_factory = HttpClientFactory::new(&args.http_client);
 
-    let web3 = shared::ethrpc::web3(
+    let web3 = shared::ethrpc::multi_web3(
         &args.shared.ethrpc,
         &http_factory,
-        &args.shared.node_url,
+        &args.shared.node_urls(),
         "base",
     );
     let simulation_web3 = args.shared.simulation_node_url.as_ref().map(|node_url| {
@@ -141,14 +143,15 @@ pub async fn run(args: Arguments) {
     let domain_separator = DomainSeparator::new(chain_id, settlement_contract.address());
     let postgres = Postgres::try_new(args.db_url.as_str()).expect("failed to create database");
 
-    let balance_fetcher = account_balances::fetcher(
+    // Batches of orders often simulate the same transfers.
+    let balance_fetcher = account_balances::sharing(account_balances::fetcher(
         &web3,
         account_balances::Contracts {
             settlement: settlement_contract.address(),
             vault_relayer,
             vault: vault.as_ref().map(|contract| contract.address()),
         },
-    );
+    ));
 
     let gas_price_estimator = Arc::new(InstrumentedGasEstimator::new(
         shared::gas_price_estimation::create_priority_estimator(
@@ -156,6 +159,7 @@ pub async fn run(args: Arguments) {
             &web3,
             args.shared.gas_estimators.as_slice(),
             args.shared.blocknative_api_key.clone(),
+            args.shared.fee_history(),
         )
         .await
         .expect("failed to create gas price estimator"),
@@ -369,6 +373,7 @@ pub async fn run(args: Arguments) {
         Arc::new(postgres.clone()),
         args.max_limit_orders_per_user,
         code_fetcher,
+        permit_validator::validator(&web3, vault_relayer),
         app_data_validator.clone(),
         args.max_gas_per_order,
     ));
@@ -395,9 +400,11 @@ pub async fn run(args: Arguments) {
         order_validator.clone(),
         app_data.clone(),
         args.active_order_competition_threshold,
+        args.max_limit_orders_per_user,
     ));
 
     check_database_connection(orderbook.as_ref()).await;
+    let order_events = Arc::new(OrderEvents::new(postgres.clone()));
     let quotes = Arc::new(
         QuoteHandler::new(order_validator, optimal_quoter, app_data.clone())
             .with_fast_quoter(fast_quoter),
@@ -414,6 +421,7 @@ pub async fn run(args: Arguments) {
             let _ = shutdown_receiver.await;
         },
         native_price_estimator,
+        order_events,
     );
 
     let mut metrics_address = args.bind_address;
@@ -479,6 +487,7 @@ fn serve_api(
     address: SocketAddr,
     shutdown_receiver: impl Future<Output = ()> + Send + 'static,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
+    order_events: Arc<OrderEvents>,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
@@ -486,6 +495,7 @@ fn serve_api(
         quotes,
         app_data,
         native_price_estimator,
+        order_events,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
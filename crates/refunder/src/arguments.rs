@@ -1,7 +1,13 @@
 use {
     clap::Parser,
     ethcontract::H160,
-    shared::{arguments::display_option, ethrpc, http_client, logging_args_with_default_filter},
+    shared::{
+        arguments::display_option,
+        ethrpc,
+        gas_price_estimation::GasEstimatorType,
+        http_client,
+        logging_args_with_default_filter,
+    },
     std::time::Duration,
     tracing::level_filters::LevelFilter,
     url::Url,
@@ -64,6 +70,18 @@ pub struct Arguments {
     #[clap(long, env, hide_env_values = true)]
     pub refunder_pk: String,
 
+    /// Which gas estimators to use for the refund transactions. Multiple
+    /// estimators are used in sequence if a previous one fails.
+    #[clap(
+        long,
+        env,
+        default_value = "Web3",
+        value_enum,
+        ignore_case = true,
+        use_value_delimiter = true
+    )]
+    pub gas_estimators: Vec<GasEstimatorType>,
+
     /// The port at which we serve our metrics
     #[clap(long, env, default_value = "9590")]
     pub metrics_port: u16,
@@ -83,6 +101,7 @@ impl std::fmt::Display for Arguments {
             logging,
             db_url,
             refunder_pk,
+            gas_estimators,
         } = self;
 
         write!(f, "{}", http_client)?;
@@ -97,6 +116,7 @@ impl std::fmt::Display for Arguments {
         writeln!(f, "ethflow_contracts: {:?}", ethflow_contracts)?;
         let _intentionally_ignored = refunder_pk;
         writeln!(f, "refunder_pk: SECRET")?;
+        writeln!(f, "gas_estimators: {:?}", gas_estimators)?;
         writeln!(f, "metrics_port: {}", metrics_port)?;
         Ok(())
     }
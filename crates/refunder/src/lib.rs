@@ -63,6 +63,15 @@ pub async fn run(args: arguments::Arguments) {
         .map(|contract| CoWSwapEthFlow::at(&web3, *contract))
         .collect();
     let refunder_account = Account::Offline(args.refunder_pk.parse::<PrivateKey>().unwrap(), None);
+    let gas_estimator = shared::gas_price_estimation::create_priority_estimator(
+        &http_factory,
+        &web3,
+        &args.gas_estimators,
+        None,
+        Default::default(),
+    )
+    .await
+    .expect("failed to create gas price estimator");
     let mut refunder = RefundService::new(
         pg_pool,
         web3,
@@ -70,6 +79,7 @@ pub async fn run(args: arguments::Arguments) {
         i64::try_from(args.min_validity_duration.as_secs()).unwrap_or(i64::MAX),
         args.min_price_deviation_bps,
         refunder_account,
+        Box::new(gas_estimator),
     );
     loop {
         tracing::info!("Staring a new refunding loop");
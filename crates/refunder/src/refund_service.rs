@@ -11,6 +11,7 @@ use {
     ethcontract::{Account, H160, H256},
     ethrpc::{Web3, block_stream::timestamp_of_current_block_in_seconds},
     futures::{StreamExt, stream},
+    gas_estimation::GasPriceEstimating,
     sqlx::PgPool,
     std::collections::HashMap,
 };
@@ -45,6 +46,7 @@ impl RefundService {
         min_validity_duration: i64,
         min_price_deviation_bps: i64,
         account: Account,
+        gas_estimator: Box<dyn GasPriceEstimating>,
     ) -> Self {
         RefundService {
             db,
@@ -55,7 +57,7 @@ impl RefundService {
             submitter: Submitter {
                 web3: web3.clone(),
                 account,
-                gas_estimator: Box::new(web3),
+                gas_estimator,
                 gas_parameters_of_last_tx: None,
                 nonce_of_last_submission: None,
             },
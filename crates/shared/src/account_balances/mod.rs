@@ -10,6 +10,7 @@ use {
 };
 
 mod cached;
+mod sharing;
 mod simulation;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -92,3 +93,9 @@ pub fn cached(
     cached.spawn_background_task(blocks);
     cached
 }
+
+/// Create a [`BalanceFetching`] instance which shares identical transfer
+/// simulations that are in flight at the same time.
+pub fn sharing(inner: Arc<dyn BalanceFetching>) -> Arc<dyn BalanceFetching> {
+    Arc::new(sharing::Balances::new(inner))
+}
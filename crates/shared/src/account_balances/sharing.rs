@@ -0,0 +1,125 @@
+use {
+    crate::{
+        account_balances::{BalanceFetching, Query, TransferSimulationError},
+        request_sharing::BoxRequestSharing,
+    },
+    anyhow::{Result, anyhow},
+    futures::FutureExt,
+    primitive_types::U256,
+    std::sync::Arc,
+};
+
+type SharedResult = Result<(), Arc<TransferSimulationError>>;
+
+/// Shares the transfer simulations of identical queries which are in flight at
+/// the same time, for example when many orders of the same owner get placed at
+/// once.
+pub struct Balances {
+    inner: Arc<dyn BalanceFetching>,
+    transfers: BoxRequestSharing<(Query, U256), SharedResult>,
+}
+
+impl Balances {
+    pub fn new(inner: Arc<dyn BalanceFetching>) -> Self {
+        Self {
+            inner,
+            transfers: BoxRequestSharing::labelled("can_transfer".into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BalanceFetching for Balances {
+    async fn get_balances(&self, queries: &[Query]) -> Vec<Result<U256>> {
+        self.inner.get_balances(queries).await
+    }
+
+    async fn can_transfer(
+        &self,
+        query: &Query,
+        amount: U256,
+    ) -> Result<(), TransferSimulationError> {
+        let inner = self.inner.clone();
+        let result = self
+            .transfers
+            .shared_or_else((query.clone(), amount), move |(query, amount)| {
+                let (query, amount) = (query.clone(), *amount);
+                async move { inner.can_transfer(&query, amount).await.map_err(Arc::new) }.boxed()
+            })
+            .await;
+        result.map_err(|err| match &*err {
+            TransferSimulationError::InsufficientAllowance => {
+                TransferSimulationError::InsufficientAllowance
+            }
+            TransferSimulationError::InsufficientBalance => {
+                TransferSimulationError::InsufficientBalance
+            }
+            TransferSimulationError::TransferFailed => TransferSimulationError::TransferFailed,
+            TransferSimulationError::Other(err) => {
+                TransferSimulationError::Other(anyhow!("{err:?}"))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ethcontract::H160,
+        model::order::SellTokenSource,
+        std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::Duration,
+        },
+    };
+
+    /// Counts the transfer simulations which take a while to complete.
+    #[derive(Default)]
+    struct SlowTransfers(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl BalanceFetching for SlowTransfers {
+        async fn get_balances(&self, _: &[Query]) -> Vec<Result<U256>> {
+            panic!("not used in this test")
+        }
+
+        async fn can_transfer(&self, _: &Query, _: U256) -> Result<(), TransferSimulationError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Err(TransferSimulationError::InsufficientBalance)
+        }
+    }
+
+    #[tokio::test]
+    async fn shares_concurrent_transfer_simulations() {
+        let query = Query {
+            owner: H160([1; 20]),
+            token: H160([2; 20]),
+            source: SellTokenSource::Erc20,
+            interactions: vec![],
+        };
+        let inner = Arc::new(SlowTransfers::default());
+
+        let fetcher = Balances::new(inner.clone());
+        let (a, b, c) = futures::join!(
+            fetcher.can_transfer(&query, 1.into()),
+            fetcher.can_transfer(&query, 1.into()),
+            fetcher.can_transfer(&query, 2.into()),
+        );
+        assert!(matches!(
+            a,
+            Err(TransferSimulationError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            b,
+            Err(TransferSimulationError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            c,
+            Err(TransferSimulationError::InsufficientBalance)
+        ));
+        // The simulation of the same amount was shared.
+        assert_eq!(inner.0.load(Ordering::SeqCst), 2);
+    }
+}
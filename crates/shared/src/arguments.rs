@@ -3,7 +3,7 @@
 
 use {
     crate::{
-        gas_price_estimation::GasEstimatorType,
+        gas_price_estimation::{GasEstimatorType, fee_history},
         sources::{
             BaselineSource,
             balancer_v2::BalancerFactoryKind,
@@ -120,6 +120,12 @@ pub struct Arguments {
     #[clap(long, env, default_value = "http://localhost:8545")]
     pub node_url: Url,
 
+    /// Additional URLs of Ethereum nodes serving the same chain as `node_url`.
+    /// If specified, requests are load-balanced across all of the nodes and
+    /// fail over to other nodes on errors.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub node_fallback_urls: Vec<Url>,
+
     /// An Ethereum node URL that supports `eth_call`s with state overrides to
     /// be used for simulations.
     #[clap(long, env)]
@@ -137,6 +143,7 @@ pub struct Arguments {
     /// `GasNow`: supports mainnet.
     /// `Web3`: supports every network.
     /// `Native`: supports every network.
+    /// `FeeHistory`: supports every network.
     #[clap(
         long,
         env,
@@ -147,6 +154,17 @@ pub struct Arguments {
     )]
     pub gas_estimators: Vec<GasEstimatorType>,
 
+    /// The number of recent blocks the `FeeHistory` gas estimator derives its
+    /// estimate from.
+    #[clap(long, env, default_value = "20")]
+    pub fee_history_block_count: u64,
+
+    /// The percentile of the priority fees paid in each block, weighted by gas
+    /// used, that the `FeeHistory` gas estimator considers to be that block's
+    /// priority fee.
+    #[clap(long, env, default_value = "20", value_parser = parse_percentile)]
+    pub fee_history_reward_percentile: f64,
+
     /// BlockNative requires api key to work. Optional since BlockNative could
     /// be skipped in gas estimators.
     #[clap(long, env)]
@@ -258,6 +276,25 @@ pub struct Arguments {
     pub token_quality_cache_prefetch_time: Duration,
 }
 
+impl Arguments {
+    /// Returns the URLs of all configured Ethereum nodes, starting with the
+    /// primary `node_url`.
+    pub fn node_urls(&self) -> Vec<Url> {
+        std::iter::once(self.node_url.clone())
+            .chain(self.node_fallback_urls.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the parameters of the `FeeHistory` gas estimator.
+    pub fn fee_history(&self) -> fee_history::Params {
+        fee_history::Params {
+            block_count: self.fee_history_block_count,
+            reward_percentile: self.fee_history_reward_percentile,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn display_secret_option<T>(
     f: &mut Formatter<'_>,
     name: &str,
@@ -335,9 +372,12 @@ impl Display for Arguments {
             tenderly,
             logging,
             node_url,
+            node_fallback_urls,
             chain_id,
             simulation_node_url,
             gas_estimators,
+            fee_history_block_count,
+            fee_history_reward_percentile,
             blocknative_api_key,
             base_tokens,
             baseline_sources,
@@ -364,9 +404,16 @@ impl Display for Arguments {
         write!(f, "{}", tenderly)?;
         write!(f, "{}", logging)?;
         writeln!(f, "node_url: {}", node_url)?;
+        display_list(f, "node_fallback_urls", node_fallback_urls)?;
         display_option(f, "chain_id", chain_id)?;
         display_option(f, "simulation_node_url", simulation_node_url)?;
         writeln!(f, "gas_estimators: {:?}", gas_estimators)?;
+        writeln!(f, "fee_history_block_count: {}", fee_history_block_count)?;
+        writeln!(
+            f,
+            "fee_history_reward_percentile: {}",
+            fee_history_reward_percentile
+        )?;
         display_secret_option(f, "blocknative_api_key", blocknative_api_key.as_ref())?;
         writeln!(f, "base_tokens: {:?}", base_tokens)?;
         writeln!(f, "baseline_sources: {:?}", baseline_sources)?;
@@ -455,6 +502,12 @@ pub fn parse_percentage_factor(s: &str) -> Result<f64> {
     Ok(percentage_factor)
 }
 
+pub fn parse_percentile(s: &str) -> Result<f64> {
+    let percentile = f64::from_str(s)?;
+    ensure!(percentile.is_finite() && (0. ..=100.).contains(&percentile));
+    Ok(percentile)
+}
+
 pub fn wei_from_ether(s: &str) -> anyhow::Result<U256> {
     let in_ether = s.parse::<BigDecimal>()?;
     let base = BigDecimal::new(1.into(), -18);
@@ -1,11 +1,17 @@
 //! Global block stream arguments.
 
 use {
+    crate::arguments::display_option,
     anyhow::Result,
     clap::Parser,
     ethrpc::{
         Web3,
-        block_stream::{BlockRetrieving, CurrentBlockWatcher, current_block_stream},
+        block_stream::{
+            BlockRetrieving,
+            CurrentBlockWatcher,
+            current_block_stream,
+            current_block_ws_stream,
+        },
     },
     std::{
         fmt::{self, Display, Formatter},
@@ -28,6 +34,12 @@ pub struct Arguments {
         value_parser = humantime::parse_duration,
     )]
     pub block_stream_poll_interval: Duration,
+
+    /// The node's WebSocket RPC endpoint. If specified, new blocks are
+    /// observed with a `newHeads` subscription and polling is only used as a
+    /// fallback.
+    #[clap(long, env)]
+    pub node_ws_url: Option<Url>,
 }
 
 impl Arguments {
@@ -36,7 +48,12 @@ impl Arguments {
     }
 
     pub async fn stream(&self, rpc: Url) -> Result<CurrentBlockWatcher> {
-        current_block_stream(rpc, self.block_stream_poll_interval).await
+        match &self.node_ws_url {
+            Some(ws_url) => {
+                current_block_ws_stream(rpc, ws_url.clone(), self.block_stream_poll_interval).await
+            }
+            None => current_block_stream(rpc, self.block_stream_poll_interval).await,
+        }
     }
 }
 
@@ -44,6 +61,7 @@ impl Display for Arguments {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let Self {
             block_stream_poll_interval,
+            node_ws_url,
         } = self;
 
         writeln!(
@@ -51,6 +69,7 @@ impl Display for Arguments {
             "block_stream_poll_interval: {:?}",
             block_stream_poll_interval
         )?;
+        display_option(f, "node_ws_url", node_ws_url)?;
 
         Ok(())
     }
@@ -10,6 +10,7 @@ use {
     reqwest::Url,
     std::{
         fmt::{self, Display, Formatter},
+        num::NonZeroUsize,
         time::Duration,
     },
 };
@@ -34,6 +35,22 @@ pub struct Arguments {
     /// out an incomplete batch.
     #[clap(long, env, value_parser = humantime::parse_duration, default_value = "0s")]
     pub ethrpc_batch_delay: Duration,
+
+    /// Number of node endpoints that need to agree on the result of calls to
+    /// one of the `ethrpc_quorum_methods`. Only applies when multiple node
+    /// endpoints are configured and must not exceed their number.
+    #[clap(long, env, default_value = "1")]
+    pub ethrpc_quorum: NonZeroUsize,
+
+    /// JSON RPC methods for which a quorum of node endpoints needs to agree on
+    /// the result.
+    #[clap(long, env, use_value_delimiter = true, default_value = "eth_getLogs")]
+    pub ethrpc_quorum_methods: Vec<String>,
+
+    /// How long to avoid a node endpoint after it failed. Only applies when
+    /// multiple node endpoints are configured.
+    #[clap(long, env, value_parser = humantime::parse_duration, default_value = "10s")]
+    pub ethrpc_failure_backoff: Duration,
 }
 
 impl Display for Arguments {
@@ -42,6 +59,9 @@ impl Display for Arguments {
             ethrpc_max_batch_size,
             ethrpc_max_concurrent_requests,
             ethrpc_batch_delay,
+            ethrpc_quorum,
+            ethrpc_quorum_methods,
+            ethrpc_failure_backoff,
         } = self;
 
         writeln!(f, "ethrpc_max_batch_size: {}", ethrpc_max_batch_size)?;
@@ -51,6 +71,9 @@ impl Display for Arguments {
             ethrpc_max_concurrent_requests
         )?;
         writeln!(f, "ethrpc_batch_delay: {:?}", ethrpc_batch_delay)?;
+        writeln!(f, "ethrpc_quorum: {}", ethrpc_quorum)?;
+        writeln!(f, "ethrpc_quorum_methods: {:?}", ethrpc_quorum_methods)?;
+        writeln!(f, "ethrpc_failure_backoff: {:?}", ethrpc_failure_backoff)?;
 
         Ok(())
     }
@@ -62,6 +85,9 @@ impl Arguments {
             ethrpc_max_batch_size: self.ethrpc_max_batch_size,
             ethrpc_max_concurrent_requests: self.ethrpc_max_concurrent_requests,
             ethrpc_batch_delay: self.ethrpc_batch_delay,
+            ethrpc_quorum: self.ethrpc_quorum,
+            ethrpc_quorum_methods: self.ethrpc_quorum_methods.clone(),
+            ethrpc_failure_backoff: self.ethrpc_failure_backoff,
         }
     }
 }
@@ -76,3 +102,15 @@ pub fn web3(
     let http_builder = http_factory.builder();
     ethrpc::web3(args.ethrpc(), http_builder, url, name)
 }
+
+/// Create a Web3 instance that load-balances requests across multiple node
+/// endpoints and fails over between them.
+pub fn multi_web3(
+    args: &Arguments,
+    http_factory: &HttpClientFactory,
+    urls: &[Url],
+    name: impl ToString,
+) -> Web3 {
+    let http_builder = http_factory.builder();
+    ethrpc::multi_web3(args.ethrpc(), http_builder, urls, name)
+}
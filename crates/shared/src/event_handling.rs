@@ -8,7 +8,7 @@ use {
         dyns::DynTransport,
         errors::ExecutionError,
     },
-    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving, RangeInclusive},
+    ethrpc::block_stream::{BlockNumberHash, BlockRetrieving, RangeInclusive, Reorg},
     futures::{Stream, StreamExt, TryStreamExt, future},
     std::sync::Arc,
     tokio::sync::Mutex,
@@ -50,6 +50,9 @@ where
     contract: C,
     store: S,
     last_handled_blocks: Vec<BlockNumberHash>,
+    /// Set when a reorg was reported by the block stream, so that the events
+    /// of the replaced blocks get overwritten on the next update.
+    pending_reorg: bool,
 }
 
 /// `EventStoring` is used by `EventHandler` for the purpose of giving the user
@@ -122,6 +125,7 @@ where
                     None => vec![],
                 }
             },
+            pending_reorg: false,
         }
     }
 
@@ -162,6 +166,29 @@ where
         self.last_handled_blocks.last().cloned()
     }
 
+    /// Forgets about the handled blocks that were replaced by the specified
+    /// reorg, so that the next update re-indexes events starting right after
+    /// the common ancestor instead of having to detect the reorg itself.
+    pub fn handle_reorg(&mut self, reorg: &Reorg) {
+        let ancestor = reorg.common_ancestor.0;
+        let (Some(first), Some(last)) = (
+            self.last_handled_blocks.first(),
+            self.last_handled_blocks.last(),
+        ) else {
+            return;
+        };
+        // Nothing to do if none of the handled blocks got replaced. If the
+        // reorg is deeper than the handled blocks, we keep them and rely on the
+        // regular reorg detection instead.
+        if last.0 <= ancestor || first.0 > ancestor {
+            return;
+        }
+
+        tracing::debug!(?reorg, "dropping handled blocks replaced by reorg");
+        self.last_handled_blocks.retain(|block| block.0 <= ancestor);
+        self.pending_reorg = true;
+    }
+
     /// Defines block range, for which events should be fetched
     async fn event_block_range(&self) -> Result<EventRange> {
         let handled_blocks = if self.last_handled_blocks.is_empty() {
@@ -284,7 +311,8 @@ where
 
     /// Get new events from the contract and insert them into the database.
     pub async fn update_events(&mut self) -> Result<()> {
-        let event_range = self.event_block_range().await?;
+        let mut event_range = self.event_block_range().await?;
+        event_range.is_reorg |= self.pending_reorg;
 
         if let Some(range) = event_range.history_range {
             self.update_events_from_old_blocks(range).await?;
@@ -295,6 +323,7 @@ where
             self.store_mut()
                 .persist_last_indexed_block(last_block.0)
                 .await?;
+            self.pending_reorg = false;
         }
         Ok(())
     }
@@ -550,6 +579,10 @@ where
     fn name(&self) -> &str {
         "EventHandler"
     }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        self.lock().await.handle_reorg(reorg)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -16,6 +16,8 @@ use {
     std::sync::{Arc, Mutex},
 };
 
+pub mod fee_history;
+
 #[derive(Copy, Clone, Debug, clap::ValueEnum)]
 #[clap(rename_all = "verbatim")]
 pub enum GasEstimatorType {
@@ -24,6 +26,7 @@ pub enum GasEstimatorType {
     Web3,
     BlockNative,
     Native,
+    FeeHistory,
 }
 
 #[derive(Clone)]
@@ -51,6 +54,7 @@ pub async fn create_priority_estimator(
     web3: &Web3,
     estimator_types: &[GasEstimatorType],
     blocknative_api_key: Option<String>,
+    fee_history: fee_history::Params,
 ) -> Result<impl GasPriceEstimating + use<>> {
     let client = || Client(http_factory.create());
     let network_id = web3.eth().chain_id().await?.to_string();
@@ -98,6 +102,9 @@ pub async fn create_priority_estimator(
                     Err(err) => tracing::error!("nativegasestimator failed: {}", err),
                 }
             }
+            GasEstimatorType::FeeHistory => estimators.push(Box::new(
+                fee_history::FeeHistoryGasEstimator::new(web3.clone(), fee_history),
+            )),
         }
     }
     anyhow::ensure!(
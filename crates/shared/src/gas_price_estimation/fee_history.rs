@@ -0,0 +1,185 @@
+//! A gas price estimator that only relies on the connected node. It derives
+//! the base fee and priority fee from the fee market of recent blocks as
+//! reported by `eth_feeHistory` and predicts the base fee a few blocks ahead.
+
+use {
+    crate::ethrpc::Web3,
+    anyhow::{Context, Result, ensure},
+    gas_estimation::{GasPrice1559, GasPriceEstimating},
+    std::time::Duration,
+    web3::types::{BlockNumber, FeeHistory},
+};
+
+/// The maximum change of the base fee from one block to the next (12.5%) as
+/// defined by EIP-1559.
+const MAX_BASE_FEE_CHANGE: f64 = 0.125;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    /// The number of recent blocks the estimate is derived from.
+    pub block_count: u64,
+    /// The percentile of the priority fees paid in each block, weighted by
+    /// gas used, that is considered to be that block's priority fee.
+    pub reward_percentile: f64,
+    /// The number of blocks ahead the base fee is predicted for.
+    pub blocks_ahead: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            block_count: 20,
+            reward_percentile: 20.,
+            blocks_ahead: 3,
+        }
+    }
+}
+
+pub struct FeeHistoryGasEstimator {
+    web3: Web3,
+    params: Params,
+}
+
+impl FeeHistoryGasEstimator {
+    pub fn new(web3: Web3, params: Params) -> Self {
+        Self { web3, params }
+    }
+}
+
+#[async_trait::async_trait]
+impl GasPriceEstimating for FeeHistoryGasEstimator {
+    /// The gas and time limits are not considered, the urgency of the estimate
+    /// is configured with the number of blocks the base fee is predicted for.
+    async fn estimate_with_limits(
+        &self,
+        _gas_limit: f64,
+        _time_limit: Duration,
+    ) -> Result<GasPrice1559> {
+        let history = self
+            .web3
+            .eth()
+            .fee_history(
+                self.params.block_count.into(),
+                BlockNumber::Latest,
+                Some(vec![self.params.reward_percentile]),
+            )
+            .await
+            .context("failed to fetch fee history")?;
+        estimate(&history, &self.params)
+    }
+}
+
+/// Computes the gas price estimate from the fee history.
+///
+/// The base fee is predicted by extrapolating the average gas usage of the
+/// recent blocks, while the max fee covers the base fee increasing at the
+/// maximal rate until the predicted block. The priority fee is the median of
+/// the recent blocks' priority fees.
+fn estimate(history: &FeeHistory, params: &Params) -> Result<GasPrice1559> {
+    // The fee history includes the base fee of the next block.
+    let next_base_fee = history
+        .base_fee_per_gas
+        .last()
+        .context("empty fee history")?
+        .to_f64_lossy();
+
+    ensure!(!history.gas_used_ratio.is_empty(), "empty fee history");
+    let gas_used_ratio =
+        history.gas_used_ratio.iter().sum::<f64>() / history.gas_used_ratio.len() as f64;
+    // A block's gas target is half of its gas limit, so the base fee changes
+    // proportionally to how much the gas used deviates from half of the limit.
+    let change = ((2. * gas_used_ratio - 1.) * MAX_BASE_FEE_CHANGE)
+        .clamp(-MAX_BASE_FEE_CHANGE, MAX_BASE_FEE_CHANGE);
+    let exponent = params.blocks_ahead.saturating_sub(1) as f64;
+    let base_fee_per_gas = next_base_fee * (1. + change).powf(exponent);
+    let max_base_fee_per_gas = next_base_fee * (1. + MAX_BASE_FEE_CHANGE).powf(exponent);
+
+    // Empty blocks report a priority fee of 0 which would skew the estimate.
+    let mut rewards = history
+        .reward
+        .iter()
+        .flatten()
+        .zip(&history.gas_used_ratio)
+        .filter(|(_, ratio)| **ratio > 0.)
+        .filter_map(|(rewards, _)| rewards.first())
+        .map(|reward| reward.to_f64_lossy())
+        .collect::<Vec<_>>();
+    rewards.sort_by(f64::total_cmp);
+    let max_priority_fee_per_gas = rewards.get(rewards.len() / 2).copied().unwrap_or_default();
+
+    Ok(GasPrice1559 {
+        base_fee_per_gas,
+        max_fee_per_gas: max_base_fee_per_gas + max_priority_fee_per_gas,
+        max_priority_fee_per_gas,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::ethrpc::create_env_test_transport, ethcontract::U256};
+
+    fn history(base_fee: u64, gas_used_ratio: Vec<f64>, rewards: Vec<u64>) -> FeeHistory {
+        FeeHistory {
+            oldest_block: 0.into(),
+            base_fee_per_gas: vec![U256::from(base_fee); gas_used_ratio.len() + 1],
+            gas_used_ratio,
+            reward: Some(rewards.into_iter().map(|r| vec![r.into()]).collect()),
+        }
+    }
+
+    #[test]
+    fn stable_base_fee_at_gas_target() {
+        let history = history(100, vec![0.5, 0.5, 0.5], vec![3, 1, 2]);
+        let estimate = estimate(&history, &Params::default()).unwrap();
+        assert_eq!(
+            estimate,
+            GasPrice1559 {
+                base_fee_per_gas: 100.,
+                max_fee_per_gas: 100. * 1.125f64.powi(2) + 2.,
+                max_priority_fee_per_gas: 2.,
+            }
+        );
+    }
+
+    #[test]
+    fn predicts_base_fee_from_gas_usage() {
+        let params = Params {
+            blocks_ahead: 2,
+            ..Default::default()
+        };
+
+        let full = history(100, vec![1., 1.], vec![1, 1]);
+        assert_eq!(estimate(&full, &params).unwrap().base_fee_per_gas, 112.5);
+
+        let half_full = history(100, vec![0.75, 0.75], vec![1, 1]);
+        assert_eq!(
+            estimate(&half_full, &params).unwrap().base_fee_per_gas,
+            106.25
+        );
+
+        let empty = history(100, vec![0., 0.], vec![0, 0]);
+        assert_eq!(estimate(&empty, &params).unwrap().base_fee_per_gas, 87.5);
+    }
+
+    #[test]
+    fn ignores_rewards_of_empty_blocks() {
+        let history = history(100, vec![0., 0.5, 0., 0.5, 0.5], vec![0, 5, 0, 7, 6]);
+        let estimate = estimate(&history, &Params::default()).unwrap();
+        assert_eq!(estimate.max_priority_fee_per_gas, 6.);
+    }
+
+    #[test]
+    fn fails_on_empty_history() {
+        let history = history(100, vec![], vec![]);
+        assert!(estimate(&history, &Params::default()).is_err());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn mainnet() {
+        let web3 = Web3::new(create_env_test_transport());
+        let estimator = FeeHistoryGasEstimator::new(web3, Default::default());
+        let estimate = estimator.estimate().await.unwrap();
+        println!("{estimate:?}");
+    }
+}
@@ -24,6 +24,7 @@ pub mod interaction;
 pub mod maintenance;
 pub mod order_quoting;
 pub mod order_validation;
+pub mod permit_validator;
 pub mod price_estimation;
 pub mod recent_block_cache;
 pub mod remaining_amounts;
@@ -1,6 +1,6 @@
 use {
     anyhow::{Result, ensure},
-    ethrpc::block_stream::{self, BlockInfo, CurrentBlockWatcher},
+    ethrpc::block_stream::{self, BlockInfo, CurrentBlockWatcher, Reorg, ReorgTracker},
     futures::{Stream, StreamExt as _, future::join_all},
     std::{sync::Arc, time::Duration},
     tokio::time,
@@ -41,6 +41,7 @@ impl ServiceMaintenance {
         futures::pin_mut!(blocks);
 
         let mut retry_block = None;
+        let mut reorgs = ReorgTracker::default();
 
         while let Some(block) = match retry_block.take() {
             // We have a pending retry to process. First see if there is a new
@@ -56,6 +57,10 @@ impl ServiceMaintenance {
                 "running maintenance",
             );
 
+            if let Some(reorg) = reorgs.unhandled(&block) {
+                self.handle_reorg(&reorg).await;
+            }
+
             self.metrics
                 .last_seen_block
                 .set(i64::try_from(block.number).unwrap_or(i64::MAX));
@@ -100,6 +105,11 @@ impl ServiceMaintenance {
 pub trait Maintaining: Send + Sync {
     async fn run_maintenance(&self) -> Result<()>;
     fn name(&self) -> &str;
+
+    /// Gets called when the block stream observed a reorg, before the
+    /// maintenance for the new block runs. This allows components to
+    /// invalidate state derived from the replaced blocks.
+    async fn handle_reorg(&self, _reorg: &Reorg) {}
 }
 
 #[async_trait::async_trait]
@@ -134,6 +144,10 @@ impl Maintaining for ServiceMaintenance {
     fn name(&self) -> &str {
         SERVICE_MAINTENANCE_NAME
     }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        join_all(self.maintainers.iter().map(|m| m.handle_reorg(reorg))).await;
+    }
 }
 
 #[derive(prometheus_metric_storage::MetricStorage)]
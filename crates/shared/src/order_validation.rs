@@ -10,6 +10,7 @@ use {
             QuoteParameters,
             QuoteSearchParameters,
         },
+        permit_validator::{PermitValidating, PermitValidationError},
         price_estimation::{PriceEstimationError, Verification},
         signature_validator::{SignatureCheck, SignatureValidating, SignatureValidationError},
         trade_finding,
@@ -34,14 +35,16 @@ use {
             OrderData,
             OrderKind,
             OrderMetadata,
+            OrderUid,
             SellTokenSource,
             VerificationError,
         },
         quote::{OrderQuoteSide, QuoteSigningScheme, SellAmount},
-        signature::{self, Signature, SigningScheme, hashed_eip712_message},
+        signature::{self, Eip6492Signature, Signature, SigningScheme, hashed_eip712_message},
         time,
     },
-    std::{sync::Arc, time::Duration},
+    order_validation::permit::Permit,
+    std::{ops::ControlFlow, sync::Arc, time::Duration},
 };
 
 #[mockall::automock]
@@ -95,6 +98,18 @@ pub trait OrderValidating: Send + Sync {
         settlement_contract: H160,
         full_app_data_override: Option<String>,
     ) -> Result<(Order, Option<Quote>), ValidationError>;
+
+    /// Runs the same checks as `validate_and_construct_order` without placing
+    /// the order or storing a quote. Instead of stopping at the first failed
+    /// check, it reports every error it can find along with the conditions
+    /// that don't prevent the order from being placed but that the user
+    /// should know about.
+    async fn diagnose_order(
+        &self,
+        order: OrderCreation,
+        domain_separator: &DomainSeparator,
+        full_app_data_override: Option<String>,
+    ) -> OrderDiagnosis;
 }
 
 #[derive(Debug)]
@@ -149,6 +164,8 @@ pub enum ValidationError {
     /// An invalid EIP-1271 signature, where the on-chain validation check
     /// reverted or did not return the expected value.
     InvalidEip1271Signature(H256),
+    /// A permit among the pre-hooks of the order can't be used.
+    InvalidPermit(PermitValidationError),
     ZeroAmount,
     IncompatibleSigningScheme,
     TooManyLimitOrders,
@@ -200,6 +217,47 @@ impl From<CalculateQuoteError> for ValidationError {
     }
 }
 
+/// A condition which doesn't prevent an order from being placed but may keep
+/// it from being executed as the user expects.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationWarning {
+    /// The owner of the order could not be determined, so the checks that
+    /// depend on it were skipped.
+    UnknownOwner,
+    /// EIP-1271 signatures are not verified when placing orders, so the order
+    /// may never become executable.
+    Eip1271SignatureNotVerified,
+    /// The owner can't transfer the sell token yet. Pre-signed orders are
+    /// placed anyway but only get executed once the transfer succeeds.
+    PresignedWithoutFunds,
+    /// The quote referenced by the order doesn't exist or doesn't match the
+    /// order, so a fresh quote was computed.
+    QuoteNotFound,
+    /// The order could not be quoted because there is no liquidity for it.
+    NoLiquidity,
+    /// The order's limit price is outside the market price, so it is placed as
+    /// a limit order.
+    OutsideMarketPrice,
+}
+
+/// The result of validating an order without placing it.
+#[derive(Debug, Default)]
+pub struct OrderDiagnosis {
+    pub owner: Option<H160>,
+    pub uid: Option<OrderUid>,
+    /// The class the order would be placed with.
+    pub class: Option<OrderClass>,
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationWarning>,
+}
+
+impl OrderDiagnosis {
+    /// Whether the order would be accepted.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
 #[mockall::automock]
 #[async_trait]
 pub trait LimitOrderCounting: Send + Sync {
@@ -223,6 +281,7 @@ pub struct OrderValidator {
     limit_order_counter: Arc<dyn LimitOrderCounting>,
     max_limit_orders_per_user: u64,
     pub code_fetcher: Arc<dyn CodeFetching>,
+    permit_validator: Arc<dyn PermitValidating>,
     app_data_validator: Validator,
     max_gas_per_order: u64,
 }
@@ -274,6 +333,10 @@ impl PreOrderData {
     }
 }
 
+/// The gas limit of the pre-hook deploying a smart contract wallet which signed
+/// an order with an EIP-6492 signature.
+const EIP6492_DEPLOYMENT_GAS_LIMIT: u64 = 500_000;
+
 pub struct OrderAppData {
     pub inner: ValidatedAppData,
     pub interactions: Interactions,
@@ -294,6 +357,7 @@ impl OrderValidator {
         limit_order_counter: Arc<dyn LimitOrderCounting>,
         max_limit_orders_per_user: u64,
         code_fetcher: Arc<dyn CodeFetching>,
+        permit_validator: Arc<dyn PermitValidating>,
         app_data_validator: Validator,
         max_gas_per_order: u64,
     ) -> Self {
@@ -310,6 +374,7 @@ impl OrderValidator {
             limit_order_counter,
             max_limit_orders_per_user,
             code_fetcher,
+            permit_validator,
             app_data_validator,
             max_gas_per_order,
         }
@@ -363,6 +428,142 @@ impl OrderValidator {
         }
     }
 
+    /// Unwraps EIP-6492 signatures of smart contract wallets. If the wallet is
+    /// not deployed yet, a pre-hook deploying it is run before all other hooks
+    /// so that the signature gets validated and settled like any other
+    /// EIP-1271 signature.
+    async fn unwrap_eip6492_signature(
+        &self,
+        order: &mut OrderCreation,
+        owner: H160,
+        app_data: &mut OrderAppData,
+    ) -> Result<(), ValidationError> {
+        let Signature::Eip1271(signature) = &order.signature else {
+            return Ok(());
+        };
+        let Some(wrapped) = Eip6492Signature::parse(signature) else {
+            return Ok(());
+        };
+        let deployed = self
+            .code_fetcher
+            .code_size(owner)
+            .await
+            .map_err(ValidationError::Other)?
+            > 0;
+        tracing::debug!(
+            ?owner,
+            factory = ?wrapped.factory,
+            deployed,
+            "unwrapped EIP-6492 signature",
+        );
+        if !deployed {
+            app_data.inner.protocol.hooks.pre.insert(
+                0,
+                Hook {
+                    target: wrapped.factory,
+                    call_data: wrapped.factory_calldata,
+                    gas_limit: EIP6492_DEPLOYMENT_GAS_LIMIT,
+                },
+            );
+            app_data.interactions = self.custom_interactions(&app_data.inner.protocol.hooks);
+        }
+        order.signature = Signature::Eip1271(wrapped.signature);
+        Ok(())
+    }
+
+    /// Runs the partial validation checks in order and reports every failed
+    /// check. Stops as soon as `report` breaks.
+    async fn run_partial_checks(
+        &self,
+        order: &PreOrderData,
+        mut report: impl FnMut(PartialValidationError) -> ControlFlow<()> + Send,
+    ) -> ControlFlow<()> {
+        if !self
+            .banned_users
+            .banned([order.receiver, order.owner])
+            .await
+            .is_empty()
+        {
+            report(PartialValidationError::Forbidden)?;
+        }
+
+        if order.class == OrderClass::Market && order.partially_fillable {
+            report(PartialValidationError::UnsupportedOrderType)?;
+        }
+
+        if order.buy_token_balance != BuyTokenDestination::Erc20 {
+            report(PartialValidationError::UnsupportedBuyTokenDestination(
+                order.buy_token_balance,
+            ))?;
+        }
+        if !matches!(
+            order.sell_token_balance,
+            SellTokenSource::Erc20 | SellTokenSource::External
+        ) {
+            report(PartialValidationError::UnsupportedSellTokenSource(
+                order.sell_token_balance,
+            ))?;
+        }
+
+        if let Err(err) = self.validity_configuration.validate_period(order) {
+            report(err.into())?;
+        }
+
+        if has_same_buy_and_sell_token(order, &self.native_token) {
+            report(PartialValidationError::SameBuyAndSellToken)?;
+        }
+        if order.sell_token == BUY_ETH_ADDRESS {
+            report(PartialValidationError::InvalidNativeSellToken)?;
+        }
+
+        for &token in &[order.sell_token, order.buy_token] {
+            match self.bad_token_detector.detect(token).await {
+                Ok(TokenQuality::Good) => (),
+                Ok(TokenQuality::Bad { reason }) => {
+                    report(PartialValidationError::UnsupportedToken { token, reason })?
+                }
+                Err(err) => report(PartialValidationError::Other(err))?,
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    /// Validates the permits of the order owner among the pre-hooks and returns
+    /// the allowance of the sell token they grant the vault relayer. Permits
+    /// that can't be verified don't grant any allowance, in which case the
+    /// transfer simulation decides whether the order is valid.
+    async fn permitted_allowance(
+        &self,
+        order: &OrderCreation,
+        owner: H160,
+        app_data: &OrderAppData,
+    ) -> Result<U256, ValidationError> {
+        let permits = app_data
+            .inner
+            .protocol
+            .hooks
+            .pre
+            .iter()
+            .filter_map(|hook| Permit::decode(hook.target, &hook.call_data))
+            .filter(|permit| permit.owner() == owner);
+        let mut allowance = U256::zero();
+        for permit in permits {
+            let permitted = match self.permit_validator.validate(&permit).await {
+                Ok(permitted) => permitted,
+                Err(PermitValidationError::Other(err)) => {
+                    tracing::debug!(?permit, ?err, "could not verify permit");
+                    U256::zero()
+                }
+                Err(err) => return Err(ValidationError::InvalidPermit(err)),
+            };
+            if permit.token() == order.sell_token {
+                allowance = allowance.max(permitted);
+            }
+        }
+        Ok(allowance)
+    }
+
     /// Verifies that tokens can actually be transferred from the user account
     /// to the settlement contract (takes pre-hooks into account).
     async fn ensure_token_is_transferable(
@@ -370,7 +571,38 @@ impl OrderValidator {
         order: &OrderCreation,
         owner: H160,
         app_data: &OrderAppData,
+        permitted_allowance: U256,
     ) -> Result<(), ValidationError> {
+        match self
+            .simulate_transfer(order, owner, app_data, permitted_allowance)
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(TransferSimulationError::Other(_)) => {
+                Err(ValidationError::TransferSimulationFailed)
+            }
+            // We have an exception for pre-sign orders where they do not
+            // require sufficient balance or allowance. The idea, is that
+            // this allows smart contracts to place orders bundled with
+            // other transactions that either produce the required balance
+            // or set the allowance. This would, for example, allow a Gnosis
+            // Safe to bundle the pre-signature transaction with a WETH wrap
+            // and WETH approval to the vault relayer contract.
+            Err(_) if order.signature == Signature::PreSign => Ok(()),
+            Err(err) => Err(transfer_simulation_error(err)),
+        }
+    }
+
+    /// Simulates transferring the sell token from the user account to the
+    /// settlement contract. A missing allowance is ignored if a permit among
+    /// the pre-hooks grants enough of it for the whole order.
+    async fn simulate_transfer(
+        &self,
+        order: &OrderCreation,
+        owner: H160,
+        app_data: &OrderAppData,
+        permitted_allowance: U256,
+    ) -> Result<(), TransferSimulationError> {
         let mut res = Ok(());
 
         // Simulate transferring a small token balance into the settlement contract.
@@ -396,96 +628,121 @@ impl OrderValidator {
                 .await
             {
                 Ok(_) => return Ok(()),
-                Err(
-                    TransferSimulationError::InsufficientAllowance
-                    | TransferSimulationError::InsufficientBalance
-                    | TransferSimulationError::TransferFailed,
-                ) if order.signature == Signature::PreSign => {
-                    // We have an exception for pre-sign orders where they do not
-                    // require sufficient balance or allowance. The idea, is that
-                    // this allows smart contracts to place orders bundled with
-                    // other transactions that either produce the required balance
-                    // or set the allowance. This would, for example, allow a Gnosis
-                    // Safe to bundle the pre-signature transaction with a WETH wrap
-                    // and WETH approval to the vault relayer contract.
+                // The balance gets checked before the allowance so the
+                // transfer succeeds once the permit has been executed.
+                Err(TransferSimulationError::InsufficientAllowance)
+                    if permitted_allowance
+                        >= order.sell_amount.saturating_add(order.fee_amount) =>
+                {
                     return Ok(());
                 }
-                Err(err) => match err {
-                    TransferSimulationError::InsufficientAllowance => {
-                        // This error will be triggered regardless of the amount
-                        return Err(ValidationError::InsufficientAllowance);
-                    }
-                    TransferSimulationError::InsufficientBalance => {
-                        // Since the amount starts at 1 atom, if this error is triggered then it
-                        // will be triggered for the other amounts too
-                        return Err(ValidationError::InsufficientBalance);
-                    }
-                    TransferSimulationError::TransferFailed => {
-                        res = Err(ValidationError::TransferSimulationFailed);
-                    }
-                    TransferSimulationError::Other(err) => {
-                        tracing::warn!("TransferSimulation failed: {:?}", err);
-                        res = Err(ValidationError::TransferSimulationFailed);
+                // The allowance error will be triggered regardless of the amount.
+                // Since the amount starts at 1 atom, if the balance error is
+                // triggered then it will be triggered for the other amounts too.
+                Err(
+                    err @ (TransferSimulationError::InsufficientAllowance
+                    | TransferSimulationError::InsufficientBalance),
+                ) => return Err(err),
+                // A failed transfer is exempted for pre-sign orders, so an
+                // unexpected error of a later simulation must not replace it.
+                Err(TransferSimulationError::TransferFailed) => {
+                    res = Err(TransferSimulationError::TransferFailed);
+                }
+                Err(TransferSimulationError::Other(err)) => {
+                    tracing::warn!("TransferSimulation failed: {:?}", err);
+                    if res.is_ok() {
+                        res = Err(TransferSimulationError::Other(err));
                     }
-                },
+                }
             }
         }
 
         res
     }
-}
 
-#[async_trait::async_trait]
-impl OrderValidating for OrderValidator {
-    async fn partial_validate(&self, order: PreOrderData) -> Result<(), PartialValidationError> {
-        if !self
-            .banned_users
-            .banned([order.receiver, order.owner])
-            .await
-            .is_empty()
-        {
-            return Err(PartialValidationError::Forbidden);
-        }
-
-        if order.class == OrderClass::Market && order.partially_fillable {
-            return Err(PartialValidationError::UnsupportedOrderType);
-        }
-
-        if order.buy_token_balance != BuyTokenDestination::Erc20 {
-            return Err(PartialValidationError::UnsupportedBuyTokenDestination(
-                order.buy_token_balance,
-            ));
-        }
-        if !matches!(
-            order.sell_token_balance,
-            SellTokenSource::Erc20 | SellTokenSource::External
-        ) {
-            return Err(PartialValidationError::UnsupportedSellTokenSource(
-                order.sell_token_balance,
-            ));
+    /// Validates the EIP-1271 signature of the order and returns the gas
+    /// needed to verify it on-chain. Other signatures need no additional
+    /// gas.
+    async fn eip1271_verification_gas(
+        &self,
+        order: &OrderCreation,
+        owner: H160,
+        data: &OrderData,
+        domain_separator: &DomainSeparator,
+        interactions: &Interactions,
+    ) -> Result<u64, ValidationError> {
+        let Signature::Eip1271(signature) = &order.signature else {
+            // in any other case, just apply 0
+            return Ok(0);
+        };
+        if self.eip1271_skip_creation_validation {
+            tracing::debug!(?signature, "skipping EIP-1271 signature validation");
+            // We don't care! Because we are skipping validation anyway
+            return Ok(0);
         }
+        let hash = hashed_eip712_message(domain_separator, &data.hash_struct());
+        self.signature_validator
+            .validate_signature_and_get_additional_gas(SignatureCheck {
+                signer: owner,
+                hash,
+                signature: signature.to_owned(),
+                interactions: interactions.pre.clone(),
+            })
+            .await
+            .map_err(|err| match err {
+                SignatureValidationError::Invalid => {
+                    ValidationError::InvalidEip1271Signature(H256(hash))
+                }
+                SignatureValidationError::Other(err) => ValidationError::Other(err),
+            })
+    }
 
-        self.validity_configuration.validate_period(&order)?;
-
-        if has_same_buy_and_sell_token(&order, &self.native_token) {
-            return Err(PartialValidationError::SameBuyAndSellToken);
-        }
-        if order.sell_token == BUY_ETH_ADDRESS {
-            return Err(PartialValidationError::InvalidNativeSellToken);
+    /// Like `get_or_create_quote` but doesn't store freshly computed quotes.
+    async fn find_or_calculate_quote(
+        &self,
+        quote_search_parameters: &QuoteSearchParameters,
+        quote_id: Option<i64>,
+        warnings: &mut Vec<ValidationWarning>,
+    ) -> Result<Quote, ValidationError> {
+        match self
+            .quoter
+            .find_quote(quote_id, quote_search_parameters.clone())
+            .await
+        {
+            Ok(quote) => Ok(quote),
+            Err(err) => {
+                tracing::debug!(?err, "failed to find quote for order diagnosis");
+                if quote_id.is_some() {
+                    warnings.push(ValidationWarning::QuoteNotFound);
+                }
+                let parameters = quote_parameters(quote_search_parameters)?;
+                Ok(self.quoter.calculate_quote(parameters).await?)
+            }
         }
+    }
+}
 
-        for &token in &[order.sell_token, order.buy_token] {
-            if let TokenQuality::Bad { reason } = self
-                .bad_token_detector
-                .detect(token)
-                .await
-                .map_err(PartialValidationError::Other)?
-            {
-                return Err(PartialValidationError::UnsupportedToken { token, reason });
-            }
+fn transfer_simulation_error(err: TransferSimulationError) -> ValidationError {
+    match err {
+        TransferSimulationError::InsufficientAllowance => ValidationError::InsufficientAllowance,
+        TransferSimulationError::InsufficientBalance => ValidationError::InsufficientBalance,
+        TransferSimulationError::TransferFailed | TransferSimulationError::Other(_) => {
+            ValidationError::TransferSimulationFailed
         }
+    }
+}
 
-        Ok(())
+#[async_trait::async_trait]
+impl OrderValidating for OrderValidator {
+    async fn partial_validate(&self, order: PreOrderData) -> Result<(), PartialValidationError> {
+        let mut result = Ok(());
+        let _ = self
+            .run_partial_checks(&order, |err| {
+                result = Err(err);
+                ControlFlow::Break(())
+            })
+            .await;
+        result
     }
 
     fn validate_app_data(
@@ -543,18 +800,20 @@ impl OrderValidating for OrderValidator {
 
     async fn validate_and_construct_order(
         &self,
-        order: OrderCreation,
+        mut order: OrderCreation,
         domain_separator: &DomainSeparator,
         settlement_contract: H160,
         full_app_data_override: Option<String>,
     ) -> Result<(Order, Option<Quote>), ValidationError> {
         // Happens before signature verification because a miscalculated app data hash
         // by the API user would lead to being unable to validate the signature below.
-        let app_data = self.validate_app_data(&order.app_data, &full_app_data_override)?;
+        let mut app_data = self.validate_app_data(&order.app_data, &full_app_data_override)?;
         let app_data_signer = app_data.inner.protocol.signer;
 
         let owner = order.verify_owner(domain_separator, app_data_signer)?;
         tracing::debug!(?owner, "recovered owner from order and signature");
+        self.unwrap_eip6492_signature(&mut order, owner, &mut app_data)
+            .await?;
         let signing_scheme = order.signature.scheme();
         let data = OrderData {
             app_data: app_data.inner.hash,
@@ -562,32 +821,15 @@ impl OrderValidating for OrderValidator {
         };
         let uid = data.uid(domain_separator, &owner);
 
-        let verification_gas_limit = if let Signature::Eip1271(signature) = &order.signature {
-            if self.eip1271_skip_creation_validation {
-                tracing::debug!(?signature, "skipping EIP-1271 signature validation");
-                // We don't care! Because we are skipping validation anyway
-                0u64
-            } else {
-                let hash = hashed_eip712_message(domain_separator, &data.hash_struct());
-                self.signature_validator
-                    .validate_signature_and_get_additional_gas(SignatureCheck {
-                        signer: owner,
-                        hash,
-                        signature: signature.to_owned(),
-                        interactions: app_data.interactions.pre.clone(),
-                    })
-                    .await
-                    .map_err(|err| match err {
-                        SignatureValidationError::Invalid => {
-                            ValidationError::InvalidEip1271Signature(H256(hash))
-                        }
-                        SignatureValidationError::Other(err) => ValidationError::Other(err),
-                    })?
-            }
-        } else {
-            // in any other case, just apply 0
-            0u64
-        };
+        let verification_gas_limit = self
+            .eip1271_verification_gas(
+                &order,
+                owner,
+                &data,
+                domain_separator,
+                &app_data.interactions,
+            )
+            .await?;
 
         if data.buy_amount.is_zero() || data.sell_amount.is_zero() {
             return Err(ValidationError::ZeroAmount);
@@ -599,33 +841,11 @@ impl OrderValidating for OrderValidator {
             .await
             .map_err(ValidationError::Partial)?;
 
-        let verification = Verification {
-            from: owner,
-            receiver: order.receiver.unwrap_or(owner),
-            sell_token_source: order.sell_token_balance,
-            buy_token_destination: order.buy_token_balance,
-            pre_interactions: trade_finding::map_interactions(&app_data.interactions.pre),
-            post_interactions: trade_finding::map_interactions(&app_data.interactions.post),
-        };
+        let quote_parameters =
+            quote_search_parameters(&order, &data, owner, &app_data, verification_gas_limit)?;
 
-        let quote_parameters = QuoteSearchParameters {
-            sell_token: data.sell_token,
-            buy_token: data.buy_token,
-            sell_amount: data.sell_amount,
-            buy_amount: data.buy_amount,
-            fee_amount: data.fee_amount,
-            kind: data.kind,
-            signing_scheme: convert_signing_scheme_into_quote_signing_scheme(
-                order.signature.scheme(),
-                true,
-                verification_gas_limit,
-            )
-            .map_err(|_| ValidationError::InvalidSignature)?,
-            additional_gas: app_data.inner.protocol.hooks.gas_limit(),
-            verification,
-        };
-
-        self.ensure_token_is_transferable(&order, owner, &app_data)
+        let permitted_allowance = self.permitted_allowance(&order, owner, &app_data).await?;
+        self.ensure_token_is_transferable(&order, owner, &app_data, permitted_allowance)
             .await?;
 
         // Check if we need to re-classify the market order if it is outside the market
@@ -761,6 +981,198 @@ impl OrderValidating for OrderValidator {
 
         Ok((order, quote))
     }
+
+    async fn diagnose_order(
+        &self,
+        mut order: OrderCreation,
+        domain_separator: &DomainSeparator,
+        full_app_data_override: Option<String>,
+    ) -> OrderDiagnosis {
+        let mut diagnosis = OrderDiagnosis::default();
+
+        let app_data = match self.validate_app_data(&order.app_data, &full_app_data_override) {
+            Ok(app_data) => Some(app_data),
+            Err(err) => {
+                diagnosis.errors.push(err.into());
+                None
+            }
+        };
+        let app_data_signer = app_data
+            .as_ref()
+            .and_then(|app_data| app_data.inner.protocol.signer);
+        let owner = match order.verify_owner(domain_separator, app_data_signer) {
+            Ok(owner) => Some(owner),
+            Err(err) => {
+                diagnosis.errors.push(err.into());
+                order.from
+            }
+        };
+        let Some(owner) = owner else {
+            diagnosis.warnings.push(ValidationWarning::UnknownOwner);
+            return diagnosis;
+        };
+        // Keep checking orders with invalid app data as if they had no hooks.
+        let mut app_data = app_data.unwrap_or_else(|| OrderAppData {
+            inner: ValidatedAppData {
+                hash: order.app_data.hash(),
+                document: String::new(),
+                protocol: Default::default(),
+            },
+            interactions: Default::default(),
+        });
+        if let Err(err) = self
+            .unwrap_eip6492_signature(&mut order, owner, &mut app_data)
+            .await
+        {
+            diagnosis.errors.push(err);
+        }
+        let signing_scheme = order.signature.scheme();
+        let data = OrderData {
+            app_data: app_data.inner.hash,
+            ..order.data()
+        };
+        diagnosis.owner = Some(owner);
+        diagnosis.uid = Some(data.uid(domain_separator, &owner));
+
+        if matches!(order.signature, Signature::Eip1271(_)) && self.eip1271_skip_creation_validation
+        {
+            diagnosis
+                .warnings
+                .push(ValidationWarning::Eip1271SignatureNotVerified);
+        }
+        let verification_gas_limit = self
+            .eip1271_verification_gas(
+                &order,
+                owner,
+                &data,
+                domain_separator,
+                &app_data.interactions,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                diagnosis.errors.push(err);
+                0
+            });
+
+        if data.buy_amount.is_zero() || data.sell_amount.is_zero() {
+            diagnosis.errors.push(ValidationError::ZeroAmount);
+        }
+
+        let pre_order = PreOrderData::from_order_creation(owner, &data, signing_scheme);
+        let class = pre_order.class;
+        let _ = self
+            .run_partial_checks(&pre_order, |err| {
+                diagnosis.errors.push(ValidationError::Partial(err));
+                ControlFlow::Continue(())
+            })
+            .await;
+
+        let permitted_allowance = self
+            .permitted_allowance(&order, owner, &app_data)
+            .await
+            .unwrap_or_else(|err| {
+                diagnosis.errors.push(err);
+                U256::zero()
+            });
+        match self
+            .simulate_transfer(&order, owner, &app_data, permitted_allowance)
+            .await
+        {
+            Ok(()) => (),
+            Err(TransferSimulationError::Other(_)) => {
+                diagnosis
+                    .errors
+                    .push(ValidationError::TransferSimulationFailed);
+            }
+            Err(_) if order.signature == Signature::PreSign => {
+                diagnosis
+                    .warnings
+                    .push(ValidationWarning::PresignedWithoutFunds);
+            }
+            Err(err) => diagnosis.errors.push(transfer_simulation_error(err)),
+        }
+
+        if class == OrderClass::Market && !data.fee_amount.is_zero() {
+            diagnosis.errors.push(ValidationError::NonZeroFee);
+        }
+
+        let quote_parameters = match quote_search_parameters(
+            &order,
+            &data,
+            owner,
+            &app_data,
+            verification_gas_limit,
+        ) {
+            Ok(parameters) => parameters,
+            Err(err) => {
+                diagnosis.errors.push(err);
+                diagnosis.class = Some(class);
+                return diagnosis;
+            }
+        };
+        let quote = match self
+            .find_or_calculate_quote(&quote_parameters, order.quote_id, &mut diagnosis.warnings)
+            .await
+        {
+            Ok(quote) => Some(quote),
+            // Limit orders can still be placed without liquidity.
+            Err(ValidationError::PriceForQuote(PriceEstimationError::NoLiquidity))
+                if class == OrderClass::Limit =>
+            {
+                diagnosis.warnings.push(ValidationWarning::NoLiquidity);
+                None
+            }
+            Err(err) => {
+                diagnosis.errors.push(err);
+                None
+            }
+        };
+        let outside_market_price = quote.as_ref().is_some_and(|quote| {
+            is_order_outside_market_price(
+                &Amounts {
+                    sell: data.sell_amount,
+                    buy: data.buy_amount,
+                    fee: data.fee_amount,
+                },
+                &Amounts {
+                    sell: quote.sell_amount,
+                    buy: quote.buy_amount,
+                    fee: quote.fee_amount,
+                },
+                data.kind,
+            )
+        });
+
+        // Mirrors the classification of `validate_and_construct_order`.
+        diagnosis.class = Some(match class {
+            OrderClass::Market if outside_market_price => {
+                diagnosis
+                    .warnings
+                    .push(ValidationWarning::OutsideMarketPrice);
+                OrderClass::Limit
+            }
+            OrderClass::Market => OrderClass::Market,
+            OrderClass::Limit | OrderClass::Liquidity => {
+                if outside_market_price {
+                    diagnosis
+                        .errors
+                        .extend(self.check_max_limit_orders(owner).await.err());
+                }
+                OrderClass::Limit
+            }
+        });
+
+        if class != OrderClass::Liquidity
+            && quote.as_ref().is_some_and(|quote| {
+                quote.data.fee_parameters.gas_amount as u64 + quote_parameters.additional_cost()
+                    > self.max_gas_per_order
+            })
+        {
+            diagnosis.errors.push(ValidationError::TooMuchGas);
+        }
+
+        diagnosis
+    }
 }
 
 /// Order validity period configuration.
@@ -825,6 +1237,41 @@ fn has_same_buy_and_sell_token(order: &PreOrderData, native_token: &WETH9) -> bo
         || (order.sell_token == native_token.address() && order.buy_token == BUY_ETH_ADDRESS)
 }
 
+/// The parameters for finding the quote of an order that is being created.
+fn quote_search_parameters(
+    order: &OrderCreation,
+    data: &OrderData,
+    owner: H160,
+    app_data: &OrderAppData,
+    verification_gas_limit: u64,
+) -> Result<QuoteSearchParameters, ValidationError> {
+    let verification = Verification {
+        from: owner,
+        receiver: order.receiver.unwrap_or(owner),
+        sell_token_source: order.sell_token_balance,
+        buy_token_destination: order.buy_token_balance,
+        pre_interactions: trade_finding::map_interactions(&app_data.interactions.pre),
+        post_interactions: trade_finding::map_interactions(&app_data.interactions.post),
+    };
+
+    Ok(QuoteSearchParameters {
+        sell_token: data.sell_token,
+        buy_token: data.buy_token,
+        sell_amount: data.sell_amount,
+        buy_amount: data.buy_amount,
+        fee_amount: data.fee_amount,
+        kind: data.kind,
+        signing_scheme: convert_signing_scheme_into_quote_signing_scheme(
+            order.signature.scheme(),
+            true,
+            verification_gas_limit,
+        )
+        .map_err(|_| ValidationError::InvalidSignature)?,
+        additional_gas: app_data.inner.protocol.hooks.gas_limit(),
+        verification,
+    })
+}
+
 /// Retrieves the quote for an order that is being created and verify that its
 /// fee is sufficient.
 ///
@@ -864,30 +1311,7 @@ async fn get_or_create_quote(
         // We couldn't find a quote, so try computing a fresh quote to use instead.
         Err(err) => {
             tracing::debug!(?err, "failed to find quote for order creation");
-            let parameters = QuoteParameters {
-                sell_token: quote_search_parameters.sell_token,
-                buy_token: quote_search_parameters.buy_token,
-                side: match quote_search_parameters.kind {
-                    OrderKind::Buy => OrderQuoteSide::Buy {
-                        buy_amount_after_fee: quote_search_parameters
-                            .buy_amount
-                            .try_into()
-                            .map_err(|_| ValidationError::ZeroAmount)?,
-                    },
-                    OrderKind::Sell => OrderQuoteSide::Sell {
-                        sell_amount: SellAmount::AfterFee {
-                            value: quote_search_parameters
-                                .sell_amount
-                                .try_into()
-                                .map_err(|_| ValidationError::ZeroAmount)?,
-                        },
-                    },
-                },
-                verification: quote_search_parameters.verification.clone(),
-                signing_scheme: quote_search_parameters.signing_scheme,
-                additional_gas: quote_search_parameters.additional_gas,
-            };
-
+            let parameters = quote_parameters(quote_search_parameters)?;
             let quote = quoter.calculate_quote(parameters).await?;
             let quote = quoter
                 .store_quote(quote)
@@ -902,6 +1326,36 @@ async fn get_or_create_quote(
     Ok(quote)
 }
 
+/// The parameters for calculating a fresh quote for an order that is being
+/// created.
+fn quote_parameters(
+    quote_search_parameters: &QuoteSearchParameters,
+) -> Result<QuoteParameters, ValidationError> {
+    Ok(QuoteParameters {
+        sell_token: quote_search_parameters.sell_token,
+        buy_token: quote_search_parameters.buy_token,
+        side: match quote_search_parameters.kind {
+            OrderKind::Buy => OrderQuoteSide::Buy {
+                buy_amount_after_fee: quote_search_parameters
+                    .buy_amount
+                    .try_into()
+                    .map_err(|_| ValidationError::ZeroAmount)?,
+            },
+            OrderKind::Sell => OrderQuoteSide::Sell {
+                sell_amount: SellAmount::AfterFee {
+                    value: quote_search_parameters
+                        .sell_amount
+                        .try_into()
+                        .map_err(|_| ValidationError::ZeroAmount)?,
+                },
+            },
+        },
+        verification: quote_search_parameters.verification.clone(),
+        signing_scheme: quote_search_parameters.signing_scheme,
+        additional_gas: quote_search_parameters.additional_gas,
+    })
+}
+
 /// Amounts used for market price checker.
 #[derive(Debug)]
 pub struct Amounts {
@@ -970,11 +1424,16 @@ mod tests {
             bad_token::{MockBadTokenDetecting, TokenQuality},
             code_fetching::MockCodeFetching,
             order_quoting::{FindQuoteError, MockOrderQuoting},
+            permit_validator::MockPermitValidating,
             signature_validator::MockSignatureValidating,
         },
         contracts::dummy_contract,
-        ethcontract::web3::signing::SecretKeyRef,
+        ethcontract::{
+            common::abi::{self, Token},
+            web3::signing::SecretKeyRef,
+        },
         futures::FutureExt,
+        hex_literal::hex,
         maplit::hashset,
         mockall::predicate::{always, eq},
         model::{
@@ -1052,6 +1511,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1199,6 +1659,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1293,6 +1754,7 @@ mod tests {
             Arc::new(limit_order_counter),
             max_limit_orders_per_user,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1450,6 +1912,175 @@ mod tests {
         assert!(order.metadata.class.is_limit());
     }
 
+    #[tokio::test]
+    async fn post_validate_eip6492_signature() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Ok(Default::default()));
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+
+        let owner = H160([1; 20]);
+        let factory = H160([0xfa; 20]);
+        let hooks = dummy_contract!(HooksTrampoline, [0xcf; 20]);
+        let domain_separator = DomainSeparator::default();
+        let creation = OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(1),
+            from: Some(owner),
+            signature: Signature::Eip1271(
+                Eip6492Signature {
+                    factory,
+                    factory_calldata: vec![0xde, 0xad],
+                    signature: vec![1, 2, 3],
+                }
+                .encode(),
+            ),
+            app_data: OrderCreationAppData::Full {
+                full: json!({
+                    "metadata": {
+                        "hooks": {
+                            "pre": [
+                                {
+                                    "target": "0x1111111111111111111111111111111111111111",
+                                    "callData": "0x112233",
+                                    "gasLimit": "42",
+                                }
+                            ],
+                        },
+                    },
+                })
+                .to_string(),
+            },
+            ..Default::default()
+        };
+        let order_hash = hashed_eip712_message(&domain_separator, &creation.data().hash_struct());
+
+        // The wallet gets deployed before the hooks of the user run.
+        let pre_interactions = vec![InteractionData {
+            target: hooks.address(),
+            value: U256::zero(),
+            call_data: hooks
+                .execute(vec![
+                    (
+                        factory,
+                        Bytes(vec![0xde, 0xad]),
+                        EIP6492_DEPLOYMENT_GAS_LIMIT.into(),
+                    ),
+                    (
+                        addr!("1111111111111111111111111111111111111111"),
+                        Bytes(vec![0x11, 0x22, 0x33]),
+                        42.into(),
+                    ),
+                ])
+                .tx
+                .data
+                .unwrap()
+                .0,
+        }];
+        let mut signature_validator = MockSignatureValidating::new();
+        signature_validator
+            .expect_validate_signature_and_get_additional_gas()
+            .with(eq(SignatureCheck {
+                signer: owner,
+                hash: order_hash,
+                signature: vec![1, 2, 3],
+                interactions: pre_interactions.clone(),
+            }))
+            .returning(|_| Ok(0u64));
+        let mut code_fetcher = MockCodeFetching::new();
+        code_fetcher
+            .expect_code_size()
+            .with(eq(owner))
+            .returning(|_| Ok(0));
+
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration {
+                min: Duration::from_secs(1),
+                max_market: Duration::from_secs(100),
+                max_limit: Duration::from_secs(200),
+            },
+            false,
+            Arc::new(bad_token_detector),
+            hooks.clone(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(signature_validator),
+            Arc::new(limit_order_counter),
+            1,
+            Arc::new(code_fetcher),
+            Arc::new(MockPermitValidating::new()),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let (order, _) = validator
+            .validate_and_construct_order(
+                creation.clone(),
+                &domain_separator,
+                Default::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(order.signature, Signature::Eip1271(vec![1, 2, 3]));
+        assert_eq!(order.interactions.pre, pre_interactions);
+
+        // Already deployed wallets only need the unwrapped signature.
+        let pre_interactions = vec![InteractionData {
+            target: hooks.address(),
+            value: U256::zero(),
+            call_data: hooks
+                .execute(vec![(
+                    addr!("1111111111111111111111111111111111111111"),
+                    Bytes(vec![0x11, 0x22, 0x33]),
+                    42.into(),
+                )])
+                .tx
+                .data
+                .unwrap()
+                .0,
+        }];
+        let mut signature_validator = MockSignatureValidating::new();
+        signature_validator
+            .expect_validate_signature_and_get_additional_gas()
+            .with(eq(SignatureCheck {
+                signer: owner,
+                hash: order_hash,
+                signature: vec![1, 2, 3],
+                interactions: pre_interactions.clone(),
+            }))
+            .returning(|_| Ok(0u64));
+        let mut code_fetcher = MockCodeFetching::new();
+        code_fetcher.expect_code_size().returning(|_| Ok(42));
+        let validator = OrderValidator {
+            signature_validator: Arc::new(signature_validator),
+            code_fetcher: Arc::new(code_fetcher),
+            ..validator
+        };
+
+        let (order, _) = validator
+            .validate_and_construct_order(creation, &domain_separator, Default::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(order.signature, Signature::Eip1271(vec![1, 2, 3]));
+        assert_eq!(order.interactions.pre, pre_interactions);
+    }
+
     #[tokio::test]
     async fn post_validate_too_many_limit_orders() {
         let mut order_quoter = MockOrderQuoting::new();
@@ -1501,6 +2132,7 @@ mod tests {
             Arc::new(limit_order_counter),
             MAX_LIMIT_ORDERS_PER_USER,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1572,6 +2204,7 @@ mod tests {
             Arc::new(limit_order_counter),
             MAX_LIMIT_ORDERS_PER_USER,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1630,6 +2263,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1681,6 +2315,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1735,6 +2370,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1792,6 +2428,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1848,6 +2485,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -1878,6 +2516,84 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn diagnose_reports_all_errors_and_warnings() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter
+            .expect_find_quote()
+            .with(eq(Some(42)), always())
+            .returning(|_, _| Err(FindQuoteError::NotFound(None)));
+        // Diagnosing an order must not store a quote.
+        order_quoter
+            .expect_calculate_quote()
+            .returning(|_| Ok(Default::default()));
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Err(TransferSimulationError::InsufficientBalance));
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            dummy_contract!(HooksTrampoline, [0xcf; 20]),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(MockLimitOrderCounting::new()),
+            0,
+            Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
+            Default::default(),
+            u64::MAX,
+        );
+        let order = OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(1),
+            buy_amount: U256::zero(),
+            sell_amount: U256::from(1),
+            fee_amount: U256::from(1),
+            kind: OrderKind::Sell,
+            quote_id: Some(42),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            app_data: OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            },
+            ..Default::default()
+        };
+
+        let diagnosis = validator
+            .diagnose_order(order, &Default::default(), None)
+            .await;
+
+        assert!(!diagnosis.is_valid());
+        assert!(diagnosis.owner.is_some());
+        assert!(diagnosis.uid.is_some());
+        assert!(matches!(
+            diagnosis.errors.as_slice(),
+            [
+                ValidationError::ZeroAmount,
+                ValidationError::Partial(PartialValidationError::SameBuyAndSellToken),
+                ValidationError::InsufficientBalance,
+                ValidationError::NonZeroFee,
+            ]
+        ));
+        assert_eq!(
+            diagnosis.warnings,
+            [
+                ValidationWarning::QuoteNotFound,
+                ValidationWarning::OutsideMarketPrice,
+            ]
+        );
+        assert_eq!(diagnosis.class, Some(OrderClass::Limit));
+    }
+
     #[test]
     fn allows_insufficient_allowance_and_balance_for_presign_orders() {
         fn assert_allows_failed_transfer(
@@ -1911,6 +2627,7 @@ mod tests {
                 Arc::new(limit_order_counter),
                 0,
                 Arc::new(MockCodeFetching::new()),
+                Arc::new(MockPermitValidating::new()),
                 Default::default(),
                 u64::MAX,
             );
@@ -1967,6 +2684,143 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn permit_hooks_grant_missing_allowance() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Ok(Default::default()));
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Err(TransferSimulationError::InsufficientAllowance));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let mut permit_validator = MockPermitValidating::new();
+        permit_validator
+            .expect_validate()
+            .returning(|_| Ok(U256::MAX));
+        let validator = OrderValidator::new(
+            dummy_contract!(WETH9, [0xef; 20]),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            dummy_contract!(HooksTrampoline, [0xcf; 20]),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(limit_order_counter),
+            1,
+            Arc::new(MockCodeFetching::new()),
+            Arc::new(permit_validator),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let owner = addr!("7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+        let permit = [
+            &hex!("d505accf")[..],
+            &abi::encode(&[
+                Token::Address(owner),
+                Token::Address(H160([0xc0; 20])),
+                Token::Uint(U256::MAX),
+                Token::Uint(U256::MAX),
+                Token::Uint(27.into()),
+                Token::FixedBytes(vec![1; 32]),
+                Token::FixedBytes(vec![2; 32]),
+            ]),
+        ]
+        .concat();
+        let creation = OrderCreation {
+            valid_to: u32::MAX,
+            sell_token: H160::from_low_u64_be(1),
+            sell_amount: 1.into(),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: 1.into(),
+            app_data: OrderCreationAppData::Full {
+                full: json!({
+                    "metadata": {
+                        "hooks": {
+                            "pre": [
+                                {
+                                    "target": H160::from_low_u64_be(1),
+                                    "callData": format!("0x{}", hex::encode(permit)),
+                                    "gasLimit": "50000",
+                                }
+                            ],
+                        },
+                    },
+                })
+                .to_string(),
+            },
+            ..Default::default()
+        }
+        .sign(
+            EcdsaSigningScheme::Eip712,
+            &Default::default(),
+            SecretKeyRef::new(
+                &secp256k1::SecretKey::from_str(
+                    "0000000000000000000000000000000000000000000000000000000000000001",
+                )
+                .unwrap(),
+            ),
+        );
+        let (order, _) = validator
+            .validate_and_construct_order(
+                creation.clone(),
+                &Default::default(),
+                Default::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(order.metadata.owner, owner);
+
+        // Invalid permits get rejected.
+        let mut permit_validator = MockPermitValidating::new();
+        permit_validator
+            .expect_validate()
+            .returning(|_| Err(PermitValidationError::Expired));
+        let validator = OrderValidator {
+            permit_validator: Arc::new(permit_validator),
+            ..validator
+        };
+        let err = validator
+            .validate_and_construct_order(
+                creation.clone(),
+                &Default::default(),
+                Default::default(),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationError::InvalidPermit(PermitValidationError::Expired)
+        ));
+
+        // Permits that can't be verified don't grant any allowance, so the
+        // transfer simulation decides.
+        let mut permit_validator = MockPermitValidating::new();
+        permit_validator
+            .expect_validate()
+            .returning(|_| Err(PermitValidationError::Other(anyhow!("no nonces()"))));
+        let validator = OrderValidator {
+            permit_validator: Arc::new(permit_validator),
+            ..validator
+        };
+        let err = validator
+            .validate_and_construct_order(creation, &Default::default(), Default::default(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ValidationError::InsufficientAllowance));
+    }
+
     #[tokio::test]
     async fn get_quote_find_by_id() {
         let mut order_quoter = MockOrderQuoting::new();
@@ -2259,6 +3113,7 @@ mod tests {
             Arc::new(limit_order_counter),
             0,
             Arc::new(MockCodeFetching::new()),
+            Arc::new(MockPermitValidating::new()),
             Default::default(),
             u64::MAX,
         );
@@ -0,0 +1,184 @@
+//! Validation of the token approval permits orders carry as pre-hooks.
+
+use {
+    anyhow::{Context as _, Result},
+    ethrpc::Web3,
+    hex_literal::hex,
+    model::{
+        DomainSeparator,
+        signature::{EcdsaSignature, EcdsaSigningScheme},
+        time,
+    },
+    order_validation::permit::Permit,
+    primitive_types::{H160, U256},
+    std::sync::Arc,
+    thiserror::Error,
+    web3::types::{Bytes, CallRequest},
+};
+
+#[derive(Debug, Error)]
+pub enum PermitValidationError {
+    #[error("permit deadline has passed")]
+    Expired,
+    #[error("permit nonce does not match the next nonce of the owner")]
+    InvalidNonce,
+    #[error("permit is not signed by its owner")]
+    InvalidSignature,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[mockall::automock]
+#[async_trait::async_trait]
+pub trait PermitValidating: Send + Sync {
+    /// Checks the deadline, nonce and signature of the permit and returns the
+    /// allowance it grants the vault relayer.
+    ///
+    /// Permits that can't be verified, for example because the token doesn't
+    /// expose its nonces or the signature format isn't supported, don't grant
+    /// any allowance but aren't rejected either.
+    async fn validate(&self, permit: &Permit) -> Result<U256, PermitValidationError>;
+}
+
+/// Creates the default [`PermitValidating`] instance.
+pub fn validator(web3: &Web3, vault_relayer: H160) -> Arc<dyn PermitValidating> {
+    Arc::new(Validator {
+        web3: web3.clone(),
+        vault_relayer,
+    })
+}
+
+struct Validator {
+    web3: Web3,
+    vault_relayer: H160,
+}
+
+impl Validator {
+    async fn call(&self, to: H160, data: Vec<u8>) -> Result<Vec<u8>> {
+        let result = self
+            .web3
+            .eth()
+            .call(
+                CallRequest {
+                    to: Some(to),
+                    data: Some(Bytes(data)),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await?;
+        Ok(result.0)
+    }
+
+    async fn domain_separator(&self, contract: H160) -> Result<DomainSeparator> {
+        const DOMAIN_SEPARATOR: [u8; 4] = hex!("3644e515");
+        let result = self.call(contract, DOMAIN_SEPARATOR.to_vec()).await?;
+        let domain_separator = result
+            .get(..32)
+            .context("unexpected DOMAIN_SEPARATOR() result")?;
+        Ok(DomainSeparator(domain_separator.try_into()?))
+    }
+
+    /// Fetches the nonce the next permit of the owner has to be signed with.
+    async fn nonce(&self, permit: &Permit) -> Result<U256> {
+        const NONCES: [u8; 4] = hex!("7ecebe00");
+        const PERMIT2_ALLOWANCE: [u8; 4] = hex!("927da105");
+        let (call_data, index) = match permit {
+            Permit::Eip2612 { .. } | Permit::Dai { .. } => {
+                ([&NONCES[..], &word(permit.owner())].concat(), 0)
+            }
+            // `allowance(owner, token, spender)` returns the amount,
+            // expiration and nonce.
+            Permit::Permit2 { .. } => (
+                [
+                    &PERMIT2_ALLOWANCE[..],
+                    &word(permit.owner()),
+                    &word(permit.token()),
+                    &word(permit.spender()),
+                ]
+                .concat(),
+                2,
+            ),
+        };
+        let result = self.call(permit.verifying_contract(), call_data).await?;
+        let nonce = result
+            .get(32 * index..32 * (index + 1))
+            .context("unexpected nonce result")?;
+        Ok(U256::from_big_endian(nonce))
+    }
+
+    async fn is_contract(&self, address: H160) -> Result<bool> {
+        let code = self.web3.eth().code(address, None).await?;
+        Ok(!code.0.is_empty())
+    }
+}
+
+fn word(address: H160) -> [u8; 32] {
+    let mut word = [0; 32];
+    word[12..].copy_from_slice(address.as_bytes());
+    word
+}
+
+#[async_trait::async_trait]
+impl PermitValidating for Validator {
+    async fn validate(&self, permit: &Permit) -> Result<U256, PermitValidationError> {
+        if permit
+            .deadline()
+            .is_some_and(|deadline| deadline < time::now_in_epoch_seconds().into())
+        {
+            return Err(PermitValidationError::Expired);
+        }
+
+        let (domain_separator, nonce) = match futures::try_join!(
+            self.domain_separator(permit.verifying_contract()),
+            self.nonce(permit),
+        ) {
+            Ok(result) => result,
+            Err(err) => {
+                tracing::debug!(?err, "failed to fetch permit domain separator or nonce");
+                return Ok(U256::zero());
+            }
+        };
+        if permit.nonce().is_some_and(|signed| signed != nonce) {
+            return Err(PermitValidationError::InvalidNonce);
+        }
+
+        // Signatures in other formats, like EIP-2098 compact signatures which
+        // Permit2 accepts, can't be verified here.
+        let Some(signer) = permit.ecdsa_signature().and_then(|signature| {
+            EcdsaSignature::from_bytes(&signature)
+                .recover(
+                    EcdsaSigningScheme::Eip712,
+                    &domain_separator,
+                    &permit.struct_hash(nonce),
+                )
+                .ok()
+        }) else {
+            return Ok(U256::zero());
+        };
+        if signer.signer != permit.owner() {
+            // Smart contracts sign permits in their own way which we can't
+            // verify here. Their permits don't grant any allowance upfront
+            // and are only taken into account by simulating the hooks.
+            match self.is_contract(permit.owner()).await {
+                Ok(false) => return Err(PermitValidationError::InvalidSignature),
+                Ok(true) => return Ok(U256::zero()),
+                Err(err) => {
+                    tracing::debug!(?err, "failed to check whether permit owner is a contract");
+                    return Ok(U256::zero());
+                }
+            }
+        }
+
+        // The vault relayer transfers tokens directly instead of through
+        // Permit2 so Permit2 allowances don't count.
+        match permit {
+            Permit::Eip2612 { .. } | Permit::Dai { .. }
+                if permit.spender() == self.vault_relayer =>
+            {
+                Ok(permit.allowance())
+            }
+            _ => Ok(U256::zero()),
+        }
+    }
+}
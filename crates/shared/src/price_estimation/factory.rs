@@ -271,6 +271,22 @@ impl<'a> PriceEstimatorFactory<'a> {
 
                 Ok((name, coin_gecko))
             }
+            NativePriceEstimatorSource::Chainlink => {
+                let name = "Chainlink".to_string();
+                let chainlink = native::Chainlink::new(
+                    self.network.web3.clone(),
+                    &self.args.chainlink.chainlink_feeds,
+                    self.args.chainlink.chainlink_native_usd_feed,
+                    self.args.chainlink.chainlink_max_age,
+                    weth.address(),
+                    self.components.tokens.clone(),
+                )
+                .await?;
+                Ok((
+                    name.clone(),
+                    Arc::new(InstrumentedPriceEstimator::new(chainlink, name)),
+                ))
+            }
         }
     }
 
@@ -77,6 +77,7 @@ pub enum NativePriceEstimator {
     Driver(ExternalSolver),
     OneInchSpotPriceApi,
     CoinGecko,
+    Chainlink,
 }
 
 impl Display for NativePriceEstimator {
@@ -85,6 +86,7 @@ impl Display for NativePriceEstimator {
             NativePriceEstimator::Driver(s) => format!("{}|{}", &s.name, s.url),
             NativePriceEstimator::OneInchSpotPriceApi => "OneInchSpotPriceApi".into(),
             NativePriceEstimator::CoinGecko => "CoinGecko".into(),
+            NativePriceEstimator::Chainlink => "Chainlink".into(),
         };
         write!(f, "{}", formatter)
     }
@@ -135,6 +137,7 @@ impl FromStr for NativePriceEstimator {
         match s {
             "OneInchSpotPriceApi" => Ok(NativePriceEstimator::OneInchSpotPriceApi),
             "CoinGecko" => Ok(NativePriceEstimator::CoinGecko),
+            "Chainlink" => Ok(NativePriceEstimator::Chainlink),
             estimator => Ok(NativePriceEstimator::Driver(ExternalSolver::from_str(
                 estimator,
             )?)),
@@ -218,6 +221,10 @@ pub struct Arguments {
     #[clap(flatten)]
     pub coin_gecko: CoinGecko,
 
+    /// The Chainlink native price configuration
+    #[clap(flatten)]
+    pub chainlink: Chainlink,
+
     /// How inaccurate a quote must be before it gets discarded provided as a
     /// factor.
     /// E.g. a value of `0.01` means at most 1 percent of the sell or buy tokens
@@ -326,6 +333,31 @@ pub struct CoinGeckoBuffered {
     pub coin_gecko_broadcast_channel_capacity: Option<usize>,
 }
 
+/// Command line arguments for the Chainlink native price estimator.
+#[derive(clap::Parser)]
+pub struct Chainlink {
+    /// List of Chainlink price feeds used by the Chainlink native price
+    /// estimator: "<token>|<feed>|<ETH|USD>[|<max_age>]"
+    /// - token is the address of the priced token
+    /// - feed is the address of the Chainlink aggregator pricing the token
+    /// - ETH or USD is the currency the feed quotes the token in
+    /// - max_age optionally overrides `chainlink_max_age` for the feed
+    ///
+    /// Tokens without a native token feed are priced by dividing their USD
+    /// price by the price of `chainlink_native_usd_feed`.
+    #[clap(long, env, value_delimiter = ',', verbatim_doc_comment)]
+    pub chainlink_feeds: Vec<native::ChainlinkFeed>,
+
+    /// The Chainlink feed pricing the native token in USD.
+    #[clap(long, env)]
+    pub chainlink_native_usd_feed: Option<H160>,
+
+    /// How old the latest answer of a Chainlink feed may be before it is
+    /// considered stale.
+    #[clap(long, env, default_value = "1h", value_parser = humantime::parse_duration)]
+    pub chainlink_max_age: Duration,
+}
+
 /// Controls which level of quote verification gets applied.
 #[derive(Copy, Clone, Debug, clap::ValueEnum)]
 #[clap(rename_all = "kebab-case")]
@@ -354,6 +386,7 @@ impl Display for Arguments {
             one_inch_api_key,
             one_inch_url,
             coin_gecko,
+            chainlink,
             quote_inaccuracy_limit,
             quote_verification,
             quote_timeout,
@@ -433,6 +466,15 @@ impl Display for Arguments {
                 |coin_gecko_buffered| coin_gecko_buffered.coin_gecko_broadcast_channel_capacity
             ),
         )?;
+        writeln!(f, "chainlink_feeds: {:?}", chainlink.chainlink_feeds)?;
+        display_option(
+            f,
+            "chainlink_native_usd_feed",
+            &chainlink
+                .chainlink_native_usd_feed
+                .map(|feed| format!("{feed:?}")),
+        )?;
+        writeln!(f, "chainlink_max_age: {:?}", chainlink.chainlink_max_age)?;
         writeln!(f, "quote_inaccuracy_limit: {}", quote_inaccuracy_limit)?;
         writeln!(f, "quote_verification: {:?}", quote_verification)?;
         writeln!(f, "quote_timeout: {:?}", quote_timeout)?;
@@ -639,6 +681,7 @@ mod tests {
             )
             .to_string(),
             &NativePriceEstimator::OneInchSpotPriceApi.to_string(),
+            &NativePriceEstimator::Chainlink.to_string(),
             "one|http://localhost:1111/,two|http://localhost:2222/;three|http://localhost:3333/,four|http://localhost:4444/",
             &format!("one|http://localhost:1111/,two|http://localhost:2222/;{},four|http://localhost:4444/", NativePriceEstimator::OneInchSpotPriceApi),
         ] {
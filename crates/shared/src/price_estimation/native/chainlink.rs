@@ -0,0 +1,376 @@
+//! Native price estimator reading the latest answers of Chainlink price feeds.
+
+use {
+    super::{NativePriceEstimateResult, NativePriceEstimating},
+    crate::{price_estimation::PriceEstimationError, token_info::TokenInfoFetching},
+    anyhow::{Context, Result, anyhow, bail, ensure},
+    ethrpc::{
+        Web3,
+        multicall::{Call, MulticallExt},
+    },
+    futures::{FutureExt, future::BoxFuture},
+    hex_literal::hex,
+    model::time,
+    primitive_types::{H160, U256},
+    std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration},
+};
+
+/// `latestRoundData()`
+const LATEST_ROUND_DATA: [u8; 4] = hex!("feaf968c");
+/// `decimals()`
+const DECIMALS: [u8; 4] = hex!("313ce567");
+
+/// The currency a Chainlink feed prices its token in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Quote {
+    Native,
+    Usd,
+}
+
+/// A Chainlink feed pricing a token. Parsed from
+/// `<token>|<feed>|<ETH|USD>[|<max_age>]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChainlinkFeed {
+    pub token: H160,
+    pub feed: H160,
+    pub quote: Quote,
+    /// Overrides the default maximum age of the feed's latest answer, e.g.
+    /// for feeds with a long heartbeat.
+    pub max_age: Option<Duration>,
+}
+
+impl FromStr for ChainlinkFeed {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.split('|').collect();
+        let (token, feed, quote, max_age) = match parts.as_slice() {
+            [token, feed, quote] => (token, feed, quote, None),
+            [token, feed, quote, max_age] => (token, feed, quote, Some(max_age)),
+            _ => bail!("expected '<token>|<feed>|<ETH|USD>[|<max_age>]', got '{s}'"),
+        };
+        Ok(Self {
+            token: token.parse().context("invalid token")?,
+            feed: feed.parse().context("invalid feed")?,
+            quote: match *quote {
+                "ETH" => Quote::Native,
+                "USD" => Quote::Usd,
+                quote => bail!("unsupported quote currency {quote}"),
+            },
+            max_age: max_age
+                .map(|max_age| humantime::parse_duration(max_age))
+                .transpose()
+                .context("invalid max age")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Feed {
+    address: H160,
+    decimals: u8,
+    max_age: Duration,
+}
+
+impl Feed {
+    /// Converts the result of `latestRoundData()` into the price of the
+    /// feed's token.
+    fn price(&self, round_data: &[u8], now: u64) -> Result<f64, PriceEstimationError> {
+        // (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt,
+        // uint80 answeredInRound)
+        let word = |index: usize| {
+            round_data
+                .get(32 * index..32 * (index + 1))
+                .context("unexpected latestRoundData() result")
+        };
+        let answer = word(1)?;
+        let updated_at = U256::from_big_endian(word(3)?);
+
+        // The answer is an `int256` which has to be positive for a valid price.
+        let answer = U256::from_big_endian(answer);
+        if answer.is_zero() || answer.bit(255) {
+            return Err(PriceEstimationError::NoLiquidity);
+        }
+        let age = now.saturating_sub(updated_at.try_into().unwrap_or(u64::MAX));
+        if age > self.max_age.as_secs() {
+            return Err(PriceEstimationError::EstimatorInternal(
+                Stale {
+                    feed: self.address,
+                    age,
+                }
+                .into(),
+            ));
+        }
+        Ok(answer.to_f64_lossy() / 10f64.powi(self.decimals.into()))
+    }
+}
+
+/// The latest answer of a feed is older than its maximum age.
+#[derive(Debug, thiserror::Error)]
+#[error("latest answer of Chainlink feed {feed:?} is stale ({age}s old)")]
+struct Stale {
+    feed: H160,
+    age: u64,
+}
+
+fn is_stale(err: &PriceEstimationError) -> bool {
+    matches!(err, PriceEstimationError::EstimatorInternal(err) if err.is::<Stale>())
+}
+
+/// The feeds pricing a token. Feeds quoted in the native token are preferred.
+#[derive(Default)]
+struct TokenFeeds {
+    native: Option<Feed>,
+    usd: Option<Feed>,
+}
+
+pub struct Chainlink {
+    web3: Web3,
+    native_token: H160,
+    native_decimals: u8,
+    feeds: HashMap<H160, TokenFeeds>,
+    /// The feed pricing the native token in USD, used to convert the prices of
+    /// tokens which only have a USD feed.
+    native_usd: Option<Feed>,
+    infos: Arc<dyn TokenInfoFetching>,
+}
+
+impl Chainlink {
+    pub async fn new(
+        web3: Web3,
+        feeds: &[ChainlinkFeed],
+        native_usd_feed: Option<H160>,
+        max_age: Duration,
+        native_token: H160,
+        infos: Arc<dyn TokenInfoFetching>,
+    ) -> Result<Self> {
+        ensure!(
+            native_usd_feed.is_some() || feeds.iter().all(|feed| feed.quote == Quote::Native),
+            "Chainlink USD feeds require a native token USD feed"
+        );
+        let native_decimals = infos
+            .get_token_info(native_token)
+            .await?
+            .decimals
+            .context("could not determine decimals of native token")?;
+
+        // The decimals of a feed never change, so they are only fetched once.
+        let addresses: Vec<H160> = feeds
+            .iter()
+            .map(|feed| feed.feed)
+            .chain(native_usd_feed)
+            .collect();
+        let calls = addresses
+            .iter()
+            .map(|address| Call {
+                to: *address,
+                data: DECIMALS.to_vec(),
+                ..Default::default()
+            })
+            .collect();
+        let decimals = web3
+            .eth()
+            .multicall(calls, Default::default(), None)
+            .await
+            .into_iter()
+            .zip(&addresses)
+            .map(|(result, address)| {
+                let data = result.with_context(|| format!("decimals() of feed {address:?}"))?;
+                let decimals = U256::from_big_endian(
+                    data.get(..32)
+                        .with_context(|| format!("decimals() of feed {address:?}"))?,
+                );
+                u8::try_from(decimals).map_err(|_| anyhow!("invalid decimals of feed {address:?}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut token_feeds = HashMap::<H160, TokenFeeds>::new();
+        for (config, decimals) in feeds.iter().zip(&decimals) {
+            let feed = Feed {
+                address: config.feed,
+                decimals: *decimals,
+                max_age: config.max_age.unwrap_or(max_age),
+            };
+            let entry = token_feeds.entry(config.token).or_default();
+            match config.quote {
+                Quote::Native => entry.native = Some(feed),
+                Quote::Usd => entry.usd = Some(feed),
+            }
+        }
+        let native_usd = native_usd_feed.map(|address| Feed {
+            address,
+            decimals: *decimals.last().unwrap(),
+            max_age,
+        });
+
+        Ok(Self {
+            web3,
+            native_token,
+            native_decimals,
+            feeds: token_feeds,
+            native_usd,
+            infos,
+        })
+    }
+
+    /// Fetches the prices of the given feeds in a single multicall.
+    async fn prices(&self, feeds: &[&Feed]) -> Vec<Result<f64, PriceEstimationError>> {
+        let calls = feeds
+            .iter()
+            .map(|feed| Call {
+                to: feed.address,
+                data: LATEST_ROUND_DATA.to_vec(),
+                ..Default::default()
+            })
+            .collect();
+        let now = u64::from(time::now_in_epoch_seconds());
+        self.web3
+            .eth()
+            .multicall(calls, Default::default(), None)
+            .await
+            .into_iter()
+            .zip(feeds)
+            .map(|(result, feed)| -> Result<f64, PriceEstimationError> {
+                let round_data = result.with_context(|| {
+                    format!("latestRoundData() of feed {:?} failed", feed.address)
+                })?;
+                feed.price(&round_data, now)
+            })
+            .collect()
+    }
+
+    /// Computes the price of a whole token in whole native tokens. Falls back
+    /// to the USD feed if the native token feed is stale.
+    async fn price(&self, feeds: &TokenFeeds) -> NativePriceEstimateResult {
+        match (&feeds.native, &feeds.usd, &self.native_usd) {
+            (Some(native), usd, native_usd) => {
+                let price = self.prices(&[native]).await.remove(0);
+                match (price, usd, native_usd) {
+                    (Err(err), Some(usd), Some(native_usd)) if is_stale(&err) => {
+                        tracing::debug!(?err, "falling back to Chainlink USD feed");
+                        self.usd_price(usd, native_usd).await
+                    }
+                    (price, _, _) => price,
+                }
+            }
+            (None, Some(usd), Some(native_usd)) => self.usd_price(usd, native_usd).await,
+            _ => Err(PriceEstimationError::NoLiquidity),
+        }
+    }
+
+    /// Converts the USD price of a whole token into whole native tokens.
+    async fn usd_price(&self, usd: &Feed, native_usd: &Feed) -> NativePriceEstimateResult {
+        let mut prices = self.prices(&[usd, native_usd]).await.into_iter();
+        let token_usd = prices.next().unwrap()?;
+        let native_usd = prices.next().unwrap()?;
+        Ok(token_usd / native_usd)
+    }
+
+    async fn estimate(&self, token: H160) -> NativePriceEstimateResult {
+        if token == self.native_token {
+            return Ok(1.);
+        }
+        let feeds = self
+            .feeds
+            .get(&token)
+            .ok_or(PriceEstimationError::NoLiquidity)?;
+        let (price, decimals) = futures::join!(self.price(feeds), self.infos.get_token_info(token));
+        let decimals = decimals?
+            .decimals
+            .with_context(|| format!("missing decimals: {token:?}"))?;
+
+        // Feeds price whole tokens but native prices are denominated in atoms.
+        let price = price? * 10f64.powi(i32::from(self.native_decimals) - i32::from(decimals));
+        if !price.is_normal() {
+            return Err(PriceEstimationError::NoLiquidity);
+        }
+        Ok(price)
+    }
+}
+
+impl NativePriceEstimating for Chainlink {
+    fn estimate_native_price(&self, token: H160) -> BoxFuture<'_, NativePriceEstimateResult> {
+        self.estimate(token).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_feeds() {
+        assert_eq!(
+            ChainlinkFeed::from_str(
+                "0x0101010101010101010101010101010101010101|0x0202020202020202020202020202020202020202|ETH"
+            )
+            .unwrap(),
+            ChainlinkFeed {
+                token: H160([1; 20]),
+                feed: H160([2; 20]),
+                quote: Quote::Native,
+                max_age: None,
+            }
+        );
+        assert_eq!(
+            ChainlinkFeed::from_str(
+                "0x0101010101010101010101010101010101010101|0x0202020202020202020202020202020202020202|USD|25h"
+            )
+            .unwrap(),
+            ChainlinkFeed {
+                token: H160([1; 20]),
+                feed: H160([2; 20]),
+                quote: Quote::Usd,
+                max_age: Some(Duration::from_secs(25 * 60 * 60)),
+            }
+        );
+        assert!(
+            ChainlinkFeed::from_str(
+                "0x0101010101010101010101010101010101010101|0x0202020202020202020202020202020202020202|EUR"
+            )
+            .is_err()
+        );
+        assert!(ChainlinkFeed::from_str("0x0101010101010101010101010101010101010101").is_err());
+    }
+
+    fn round_data(answer: U256, updated_at: u64) -> Vec<u8> {
+        let mut data = vec![0; 5 * 32];
+        answer.to_big_endian(&mut data[32..64]);
+        U256::from(updated_at).to_big_endian(&mut data[96..128]);
+        data
+    }
+
+    #[test]
+    fn converts_answers_to_prices() {
+        let feed = Feed {
+            address: H160([1; 20]),
+            decimals: 8,
+            max_age: Duration::from_secs(3600),
+        };
+
+        assert_eq!(
+            feed.price(&round_data(U256::from(250_000_000_u64), 1000), 2000)
+                .unwrap(),
+            2.5
+        );
+        // Stale answers are not used.
+        assert!(is_stale(
+            &feed
+                .price(&round_data(U256::from(250_000_000_u64), 1000), 5000)
+                .unwrap_err()
+        ));
+        // Negative and zero answers are invalid.
+        assert!(matches!(
+            feed.price(&round_data(U256::MAX, 1000), 2000),
+            Err(PriceEstimationError::NoLiquidity)
+        ));
+        assert!(matches!(
+            feed.price(&round_data(U256::zero(), 1000), 2000),
+            Err(PriceEstimationError::NoLiquidity)
+        ));
+        // Truncated results are errors.
+        let err = feed.price(&[0; 64], 2000).unwrap_err();
+        assert!(matches!(err, PriceEstimationError::EstimatorInternal(_)));
+        assert!(!is_stale(&err));
+    }
+}
@@ -8,10 +8,15 @@ use {
     std::sync::{Arc, LazyLock},
 };
 
+mod chainlink;
 mod coingecko;
 mod oneinch;
 
-pub use self::{coingecko::CoinGecko, oneinch::OneInch};
+pub use self::{
+    chainlink::{Chainlink, ChainlinkFeed},
+    coingecko::CoinGecko,
+    oneinch::OneInch,
+};
 
 pub type NativePrice = f64;
 pub type NativePriceEstimateResult = Result<NativePrice, PriceEstimationError>;
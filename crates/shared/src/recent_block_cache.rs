@@ -29,7 +29,7 @@ use {
     anyhow::{Context, Result},
     cached::{Cached, SizedCache},
     ethcontract::BlockNumber,
-    ethrpc::block_stream::CurrentBlockWatcher,
+    ethrpc::block_stream::{CurrentBlockWatcher, ReorgTracker},
     futures::{FutureExt, StreamExt},
     itertools::Itertools,
     prometheus::IntCounterVec,
@@ -201,11 +201,19 @@ where
         tokio::task::spawn(
             async move {
                 let mut stream = ethrpc::block_stream::into_stream(block_stream);
+                let mut reorgs = ReorgTracker::default();
                 while let Some(block) = stream.next().await {
                     let Some(inner) = inner.upgrade() else {
                         tracing::debug!("cache no longer in use; terminate GC task");
                         break;
                     };
+                    if let Some(reorg) = reorgs.unhandled(&block) {
+                        inner
+                            .mutexed
+                            .lock()
+                            .unwrap()
+                            .remove_cached_blocks_newer_than(reorg.common_ancestor.0);
+                    }
                     if let Err(err) = inner.update_cache_at_block(block.number).await {
                         tracing::warn!(?err, "failed to update cache");
                     }
@@ -455,6 +463,21 @@ where
         );
     }
 
+    /// Drops all values cached for blocks that got replaced by a reorg.
+    fn remove_cached_blocks_newer_than(&mut self, newest_to_keep: u64) {
+        tracing::debug!("dropping blocks newer than {} from cache", newest_to_keep);
+        self.entries
+            .retain(|(block, _), _| *block <= newest_to_keep);
+
+        self.cached_most_recently_at_block.clear();
+        for (block, key) in self.entries.keys() {
+            // Entries are ordered by block, so later blocks overwrite earlier ones.
+            self.cached_most_recently_at_block
+                .insert(key.clone(), *block);
+        }
+        self.last_update_block = cmp::min(self.last_update_block, newest_to_keep);
+    }
+
     fn keys_of_recently_used_entries(&self) -> impl Iterator<Item = K> + '_ {
         self.recently_used.key_order().cloned()
     }
@@ -827,6 +850,44 @@ mod tests {
         assert_eq!(cache.mutexed.lock().unwrap().entries.len(), 2);
     }
 
+    #[tokio::test]
+    async fn drops_blocks_replaced_by_reorg() {
+        let fetcher = FakeCacheFetcher::new(vec![TestValue::new(0, "a")]);
+        let block_stream = mock_single_block(BlockInfo {
+            number: 12,
+            ..Default::default()
+        });
+        let cache = RecentBlockCache::new(
+            CacheConfig {
+                number_of_blocks_to_cache: NonZeroU64::new(5).unwrap(),
+                maximum_recent_block_age: 5,
+                ..Default::default()
+            },
+            fetcher,
+            block_stream,
+            "",
+        )
+        .unwrap()
+        .inner;
+        let key = TestKey(0);
+
+        for block in 10..=12 {
+            cache
+                .fetch(std::iter::once(key), Block::Number(block))
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+
+        let mut mutexed = cache.mutexed.lock().unwrap();
+        mutexed.remove_cached_blocks_newer_than(10);
+        assert!(mutexed.get(key, Some(10)).is_some());
+        assert!(mutexed.get(key, Some(11)).is_none());
+        assert!(mutexed.get(key, Some(12)).is_none());
+        assert_eq!(mutexed.cached_most_recently_at_block[&key], 10);
+        assert_eq!(mutexed.last_update_block, 10);
+    }
+
     #[tokio::test]
     async fn respects_max_age_limit_for_recent() {
         let fetcher = FakeCacheFetcher::default();
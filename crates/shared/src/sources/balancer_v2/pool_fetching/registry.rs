@@ -23,7 +23,7 @@ use {
     ethcontract::{BlockId, H256, Instance, dyns::DynAllEventsBuilder, errors::MethodError},
     ethrpc::{
         Web3Transport,
-        block_stream::{BlockNumberHash, BlockRetrieving},
+        block_stream::{BlockNumberHash, BlockRetrieving, Reorg},
     },
     futures::future,
     hex_literal::hex,
@@ -126,6 +126,10 @@ where
     fn name(&self) -> &str {
         "BalancerPoolFetcher"
     }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        self.updater.handle_reorg(reorg).await
+    }
 }
 
 fn base_pool_factory(contract_instance: &Instance<Web3Transport>) -> BalancerV2BasePoolFactory {
@@ -17,11 +17,11 @@ use {
     std::collections::BTreeMap,
 };
 
-mod error;
+pub(crate) mod error;
 pub mod fixed_point;
 mod math;
-mod stable_math;
-mod weighted_math;
+pub(crate) mod stable_math;
+pub(crate) mod weighted_math;
 
 const WEIGHTED_SWAP_GAS_COST: usize = 100_000;
 // See https://dune.xyz/queries/219641 for cost of pure stable swaps
@@ -0,0 +1,73 @@
+//! Balancer V3 liquidity.
+//!
+//! All Balancer V3 pools are registered in a single vault, which emits a
+//! `PoolRegistered` event with the static pool configuration (tokens, rate
+//! providers and hooks). The pool registry is indexed from these events, while
+//! the block-dependent state of the pools is queried from the vault on demand.
+//!
+//! Unlike Balancer V2, the vault scales token amounts with both the token
+//! decimals and the token rate from the token's rate provider before passing
+//! them to the pool math. This makes "boosted" pools holding ERC-4626 wrapped
+//! tokens trade at the rate of the underlying asset. These pools are exposed
+//! with their wrapped tokens, swapping through the ERC-4626 buffers of the
+//! vault is not supported.
+//!
+//! Pools with hooks that can alter swaps (dynamic swap fees, before and after
+//! swap hooks) are not supported, since their swap amounts can't be computed
+//! off-chain.
+
+pub mod pool_fetching;
+mod swap;
+
+use {
+    crate::sources::balancer_v2::{pool_fetching::AmplificationParameter, swap::fixed_point::Bfp},
+    ethcontract::{H160, U256},
+    model::TokenPair,
+};
+
+/// The state of a Balancer V3 pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    /// The pool tokens in the order they are registered in the vault.
+    pub tokens: Vec<Token>,
+    pub swap_fee: Bfp,
+    pub kind: PoolKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    pub address: H160,
+    /// The raw balance of the token in the pool.
+    pub balance: U256,
+    /// The factor scaling raw token amounts to the 18 decimal amounts used by
+    /// the pool math. This is the product of the decimal scaling factor and
+    /// the token rate.
+    pub scaling_factor: Bfp,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolKind {
+    /// A weighted pool with the normalized weights of its tokens in token
+    /// order.
+    Weighted { weights: Vec<Bfp> },
+    Stable {
+        amplification_parameter: AmplificationParameter,
+    },
+}
+
+impl Pool {
+    /// Returns the index of the token in the pool.
+    pub fn index(&self, token: H160) -> Option<usize> {
+        self.tokens.iter().position(|t| t.address == token)
+    }
+
+    /// Returns all token pairs that can be traded on the pool.
+    pub fn token_pairs(&self) -> impl Iterator<Item = TokenPair> + '_ {
+        self.tokens.iter().enumerate().flat_map(move |(i, a)| {
+            self.tokens[i + 1..]
+                .iter()
+                .filter_map(move |b| TokenPair::new(a.address, b.address))
+        })
+    }
+}
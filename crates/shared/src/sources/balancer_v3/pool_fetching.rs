@@ -0,0 +1,436 @@
+//! Indexing of Balancer V3 pools from vault `PoolRegistered` events and
+//! fetching of their on-chain state.
+
+use {
+    super::{Pool, PoolKind, Token},
+    crate::{
+        event_handling::{EventHandler, EventRetrieving, EventStoring},
+        maintenance::Maintaining,
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+        sources::{
+            balancer_v2::{pool_fetching::AmplificationParameter, swap::fixed_point::Bfp},
+            uniswap_v2::pool_fetching::handle_contract_error,
+        },
+    },
+    anyhow::{Context, Result, ensure},
+    contracts::{
+        BalancerV3StablePool,
+        BalancerV3Vault,
+        BalancerV3WeightedPool,
+        balancer_v3_vault::{Event as VaultEvent, event_data::PoolRegistered},
+    },
+    ethcontract::{BlockId, Event, H160, H256, dyns::DynAllEventsBuilder},
+    ethrpc::{
+        Web3,
+        block_stream::{BlockRetrieving, CurrentBlockWatcher, RangeInclusive, Reorg},
+    },
+    futures::future,
+    hex_literal::hex,
+    model::TokenPair,
+    std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    },
+    tokio::sync::Mutex,
+};
+
+pub struct BalancerV3VaultContract(BalancerV3Vault);
+
+const POOL_REGISTERED_TOPIC: H256 = H256(hex!(
+    "bc1561eeab9f40962e2fb827a7ff9c7cdb47a9d7c84caeefa4ed90e043842dad"
+));
+
+impl EventRetrieving for BalancerV3VaultContract {
+    type Event = VaultEvent;
+
+    fn get_events(&self) -> DynAllEventsBuilder<Self::Event> {
+        let mut events = self.0.all_events();
+        events.filter = events.filter.topic0(POOL_REGISTERED_TOPIC.into());
+        events
+    }
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait BalancerV3PoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// The static information of a registered pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolInfo {
+    pub address: H160,
+    pub tokens: Vec<H160>,
+    pub block_created: u64,
+}
+
+impl PoolInfo {
+    /// Creates the pool info from its `PoolRegistered` event. Returns `None`
+    /// for pools with hooks that can alter swaps.
+    pub fn from_event(event: &PoolRegistered, block_created: u64) -> Option<Self> {
+        let hooks = &event.hooks_config;
+        let (compute_dynamic_swap_fee, before_swap, after_swap) = (hooks.3, hooks.4, hooks.5);
+        if compute_dynamic_swap_fee || before_swap || after_swap {
+            return None;
+        }
+
+        Some(Self {
+            address: event.pool,
+            tokens: event
+                .token_config
+                .iter()
+                .map(|(token, ..)| *token)
+                .collect(),
+            block_created,
+        })
+    }
+
+    fn token_pairs(&self) -> impl Iterator<Item = TokenPair> + '_ {
+        self.tokens.iter().enumerate().flat_map(move |(i, a)| {
+            self.tokens[i + 1..]
+                .iter()
+                .filter_map(move |b| TokenPair::new(*a, *b))
+        })
+    }
+}
+
+/// In-memory storage of the pools registered in the vault.
+#[derive(Debug, Default)]
+pub struct PoolRegistry {
+    pools: HashMap<H160, PoolInfo>,
+    pools_by_token_pair: HashMap<TokenPair, HashSet<H160>>,
+    last_indexed_block: u64,
+}
+
+impl PoolRegistry {
+    /// Returns the addresses of all pools containing at least one of the
+    /// token pairs.
+    pub fn pool_addresses(&self, token_pairs: &HashSet<TokenPair>) -> HashSet<H160> {
+        token_pairs
+            .iter()
+            .filter_map(|pair| self.pools_by_token_pair.get(pair))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn insert(&mut self, pool: PoolInfo) {
+        for pair in pool.token_pairs() {
+            self.pools_by_token_pair
+                .entry(pair)
+                .or_default()
+                .insert(pool.address);
+        }
+        self.pools.insert(pool.address, pool);
+    }
+
+    /// Removes all pools registered in or after the specified block.
+    fn remove_pools_from_block(&mut self, block: u64) {
+        self.pools.retain(|_, pool| pool.block_created < block);
+        for pools in self.pools_by_token_pair.values_mut() {
+            pools.retain(|pool| self.pools.contains_key(pool));
+        }
+        self.pools_by_token_pair
+            .retain(|_, pools| !pools.is_empty());
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<VaultEvent> for PoolRegistry {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<VaultEvent>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        self.remove_pools_from_block(*range.start());
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<VaultEvent>>) -> Result<()> {
+        for event in events {
+            let block_created = event.meta.context("event missing metadata")?.block_number;
+            let VaultEvent::PoolRegistered(registered) = event.data;
+            if let Some(pool) = PoolInfo::from_event(&registered, block_created) {
+                self.insert(pool);
+            }
+        }
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        let last_event_block = self
+            .pools
+            .values()
+            .map(|pool| pool.block_created)
+            .max()
+            .unwrap_or_default();
+        Ok(last_event_block.max(self.last_indexed_block))
+    }
+
+    async fn persist_last_indexed_block(&mut self, block: u64) -> Result<()> {
+        self.last_indexed_block = block;
+        Ok(())
+    }
+}
+
+/// Fetches Balancer V3 pools registered in the vault.
+///
+/// Only weighted and stable pools are supported, other pool types are skipped.
+pub struct BalancerV3PoolFetcher {
+    registry: Mutex<EventHandler<BalancerV3VaultContract, PoolRegistry>>,
+    cache: RecentBlockCache<H160, Pool, CacheFetcher>,
+}
+
+impl BalancerV3PoolFetcher {
+    pub async fn new(
+        web3: Web3,
+        vault: H160,
+        deployment_block: u64,
+        block_retriever: Arc<dyn BlockRetrieving>,
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "balancerV3".into());
+        let vault = BalancerV3Vault::at(&web3, vault);
+        // Start right before the deployment block, so that events from the
+        // deployment block itself get indexed as well.
+        let start_block = block_retriever
+            .block(deployment_block.saturating_sub(1))
+            .await?;
+
+        let registry = Mutex::new(EventHandler::new(
+            block_retriever,
+            BalancerV3VaultContract(vault.clone()),
+            PoolRegistry::default(),
+            Some(start_block),
+        ));
+        let cache = RecentBlockCache::new(
+            config,
+            CacheFetcher { web3, vault },
+            block_stream,
+            "balancer_v3",
+        )?;
+        Ok(Self { registry, cache })
+    }
+}
+
+#[async_trait::async_trait]
+impl BalancerV3PoolFetching for BalancerV3PoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses = self
+            .registry
+            .lock()
+            .await
+            .store()
+            .pool_addresses(&token_pairs);
+        self.cache.fetch(addresses, at_block).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for BalancerV3PoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.registry.run_maintenance().await
+    }
+
+    fn name(&self) -> &str {
+        "BalancerV3PoolFetcher"
+    }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        self.registry.handle_reorg(reorg).await
+    }
+}
+
+impl CacheKey<Pool> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(pool: &Pool) -> Self {
+        pool.address
+    }
+}
+
+/// Fetches the state of Balancer V3 pools for the `RecentBlockCache`.
+struct CacheFetcher {
+    web3: Web3,
+    vault: BalancerV3Vault,
+}
+
+impl CacheFetcher {
+    /// Fetches the state of a pool. Returns `None` for paused pools and pools
+    /// of unsupported types.
+    async fn pool_state(&self, address: H160, block: BlockId) -> Result<Option<Pool>> {
+        let weighted = BalancerV3WeightedPool::at(&self.web3, address);
+        let stable = BalancerV3StablePool::at(&self.web3, address);
+        let (token_info, token_rates, swap_fee, paused, weights, amplification_parameter) = futures::join!(
+            self.vault.get_pool_token_info(address).block(block).call(),
+            self.vault.get_pool_token_rates(address).block(block).call(),
+            self.vault
+                .get_static_swap_fee_percentage(address)
+                .block(block)
+                .call(),
+            self.vault.is_pool_paused(address).block(block).call(),
+            weighted.get_normalized_weights().block(block).call(),
+            stable.get_amplification_parameter().block(block).call(),
+        );
+        if paused? {
+            return Ok(None);
+        }
+
+        let kind = match (
+            handle_contract_error(weights)?,
+            handle_contract_error(amplification_parameter)?,
+        ) {
+            (Some(weights), _) => PoolKind::Weighted {
+                weights: weights.into_iter().map(Bfp::from_wei).collect(),
+            },
+            (None, Some((value, _, precision))) => PoolKind::Stable {
+                amplification_parameter: AmplificationParameter::try_new(value, precision)?,
+            },
+            (None, None) => return Ok(None),
+        };
+
+        let (tokens, _, balances, _) = token_info?;
+        let (decimal_scaling_factors, rates) = token_rates?;
+        ensure!(
+            [balances.len(), decimal_scaling_factors.len(), rates.len()]
+                .iter()
+                .all(|len| *len == tokens.len()),
+            "unexpected number of Balancer V3 pool token values",
+        );
+        // The vault scales raw amounts with `amount * 10^(18 - decimals) * rate
+        // / 1e18`, so both factors can be combined into a single fixed point
+        // scaling factor.
+        let tokens = tokens
+            .into_iter()
+            .zip(balances)
+            .zip(decimal_scaling_factors.into_iter().zip(rates))
+            .map(|((address, balance), (decimal_scaling_factor, rate))| {
+                Ok(Token {
+                    address,
+                    balance,
+                    scaling_factor: Bfp::from_wei(
+                        decimal_scaling_factor
+                            .checked_mul(rate)
+                            .context("scaling factor overflow")?,
+                    ),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Some(Pool {
+            address,
+            tokens,
+            swap_fee: Bfp::from_wei(swap_fee?),
+            kind,
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Pool> for CacheFetcher {
+    async fn fetch_values(&self, addresses: HashSet<H160>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses: Vec<_> = addresses.into_iter().collect();
+        let block = BlockId::Number(at_block.into());
+        let pools = future::join_all(
+            addresses
+                .iter()
+                .map(|address| self.pool_state(*address, block)),
+        )
+        .await;
+
+        Ok(addresses
+            .iter()
+            .zip(pools)
+            .filter_map(|(address, pool)| match pool {
+                Ok(pool) => pool,
+                Err(err) => {
+                    tracing::debug!(pool = ?address, ?err, "failed to fetch Balancer V3 pool state");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::EventMetadata};
+
+    fn registered(pool: u8, tokens: &[u8], before_swap: bool) -> PoolRegistered {
+        let mut hooks_config = <PoolRegistered as Default>::default().hooks_config;
+        hooks_config.4 = before_swap;
+        PoolRegistered {
+            pool: H160([pool; 20]),
+            token_config: tokens
+                .iter()
+                .map(|token| (H160([*token; 20]), 0, H160::zero(), false))
+                .collect(),
+            hooks_config,
+            ..Default::default()
+        }
+    }
+
+    fn event(block_number: u64, data: PoolRegistered) -> Event<VaultEvent> {
+        Event {
+            data: VaultEvent::PoolRegistered(data),
+            meta: Some(EventMetadata {
+                block_number,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn pair(a: u8, b: u8) -> TokenPair {
+        TokenPair::new(H160([a; 20]), H160([b; 20])).unwrap()
+    }
+
+    #[tokio::test]
+    async fn indexes_registered_pools() {
+        let mut registry = PoolRegistry::default();
+        registry
+            .append_events(vec![
+                event(1, registered(0x10, &[1, 2, 3], false)),
+                event(2, registered(0x11, &[2, 3], false)),
+                event(3, registered(0x12, &[1, 2], true)),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            registry.pool_addresses(&HashSet::from([pair(1, 3)])),
+            HashSet::from([H160([0x10; 20])])
+        );
+        assert_eq!(
+            registry.pool_addresses(&HashSet::from([pair(1, 2), pair(2, 3)])),
+            HashSet::from([H160([0x10; 20]), H160([0x11; 20])])
+        );
+        assert_eq!(registry.last_event_block().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn replaces_reorged_pools() {
+        let mut registry = PoolRegistry::default();
+        registry
+            .append_events(vec![
+                event(1, registered(0x10, &[1, 2], false)),
+                event(2, registered(0x11, &[1, 2], false)),
+            ])
+            .await
+            .unwrap();
+        registry
+            .replace_events(
+                vec![event(3, registered(0x12, &[2, 3], false))],
+                RangeInclusive::try_new(2, 3).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            registry.pool_addresses(&HashSet::from([pair(1, 2), pair(2, 3)])),
+            HashSet::from([H160([0x10; 20]), H160([0x12; 20])])
+        );
+    }
+}
@@ -0,0 +1,271 @@
+//! Swap math of Balancer V3 pools.
+//!
+//! The vault scales amounts with the token decimals and rates and charges the
+//! swap fee before calling into the pool math, which is the same as for
+//! Balancer V2 weighted and stable pools:
+//! <https://github.com/balancer/balancer-v3-monorepo/blob/main/pkg/vault/contracts/Vault.sol>
+
+use {
+    super::{Pool, PoolKind, Token},
+    crate::{
+        baseline_solver::BaselineSolvable,
+        conversions::U256Ext,
+        sources::balancer_v2::swap::{error::Error, fixed_point::Bfp, stable_math, weighted_math},
+    },
+    ethcontract::{H160, U256},
+};
+
+/// Rough estimates of the gas needed for swapping on a pool through the batch
+/// router, including the Permit2 approval.
+const WEIGHTED_SWAP_GAS_COST: usize = 150_000;
+const STABLE_SWAP_GAS_COST: usize = 220_000;
+
+impl Token {
+    /// Converts the raw balance to the 18 decimal live balance of the vault.
+    fn upscaled_balance(&self) -> Result<Bfp, Error> {
+        self.upscale_down(self.balance)
+    }
+
+    /// Scales a raw token amount to 18 decimals and applies the token rate,
+    /// rounding down.
+    /// <https://github.com/balancer/balancer-v3-monorepo/blob/main/pkg/solidity-utils/contracts/helpers/ScalingHelpers.sol>
+    fn upscale_down(&self, amount: U256) -> Result<Bfp, Error> {
+        Bfp::from_wei(amount).mul_down(self.scaling_factor)
+    }
+
+    /// Same as [`Token::upscale_down`] but rounding up.
+    fn upscale_up(&self, amount: U256) -> Result<Bfp, Error> {
+        Bfp::from_wei(amount).mul_up(self.scaling_factor)
+    }
+
+    /// Converts an 18 decimal amount back to a raw token amount, rounding
+    /// down.
+    fn downscale_down(&self, amount: Bfp) -> Result<U256, Error> {
+        Ok(amount.div_down(self.scaling_factor)?.as_uint256())
+    }
+
+    /// Same as [`Token::downscale_down`] but rounding up.
+    fn downscale_up(&self, amount: Bfp) -> Result<U256, Error> {
+        Ok(amount.div_up(self.scaling_factor)?.as_uint256())
+    }
+}
+
+impl Pool {
+    fn upscaled_balances(&self) -> Option<Vec<Bfp>> {
+        self.tokens
+            .iter()
+            .map(|token| token.upscaled_balance().ok())
+            .collect()
+    }
+
+    /// Computes the scaled output amount for a scaled input amount after
+    /// fees.
+    fn calc_out_given_in(&self, i: usize, j: usize, amount_in: Bfp) -> Option<Bfp> {
+        let mut balances = self.upscaled_balances()?;
+        match &self.kind {
+            PoolKind::Weighted { weights } => weighted_math::calc_out_given_in_v3(
+                balances[i],
+                *weights.get(i)?,
+                balances[j],
+                *weights.get(j)?,
+                amount_in,
+            )
+            .ok(),
+            PoolKind::Stable {
+                amplification_parameter,
+            } => stable_math::calc_out_given_in(
+                amplification_parameter.with_base(*stable_math::AMP_PRECISION)?,
+                &mut balances,
+                i,
+                j,
+                amount_in,
+            )
+            .ok(),
+        }
+    }
+
+    /// Computes the scaled input amount before fees for a scaled output
+    /// amount.
+    fn calc_in_given_out(&self, i: usize, j: usize, amount_out: Bfp) -> Option<Bfp> {
+        let mut balances = self.upscaled_balances()?;
+        match &self.kind {
+            PoolKind::Weighted { weights } => weighted_math::calc_in_given_out_v3(
+                balances[i],
+                *weights.get(i)?,
+                balances[j],
+                *weights.get(j)?,
+                amount_out,
+            )
+            .ok(),
+            PoolKind::Stable {
+                amplification_parameter,
+            } => stable_math::calc_in_given_out(
+                amplification_parameter.with_base(*stable_math::AMP_PRECISION)?,
+                &mut balances,
+                i,
+                j,
+                amount_out,
+            )
+            .ok(),
+        }
+    }
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        let (i, j) = (self.index(in_token)?, self.index(out_token)?);
+
+        // For exact input swaps, the fee is charged on the scaled input amount.
+        let in_amount = self.tokens[i].upscale_down(in_amount).ok()?;
+        let fee = in_amount.mul_up(self.swap_fee).ok()?;
+        let in_amount_minus_fees = in_amount.sub(fee).ok()?;
+
+        let out_amount = self.calc_out_given_in(i, j, in_amount_minus_fees)?;
+        self.tokens[j].downscale_down(out_amount).ok()
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        let (i, j) = (self.index(in_token)?, self.index(out_token)?);
+
+        let out_amount = self.tokens[j].upscale_up(out_amount).ok()?;
+        let in_amount = self.calc_in_given_out(i, j, out_amount)?;
+
+        // For exact output swaps, the fee is charged on top of the computed
+        // input amount, so that it amounts to the fee percentage of the total
+        // input amount.
+        let fee = in_amount
+            .as_uint256()
+            .checked_mul(self.swap_fee.as_uint256())?
+            .checked_ceil_div(&self.swap_fee.complement().as_uint256())?;
+        let in_amount_plus_fees = in_amount.add(Bfp::from_wei(fee)).ok()?;
+        self.tokens[i].downscale_up(in_amount_plus_fees).ok()
+    }
+
+    fn gas_cost(&self) -> usize {
+        match self.kind {
+            PoolKind::Weighted { .. } => WEIGHTED_SWAP_GAS_COST,
+            PoolKind::Stable { .. } => STABLE_SWAP_GAS_COST,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sources::balancer_v2::pool_fetching::AmplificationParameter};
+
+    fn token(address: u64, balance: U256, scaling_factor: Bfp) -> Token {
+        Token {
+            address: H160::from_low_u64_be(address),
+            balance,
+            scaling_factor,
+        }
+    }
+
+    fn weighted_pool(tokens: Vec<Token>, swap_fee: &str) -> Pool {
+        Pool {
+            address: H160::from_low_u64_be(42),
+            tokens,
+            swap_fee: swap_fee.parse().unwrap(),
+            kind: PoolKind::Weighted {
+                weights: vec!["0.5".parse().unwrap(), "0.5".parse().unwrap()],
+            },
+        }
+    }
+
+    fn stable_pool(tokens: Vec<Token>, swap_fee: &str) -> Pool {
+        Pool {
+            address: H160::from_low_u64_be(42),
+            tokens,
+            swap_fee: swap_fee.parse().unwrap(),
+            kind: PoolKind::Stable {
+                amplification_parameter: AmplificationParameter::try_new(
+                    200_000.into(),
+                    1_000.into(),
+                )
+                .unwrap(),
+            },
+        }
+    }
+
+    #[test]
+    fn scales_amounts_with_token_rates() {
+        let (a, b) = (H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+        let amount = U256::exp10(18);
+
+        for pool in [weighted_pool, stable_pool] {
+            // A token with a rate of 2 trades like the same token without rate
+            // and twice the amounts.
+            let with_rate = pool(
+                vec![
+                    token(1, U256::exp10(21) / 2, "2".parse().unwrap()),
+                    token(2, U256::exp10(21), Bfp::one()),
+                ],
+                "0.003",
+            );
+            let without_rate = pool(
+                vec![
+                    token(1, U256::exp10(21), Bfp::one()),
+                    token(2, U256::exp10(21), Bfp::one()),
+                ],
+                "0.003",
+            );
+            assert_eq!(
+                with_rate.get_amount_out(b, (amount, a)),
+                without_rate.get_amount_out(b, (amount * 2, a)),
+            );
+
+            // Decimal scaling factors are applied the same way.
+            let six_decimals = pool(
+                vec![
+                    token(1, U256::exp10(21), Bfp::one()),
+                    token(2, U256::exp10(9), Bfp::exp10(12)),
+                ],
+                "0.003",
+            );
+            assert_eq!(
+                six_decimals.get_amount_out(b, (amount, a)).unwrap(),
+                without_rate.get_amount_out(b, (amount, a)).unwrap() / U256::exp10(12),
+            );
+        }
+    }
+
+    #[test]
+    fn charges_swap_fees() {
+        let (a, b) = (H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+        let tokens = || {
+            vec![
+                token(1, U256::exp10(21), Bfp::one()),
+                token(2, U256::exp10(21), "1.1".parse().unwrap()),
+            ]
+        };
+        let amount = U256::exp10(18);
+
+        for pool in [weighted_pool, stable_pool] {
+            let (with_fee, without_fee) = (pool(tokens(), "0.01"), pool(tokens(), "0"));
+
+            let out_amount = with_fee.get_amount_out(b, (amount, a)).unwrap();
+            assert!(out_amount < without_fee.get_amount_out(b, (amount, a)).unwrap());
+
+            let in_amount = with_fee.get_amount_in(a, (out_amount, b)).unwrap();
+            assert!(in_amount > without_fee.get_amount_in(a, (out_amount, b)).unwrap());
+            // Computing the input amount for the output amount of a swap gives
+            // back the original input amount up to rounding.
+            let difference = in_amount.max(amount) - in_amount.min(amount);
+            assert!(difference <= amount / 1_000_000);
+        }
+    }
+
+    #[test]
+    fn unknown_tokens() {
+        let pool = weighted_pool(
+            vec![
+                token(1, U256::exp10(21), Bfp::one()),
+                token(2, U256::exp10(21), Bfp::one()),
+            ],
+            "0.003",
+        );
+        let (a, c) = (H160::from_low_u64_be(1), H160::from_low_u64_be(3));
+        assert_eq!(pool.get_amount_out(c, (U256::exp10(18), a)), None);
+        assert_eq!(pool.get_amount_in(c, (U256::exp10(18), a)), None);
+    }
+}
@@ -0,0 +1,271 @@
+//! Off-chain implementation of the Curve CryptoSwap invariant for pools with
+//! two tokens.
+//!
+//! This follows the Vyper implementation[^1] of the two token CryptoSwap pools,
+//! including the rounding, so that computed amounts match the on-chain ones.
+//! Note that pools that are currently ramping their `A` or `gamma` parameters
+//! recompute `D` on the fly, which isn't done here.
+//!
+//! [^1]: <https://github.com/curvefi/curve-crypto-contract/blob/master/contracts/two/CurveCryptoSwap2ETH.vy>
+
+use {super::Token, ethcontract::U256};
+
+/// The denominator of pool fees.
+pub const FEE_DENOMINATOR: u64 = 10_000_000_000;
+/// The multiplier of the amplification parameter returned by `A()`.
+const A_MULTIPLIER: u64 = 10_000;
+const N_COINS: usize = 2;
+const MAX_ITERATIONS: usize = 255;
+
+/// The parameters of a CryptoSwap pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameters {
+    /// The amplification parameter as returned by `A()`, i.e. including
+    /// `N_COINS ** N_COINS` and `A_MULTIPLIER`.
+    pub a: U256,
+    pub gamma: U256,
+    /// The invariant of the pool.
+    pub d: U256,
+    /// The price of the second token in the first one scaled by `1e18`.
+    pub price_scale: U256,
+    /// The fee over [`FEE_DENOMINATOR`] charged when the pool is balanced.
+    pub mid_fee: U256,
+    /// The fee over [`FEE_DENOMINATOR`] charged when the pool is imbalanced.
+    pub out_fee: U256,
+    /// How fast the fee goes from `mid_fee` to `out_fee`.
+    pub fee_gamma: U256,
+}
+
+impl Parameters {
+    /// Computes the amount of token `j` received for `dx` of token `i`.
+    pub fn get_dy(&self, tokens: &[Token], i: usize, j: usize, dx: U256) -> Option<U256> {
+        let (scales, mut xp) = self.xp(tokens, i, j)?;
+        xp[i] = xp[i].checked_add(mul_div(dx, scales[i], precision())?)?;
+
+        let y = self.newton_y(&xp, j)?;
+        let dy = xp[j].checked_sub(y)?.checked_sub(1.into())?;
+        xp[j] = y;
+
+        let dy = mul_div(dy, precision(), scales[j])?;
+        let fee = mul_div(self.fee(&xp)?, dy, FEE_DENOMINATOR.into())?;
+        dy.checked_sub(fee)
+    }
+
+    /// Computes the amount of token `i` needed to receive `dy` of token `j`.
+    ///
+    /// Since the fee depends on the balances after the swap, this approximates
+    /// the fee with the balances of the previous iteration.
+    pub fn get_dx(&self, tokens: &[Token], i: usize, j: usize, dy: U256) -> Option<U256> {
+        const FEE_ITERATIONS: usize = 2;
+
+        let (scales, initial) = self.xp(tokens, i, j)?;
+        let mut xp = initial.clone();
+        let mut fee = self.fee(&xp)?;
+        for _ in 0..FEE_ITERATIONS {
+            let dy = mul_div(
+                dy,
+                FEE_DENOMINATOR.into(),
+                U256::from(FEE_DENOMINATOR).checked_sub(fee)?,
+            )?
+            .checked_add(1.into())?;
+            xp = initial.clone();
+            xp[j] = xp[j].checked_sub(mul_div(dy, scales[j], precision())?)?;
+            xp[i] = self.newton_y(&xp, i)?;
+            fee = self.fee(&xp)?;
+        }
+
+        mul_div(xp[i].checked_sub(initial[i])?, precision(), scales[i])?.checked_add(1.into())
+    }
+
+    /// Returns the scales converting token amounts to the internal price
+    /// scaled balances along with the scaled balances.
+    fn xp(
+        &self,
+        tokens: &[Token],
+        i: usize,
+        j: usize,
+    ) -> Option<([U256; N_COINS], [U256; N_COINS])> {
+        if tokens.len() != N_COINS || i == j || i >= N_COINS || j >= N_COINS {
+            return None;
+        }
+
+        let scales = [
+            tokens[0].rate,
+            mul_div(tokens[1].rate, self.price_scale, precision())?,
+        ];
+        let xp = [
+            mul_div(tokens[0].balance, scales[0], precision())?,
+            mul_div(tokens[1].balance, scales[1], precision())?,
+        ];
+        Some((scales, xp))
+    }
+
+    fn fee(&self, xp: &[U256; N_COINS]) -> Option<U256> {
+        let one = precision();
+        let sum = xp[0].checked_add(xp[1])?;
+        let k = mul_div(
+            mul_div(one.checked_mul(U256::from(N_COINS.pow(2)))?, xp[0], sum)?,
+            xp[1],
+            sum,
+        )?;
+        let f = mul_div(
+            self.fee_gamma,
+            one,
+            self.fee_gamma.checked_add(one)?.checked_sub(k)?,
+        )?;
+        self.mid_fee
+            .checked_mul(f)?
+            .checked_add(self.out_fee.checked_mul(one.checked_sub(f)?)?)?
+            .checked_div(one)
+    }
+
+    /// Computes the scaled balance of token `i` such that the invariant is
+    /// kept given the scaled balance of the other token.
+    fn newton_y(&self, xp: &[U256; N_COINS], i: usize) -> Option<U256> {
+        let one = precision();
+        let n = U256::from(N_COINS);
+        let (ann, gamma, d) = (self.a, self.gamma, self.d);
+
+        let x_j = xp[1 - i];
+        let mut y = d
+            .checked_pow(2.into())?
+            .checked_div(x_j.checked_mul(n * n)?)?;
+        let k0_i = mul_div(one * n, x_j, d)?;
+        let convergence_limit = (x_j / U256::exp10(14))
+            .max(d / U256::exp10(14))
+            .max(100.into());
+
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+            let k0 = mul_div(k0_i.checked_mul(y)?, n, d)?;
+            let s = x_j.checked_add(y)?;
+
+            let g1k0 = gamma.checked_add(one)?;
+            let g1k0 = if g1k0 > k0 {
+                g1k0 - k0 + 1
+            } else {
+                k0 - g1k0 + 1
+            };
+
+            let mul1 = mul_div(
+                mul_div(
+                    mul_div(one.checked_mul(d)? / gamma, g1k0, gamma)?,
+                    g1k0,
+                    1.into(),
+                )?,
+                A_MULTIPLIER.into(),
+                ann,
+            )?;
+            let mul2 = one.checked_add(mul_div(one * 2, k0, g1k0)?)?;
+
+            let yfprime = one
+                .checked_mul(y)?
+                .checked_add(s.checked_mul(mul2)?)?
+                .checked_add(mul1)?;
+            let dyfprime = d.checked_mul(mul2)?;
+            if yfprime < dyfprime {
+                y = y_prev / 2;
+                continue;
+            }
+            let yfprime = yfprime - dyfprime;
+            let fprime = yfprime.checked_div(y)?;
+
+            let y_minus = mul1.checked_div(fprime)?;
+            let y_plus = yfprime
+                .checked_add(one.checked_mul(d)?)?
+                .checked_div(fprime)?
+                .checked_add(mul_div(y_minus, one, k0)?)?;
+            let y_minus = y_minus.checked_add(mul_div(one, s, fprime)?)?;
+            y = if y_plus < y_minus {
+                y_prev / 2
+            } else {
+                y_plus - y_minus
+            };
+
+            let diff = if y > y_prev { y - y_prev } else { y_prev - y };
+            if diff < convergence_limit.max(y / U256::exp10(14)) {
+                // Values too far from the balanced state are unsafe.
+                let frac = mul_div(y, one, d)?;
+                return (frac >= U256::exp10(16) && frac <= U256::exp10(20)).then_some(y);
+            }
+        }
+        None
+    }
+}
+
+fn precision() -> U256 {
+    U256::exp10(18)
+}
+
+fn mul_div(a: U256, b: U256, c: U256) -> Option<U256> {
+    a.checked_mul(b)?.checked_div(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::H160};
+
+    /// A USDC/WETH like pool at a price of 2000 USDC per WETH.
+    fn pool() -> (Parameters, Vec<Token>) {
+        let tokens = vec![
+            Token {
+                address: H160::from_low_u64_be(1),
+                balance: U256::from(2_000_000) * U256::exp10(6),
+                rate: U256::exp10(30),
+            },
+            Token {
+                address: H160::from_low_u64_be(2),
+                balance: U256::from(1_000) * U256::exp10(18),
+                rate: U256::exp10(18),
+            },
+        ];
+        let parameters = Parameters {
+            a: U256::from(400_000),
+            gamma: U256::from(145_000_000_000_000_u64),
+            d: U256::from(4_000_000) * U256::exp10(18),
+            price_scale: U256::from(2_000) * U256::exp10(18),
+            mid_fee: U256::from(26_000_000),
+            out_fee: U256::from(45_000_000),
+            fee_gamma: U256::from(230_000_000_000_000_u64),
+        };
+        (parameters, tokens)
+    }
+
+    #[test]
+    fn swaps_at_price_scale() {
+        let (parameters, tokens) = pool();
+
+        let dy = parameters
+            .get_dy(&tokens, 0, 1, U256::from(2_000) * U256::exp10(6))
+            .unwrap();
+        // Roughly 1 WETH minus a 0.26% fee.
+        assert!(dy < U256::exp10(18));
+        assert!(dy > U256::exp10(18) * 99 / 100);
+
+        let dy = parameters.get_dy(&tokens, 1, 0, U256::exp10(18)).unwrap();
+        assert!(dy < U256::from(2_000) * U256::exp10(6));
+        assert!(dy > U256::from(1_980) * U256::exp10(6));
+    }
+
+    #[test]
+    fn computes_input_amounts() {
+        let (parameters, tokens) = pool();
+
+        let dy = U256::exp10(18);
+        let dx = parameters.get_dx(&tokens, 0, 1, dy).unwrap();
+        assert!(parameters.get_dy(&tokens, 0, 1, dx).unwrap() >= dy);
+        assert!(parameters.get_dy(&tokens, 0, 1, dx * 999 / 1000).unwrap() < dy);
+    }
+
+    #[test]
+    fn rejects_invalid_swaps() {
+        let (parameters, tokens) = pool();
+
+        assert_eq!(parameters.get_dy(&tokens, 0, 0, 1.into()), None);
+        assert_eq!(parameters.get_dy(&tokens[..1], 0, 1, 1.into()), None);
+        assert_eq!(
+            parameters.get_dx(&tokens, 0, 1, U256::from(1_000) * U256::exp10(18)),
+            None
+        );
+    }
+}
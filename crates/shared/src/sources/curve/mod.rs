@@ -0,0 +1,127 @@
+//! Curve StableSwap and CryptoSwap liquidity.
+
+pub mod crypto;
+pub mod pool_fetching;
+pub mod stable;
+
+use {
+    crate::baseline_solver::BaselineSolvable,
+    ethcontract::{H160, U256},
+    model::TokenPair,
+};
+
+/// Approximate gas cost of a swap over a StableSwap pool.
+const STABLE_SWAP_GAS_COST: usize = 130_000;
+/// Approximate gas cost of a swap over a CryptoSwap pool.
+const CRYPTO_SWAP_GAS_COST: usize = 200_000;
+
+/// The state of a Curve pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    /// The pool tokens in the order of the pool's coin indices.
+    pub tokens: Vec<Token>,
+    pub kind: PoolKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    pub address: H160,
+    pub balance: U256,
+    /// The rate scaled by `1e18` that normalizes balances of the token to 18
+    /// decimals, including the exchange rate of rate oracle tokens.
+    pub rate: U256,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolKind {
+    StableSwap(stable::Parameters),
+    CryptoSwap(crypto::Parameters),
+}
+
+impl Pool {
+    /// Returns the coin index of the token in the pool.
+    pub fn index(&self, token: H160) -> Option<usize> {
+        self.tokens.iter().position(|t| t.address == token)
+    }
+
+    /// Returns all token pairs that can be traded on the pool.
+    pub fn token_pairs(&self) -> impl Iterator<Item = TokenPair> + '_ {
+        self.tokens.iter().enumerate().flat_map(move |(i, a)| {
+            self.tokens[i + 1..]
+                .iter()
+                .filter_map(move |b| TokenPair::new(a.address, b.address))
+        })
+    }
+
+    fn get_dy(&self, i: usize, j: usize, dx: U256) -> Option<U256> {
+        match &self.kind {
+            PoolKind::StableSwap(parameters) => parameters.get_dy(&self.tokens, i, j, dx),
+            PoolKind::CryptoSwap(parameters) => parameters.get_dy(&self.tokens, i, j, dx),
+        }
+    }
+
+    fn get_dx(&self, i: usize, j: usize, dy: U256) -> Option<U256> {
+        match &self.kind {
+            PoolKind::StableSwap(parameters) => parameters.get_dx(&self.tokens, i, j, dy),
+            PoolKind::CryptoSwap(parameters) => parameters.get_dx(&self.tokens, i, j, dy),
+        }
+    }
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        self.get_dy(self.index(in_token)?, self.index(out_token)?, in_amount)
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        let (i, j) = (self.index(in_token)?, self.index(out_token)?);
+        let in_amount = self.get_dx(i, j, out_amount)?;
+        // Fees depending on the balances after the swap are only approximated
+        // when computing input amounts, so make sure the amount is enough.
+        (self.get_dy(i, j, in_amount)? >= out_amount).then_some(in_amount)
+    }
+
+    fn gas_cost(&self) -> usize {
+        match self.kind {
+            PoolKind::StableSwap(_) => STABLE_SWAP_GAS_COST,
+            PoolKind::CryptoSwap(_) => CRYPTO_SWAP_GAS_COST,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_amounts_by_token_address() {
+        let token = |n: u64| Token {
+            address: H160::from_low_u64_be(n),
+            balance: U256::exp10(24),
+            rate: U256::exp10(18),
+        };
+        let pool = Pool {
+            address: H160::from_low_u64_be(42),
+            tokens: vec![token(1), token(2), token(3)],
+            kind: PoolKind::StableSwap(stable::Parameters {
+                amplification_parameter: U256::from(100 * stable::A_PRECISION),
+                fee: U256::from(4_000_000),
+                offpeg_fee_multiplier: stable::FEE_DENOMINATOR.into(),
+            }),
+        };
+
+        assert_eq!(pool.token_pairs().count(), 3);
+
+        let (a, b) = (H160::from_low_u64_be(1), H160::from_low_u64_be(3));
+        let out_amount = pool.get_amount_out(b, (U256::exp10(18), a)).unwrap();
+        assert_eq!(out_amount, pool.get_dy(0, 2, U256::exp10(18)).unwrap());
+        let in_amount = pool.get_amount_in(a, (out_amount, b)).unwrap();
+        assert!(pool.get_amount_out(b, (in_amount, a)).unwrap() >= out_amount);
+
+        assert_eq!(
+            pool.get_amount_out(b, (U256::exp10(18), H160::from_low_u64_be(4))),
+            None
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Discovery of Curve pools through the Curve meta registry and fetching of
+//! their on-chain state.
+
+use {
+    super::{Pool, PoolKind, Token, crypto, stable},
+    crate::{
+        maintenance::Maintaining,
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+        sources::uniswap_v2::pool_fetching::handle_contract_error,
+        token_info::TokenInfoFetching,
+    },
+    anyhow::{Context, Result},
+    contracts::{CurveCryptoSwapPool, CurveRegistry, CurveStableSwapPool},
+    ethcontract::{BlockId, H160, U256},
+    ethrpc::{Web3, block_stream::CurrentBlockWatcher},
+    futures::future,
+    model::TokenPair,
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{Arc, RwLock},
+    },
+};
+
+/// The placeholder address Curve pools use for the native token.
+const NATIVE_TOKEN: H160 = H160([0xee; 20]);
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait CurvePoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// Fetches Curve pools registered in the Curve meta registry.
+///
+/// Pools trading the native token and meta pools whose base pool rate can't
+/// be read from the pool itself are not supported.
+pub struct CurvePoolFetcher {
+    inner: Arc<Inner>,
+    cache: RecentBlockCache<H160, Pool, CacheFetcher>,
+}
+
+struct Inner {
+    web3: Web3,
+    registry: CurveRegistry,
+    token_infos: Arc<dyn TokenInfoFetching>,
+    pools: RwLock<Pools>,
+}
+
+/// The pools discovered so far.
+#[derive(Default)]
+struct Pools {
+    by_address: HashMap<H160, Arc<PoolInfo>>,
+    by_pair: HashMap<TokenPair, HashSet<H160>>,
+    /// The number of registry entries that were already indexed.
+    indexed: usize,
+}
+
+/// The static information of a pool.
+#[derive(Debug)]
+struct PoolInfo {
+    address: H160,
+    tokens: Vec<H160>,
+    /// The rates normalizing balances to 18 decimals based on the token
+    /// decimals.
+    rates: Vec<U256>,
+    kind: PoolInfoKind,
+}
+
+#[derive(Debug)]
+enum PoolInfoKind {
+    /// A StableSwap pool. Newer pools expose the rates they use, including
+    /// oracle rates, through `stored_rates()`.
+    StableSwap {
+        stored_rates: bool,
+    },
+    CryptoSwap,
+}
+
+impl CurvePoolFetcher {
+    pub async fn new(
+        web3: Web3,
+        registry: H160,
+        token_infos: Arc<dyn TokenInfoFetching>,
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "curve".into());
+        let inner = Arc::new(Inner {
+            registry: CurveRegistry::at(&web3, registry),
+            web3,
+            token_infos,
+            pools: Default::default(),
+        });
+        inner.update_pools().await?;
+
+        let cache =
+            RecentBlockCache::new(config, CacheFetcher(inner.clone()), block_stream, "curve")?;
+        Ok(Self { inner, cache })
+    }
+}
+
+#[async_trait::async_trait]
+impl CurvePoolFetching for CurvePoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses: HashSet<H160> = {
+            let pools = self.inner.pools.read().unwrap();
+            token_pairs
+                .iter()
+                .filter_map(|pair| pools.by_pair.get(pair))
+                .flatten()
+                .copied()
+                .collect()
+        };
+        self.cache.fetch(addresses, at_block).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for CurvePoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.inner.update_pools().await
+    }
+
+    fn name(&self) -> &str {
+        "CurvePoolFetcher"
+    }
+}
+
+impl Inner {
+    /// Indexes the pools that were added to the registry since the last update.
+    async fn update_pools(&self) -> Result<()> {
+        let count = self.registry.pool_count().call().await?.as_usize();
+        let indexed = self.pools.read().unwrap().indexed;
+        if count <= indexed {
+            return Ok(());
+        }
+
+        let infos =
+            future::try_join_all((indexed..count).map(|index| self.pool_info(index))).await?;
+        let mut pools = self.pools.write().unwrap();
+        for info in infos.into_iter().flatten() {
+            for (i, a) in info.tokens.iter().enumerate() {
+                for b in &info.tokens[i + 1..] {
+                    if let Some(pair) = TokenPair::new(*a, *b) {
+                        pools.by_pair.entry(pair).or_default().insert(info.address);
+                    }
+                }
+            }
+            pools.by_address.insert(info.address, Arc::new(info));
+        }
+        pools.indexed = count;
+        tracing::debug!(pools = pools.by_address.len(), "indexed Curve pools");
+        Ok(())
+    }
+
+    /// Reads the static information of the pool at the registry index. Returns
+    /// `None` for unsupported pools.
+    async fn pool_info(&self, index: usize) -> Result<Option<PoolInfo>> {
+        let address = self.registry.pool_list(index.into()).call().await?;
+        let (coins, is_meta) = futures::try_join!(
+            self.registry.get_coins(address).call(),
+            self.registry.is_meta(address).call(),
+        )?;
+        let tokens: Vec<H160> = coins
+            .into_iter()
+            .take_while(|coin| !coin.is_zero())
+            .collect();
+        if tokens.len() < 2 || tokens.contains(&NATIVE_TOKEN) {
+            return Ok(None);
+        }
+
+        let stable = CurveStableSwapPool::at(&self.web3, address);
+        let crypto = CurveCryptoSwapPool::at(&self.web3, address);
+        let (gamma, stored_rates) =
+            futures::join!(crypto.gamma().call(), stable.stored_rates().call());
+        let kind = match (
+            handle_contract_error(gamma)?,
+            handle_contract_error(stored_rates)?,
+        ) {
+            (Some(_), _) if tokens.len() == 2 => PoolInfoKind::CryptoSwap,
+            (Some(_), _) => return Ok(None),
+            (None, Some(_)) => PoolInfoKind::StableSwap { stored_rates: true },
+            (None, None) if !is_meta => PoolInfoKind::StableSwap {
+                stored_rates: false,
+            },
+            (None, None) => return Ok(None),
+        };
+
+        let token_infos = self.token_infos.get_token_infos(&tokens).await;
+        let Some(rates) = tokens
+            .iter()
+            .map(|token| {
+                let decimals = token_infos.get(token)?.decimals?;
+                Some(U256::exp10(36_usize.checked_sub(decimals.into())?))
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            tracing::debug!(?address, "skipping Curve pool with unknown token decimals");
+            return Ok(None);
+        };
+
+        Ok(Some(PoolInfo {
+            address,
+            tokens,
+            rates,
+            kind,
+        }))
+    }
+
+    async fn pool_state(&self, info: &PoolInfo, block: BlockId) -> Result<Option<Pool>> {
+        let balances = future::join_all((0..info.tokens.len()).map(|i| {
+            CurveStableSwapPool::at(&self.web3, info.address)
+                .balances(i.into())
+                .block(block)
+                .call()
+        }));
+
+        let (balances, rates, kind) = match info.kind {
+            PoolInfoKind::StableSwap { stored_rates } => {
+                let pool = CurveStableSwapPool::at(&self.web3, info.address);
+                let rates = async {
+                    if stored_rates {
+                        pool.stored_rates().block(block).call().await.map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                };
+                let (balances, rates, a_precise, a, fee, offpeg_fee_multiplier) = futures::join!(
+                    balances,
+                    rates,
+                    pool.a_precise().block(block).call(),
+                    pool.a().block(block).call(),
+                    pool.fee().block(block).call(),
+                    pool.offpeg_fee_multiplier().block(block).call(),
+                );
+                // Older pools don't expose the precise amplification parameter
+                // or dynamic fees.
+                let amplification_parameter = match handle_contract_error(a_precise)? {
+                    Some(a_precise) => a_precise,
+                    None => a?
+                        .checked_mul(stable::A_PRECISION.into())
+                        .context("amplification parameter overflow")?,
+                };
+                let parameters = stable::Parameters {
+                    amplification_parameter,
+                    fee: fee?,
+                    offpeg_fee_multiplier: handle_contract_error(offpeg_fee_multiplier)?
+                        .unwrap_or(stable::FEE_DENOMINATOR.into()),
+                };
+                let rates = rates?.unwrap_or_else(|| info.rates.clone());
+                (balances, rates, PoolKind::StableSwap(parameters))
+            }
+            PoolInfoKind::CryptoSwap => {
+                let pool = CurveCryptoSwapPool::at(&self.web3, info.address);
+                let (balances, a, gamma, d, price_scale, mid_fee, out_fee, fee_gamma) = futures::join!(
+                    balances,
+                    pool.a().block(block).call(),
+                    pool.gamma().block(block).call(),
+                    pool.d().block(block).call(),
+                    pool.price_scale().block(block).call(),
+                    pool.mid_fee().block(block).call(),
+                    pool.out_fee().block(block).call(),
+                    pool.fee_gamma().block(block).call(),
+                );
+                let parameters = crypto::Parameters {
+                    a: a?,
+                    gamma: gamma?,
+                    d: d?,
+                    price_scale: price_scale?,
+                    mid_fee: mid_fee?,
+                    out_fee: out_fee?,
+                    fee_gamma: fee_gamma?,
+                };
+                (
+                    balances,
+                    info.rates.clone(),
+                    PoolKind::CryptoSwap(parameters),
+                )
+            }
+        };
+
+        let balances = balances.into_iter().collect::<Result<Vec<_>, _>>()?;
+        if rates.len() != info.tokens.len() {
+            tracing::debug!(pool = ?info.address, "unexpected number of Curve pool rates");
+            return Ok(None);
+        }
+        let tokens = info
+            .tokens
+            .iter()
+            .zip(balances)
+            .zip(rates)
+            .map(|((address, balance), rate)| Token {
+                address: *address,
+                balance,
+                rate,
+            })
+            .collect();
+        Ok(Some(Pool {
+            address: info.address,
+            tokens,
+            kind,
+        }))
+    }
+}
+
+impl CacheKey<Pool> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(pool: &Pool) -> Self {
+        pool.address
+    }
+}
+
+/// Fetches the state of Curve pools for the `RecentBlockCache`.
+struct CacheFetcher(Arc<Inner>);
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Pool> for CacheFetcher {
+    async fn fetch_values(&self, addresses: HashSet<H160>, at_block: Block) -> Result<Vec<Pool>> {
+        let infos: Vec<_> = {
+            let pools = self.0.pools.read().unwrap();
+            addresses
+                .iter()
+                .filter_map(|address| pools.by_address.get(address).cloned())
+                .collect()
+        };
+        let block = BlockId::Number(at_block.into());
+        let pools = future::join_all(infos.iter().map(|info| self.0.pool_state(info, block))).await;
+
+        Ok(infos
+            .iter()
+            .zip(pools)
+            .filter_map(|(info, pool)| match pool {
+                Ok(pool) => pool,
+                Err(err) => {
+                    tracing::debug!(pool = ?info.address, ?err, "failed to fetch Curve pool state");
+                    None
+                }
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,241 @@
+//! Off-chain implementation of the Curve StableSwap invariant.
+//!
+//! This follows the Vyper implementation[^1] of the StableSwap and
+//! StableSwap-NG pools, including the rounding, so that computed amounts match
+//! the on-chain ones.
+//!
+//! [^1]: <https://github.com/curvefi/stableswap-ng/blob/main/contracts/main/CurveStableSwapNG.vy>
+
+use {super::Token, ethcontract::U256};
+
+/// The precision of the amplification parameter returned by `A_precise()`.
+pub const A_PRECISION: u64 = 100;
+/// The denominator of pool fees.
+pub const FEE_DENOMINATOR: u64 = 10_000_000_000;
+
+const MAX_ITERATIONS: usize = 255;
+
+/// The parameters of a StableSwap pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameters {
+    /// The amplification parameter scaled by [`A_PRECISION`].
+    pub amplification_parameter: U256,
+    /// The swap fee over [`FEE_DENOMINATOR`].
+    pub fee: U256,
+    /// The multiplier applied to the fee when the pool is imbalanced over
+    /// [`FEE_DENOMINATOR`]. Pools without dynamic fees use
+    /// [`FEE_DENOMINATOR`].
+    pub offpeg_fee_multiplier: U256,
+}
+
+impl Parameters {
+    /// Computes the amount of token `j` received for `dx` of token `i`.
+    pub fn get_dy(&self, tokens: &[Token], i: usize, j: usize, dx: U256) -> Option<U256> {
+        let xp = xp(tokens)?;
+        let amp = self.amplification_parameter;
+
+        let x = xp[i].checked_add(mul_div(dx, tokens[i].rate, precision())?)?;
+        let d = get_d(&xp, amp)?;
+        let y = get_y(i, j, x, &xp, amp, d)?;
+        let dy = xp[j].checked_sub(y)?.checked_sub(1.into())?;
+
+        let fee = self.dynamic_fee(xp[i].checked_add(x)? / 2, xp[j].checked_add(y)? / 2)?;
+        let fee = mul_div(fee, dy, FEE_DENOMINATOR.into())?;
+        mul_div(dy.checked_sub(fee)?, precision(), tokens[j].rate)
+    }
+
+    /// Computes the amount of token `i` needed to receive `dy` of token `j`.
+    pub fn get_dx(&self, tokens: &[Token], i: usize, j: usize, dy: U256) -> Option<U256> {
+        let xp = xp(tokens)?;
+        let amp = self.amplification_parameter;
+
+        let d = get_d(&xp, amp)?;
+        let fee = self.dynamic_fee(xp[i], xp[j])?;
+        let dy = mul_div(dy, tokens[j].rate, precision())?
+            .checked_add(1.into())?
+            .checked_mul(FEE_DENOMINATOR.into())?
+            .checked_div(U256::from(FEE_DENOMINATOR).checked_sub(fee)?)?;
+        let y = xp[j].checked_sub(dy)?;
+        let x = get_y(j, i, y, &xp, amp, d)?;
+        mul_div(x.checked_sub(xp[i])?, precision(), tokens[i].rate)
+    }
+
+    fn dynamic_fee(&self, xpi: U256, xpj: U256) -> Option<U256> {
+        let multiplier = self.offpeg_fee_multiplier;
+        if multiplier <= FEE_DENOMINATOR.into() {
+            return Some(self.fee);
+        }
+
+        let xps2 = xpi.checked_add(xpj)?.checked_pow(2.into())?;
+        let imbalance = (multiplier - FEE_DENOMINATOR)
+            .checked_mul(4.into())?
+            .checked_mul(xpi)?
+            .checked_mul(xpj)?
+            .checked_div(xps2)?;
+        mul_div(
+            multiplier,
+            self.fee,
+            imbalance.checked_add(FEE_DENOMINATOR.into())?,
+        )
+    }
+}
+
+fn precision() -> U256 {
+    U256::exp10(18)
+}
+
+fn mul_div(a: U256, b: U256, c: U256) -> Option<U256> {
+    a.checked_mul(b)?.checked_div(c)
+}
+
+/// Returns the balances normalized to 18 decimals.
+fn xp(tokens: &[Token]) -> Option<Vec<U256>> {
+    tokens
+        .iter()
+        .map(|token| mul_div(token.balance, token.rate, precision()))
+        .collect()
+}
+
+fn distance(a: U256, b: U256) -> U256 {
+    if a > b { a - b } else { b - a }
+}
+
+/// Computes the StableSwap invariant `D` for the normalized balances.
+fn get_d(xp: &[U256], amp: U256) -> Option<U256> {
+    let n = U256::from(xp.len());
+    let s = xp
+        .iter()
+        .try_fold(U256::zero(), |sum, x| sum.checked_add(*x))?;
+    if s.is_zero() {
+        return Some(U256::zero());
+    }
+
+    let ann = amp.checked_mul(n)?;
+    let a_precision = U256::from(A_PRECISION);
+    let mut d = s;
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for x in xp {
+            d_p = mul_div(d_p, d, x.checked_mul(n)?)?;
+        }
+        let d_prev = d;
+        let numerator = mul_div(ann, s, a_precision)?
+            .checked_add(d_p.checked_mul(n)?)?
+            .checked_mul(d)?;
+        let denominator = mul_div(ann.checked_sub(a_precision)?, d, a_precision)?
+            .checked_add(n.checked_add(1.into())?.checked_mul(d_p)?)?;
+        d = numerator.checked_div(denominator)?;
+        if distance(d, d_prev) <= 1.into() {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Computes the normalized balance of token `j` after setting the normalized
+/// balance of token `i` to `x` while keeping the invariant `d`.
+fn get_y(i: usize, j: usize, x: U256, xp: &[U256], amp: U256, d: U256) -> Option<U256> {
+    if i == j || i >= xp.len() || j >= xp.len() {
+        return None;
+    }
+
+    let n = U256::from(xp.len());
+    let ann = amp.checked_mul(n)?;
+    let a_precision = U256::from(A_PRECISION);
+    let mut c = d;
+    let mut s = U256::zero();
+    for (k, balance) in xp.iter().enumerate() {
+        let x = match k {
+            k if k == i => x,
+            k if k != j => *balance,
+            _ => continue,
+        };
+        s = s.checked_add(x)?;
+        c = mul_div(c, d, x.checked_mul(n)?)?;
+    }
+    c = mul_div(c.checked_mul(d)?, a_precision, ann.checked_mul(n)?)?;
+    let b = s.checked_add(mul_div(d, a_precision, ann)?)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(2.into())?.checked_add(b)?.checked_sub(d)?)?;
+        if distance(y, y_prev) <= 1.into() {
+            return Some(y);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, ethcontract::H160};
+
+    fn tokens(balances: &[(u128, u32)]) -> Vec<Token> {
+        balances
+            .iter()
+            .enumerate()
+            .map(|(index, &(balance, decimals))| Token {
+                address: H160::from_low_u64_be(index as u64 + 1),
+                balance: U256::from(balance) * U256::exp10(decimals as usize),
+                rate: U256::exp10(36 - decimals as usize),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn balanced_pool_swaps_close_to_one_to_one() {
+        // A 3pool-like DAI/USDC/USDT pool.
+        let tokens = tokens(&[(1_000_000, 18), (1_000_000, 6), (1_000_000, 6)]);
+        let parameters = Parameters {
+            amplification_parameter: U256::from(2_000 * A_PRECISION),
+            fee: U256::from(1_000_000),
+            offpeg_fee_multiplier: FEE_DENOMINATOR.into(),
+        };
+
+        let dx = U256::exp10(18) * 1_000;
+        let dy = parameters.get_dy(&tokens, 0, 1, dx).unwrap();
+        // 0.01% fee and negligible slippage.
+        assert!(dy < U256::exp10(6) * 1_000);
+        assert!(dy > U256::exp10(6) * 999);
+
+        let dx_needed = parameters.get_dx(&tokens, 0, 1, dy).unwrap();
+        assert!(parameters.get_dy(&tokens, 0, 1, dx_needed).unwrap() >= dy);
+        assert!(distance(dx_needed, dx) < U256::exp10(14));
+    }
+
+    #[test]
+    fn imbalanced_pool_charges_dynamic_fees() {
+        let tokens = tokens(&[(100_000, 18), (1_900_000, 18)]);
+        let mut parameters = Parameters {
+            amplification_parameter: U256::from(200 * A_PRECISION),
+            fee: U256::from(1_000_000),
+            offpeg_fee_multiplier: FEE_DENOMINATOR.into(),
+        };
+
+        let dx = U256::exp10(18) * 100;
+        let static_fee = parameters.get_dy(&tokens, 1, 0, dx).unwrap();
+        parameters.offpeg_fee_multiplier = U256::from(FEE_DENOMINATOR) * 5;
+        let dynamic_fee = parameters.get_dy(&tokens, 1, 0, dx).unwrap();
+        assert!(dynamic_fee < static_fee);
+    }
+
+    #[test]
+    fn rejects_invalid_swaps() {
+        let tokens = tokens(&[(1_000, 18), (1_000, 18)]);
+        let parameters = Parameters {
+            amplification_parameter: U256::from(100 * A_PRECISION),
+            fee: U256::from(4_000_000),
+            offpeg_fee_multiplier: FEE_DENOMINATOR.into(),
+        };
+
+        assert_eq!(parameters.get_dy(&tokens, 0, 0, 1.into()), None);
+        assert_eq!(
+            parameters.get_dx(&tokens, 0, 1, U256::exp10(18) * 1_000),
+            None
+        );
+    }
+}
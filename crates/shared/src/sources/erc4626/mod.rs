@@ -0,0 +1,149 @@
+//! ERC-4626 tokenized vaults as liquidity.
+//!
+//! Vaults like sDAI wrap an underlying asset and deterministically convert
+//! between assets and vault shares when depositing and redeeming. Conversions
+//! are modelled with the rates returned by the vault's `previewDeposit` and
+//! `previewRedeem` functions, which account for vault fees. The rates are only
+//! sampled for a single unit at the fetched block and vaults don't enforce a
+//! minimum output, so they are discounted by a small safety margin to cover
+//! rounding and rate changes until the conversion executes.
+
+pub mod vault_fetching;
+
+use {
+    crate::baseline_solver::BaselineSolvable,
+    ethcontract::{H160, U256},
+    model::TokenPair,
+};
+
+/// Approximate gas cost of a vault deposit or redemption.
+const GAS_COST: usize = 100_000;
+
+/// The amount of assets or shares the conversion rates are queried for.
+pub fn conversion_unit() -> U256 {
+    U256::exp10(18)
+}
+
+/// The state of an ERC-4626 vault.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vault {
+    /// The vault address, which is also the address of the share token.
+    pub address: H160,
+    /// The underlying asset of the vault.
+    pub asset: H160,
+    /// The shares received for `1e18` assets, as returned by
+    /// `previewDeposit` and discounted by the safety margin.
+    pub assets_to_shares: U256,
+    /// The assets received for `1e18` shares, as returned by `previewRedeem`
+    /// and discounted by the safety margin.
+    pub shares_to_assets: U256,
+}
+
+impl Vault {
+    /// Returns the token pair of the vault asset and shares.
+    pub fn tokens(&self) -> Option<TokenPair> {
+        TokenPair::new(self.asset, self.address)
+    }
+
+    /// Returns the conversion rate for swapping `in_token` for `out_token`, or
+    /// `None` if the tokens aren't the vault asset and shares.
+    fn rate(&self, in_token: H160, out_token: H160) -> Option<U256> {
+        if in_token == self.asset && out_token == self.address {
+            Some(self.assets_to_shares)
+        } else if in_token == self.address && out_token == self.asset {
+            Some(self.shares_to_assets)
+        } else {
+            None
+        }
+    }
+}
+
+impl BaselineSolvable for Vault {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        let rate = self.rate(in_token, out_token)?;
+        in_amount.checked_mul(rate)?.checked_div(conversion_unit())
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        let rate = self.rate(in_token, out_token)?;
+        if rate.is_zero() {
+            return None;
+        }
+        let in_amount = out_amount
+            .checked_mul(conversion_unit())?
+            .checked_add(rate - 1)?
+            / rate;
+        (self.get_amount_out(out_token, (in_amount, in_token))? >= out_amount).then_some(in_amount)
+    }
+
+    fn gas_cost(&self) -> usize {
+        GAS_COST
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault() -> Vault {
+        Vault {
+            address: H160([0x02; 20]),
+            asset: H160([0x01; 20]),
+            // 1 share is worth 1.05 assets.
+            assets_to_shares: U256::from(952_380_952_380_952_380_u64),
+            shares_to_assets: U256::from(1_050_000_000_000_000_000_u64),
+        }
+    }
+
+    #[test]
+    fn converts_amounts() {
+        let vault = vault();
+        let (asset, shares) = (vault.asset, vault.address);
+
+        assert_eq!(
+            vault.get_amount_out(shares, (U256::exp10(18), asset)),
+            Some(U256::from(952_380_952_380_952_380_u64))
+        );
+        assert_eq!(
+            vault.get_amount_out(asset, (U256::exp10(18), shares)),
+            Some(U256::from(1_050_000_000_000_000_000_u64))
+        );
+
+        for (in_token, out_token) in [(asset, shares), (shares, asset)] {
+            let out_amount = U256::from(1_234_567_890_u64);
+            let in_amount = vault
+                .get_amount_in(in_token, (out_amount, out_token))
+                .unwrap();
+            assert!(
+                vault
+                    .get_amount_out(out_token, (in_amount, in_token))
+                    .unwrap()
+                    >= out_amount
+            );
+            assert!(
+                vault
+                    .get_amount_out(out_token, (in_amount - 1, in_token))
+                    .unwrap()
+                    < out_amount
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_tokens() {
+        let vault = vault();
+        let other = H160([0x03; 20]);
+        assert_eq!(
+            vault.get_amount_out(other, (1_000.into(), vault.asset)),
+            None
+        );
+        assert_eq!(
+            vault.get_amount_in(vault.asset, (1_000.into(), other)),
+            None
+        );
+        assert_eq!(
+            vault.get_amount_out(vault.asset, (1_000.into(), vault.asset)),
+            None
+        );
+    }
+}
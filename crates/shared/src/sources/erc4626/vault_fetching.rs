@@ -0,0 +1,212 @@
+//! Fetching of ERC-4626 vault conversion rates for a configured list of
+//! vaults.
+
+use {
+    super::{Vault, conversion_unit},
+    crate::{
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+        sources::uniswap_v2::pool_fetching::handle_contract_error,
+    },
+    anyhow::{Context, Result},
+    contracts::IERC4626,
+    ethcontract::{BlockId, H160, U256},
+    ethrpc::{Web3, block_stream::CurrentBlockWatcher},
+    futures::future,
+    model::TokenPair,
+    primitive_types::U512,
+    std::collections::{HashMap, HashSet},
+};
+
+/// The safety margin in basis points by which conversion rates are discounted.
+const SAFETY_MARGIN_BPS: u64 = 10;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait Erc4626VaultFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Vault>>;
+}
+
+/// Fetches the state of a fixed set of ERC-4626 vaults.
+pub struct Erc4626VaultFetcher {
+    /// The vaults indexed by their asset and share token pair.
+    vaults: HashMap<TokenPair, H160>,
+    cache: RecentBlockCache<H160, Vault, CacheFetcher>,
+}
+
+impl Erc4626VaultFetcher {
+    /// Creates a new fetcher for the specified vaults. This reads the
+    /// underlying asset of every vault, and fails if any of them isn't a
+    /// valid vault.
+    pub async fn new(
+        web3: Web3,
+        vaults: &[H160],
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "erc4626".into());
+        let assets = future::try_join_all(vaults.iter().map(|&vault| {
+            let web3 = web3.clone();
+            async move {
+                IERC4626::at(&web3, vault)
+                    .asset()
+                    .call()
+                    .await
+                    .with_context(|| format!("asset of vault {vault:?}"))
+            }
+        }))
+        .await?;
+        let assets = vaults
+            .iter()
+            .copied()
+            .zip(assets)
+            .collect::<HashMap<_, _>>();
+
+        let vaults = assets
+            .iter()
+            .map(|(&vault, &asset)| {
+                let pair = TokenPair::new(asset, vault).context("vault is its own asset")?;
+                Ok((pair, vault))
+            })
+            .collect::<Result<_>>()?;
+        let cache = RecentBlockCache::new(
+            config,
+            CacheFetcher { web3, assets },
+            block_stream,
+            "erc4626",
+        )?;
+        Ok(Self { vaults, cache })
+    }
+}
+
+#[async_trait::async_trait]
+impl Erc4626VaultFetching for Erc4626VaultFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Vault>> {
+        let vaults = token_pairs
+            .iter()
+            .filter_map(|pair| self.vaults.get(pair).copied());
+        self.cache.fetch(vaults, at_block).await
+    }
+}
+
+impl CacheKey<Vault> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(vault: &Vault) -> Self {
+        vault.address
+    }
+}
+
+/// Fetches the conversion rates of ERC-4626 vaults for the
+/// `RecentBlockCache`.
+struct CacheFetcher {
+    web3: Web3,
+    /// The underlying assets of the vaults.
+    assets: HashMap<H160, H160>,
+}
+
+impl CacheFetcher {
+    /// Reads the conversion rates of the vault at the specified address.
+    /// Returns `None` if the vault can't currently convert, for example
+    /// because it is paused or empty.
+    async fn vault_state(&self, address: H160, block: BlockId) -> Result<Option<Vault>> {
+        let asset = *self.assets.get(&address).context("unknown vault")?;
+        let vault = IERC4626::at(&self.web3, address);
+        let Some((assets_to_shares, shares_to_assets)) =
+            handle_contract_error(futures::try_join!(
+                vault.preview_deposit(conversion_unit()).block(block).call(),
+                vault.preview_redeem(conversion_unit()).block(block).call(),
+            ))?
+        else {
+            return Ok(None);
+        };
+        let (assets_to_shares, shares_to_assets) = (
+            with_safety_margin(assets_to_shares),
+            with_safety_margin(shares_to_assets),
+        );
+        if assets_to_shares.is_zero() || shares_to_assets.is_zero() {
+            return Ok(None);
+        }
+
+        Ok(Some(Vault {
+            address,
+            asset,
+            assets_to_shares,
+            shares_to_assets,
+        }))
+    }
+}
+
+/// Discounts a conversion rate by the safety margin, rounding down.
+fn with_safety_margin(rate: U256) -> U256 {
+    (rate.full_mul(U256::from(10_000 - SAFETY_MARGIN_BPS)) / U512::from(10_000))
+        .try_into()
+        .expect("discounted rate is smaller than the rate")
+}
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Vault> for CacheFetcher {
+    async fn fetch_values(&self, vaults: HashSet<H160>, at_block: Block) -> Result<Vec<Vault>> {
+        let block = BlockId::Number(at_block.into());
+        let states = future::join_all(
+            vaults
+                .iter()
+                .map(|address| self.vault_state(*address, block)),
+        )
+        .await;
+
+        Ok(vaults
+            .iter()
+            .zip(states)
+            .filter_map(|(address, state)| match state {
+                Ok(state) => state,
+                Err(err) => {
+                    tracing::debug!(vault = ?address, ?err, "failed to fetch ERC-4626 vault state");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ethrpc::{Web3, create_env_test_transport},
+    };
+
+    #[test]
+    fn discounts_rates() {
+        assert_eq!(
+            with_safety_margin(U256::exp10(18)),
+            U256::from(999_000_000_000_000_000_u64)
+        );
+        assert_eq!(with_safety_margin(U256::from(10_001)), U256::from(9_990));
+        assert_eq!(with_safety_margin(U256::one()), U256::zero());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn sdai_conversion_rates() {
+        let web3 = Web3::new(create_env_test_transport());
+        // sDAI on Mainnet
+        let vault = IERC4626::at(&web3, addr!("83F20F44975D03b1b09e64809B757c47f942BEeA"));
+
+        let assets_to_shares = vault
+            .preview_deposit(conversion_unit())
+            .call()
+            .await
+            .unwrap();
+        let shares_to_assets = vault
+            .preview_redeem(conversion_unit())
+            .call()
+            .await
+            .unwrap();
+
+        // sDAI accrues interest, so shares are worth more than the assets.
+        assert!(assets_to_shares < conversion_unit());
+        assert!(shares_to_assets > conversion_unit());
+    }
+}
@@ -0,0 +1,211 @@
+//! Solidly style liquidity, as used by Velodrome V2 and Aerodrome.
+//!
+//! Solidly factories deploy two kinds of pools per token pair: "volatile"
+//! pools using the constant product invariant `x * y = k` and "stable" pools
+//! using the `x³y + y³x = k` invariant for correlated assets. Both kinds
+//! charge a swap fee that is configured per pool in the factory.
+
+pub mod pool_fetching;
+mod stable;
+
+use {
+    crate::baseline_solver::BaselineSolvable,
+    ethcontract::{H160, U256},
+    model::TokenPair,
+    num::rational::Ratio,
+};
+
+/// Approximate gas cost of a swap over a volatile pool through the router.
+const VOLATILE_GAS_COST: usize = 120_000;
+/// Approximate gas cost of a swap over a stable pool through the router.
+const STABLE_GAS_COST: usize = 150_000;
+
+/// The base amount for fees representing 100%.
+const FEE_BASE: u32 = 10_000;
+
+/// The state of a Solidly pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    pub tokens: TokenPair,
+    pub reserves: (U256, U256),
+    /// The decimals of the pool tokens, used for normalizing reserves in the
+    /// stable swap invariant.
+    pub decimals: (u8, u8),
+    pub fee: Ratio<u32>,
+    pub stable: bool,
+}
+
+impl Pool {
+    /// Creates a pool fee from the raw fee in basis points as returned by the
+    /// pool factory.
+    pub fn fee_from_bps(fee: u32) -> Ratio<u32> {
+        Ratio::new(fee, FEE_BASE)
+    }
+
+    /// Returns the reserves and decimals ordered as `(in, out)` for a swap
+    /// selling `token_in`, or `None` if the token doesn't belong to the pool.
+    fn oriented(&self, token_in: H160) -> Option<((U256, U256), (u8, u8))> {
+        let (token0, token1) = self.tokens.get();
+        let (reserve0, reserve1) = self.reserves;
+        let (decimals0, decimals1) = self.decimals;
+        if token_in == token0 {
+            Some(((reserve0, reserve1), (decimals0, decimals1)))
+        } else if token_in == token1 {
+            Some(((reserve1, reserve0), (decimals1, decimals0)))
+        } else {
+            None
+        }
+    }
+
+    /// Computes the amount of `out_token` received for selling `in_amount`,
+    /// mirroring the pool's `getAmountOut`.
+    fn amount_out(&self, in_token: H160, in_amount: U256) -> Option<U256> {
+        let ((reserve_in, reserve_out), decimals) = self.oriented(in_token)?;
+        let fee = in_amount
+            .checked_mul((*self.fee.numer()).into())?
+            .checked_div((*self.fee.denom()).into())?;
+        let in_amount = in_amount.checked_sub(fee)?;
+
+        if self.stable {
+            stable::amount_out(
+                in_amount,
+                reserve_in,
+                reserve_out,
+                decimals,
+                self.token0_in(in_token),
+            )
+        } else {
+            in_amount
+                .checked_mul(reserve_out)?
+                .checked_div(reserve_in.checked_add(in_amount)?)
+        }
+    }
+
+    /// Computes the amount of `in_token` needed for buying `out_amount`.
+    fn amount_in(&self, in_token: H160, out_amount: U256) -> Option<U256> {
+        let ((reserve_in, reserve_out), decimals) = self.oriented(in_token)?;
+        if out_amount >= reserve_out {
+            return None;
+        }
+
+        let in_amount = if self.stable {
+            stable::amount_in(
+                out_amount,
+                reserve_in,
+                reserve_out,
+                decimals,
+                self.token0_in(in_token),
+            )?
+        } else {
+            ceil_div(
+                out_amount.checked_mul(reserve_in)?,
+                reserve_out.checked_sub(out_amount)?,
+            )?
+        };
+
+        let (numer, denom) = (*self.fee.numer(), *self.fee.denom());
+        ceil_div(
+            in_amount.checked_mul(denom.into())?,
+            denom.checked_sub(numer)?.into(),
+        )
+    }
+
+    fn token0_in(&self, in_token: H160) -> bool {
+        in_token == self.tokens.get().0
+    }
+}
+
+fn ceil_div(a: U256, b: U256) -> Option<U256> {
+    if b.is_zero() {
+        return None;
+    }
+    a.checked_add(b - 1)?.checked_div(b)
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        if TokenPair::new(in_token, out_token) != Some(self.tokens) {
+            return None;
+        }
+        self.amount_out(in_token, in_amount)
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        if TokenPair::new(in_token, out_token) != Some(self.tokens) {
+            return None;
+        }
+        let in_amount = self.amount_in(in_token, out_amount)?;
+        // The stable swap invariant is solved iteratively and rounds in favour
+        // of the pool, so make sure the amount is enough.
+        (self.amount_out(in_token, in_amount)? >= out_amount).then_some(in_amount)
+    }
+
+    fn gas_cost(&self) -> usize {
+        if self.stable {
+            STABLE_GAS_COST
+        } else {
+            VOLATILE_GAS_COST
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(stable: bool) -> Pool {
+        Pool {
+            address: H160::from_low_u64_be(42),
+            tokens: TokenPair::new(H160::from_low_u64_be(1), H160::from_low_u64_be(2)).unwrap(),
+            // 1M tokens with 6 and 18 decimals respectively.
+            reserves: (U256::exp10(12), U256::exp10(24)),
+            decimals: (6, 18),
+            fee: Pool::fee_from_bps(5),
+            stable,
+        }
+    }
+
+    #[test]
+    fn volatile_pool_amounts() {
+        let pool = pool(false);
+        let (a, b) = (H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+
+        // 999_500 * 1e24 / (1e12 + 999_500) after the 0.05% fee
+        assert_eq!(
+            pool.get_amount_out(b, (1_000_000.into(), a)).unwrap(),
+            U256::from(999_499_001_000_748_499_u64)
+        );
+
+        let out_amount = U256::exp10(18);
+        let in_amount = pool.get_amount_in(a, (out_amount, b)).unwrap();
+        assert!(pool.get_amount_out(b, (in_amount, a)).unwrap() >= out_amount);
+        assert!(pool.get_amount_out(b, (in_amount - 1, a)).unwrap() < out_amount);
+    }
+
+    #[test]
+    fn stable_pool_amounts() {
+        let pool = pool(true);
+        let (a, b) = (H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+
+        // Balanced stable pools trade close to 1:1 after fees.
+        let out_amount = pool.get_amount_out(b, (U256::exp10(6), a)).unwrap();
+        assert!(out_amount < U256::exp10(18));
+        assert!(out_amount > U256::exp10(18) * 9_990 / 10_000);
+
+        let in_amount = pool.get_amount_in(a, (out_amount, b)).unwrap();
+        assert!(pool.get_amount_out(b, (in_amount, a)).unwrap() >= out_amount);
+        assert!(in_amount <= U256::exp10(6) + 1);
+
+        let in_amount = pool.get_amount_in(b, (U256::exp10(6), a)).unwrap();
+        assert!(pool.get_amount_out(a, (in_amount, b)).unwrap() >= U256::exp10(6));
+    }
+
+    #[test]
+    fn unknown_tokens() {
+        let pool = pool(true);
+        let (a, c) = (H160::from_low_u64_be(1), H160::from_low_u64_be(3));
+        assert_eq!(pool.get_amount_out(c, (1_000.into(), a)), None);
+        assert_eq!(pool.get_amount_in(a, (1_000.into(), c)), None);
+    }
+}
@@ -0,0 +1,257 @@
+//! Fetching of Solidly pool states.
+//!
+//! Pools are deployed by the factory as minimal proxies of a pool
+//! implementation at deterministic addresses, so the addresses of the stable
+//! and volatile pool of a token pair can be computed without any requests.
+
+use {
+    super::Pool,
+    crate::{
+        recent_block_cache::{Block, CacheConfig, CacheFetching, CacheKey, RecentBlockCache},
+        sources::uniswap_v2::{
+            pair_provider::create2_target_address,
+            pool_fetching::handle_contract_error,
+        },
+    },
+    anyhow::{Context, Result},
+    contracts::{ISolidlyPool, ISolidlyPoolFactory},
+    ethcontract::{BlockId, H160, U256},
+    ethrpc::{Web3, block_stream::CurrentBlockWatcher},
+    futures::future,
+    hex_literal::hex,
+    model::TokenPair,
+    std::collections::HashSet,
+    web3::signing::keccak256,
+};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait SolidlyPoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// Computes the addresses of the pools deployed by a Solidly factory.
+#[derive(Clone, Copy, Debug)]
+pub struct PairProvider {
+    pub factory: H160,
+    /// The digest of the minimal proxy init code for the pool implementation.
+    pub init_code_digest: [u8; 32],
+}
+
+impl PairProvider {
+    /// Creates a pair provider for a factory cloning the specified pool
+    /// implementation.
+    pub fn new(factory: H160, implementation: H160) -> Self {
+        // EIP-1167 minimal proxy init code as deployed by OpenZeppelin's
+        // `Clones` library.
+        let mut init_code = [0; 55];
+        init_code[..20].copy_from_slice(&hex!("3d602d80600a3d3981f3363d3d373d3d3d363d73"));
+        init_code[20..40].copy_from_slice(implementation.as_bytes());
+        init_code[40..].copy_from_slice(&hex!("5af43d82803e903d91602b57fd5bf3"));
+        Self {
+            factory,
+            init_code_digest: keccak256(&init_code),
+        }
+    }
+
+    pub fn pool_address(&self, pair: &TokenPair, stable: bool) -> H160 {
+        let (H160(token0), H160(token1)) = pair.get();
+        let salt = {
+            let mut buffer = [0u8; 41];
+            buffer[0..20].copy_from_slice(&token0);
+            buffer[20..40].copy_from_slice(&token1);
+            buffer[40] = stable.into();
+            keccak256(&buffer)
+        };
+        create2_target_address(self.factory, &salt, &self.init_code_digest)
+    }
+}
+
+/// Fetches the stable and volatile pools of a Solidly factory.
+pub struct SolidlyPoolFetcher {
+    pair_provider: PairProvider,
+    cache: RecentBlockCache<H160, Pool, CacheFetcher>,
+}
+
+impl SolidlyPoolFetcher {
+    pub async fn new(
+        web3: Web3,
+        factory: H160,
+        config: CacheConfig,
+        block_stream: CurrentBlockWatcher,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "solidly".into());
+        let factory = ISolidlyPoolFactory::at(&web3, factory);
+        let implementation = factory
+            .implementation()
+            .call()
+            .await
+            .context("pool implementation")?;
+        let pair_provider = PairProvider::new(factory.address(), implementation);
+
+        let cache = RecentBlockCache::new(
+            config,
+            CacheFetcher { web3, factory },
+            block_stream,
+            "solidly",
+        )?;
+        Ok(Self {
+            pair_provider,
+            cache,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl SolidlyPoolFetching for SolidlyPoolFetcher {
+    async fn fetch(&self, token_pairs: HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let addresses = token_pairs.iter().flat_map(|pair| {
+            [false, true].map(|stable| self.pair_provider.pool_address(pair, stable))
+        });
+        self.cache.fetch(addresses, at_block).await
+    }
+}
+
+impl CacheKey<Pool> for H160 {
+    fn first_ord() -> Self {
+        H160::zero()
+    }
+
+    fn for_value(pool: &Pool) -> Self {
+        pool.address
+    }
+}
+
+/// Fetches the state of Solidly pools for the `RecentBlockCache`.
+struct CacheFetcher {
+    web3: Web3,
+    factory: ISolidlyPoolFactory,
+}
+
+impl CacheFetcher {
+    /// Reads the state of the pool at the specified address. Returns `None` if
+    /// the pool doesn't exist or has no liquidity.
+    async fn pool_state(&self, address: H160, block: BlockId) -> Result<Option<Pool>> {
+        let pool = ISolidlyPool::at(&self.web3, address);
+        // Calls to pools that were not deployed yet fail and are skipped.
+        let Some((decimals0, decimals1, reserve0, reserve1, stable, token0, token1)) =
+            handle_contract_error(pool.metadata().block(block).call().await)?
+        else {
+            return Ok(None);
+        };
+        let fee = self
+            .factory
+            .get_fee(address, stable)
+            .block(block)
+            .call()
+            .await?;
+
+        let Some(tokens) = TokenPair::new(token0, token1).filter(|pair| pair.get().0 == token0)
+        else {
+            return Ok(None);
+        };
+        if reserve0.is_zero() || reserve1.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(Pool {
+            address,
+            tokens,
+            reserves: (reserve0, reserve1),
+            decimals: (
+                decimals(decimals0).context("token0 decimals")?,
+                decimals(decimals1).context("token1 decimals")?,
+            ),
+            fee: Pool::fee_from_bps(u32::try_from(fee).ok().context("fee overflows u32")?),
+            stable,
+        }))
+    }
+}
+
+/// Converts a token unit (`10^decimals`) as returned by the pool into the
+/// token decimals.
+fn decimals(unit: U256) -> Option<u8> {
+    (0..=77).find(|&decimals| U256::exp10(decimals.into()) == unit)
+}
+
+#[async_trait::async_trait]
+impl CacheFetching<H160, Pool> for CacheFetcher {
+    async fn fetch_values(&self, addresses: HashSet<H160>, at_block: Block) -> Result<Vec<Pool>> {
+        let block = BlockId::Number(at_block.into());
+        let pools = future::join_all(
+            addresses
+                .iter()
+                .map(|address| self.pool_state(*address, block)),
+        )
+        .await;
+
+        Ok(addresses
+            .iter()
+            .zip(pools)
+            .filter_map(|(address, pool)| match pool {
+                Ok(pool) => pool,
+                Err(err) => {
+                    tracing::debug!(pool = ?address, ?err, "failed to fetch Solidly pool state");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ethrpc::{Web3, create_env_test_transport},
+        contracts::AerodromeRouter,
+    };
+
+    #[test]
+    fn pool_addresses_depend_on_stability() {
+        let provider = PairProvider::new(H160([0x01; 20]), H160([0x02; 20]));
+        let pair = TokenPair::new(H160([0x03; 20]), H160([0x04; 20])).unwrap();
+        assert_ne!(
+            provider.pool_address(&pair, true),
+            provider.pool_address(&pair, false)
+        );
+        assert_ne!(
+            PairProvider::new(H160([0x01; 20]), H160([0x05; 20])).pool_address(&pair, true),
+            provider.pool_address(&pair, true)
+        );
+    }
+
+    #[test]
+    fn converts_token_units() {
+        assert_eq!(decimals(U256::one()), Some(0));
+        assert_eq!(decimals(U256::exp10(6)), Some(6));
+        assert_eq!(decimals(U256::exp10(18)), Some(18));
+        assert_eq!(decimals(U256::from(42)), None);
+        assert_eq!(decimals(U256::zero()), None);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn computes_aerodrome_pool_addresses() {
+        let web3 = Web3::new(create_env_test_transport());
+        let router = AerodromeRouter::deployed(&web3).await.unwrap();
+        let factory =
+            ISolidlyPoolFactory::at(&web3, router.default_factory().call().await.unwrap());
+        let provider = PairProvider::new(
+            factory.address(),
+            factory.implementation().call().await.unwrap(),
+        );
+
+        // WETH and USDC on Base
+        let (weth, usdc) = (
+            addr!("4200000000000000000000000000000000000006"),
+            addr!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"),
+        );
+        let pair = TokenPair::new(weth, usdc).unwrap();
+        for stable in [false, true] {
+            assert_eq!(
+                provider.pool_address(&pair, stable),
+                factory.get_pool(weth, usdc, stable).call().await.unwrap(),
+            );
+        }
+    }
+}
@@ -2,3 +2,4 @@
 pub mod event_fetching;
 pub mod graph_api;
 pub mod pool_fetching;
+pub mod swap;
@@ -12,7 +12,7 @@ use {
     ethcontract::{Event, H160, U256},
     ethrpc::{
         Web3,
-        block_stream::{BlockRetrieving, RangeInclusive},
+        block_stream::{BlockRetrieving, RangeInclusive, Reorg},
     },
     itertools::{Either, Itertools},
     model::TokenPair,
@@ -490,6 +490,10 @@ impl Maintaining for UniswapV3PoolFetcher {
     fn name(&self) -> &str {
         "UniswapV3PoolFetcher"
     }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        self.events.handle_reorg(reorg).await
+    }
 }
 
 #[cfg(test)]
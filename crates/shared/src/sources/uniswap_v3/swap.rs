@@ -0,0 +1,709 @@
+//! Off-chain implementation of the Uniswap V3 swap math.
+//!
+//! This is a port of the relevant parts of the `TickMath`, `SqrtPriceMath`
+//! and `SwapMath` libraries as well as the swap loop of `UniswapV3Pool`, so
+//! that input and output amounts computed here match the contracts exactly:
+//! <https://github.com/Uniswap/v3-core/tree/main/contracts>
+
+use {
+    crate::baseline_solver::BaselineSolvable,
+    ethcontract::{H160, U256},
+    model::TokenPair,
+    primitive_types::U512,
+    std::{collections::BTreeMap, ops::Bound},
+};
+
+/// The minimum tick that may be passed to `get_sqrt_ratio_at_tick`.
+pub const MIN_TICK: i32 = -887272;
+/// The maximum tick that may be passed to `get_sqrt_ratio_at_tick`.
+pub const MAX_TICK: i32 = -MIN_TICK;
+
+/// Approximate gas cost of a single Uniswap V3 swap that does not cross any
+/// ticks, as estimated by <https://dune.com/queries/1044812>.
+const POOL_SWAP_GAS_COST: usize = 108_163;
+
+/// Fee denominator used by Uniswap V3 pools, fees are expressed in
+/// "hundredths of a bip".
+const FEE_DENOMINATOR: u32 = 1_000_000;
+
+/// Uniswap V3 pool state required for computing swap amounts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub address: H160,
+    pub tokens: TokenPair,
+    /// The current `sqrt(token1/token0)` price as a Q64.96 value.
+    pub sqrt_price: U256,
+    /// The currently in-range liquidity.
+    pub liquidity: u128,
+    /// The current tick.
+    pub tick: i32,
+    /// The liquidity added (or removed when negative) when crossing each
+    /// initialized tick from left to right.
+    pub liquidity_net: BTreeMap<i32, i128>,
+    /// The pool fee in hundredths of a bip (i.e. `3000` for a 0.3% pool).
+    pub fee: u32,
+}
+
+/// The amount that is specified for a swap.
+#[derive(Clone, Copy, Debug)]
+enum Specified {
+    ExactInput(U256),
+    ExactOutput(U256),
+}
+
+/// The result of simulating a swap through a pool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct SwapResult {
+    amount_in: U256,
+    amount_out: U256,
+    sqrt_price: U256,
+    liquidity: u128,
+    tick: i32,
+}
+
+impl Pool {
+    /// Simulates a swap through the pool, returning `None` if the pool does
+    /// not hold enough liquidity to fully execute the swap or if any of the
+    /// intermediate computations overflow.
+    fn swap(&self, zero_for_one: bool, specified: Specified) -> Option<SwapResult> {
+        if self.fee >= FEE_DENOMINATOR {
+            return None;
+        }
+
+        let sqrt_price_limit = if zero_for_one {
+            min_sqrt_ratio() + 1
+        } else {
+            max_sqrt_ratio() - 1
+        };
+        if (zero_for_one && self.sqrt_price <= sqrt_price_limit)
+            || (!zero_for_one && self.sqrt_price >= sqrt_price_limit)
+        {
+            return None;
+        }
+
+        let (mut amount_remaining, exact_input) = match specified {
+            Specified::ExactInput(amount) => (amount, true),
+            Specified::ExactOutput(amount) => (amount, false),
+        };
+        let mut sqrt_price = self.sqrt_price;
+        let mut tick = self.tick;
+        let mut liquidity = self.liquidity;
+        let mut amount_in = U256::zero();
+        let mut amount_out = U256::zero();
+
+        while !amount_remaining.is_zero() && sqrt_price != sqrt_price_limit {
+            let (tick_next, liquidity_net) = self.next_initialized_tick(tick, zero_for_one);
+            let sqrt_price_next = get_sqrt_ratio_at_tick(tick_next)?;
+            let sqrt_price_target = if zero_for_one {
+                sqrt_price_next.max(sqrt_price_limit)
+            } else {
+                sqrt_price_next.min(sqrt_price_limit)
+            };
+
+            let step_sqrt_price_start = sqrt_price;
+            let step = compute_swap_step(
+                sqrt_price,
+                sqrt_price_target,
+                liquidity,
+                amount_remaining,
+                exact_input,
+                self.fee,
+            )?;
+            sqrt_price = step.sqrt_price_next;
+
+            if exact_input {
+                amount_remaining =
+                    amount_remaining.checked_sub(step.amount_in.checked_add(step.fee_amount)?)?;
+            } else {
+                amount_remaining = amount_remaining.checked_sub(step.amount_out)?;
+            }
+            amount_in = amount_in
+                .checked_add(step.amount_in)?
+                .checked_add(step.fee_amount)?;
+            amount_out = amount_out.checked_add(step.amount_out)?;
+
+            if sqrt_price == sqrt_price_next {
+                if let Some(liquidity_net) = liquidity_net {
+                    let liquidity_net = if zero_for_one {
+                        liquidity_net.checked_neg()?
+                    } else {
+                        liquidity_net
+                    };
+                    liquidity = add_liquidity_delta(liquidity, liquidity_net)?;
+                }
+                tick = if zero_for_one {
+                    tick_next - 1
+                } else {
+                    tick_next
+                };
+            } else if sqrt_price != step_sqrt_price_start {
+                tick = get_tick_at_sqrt_ratio(sqrt_price)?;
+            }
+        }
+
+        if !amount_remaining.is_zero() {
+            return None;
+        }
+        Some(SwapResult {
+            amount_in,
+            amount_out,
+            sqrt_price,
+            liquidity,
+            tick,
+        })
+    }
+
+    /// Returns the next tick to swap towards along with its liquidity net if
+    /// it is initialized. This is the equivalent of the tick bitmap lookup
+    /// done by the pool contract.
+    fn next_initialized_tick(&self, tick: i32, lte: bool) -> (i32, Option<i128>) {
+        let next = if lte {
+            self.liquidity_net
+                .range((Bound::Unbounded, Bound::Included(tick)))
+                .next_back()
+        } else {
+            self.liquidity_net
+                .range((Bound::Excluded(tick), Bound::Unbounded))
+                .next()
+        };
+        match next {
+            Some((tick, liquidity_net)) => {
+                ((*tick).clamp(MIN_TICK, MAX_TICK), Some(*liquidity_net))
+            }
+            None if lte => (MIN_TICK, None),
+            None => (MAX_TICK, None),
+        }
+    }
+
+    /// Updates the pool state to reflect that `in_amount` of `in_token` was
+    /// swapped for `out_token`. Returns `None` if the swap is not possible, in
+    /// which case the pool state is left unchanged.
+    pub fn apply_swap(
+        &mut self,
+        out_token: H160,
+        (in_amount, in_token): (U256, H160),
+    ) -> Option<()> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        let result = self.swap(zero_for_one, Specified::ExactInput(in_amount))?;
+        self.sqrt_price = result.sqrt_price;
+        self.liquidity = result.liquidity;
+        self.tick = result.tick;
+        Some(())
+    }
+
+    fn zero_for_one(&self, in_token: H160, out_token: H160) -> Option<bool> {
+        let (token0, token1) = self.tokens.get();
+        match (in_token, out_token) {
+            (a, b) if a == token0 && b == token1 => Some(true),
+            (a, b) if a == token1 && b == token0 => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl BaselineSolvable for Pool {
+    fn get_amount_out(&self, out_token: H160, (in_amount, in_token): (U256, H160)) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        let result = self.swap(zero_for_one, Specified::ExactInput(in_amount))?;
+        Some(result.amount_out)
+    }
+
+    fn get_amount_in(&self, in_token: H160, (out_amount, out_token): (U256, H160)) -> Option<U256> {
+        let zero_for_one = self.zero_for_one(in_token, out_token)?;
+        let result = self.swap(zero_for_one, Specified::ExactOutput(out_amount))?;
+        Some(result.amount_in)
+    }
+
+    fn gas_cost(&self) -> usize {
+        POOL_SWAP_GAS_COST
+    }
+}
+
+/// The result of a single step of the swap loop.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct SwapStep {
+    sqrt_price_next: U256,
+    amount_in: U256,
+    amount_out: U256,
+    fee_amount: U256,
+}
+
+/// Computes the result of swapping some amount in or out, given the
+/// parameters of the swap. Port of `SwapMath.computeSwapStep`.
+fn compute_swap_step(
+    sqrt_price_current: U256,
+    sqrt_price_target: U256,
+    liquidity: u128,
+    amount_remaining: U256,
+    exact_input: bool,
+    fee_pips: u32,
+) -> Option<SwapStep> {
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+    let fee_pips = U256::from(fee_pips);
+    let fee_complement = U256::from(FEE_DENOMINATOR) - fee_pips;
+
+    let mut amount_in = U256::zero();
+    let mut amount_out = U256::zero();
+    let sqrt_price_next = if exact_input {
+        let amount_remaining_less_fee =
+            mul_div(amount_remaining, fee_complement, FEE_DENOMINATOR.into())?;
+        amount_in = if zero_for_one {
+            get_amount0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?
+        } else {
+            get_amount1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?
+        };
+        if amount_remaining_less_fee >= amount_in {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        }
+    } else {
+        amount_out = if zero_for_one {
+            get_amount1_delta(sqrt_price_target, sqrt_price_current, liquidity, false)?
+        } else {
+            get_amount0_delta(sqrt_price_current, sqrt_price_target, liquidity, false)?
+        };
+        if amount_remaining >= amount_out {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_output(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?
+        }
+    };
+
+    let max = sqrt_price_target == sqrt_price_next;
+    if zero_for_one {
+        if !(max && exact_input) {
+            amount_in = get_amount0_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?;
+        }
+        if !(max && !exact_input) {
+            amount_out = get_amount1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?;
+        }
+    } else {
+        if !(max && exact_input) {
+            amount_in = get_amount1_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?;
+        }
+        if !(max && !exact_input) {
+            amount_out = get_amount0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?;
+        }
+    }
+
+    // Cap the output amount to not exceed the remaining output amount.
+    if !exact_input && amount_out > amount_remaining {
+        amount_out = amount_remaining;
+    }
+
+    let fee_amount = if exact_input && sqrt_price_next != sqrt_price_target {
+        // We didn't reach the target, so take the remainder of the maximum
+        // input as fee.
+        amount_remaining.checked_sub(amount_in)?
+    } else {
+        mul_div_rounding_up(amount_in, fee_pips, fee_complement)?
+    };
+
+    Some(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+/// Calculates `sqrt(1.0001^tick) * 2^96`. Port of
+/// `TickMath.getSqrtRatioAtTick`.
+///
+/// Returns `None` if the tick is outside of the `[MIN_TICK, MAX_TICK]` range.
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> Option<U256> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return None;
+    }
+    let abs_tick = tick.unsigned_abs();
+
+    const FACTORS: [(u32, &str); 19] = [
+        (0x2, "fff97272373d413259a46990580e213a"),
+        (0x4, "fff2e50f5f656932ef12357cf3c7fdcc"),
+        (0x8, "ffe5caca7e10e4e61c3624eaa0941cd0"),
+        (0x10, "ffcb9843d60f6159c9db58835c926644"),
+        (0x20, "ff973b41fa98c081472e6896dfb254c0"),
+        (0x40, "ff2ea16466c96a3843ec78b326b52861"),
+        (0x80, "fe5dee046a99a2a811c461f1969c3053"),
+        (0x100, "fcbe86c7900a88aedcffc83b479aa3a4"),
+        (0x200, "f987a7253ac413176f2b074cf7815e54"),
+        (0x400, "f3392b0822b70005940c7a398e4b70f3"),
+        (0x800, "e7159475a2c29b7443b29c7fa6e889d9"),
+        (0x1000, "d097f3bdfd2022b8845ad8f792aa5825"),
+        (0x2000, "a9f746462d870fdf8a65dc1f90e061e5"),
+        (0x4000, "70d869a156d2a1b890bb3df62baf32f7"),
+        (0x8000, "31be135f97d08fd981231505542fcfa6"),
+        (0x10000, "9aa508b5b7a84e1c677de54f3e99bc9"),
+        (0x20000, "5d6af8dedb81196699c329225ee604"),
+        (0x40000, "2216e584f5fa1ea926041bedfe98"),
+        (0x80000, "48a170391f7dc42444e8fa2"),
+    ];
+
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from_str_radix("fffcb933bd6fad37aa2d162d1a594001", 16).unwrap()
+    } else {
+        U256::one() << 128
+    };
+    for (mask, factor) in FACTORS {
+        if abs_tick & mask != 0 {
+            ratio = (ratio * U256::from_str_radix(factor, 16).unwrap()) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    // Divide by 1<<32 rounding up to go from a Q128.128 to a Q128.96.
+    let round_up = !(ratio & U256::from(u32::MAX)).is_zero();
+    Some((ratio >> 32) + U256::from(u8::from(round_up)))
+}
+
+/// Calculates the greatest tick value such that `get_sqrt_ratio_at_tick(tick)
+/// <= sqrt_price`. Equivalent to `TickMath.getTickAtSqrtRatio`.
+///
+/// Returns `None` if the price is outside of the range of valid prices.
+pub fn get_tick_at_sqrt_ratio(sqrt_price: U256) -> Option<i32> {
+    if sqrt_price < min_sqrt_ratio() || sqrt_price >= max_sqrt_ratio() {
+        return None;
+    }
+
+    // Binary search over the tick range, maintaining the invariant that
+    // `ratio(lo) <= sqrt_price < ratio(hi)`.
+    let (mut lo, mut hi) = (MIN_TICK, MAX_TICK);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if get_sqrt_ratio_at_tick(mid)? <= sqrt_price {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(lo)
+}
+
+/// The minimum value that can be returned from `get_sqrt_ratio_at_tick`.
+fn min_sqrt_ratio() -> U256 {
+    U256::from(4295128739_u64)
+}
+
+/// The maximum value that can be returned from `get_sqrt_ratio_at_tick`.
+fn max_sqrt_ratio() -> U256 {
+    U256::from_dec_str("1461446703485210103287273052203988822378723970342").unwrap()
+}
+
+/// Gets the amount of token0 between two prices. Port of
+/// `SqrtPriceMath.getAmount0Delta`.
+fn get_amount0_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (a, b) = if a > b { (b, a) } else { (a, b) };
+    if a.is_zero() {
+        return None;
+    }
+
+    let numerator1 = U256::from(liquidity) << 96;
+    let numerator2 = b - a;
+    if round_up {
+        div_rounding_up(mul_div_rounding_up(numerator1, numerator2, b)?, a)
+    } else {
+        Some(mul_div(numerator1, numerator2, b)? / a)
+    }
+}
+
+/// Gets the amount of token1 between two prices. Port of
+/// `SqrtPriceMath.getAmount1Delta`.
+fn get_amount1_delta(a: U256, b: U256, liquidity: u128, round_up: bool) -> Option<U256> {
+    let (a, b) = if a > b { (b, a) } else { (a, b) };
+    if round_up {
+        mul_div_rounding_up(liquidity.into(), b - a, q96())
+    } else {
+        mul_div(liquidity.into(), b - a, q96())
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromInput`.
+fn get_next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if sqrt_price.is_zero() || liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromOutput`.
+fn get_next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Option<U256> {
+    if sqrt_price.is_zero() || liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount0RoundingUp`.
+fn get_next_sqrt_price_from_amount0_rounding_up(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if amount.is_zero() {
+        return Some(sqrt_price);
+    }
+    let numerator1 = U256::from(liquidity) << 96;
+    let product = amount.checked_mul(sqrt_price);
+
+    let next = if add {
+        match product.and_then(|product| numerator1.checked_add(product)) {
+            Some(denominator) => mul_div_rounding_up(numerator1, sqrt_price, denominator)?,
+            None => div_rounding_up(numerator1, (numerator1 / sqrt_price).checked_add(amount)?)?,
+        }
+    } else {
+        let product = product?;
+        let denominator = numerator1.checked_sub(product).filter(|d| !d.is_zero())?;
+        mul_div_rounding_up(numerator1, sqrt_price, denominator)?
+    };
+    to_uint160(next)
+}
+
+/// Port of `SqrtPriceMath.getNextSqrtPriceFromAmount1RoundingDown`.
+fn get_next_sqrt_price_from_amount1_rounding_down(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Option<U256> {
+    if add {
+        let quotient = mul_div(amount, q96(), liquidity.into())?;
+        to_uint160(sqrt_price.checked_add(quotient)?)
+    } else {
+        let quotient = mul_div_rounding_up(amount, q96(), liquidity.into())?;
+        sqrt_price
+            .checked_sub(quotient)
+            .filter(|next| !next.is_zero())
+    }
+}
+
+/// Adds a signed liquidity delta to liquidity, returning `None` on overflow
+/// or underflow.
+fn add_liquidity_delta(liquidity: u128, delta: i128) -> Option<u128> {
+    if delta < 0 {
+        liquidity.checked_sub(delta.unsigned_abs())
+    } else {
+        liquidity.checked_add(delta.unsigned_abs())
+    }
+}
+
+fn q96() -> U256 {
+    U256::one() << 96
+}
+
+fn to_uint160(value: U256) -> Option<U256> {
+    (value.bits() <= 160).then_some(value)
+}
+
+/// Computes `a * b / denominator` with full precision, rounding down.
+fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    (a.full_mul(b) / U512::from(denominator)).try_into().ok()
+}
+
+/// Computes `a * b / denominator` with full precision, rounding up.
+fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let (quotient, remainder) = a.full_mul(b).div_mod(U512::from(denominator));
+    let quotient = U256::try_from(quotient).ok()?;
+    if remainder.is_zero() {
+        Some(quotient)
+    } else {
+        quotient.checked_add(U256::one())
+    }
+}
+
+fn div_rounding_up(a: U256, b: U256) -> Option<U256> {
+    if b.is_zero() {
+        return None;
+    }
+    let (quotient, remainder) = a.div_mod(b);
+    Some(quotient + U256::from(u8::from(!remainder.is_zero())))
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, maplit::btreemap};
+
+    fn pool(liquidity_net: BTreeMap<i32, i128>) -> Pool {
+        Pool {
+            address: H160([0x33; 20]),
+            tokens: TokenPair::new(H160([0x11; 20]), H160([0x22; 20])).unwrap(),
+            sqrt_price: get_sqrt_ratio_at_tick(0).unwrap(),
+            liquidity: 1_000_000_000_000_000_000,
+            tick: 0,
+            liquidity_net,
+            fee: 3000,
+        }
+    }
+
+    #[test]
+    fn sqrt_ratio_at_tick() {
+        assert_eq!(get_sqrt_ratio_at_tick(MIN_TICK).unwrap(), min_sqrt_ratio());
+        assert_eq!(get_sqrt_ratio_at_tick(MAX_TICK).unwrap(), max_sqrt_ratio());
+        assert_eq!(get_sqrt_ratio_at_tick(0).unwrap(), q96());
+        assert_eq!(
+            get_sqrt_ratio_at_tick(1).unwrap(),
+            U256::from_dec_str("79232123823359799118286999568").unwrap()
+        );
+        assert_eq!(
+            get_sqrt_ratio_at_tick(-1).unwrap(),
+            U256::from_dec_str("79224201403219477170569942574").unwrap()
+        );
+        assert!(get_sqrt_ratio_at_tick(MIN_TICK - 1).is_none());
+        assert!(get_sqrt_ratio_at_tick(MAX_TICK + 1).is_none());
+    }
+
+    #[test]
+    fn tick_at_sqrt_ratio() {
+        for tick in [
+            MIN_TICK,
+            -100_000,
+            -61,
+            -60,
+            -1,
+            0,
+            1,
+            60,
+            100_000,
+            MAX_TICK - 1,
+        ] {
+            let sqrt_price = get_sqrt_ratio_at_tick(tick).unwrap();
+            assert_eq!(get_tick_at_sqrt_ratio(sqrt_price).unwrap(), tick);
+            if tick > MIN_TICK {
+                assert_eq!(get_tick_at_sqrt_ratio(sqrt_price - 1).unwrap(), tick - 1);
+            }
+        }
+        assert!(get_tick_at_sqrt_ratio(min_sqrt_ratio() - 1).is_none());
+        assert!(get_tick_at_sqrt_ratio(max_sqrt_ratio()).is_none());
+    }
+
+    #[test]
+    fn swap_within_single_tick_range() {
+        let pool = pool(btreemap! {
+            -600 => 1_000_000_000_000_000_000,
+            600 => -1_000_000_000_000_000_000,
+        });
+        let (token0, token1) = pool.tokens.get();
+
+        let amount_in = U256::exp10(15);
+        let amount_out = pool.get_amount_out(token1, (amount_in, token0)).unwrap();
+        assert_eq!(amount_out, U256::from(996006981039903_u64));
+        assert_eq!(
+            pool.get_amount_in(token0, (amount_out, token1)).unwrap(),
+            amount_in
+        );
+    }
+
+    #[test]
+    fn swap_crossing_ticks() {
+        // The pool has twice the liquidity in the `[-120, -60)` range.
+        let pool = pool(btreemap! {
+            -120 => 2_000_000_000_000_000_000,
+            -60 => -1_000_000_000_000_000_000,
+            60 => -1_000_000_000_000_000_000,
+        });
+        let (token0, token1) = pool.tokens.get();
+
+        let amount_in = U256::from(5_000_000_000_000_000_u64);
+        let amount_out = pool.get_amount_out(token1, (amount_in, token0)).unwrap();
+        assert_eq!(amount_out, U256::from(4962211200082182_u64));
+        assert_eq!(
+            pool.get_amount_in(token0, (amount_out, token1)).unwrap(),
+            amount_in
+        );
+    }
+
+    #[test]
+    fn apply_swap_updates_state() {
+        let mut pool = pool(btreemap! {
+            -120 => 2_000_000_000_000_000_000,
+            -60 => -1_000_000_000_000_000_000,
+            60 => -1_000_000_000_000_000_000,
+        });
+        let (token0, token1) = pool.tokens.get();
+        let amount_in = U256::from(2_500_000_000_000_000_u64);
+        let total_out = pool
+            .get_amount_out(token1, (amount_in * 2, token0))
+            .unwrap();
+
+        let first_out = pool.get_amount_out(token1, (amount_in, token0)).unwrap();
+        pool.apply_swap(token1, (amount_in, token0)).unwrap();
+        assert_eq!(pool.tick, get_tick_at_sqrt_ratio(pool.sqrt_price).unwrap());
+        assert!(pool.tick > -60);
+
+        let second_out = pool.get_amount_out(token1, (amount_in, token0)).unwrap();
+        pool.apply_swap(token1, (amount_in, token0)).unwrap();
+        assert_eq!(pool.tick, get_tick_at_sqrt_ratio(pool.sqrt_price).unwrap());
+        assert!(pool.tick < -60);
+        assert_eq!(pool.liquidity, 2_000_000_000_000_000_000);
+
+        // Swapping in two parts yields the same amount as swapping everything
+        // at once, up to rounding.
+        assert!(second_out < first_out);
+        assert!(first_out + second_out <= total_out);
+        assert!(total_out - (first_out + second_out) <= U256::from(2));
+    }
+
+    #[test]
+    fn swap_exceeding_liquidity() {
+        let pool = pool(btreemap! {
+            -60 => 1_000_000_000_000_000_000,
+            60 => -1_000_000_000_000_000_000,
+        });
+        let (token0, token1) = pool.tokens.get();
+
+        assert!(
+            pool.get_amount_out(token1, (U256::exp10(18), token0))
+                .is_none()
+        );
+        assert!(
+            pool.get_amount_in(token1, (U256::exp10(18), token0))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn swap_unknown_tokens() {
+        let pool = pool(BTreeMap::new());
+        assert!(
+            pool.get_amount_out(H160([0x11; 20]), (U256::exp10(18), H160([0x44; 20])))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Indexing of Uniswap V4 pools from `PoolManager` events.
+
+use {
+    super::pool_fetching::Pool,
+    crate::{
+        event_handling::{EventStoring, MAX_REORG_BLOCK_COUNT},
+        impl_event_retrieving,
+    },
+    anyhow::{Context, Result, ensure},
+    contracts::uniswap_v4_pool_manager::Event as PoolManagerEvent,
+    ethcontract::{Event, H256},
+    ethrpc::block_stream::RangeInclusive,
+    model::TokenPair,
+    std::collections::{BTreeMap, HashMap, HashSet},
+};
+
+impl_event_retrieving! {
+    pub UniswapV4PoolManagerContract for contracts::uniswap_v4_pool_manager
+}
+
+/// In-memory storage of the Uniswap V4 pools indexed from `PoolManager`
+/// events.
+///
+/// Events are applied to a checkpoint of the pool states once they are older
+/// than [`MAX_REORG_BLOCK_COUNT`] blocks. More recent events are kept around so
+/// that they can be replaced on reorgs, and are applied on top of the
+/// checkpoint when fetching pools.
+#[derive(Debug, Default)]
+pub struct PoolStore {
+    /// The pool states at the checkpoint block.
+    pools: HashMap<H256, Pool>,
+    pools_by_token_pair: HashMap<TokenPair, HashSet<H256>>,
+    checkpoint: u64,
+    /// Events after the checkpoint block keyed by block number and log index.
+    events: BTreeMap<(u64, usize), Event<PoolManagerEvent>>,
+    last_indexed_block: u64,
+}
+
+impl PoolStore {
+    /// Returns the state of the pools for the specified token pairs at the
+    /// specified block.
+    pub fn pools(&self, token_pairs: &HashSet<TokenPair>, block: u64) -> Vec<Pool> {
+        let mut pools = token_pairs
+            .iter()
+            .filter_map(|pair| self.pools_by_token_pair.get(pair))
+            .flatten()
+            .map(|id| (*id, self.pools[id].clone()))
+            .collect::<HashMap<_, _>>();
+
+        for (_, event) in self.events.range(..=(block, usize::MAX)) {
+            match &event.data {
+                PoolManagerEvent::Initialize(initialize) => {
+                    if let Some(pool) = Pool::initialize(initialize)
+                        && token_pairs.contains(&pool.tokens)
+                    {
+                        pools.insert(pool.id, pool);
+                    }
+                }
+                event => {
+                    if let Some(pool) = pools.get_mut(&pool_id(event)) {
+                        pool.apply(event);
+                    }
+                }
+            }
+        }
+
+        pools.into_values().collect()
+    }
+
+    /// Applies all events up to and including the specified block to the
+    /// checkpoint.
+    fn move_checkpoint(&mut self, block: u64) {
+        if block <= self.checkpoint {
+            return;
+        }
+
+        let recent = self.events.split_off(&(block + 1, 0));
+        for (_, event) in std::mem::replace(&mut self.events, recent) {
+            match &event.data {
+                PoolManagerEvent::Initialize(initialize) => {
+                    if let Some(pool) = Pool::initialize(initialize) {
+                        self.pools_by_token_pair
+                            .entry(pool.tokens)
+                            .or_default()
+                            .insert(pool.id);
+                        self.pools.insert(pool.id, pool);
+                    }
+                }
+                event => {
+                    if let Some(pool) = self.pools.get_mut(&pool_id(event)) {
+                        pool.apply(event);
+                    }
+                }
+            }
+        }
+        self.checkpoint = block;
+    }
+}
+
+/// Returns the ID of the pool the event was emitted for.
+fn pool_id(event: &PoolManagerEvent) -> H256 {
+    match event {
+        PoolManagerEvent::Initialize(initialize) => H256(initialize.id.0),
+        PoolManagerEvent::ModifyLiquidity(modify) => H256(modify.id.0),
+        PoolManagerEvent::Swap(swap) => H256(swap.id.0),
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<PoolManagerEvent> for PoolStore {
+    async fn replace_events(
+        &mut self,
+        events: Vec<Event<PoolManagerEvent>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        ensure!(
+            *range.start() > self.checkpoint,
+            "can't replace events of blocks up to the checkpoint block {}",
+            self.checkpoint,
+        );
+        self.events.split_off(&(*range.start(), 0));
+        self.append_events(events).await
+    }
+
+    async fn append_events(&mut self, events: Vec<Event<PoolManagerEvent>>) -> Result<()> {
+        for event in events {
+            let meta = event.meta.as_ref().context("event meta is empty")?;
+            self.events
+                .insert((meta.block_number, meta.log_index), event);
+        }
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        let last_event_block = self
+            .events
+            .keys()
+            .last()
+            .map(|(block_number, _)| *block_number)
+            .unwrap_or_default();
+        Ok(last_event_block
+            .max(self.checkpoint)
+            .max(self.last_indexed_block))
+    }
+
+    async fn persist_last_indexed_block(&mut self, block: u64) -> Result<()> {
+        self.last_indexed_block = block;
+        self.move_checkpoint(block.saturating_sub(MAX_REORG_BLOCK_COUNT));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        contracts::uniswap_v4_pool_manager::event_data::{Initialize, ModifyLiquidity, Swap},
+        ethcontract::{Bytes, EventMetadata, H160, I256},
+    };
+
+    const ID: [u8; 32] = [0x42; 32];
+
+    fn event(block_number: u64, data: PoolManagerEvent) -> Event<PoolManagerEvent> {
+        Event {
+            data,
+            meta: Some(EventMetadata {
+                block_number,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn initialize(block_number: u64) -> Event<PoolManagerEvent> {
+        event(
+            block_number,
+            PoolManagerEvent::Initialize(Initialize {
+                id: Bytes(ID),
+                currency0: H160([1; 20]),
+                currency1: H160([2; 20]),
+                fee: 500,
+                tick_spacing: 10,
+                sqrt_price_x96: 1.into(),
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn modify_liquidity(block_number: u64, liquidity_delta: i64) -> Event<PoolManagerEvent> {
+        event(
+            block_number,
+            PoolManagerEvent::ModifyLiquidity(ModifyLiquidity {
+                id: Bytes(ID),
+                tick_lower: -10,
+                tick_upper: 10,
+                liquidity_delta: I256::from(liquidity_delta),
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn swap(block_number: u64, tick: i32) -> Event<PoolManagerEvent> {
+        event(
+            block_number,
+            PoolManagerEvent::Swap(Swap {
+                id: Bytes(ID),
+                tick,
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn token_pairs() -> HashSet<TokenPair> {
+        HashSet::from([TokenPair::new(H160([1; 20]), H160([2; 20])).unwrap()])
+    }
+
+    #[tokio::test]
+    async fn applies_events_up_to_block() {
+        let mut store = PoolStore::default();
+        store
+            .append_events(vec![
+                initialize(1),
+                modify_liquidity(2, 100),
+                modify_liquidity(3, 50),
+            ])
+            .await
+            .unwrap();
+
+        assert!(store.pools(&token_pairs(), 0).is_empty());
+        assert_eq!(
+            store.pools(&token_pairs(), 2)[0].state.liquidity,
+            100.into()
+        );
+        assert_eq!(
+            store.pools(&token_pairs(), 3)[0].state.liquidity,
+            150.into()
+        );
+        assert!(store.pools(&Default::default(), 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn moves_checkpoint_with_indexed_blocks() {
+        let mut store = PoolStore::default();
+        store
+            .append_events(vec![initialize(1), modify_liquidity(2, 100), swap(100, 20)])
+            .await
+            .unwrap();
+        store.persist_last_indexed_block(100).await.unwrap();
+
+        assert_eq!(store.checkpoint, 100 - MAX_REORG_BLOCK_COUNT);
+        assert_eq!(store.events.len(), 1);
+        assert_eq!(store.pools[&H256(ID)].state.liquidity, 100.into());
+
+        // Events since the checkpoint are still applied.
+        let pools = store.pools(&token_pairs(), 100);
+        assert_eq!(pools[0].state.tick, 20.into());
+        assert_eq!(store.last_event_block().await.unwrap(), 100);
+    }
+
+    #[tokio::test]
+    async fn replaces_events_after_checkpoint() {
+        let mut store = PoolStore::default();
+        store
+            .append_events(vec![initialize(1), modify_liquidity(100, 100)])
+            .await
+            .unwrap();
+        store.persist_last_indexed_block(100).await.unwrap();
+
+        store
+            .replace_events(
+                vec![modify_liquidity(100, 50)],
+                RangeInclusive::try_new(99, 100).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            store.pools(&token_pairs(), 100)[0].state.liquidity,
+            50.into()
+        );
+
+        assert!(
+            store
+                .replace_events(vec![], RangeInclusive::try_new(1, 100).unwrap())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! Uniswap V4 baseline liquidity source implementation.
+//!
+//! Uniswap V4 keeps all pools in a single `PoolManager` contract, so pools are
+//! indexed from its events instead of being fetched individually. Pools may be
+//! configured with hooks [^1] that run custom logic around swaps; only pools
+//! whose hooks can't change swap behaviour are supported, which allows them to
+//! be treated as Uniswap V3 concentrated liquidity.
+//!
+//! [^1]: <https://docs.uniswap.org/contracts/v4/concepts/hooks>
+
+pub mod event_fetching;
+pub mod pool_fetching;
+
+use ethcontract::H160;
+
+/// The fee marking a pool whose LP fee is controlled by its hooks.
+pub const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+
+/// The hook permissions are encoded in the lowest bits of the hook contract
+/// address.
+///
+/// <https://github.com/Uniswap/v4-core/blob/main/src/libraries/Hooks.sol>
+mod hook_flags {
+    pub const BEFORE_SWAP: u16 = 1 << 7;
+    pub const AFTER_SWAP: u16 = 1 << 6;
+    pub const BEFORE_SWAP_RETURNS_DELTA: u16 = 1 << 3;
+    pub const AFTER_SWAP_RETURNS_DELTA: u16 = 1 << 2;
+
+    /// The permissions of hooks that get called on swaps.
+    pub const SWAP: u16 =
+        BEFORE_SWAP | AFTER_SWAP | BEFORE_SWAP_RETURNS_DELTA | AFTER_SWAP_RETURNS_DELTA;
+}
+
+/// The key identifying a Uniswap V4 pool.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PoolKey {
+    pub currency0: H160,
+    pub currency1: H160,
+    /// The LP fee in hundredths of a bip, or [`DYNAMIC_FEE_FLAG`].
+    pub fee: u32,
+    pub tick_spacing: i32,
+    pub hooks: H160,
+}
+
+impl PoolKey {
+    /// Returns whether swaps on the pool can be computed and encoded like swaps
+    /// on a hookless pool.
+    ///
+    /// This excludes pools with hooks that get called on swaps, since they can
+    /// change swap amounts or make swaps revert, pools with dynamic fees, and
+    /// pools trading the native token, as settlements only deal with ERC20
+    /// tokens.
+    pub fn is_supported(&self) -> bool {
+        let permissions = u16::from_be_bytes([self.hooks.0[18], self.hooks.0[19]]);
+        !self.currency0.is_zero()
+            && self.fee != DYNAMIC_FEE_FLAG
+            && permissions & hook_flags::SWAP == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn supported_pools() {
+        let key = PoolKey {
+            currency0: H160([1; 20]),
+            currency1: H160([2; 20]),
+            fee: 500,
+            tick_spacing: 10,
+            hooks: H160::zero(),
+        };
+        assert!(key.is_supported());
+
+        // Hooks that only get called when modifying liquidity don't affect
+        // swaps.
+        let key = PoolKey {
+            hooks: H160(hex!("0000000000000000000000000000000000000f00")),
+            ..key
+        };
+        assert!(key.is_supported());
+
+        for hooks in [
+            hex!("0000000000000000000000000000000000000080"),
+            hex!("0000000000000000000000000000000000000040"),
+            hex!("0000000000000000000000000000000000000008"),
+            hex!("0000000000000000000000000000000000000004"),
+            hex!("00000000000000000000000000000000000020c0"),
+        ] {
+            let key = PoolKey {
+                hooks: H160(hooks),
+                ..key
+            };
+            assert!(!key.is_supported());
+        }
+
+        let key = PoolKey {
+            fee: DYNAMIC_FEE_FLAG,
+            ..key
+        };
+        assert!(!key.is_supported());
+
+        let key = PoolKey {
+            currency0: H160::zero(),
+            ..key
+        };
+        assert!(!key.is_supported());
+    }
+}
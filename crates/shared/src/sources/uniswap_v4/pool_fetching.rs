@@ -0,0 +1,285 @@
+use {
+    super::{
+        PoolKey,
+        event_fetching::{PoolStore, UniswapV4PoolManagerContract},
+    },
+    crate::{
+        event_handling::EventHandler,
+        maintenance::Maintaining,
+        recent_block_cache::Block,
+        sources::uniswap_v3::pool_fetching::PoolState,
+    },
+    anyhow::Result,
+    contracts::{
+        UniswapV4PoolManager,
+        uniswap_v4_pool_manager::{Event as PoolManagerEvent, event_data::Initialize},
+    },
+    ethcontract::{H160, H256, I256},
+    ethrpc::{
+        Web3,
+        block_stream::{BlockRetrieving, Reorg},
+    },
+    model::TokenPair,
+    num::{BigInt, Zero, rational::Ratio},
+    number::conversions::{big_int_to_u256, u256_to_big_int},
+    std::{
+        collections::{BTreeMap, HashSet},
+        sync::Arc,
+    },
+    tokio::sync::Mutex,
+};
+
+#[async_trait::async_trait]
+pub trait UniswapV4PoolFetching: Send + Sync {
+    async fn fetch(&self, token_pairs: &HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>>;
+}
+
+/// A Uniswap V4 pool along with its state.
+///
+/// Note that the fee is the LP fee of the pool, protocol fees are not
+/// accounted for.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pool {
+    pub id: H256,
+    pub key: PoolKey,
+    pub tokens: TokenPair,
+    pub state: PoolState,
+}
+
+impl Pool {
+    /// Creates a new pool from its `Initialize` event. Returns `None` for
+    /// unsupported pools.
+    pub fn initialize(event: &Initialize) -> Option<Self> {
+        let key = PoolKey {
+            currency0: event.currency0,
+            currency1: event.currency1,
+            fee: event.fee,
+            tick_spacing: event.tick_spacing,
+            hooks: event.hooks,
+        };
+        if !key.is_supported() {
+            return None;
+        }
+
+        Some(Self {
+            id: H256(event.id.0),
+            tokens: TokenPair::new(key.currency0, key.currency1)?,
+            key,
+            state: PoolState {
+                sqrt_price: event.sqrt_price_x96,
+                liquidity: 0.into(),
+                tick: event.tick.into(),
+                liquidity_net: Default::default(),
+                fee: Ratio::new(event.fee, 1_000_000),
+            },
+        })
+    }
+
+    /// Updates the pool state with a `ModifyLiquidity` or `Swap` event of the
+    /// pool.
+    pub fn apply(&mut self, event: &PoolManagerEvent) {
+        match event {
+            PoolManagerEvent::Initialize(_) => {}
+            PoolManagerEvent::ModifyLiquidity(modify) => {
+                let tick_lower = BigInt::from(modify.tick_lower);
+                let tick_upper = BigInt::from(modify.tick_upper);
+                let delta = i256_to_big_int(modify.liquidity_delta);
+
+                // liquidity tracks the liquidity on the current tick, so it only
+                // needs to be updated if the position includes the current tick.
+                if tick_lower <= self.state.tick && self.state.tick < tick_upper {
+                    let liquidity = u256_to_big_int(&self.state.liquidity) + &delta;
+                    // Can only fail if events were missed, as pool liquidity
+                    // never becomes negative.
+                    self.state.liquidity = big_int_to_u256(&liquidity).unwrap_or_default();
+                }
+
+                add_liquidity_net(&mut self.state.liquidity_net, tick_lower, delta.clone());
+                add_liquidity_net(&mut self.state.liquidity_net, tick_upper, -delta);
+            }
+            PoolManagerEvent::Swap(swap) => {
+                self.state.tick = swap.tick.into();
+                self.state.liquidity = swap.liquidity.into();
+                self.state.sqrt_price = swap.sqrt_price_x96;
+            }
+        }
+    }
+}
+
+fn add_liquidity_net(liquidity_net: &mut BTreeMap<BigInt, BigInt>, tick: BigInt, delta: BigInt) {
+    let net = liquidity_net.entry(tick.clone()).or_default();
+    *net += delta;
+    // remove 0 entries to save bandwidth
+    if net.is_zero() {
+        liquidity_net.remove(&tick);
+    }
+}
+
+fn i256_to_big_int(value: I256) -> BigInt {
+    let mut bytes = [0; 32];
+    value.into_raw().to_big_endian(&mut bytes);
+    BigInt::from_signed_bytes_be(&bytes)
+}
+
+/// Fetches Uniswap V4 pools by indexing all `PoolManager` events since its
+/// deployment.
+pub struct UniswapV4PoolFetcher {
+    events: Mutex<EventHandler<UniswapV4PoolManagerContract, PoolStore>>,
+}
+
+impl UniswapV4PoolFetcher {
+    pub async fn new(
+        web3: Web3,
+        pool_manager: H160,
+        deployment_block: u64,
+        block_retriever: Arc<dyn BlockRetrieving>,
+    ) -> Result<Self> {
+        let web3 = ethrpc::instrumented::instrument_with_label(&web3, "uniswapV4".into());
+        // Start right before the deployment block, so that events from the
+        // deployment block itself get indexed as well.
+        let start_block = block_retriever
+            .block(deployment_block.saturating_sub(1))
+            .await?;
+
+        let events = Mutex::new(EventHandler::new(
+            block_retriever,
+            UniswapV4PoolManagerContract::new(UniswapV4PoolManager::at(&web3, pool_manager)),
+            PoolStore::default(),
+            Some(start_block),
+        ));
+        Ok(Self { events })
+    }
+}
+
+#[async_trait::async_trait]
+impl UniswapV4PoolFetching for UniswapV4PoolFetcher {
+    async fn fetch(&self, token_pairs: &HashSet<TokenPair>, at_block: Block) -> Result<Vec<Pool>> {
+        let events = self.events.lock().await;
+        let block = match at_block {
+            Block::Recent => u64::MAX,
+            Block::Number(number) => {
+                let last_handled_block = events.last_handled_block().unwrap_or_default().0;
+                if number > last_handled_block {
+                    tracing::debug!(
+                        number,
+                        last_handled_block,
+                        "Uniswap V4 pools are not indexed up to the requested block",
+                    );
+                }
+                number
+            }
+        };
+
+        // return only pools which current liquidity is positive
+        Ok(events
+            .store()
+            .pools(token_pairs, block)
+            .into_iter()
+            .filter(|pool| !pool.state.liquidity.is_zero())
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for UniswapV4PoolFetcher {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.events.run_maintenance().await
+    }
+
+    fn name(&self) -> &str {
+        "UniswapV4PoolFetcher"
+    }
+
+    async fn handle_reorg(&self, reorg: &Reorg) {
+        self.events.handle_reorg(reorg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        contracts::uniswap_v4_pool_manager::event_data::{ModifyLiquidity, Swap},
+        ethcontract::Bytes,
+    };
+
+    fn pool() -> Pool {
+        Pool::initialize(&Initialize {
+            id: Bytes([0x42; 32]),
+            currency0: H160([1; 20]),
+            currency1: H160([2; 20]),
+            fee: 3000,
+            tick_spacing: 60,
+            tick: 0,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn initialize_skips_unsupported_pools() {
+        let pool = pool();
+        assert_eq!(pool.id, H256([0x42; 32]));
+        assert_eq!(pool.state.fee, Ratio::new(3, 1000));
+
+        let before_swap_hook = Initialize {
+            currency0: H160([1; 20]),
+            currency1: H160([2; 20]),
+            hooks: H160::from_low_u64_be(0x80),
+            ..Default::default()
+        };
+        assert_eq!(Pool::initialize(&before_swap_hook), None);
+    }
+
+    #[test]
+    fn apply_modify_liquidity() {
+        let mut pool = pool();
+        let modify = |tick_lower, tick_upper, liquidity_delta: i64| {
+            PoolManagerEvent::ModifyLiquidity(ModifyLiquidity {
+                tick_lower,
+                tick_upper,
+                liquidity_delta: liquidity_delta.into(),
+                ..Default::default()
+            })
+        };
+
+        pool.apply(&modify(-60, 60, 12345));
+        assert_eq!(pool.state.liquidity, 12345.into());
+        assert_eq!(
+            pool.state.liquidity_net,
+            BTreeMap::from([
+                (BigInt::from(-60), BigInt::from(12345)),
+                (BigInt::from(60), BigInt::from(-12345)),
+            ])
+        );
+
+        // position not including the current tick
+        pool.apply(&modify(60, 120, 100));
+        assert_eq!(pool.state.liquidity, 12345.into());
+
+        pool.apply(&modify(-60, 60, -12345));
+        assert_eq!(pool.state.liquidity, 0.into());
+        assert_eq!(
+            pool.state.liquidity_net,
+            BTreeMap::from([
+                (BigInt::from(60), BigInt::from(100)),
+                (BigInt::from(120), BigInt::from(-100)),
+            ])
+        );
+    }
+
+    #[test]
+    fn apply_swap() {
+        let mut pool = pool();
+        pool.apply(&PoolManagerEvent::Swap(Swap {
+            sqrt_price_x96: 1.into(),
+            liquidity: 2,
+            tick: 3,
+            ..Default::default()
+        }));
+
+        assert_eq!(pool.state.sqrt_price, 1.into());
+        assert_eq!(pool.state.liquidity, 2.into());
+        assert_eq!(pool.state.tick, BigInt::from(3));
+    }
+}
@@ -0,0 +1,110 @@
+use {
+    super::balancer_v2::NEVER,
+    contracts::BalancerV3BatchRouter,
+    ethcontract::Bytes,
+    primitive_types::H160,
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::{EncodedInteraction, Interaction},
+    },
+};
+
+/// Swaps at most `asset_in_max` for exactly `asset_out` on a single Balancer
+/// V3 pool through the batch router.
+///
+/// The router pulls the input tokens from the caller through Permit2, so the
+/// caller has to approve the router on Permit2 beforehand. The output tokens
+/// are sent to the caller.
+#[derive(Clone, Debug)]
+pub struct BalancerV3SwapGivenOutInteraction {
+    pub batch_router: BalancerV3BatchRouter,
+    pub pool: H160,
+    pub asset_in_max: TokenAmount,
+    pub asset_out: TokenAmount,
+}
+
+impl Interaction for BalancerV3SwapGivenOutInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let method = self.batch_router.swap_exact_out(
+            vec![(
+                self.asset_in_max.token,
+                vec![(
+                    self.pool,
+                    self.asset_out.token,
+                    false, // isBuffer
+                )],
+                self.asset_in_max.amount,
+                self.asset_out.amount,
+            )],
+            *NEVER,
+            false, // wethIsEth
+            Bytes::default(),
+        );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.batch_router.address(), 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        contracts::dummy_contract,
+        ethcontract::common::abi::{self, ParamType, Token},
+        hex_literal::hex,
+    };
+
+    #[test]
+    fn encode_swap_exact_out() {
+        let batch_router = dummy_contract!(BalancerV3BatchRouter, [0x01; 20]);
+        let interaction = BalancerV3SwapGivenOutInteraction {
+            batch_router: batch_router.clone(),
+            pool: H160([0x02; 20]),
+            asset_in_max: TokenAmount::new(H160([0x03; 20]), 1_000),
+            asset_out: TokenAmount::new(H160([0x04; 20]), 900),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, batch_router.address());
+        assert_eq!(value, 0.into());
+        assert_eq!(calldata.0[0..4], hex!("8eb1b65e"));
+
+        let call = abi::decode(
+            &[
+                ParamType::Array(Box::new(ParamType::Tuple(vec![
+                    ParamType::Address,
+                    ParamType::Array(Box::new(ParamType::Tuple(vec![
+                        ParamType::Address,
+                        ParamType::Address,
+                        ParamType::Bool,
+                    ]))),
+                    ParamType::Uint(256),
+                    ParamType::Uint(256),
+                ]))),
+                ParamType::Uint(256),
+                ParamType::Bool,
+                ParamType::Bytes,
+            ],
+            &calldata.0[4..],
+        )
+        .unwrap();
+        assert_eq!(
+            call,
+            vec![
+                Token::Array(vec![Token::Tuple(vec![
+                    Token::Address(H160([0x03; 20])),
+                    Token::Array(vec![Token::Tuple(vec![
+                        Token::Address(H160([0x02; 20])),
+                        Token::Address(H160([0x04; 20])),
+                        Token::Bool(false),
+                    ])]),
+                    Token::Uint(1_000.into()),
+                    Token::Uint(900.into()),
+                ])]),
+                Token::Uint(*NEVER),
+                Token::Bool(false),
+                Token::Bytes(Vec::new()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use {
+    contracts::{CurveCryptoSwapPool, CurveStableSwapPool},
+    ethcontract::Bytes,
+    primitive_types::U256,
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// The pool a Curve swap is executed on. StableSwap and CryptoSwap pools use
+/// different types for the coin indices of `exchange`.
+#[derive(Clone, Debug)]
+pub enum CurvePool {
+    StableSwap(CurveStableSwapPool),
+    CryptoSwap(CurveCryptoSwapPool),
+}
+
+/// Swaps exactly `amount_in` of the coin at index `i` for at least
+/// `min_amount_out` of the coin at index `j`.
+#[derive(Clone, Debug)]
+pub struct CurveInteraction {
+    pub pool: CurvePool,
+    pub i: usize,
+    pub j: usize,
+    pub amount_in: U256,
+    pub min_amount_out: U256,
+}
+
+impl Interaction for CurveInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let (target, calldata) = match &self.pool {
+            CurvePool::StableSwap(pool) => {
+                let index = |i: usize| i128::try_from(i).expect("coin index fits into int128");
+                let method = pool.exchange(
+                    index(self.i),
+                    index(self.j),
+                    self.amount_in,
+                    self.min_amount_out,
+                );
+                (pool.address(), method.tx.data.expect("no calldata").0)
+            }
+            CurvePool::CryptoSwap(pool) => {
+                let method = pool.exchange(
+                    self.i.into(),
+                    self.j.into(),
+                    self.amount_in,
+                    self.min_amount_out,
+                );
+                (pool.address(), method.tx.data.expect("no calldata").0)
+            }
+        };
+        (target, 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, hex_literal::hex, primitive_types::H160};
+
+    #[test]
+    fn encode_stable_swap_exchange() {
+        let pool = dummy_contract!(CurveStableSwapPool, H160([0x42; 20]));
+        let interaction = CurveInteraction {
+            pool: CurvePool::StableSwap(pool),
+            i: 1,
+            j: 2,
+            amount_in: 1_000.into(),
+            min_amount_out: 999.into(),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x42; 20]));
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "3df02124
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000000000000000000000000000000000000000002
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 00000000000000000000000000000000000000000000000000000000000003e7"
+            )
+        );
+    }
+
+    #[test]
+    fn encode_crypto_swap_exchange() {
+        let pool = dummy_contract!(CurveCryptoSwapPool, H160([0x42; 20]));
+        let interaction = CurveInteraction {
+            pool: CurvePool::CryptoSwap(pool),
+            i: 0,
+            j: 1,
+            amount_in: 1_000.into(),
+            min_amount_out: 999.into(),
+        };
+
+        let (_, _, calldata) = interaction.encode();
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "5b41b908
+                 0000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 00000000000000000000000000000000000000000000000000000000000003e7"
+            )
+        );
+    }
+}
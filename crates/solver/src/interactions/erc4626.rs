@@ -0,0 +1,88 @@
+use {
+    contracts::IERC4626,
+    ethcontract::Bytes,
+    primitive_types::{H160, U256},
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// Converts an exact amount of assets into vault shares or vice versa.
+#[derive(Clone, Debug)]
+pub struct Erc4626Interaction {
+    pub vault: IERC4626,
+    pub conversion: Erc4626Conversion,
+    /// The amount of assets to deposit or shares to redeem.
+    pub amount: U256,
+    /// The receiver of the converted tokens, which is also the owner of the
+    /// redeemed shares.
+    pub receiver: H160,
+}
+
+/// The direction of an ERC-4626 vault conversion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Erc4626Conversion {
+    /// Deposit assets for vault shares.
+    Deposit,
+    /// Redeem vault shares for assets.
+    Redeem,
+}
+
+impl Interaction for Erc4626Interaction {
+    fn encode(&self) -> EncodedInteraction {
+        let method = match self.conversion {
+            Erc4626Conversion::Deposit => self.vault.deposit(self.amount, self.receiver),
+            Erc4626Conversion::Redeem => {
+                self.vault.redeem(self.amount, self.receiver, self.receiver)
+            }
+        };
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.vault.address(), 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, hex_literal::hex};
+
+    #[test]
+    fn encode_deposit() {
+        let interaction = Erc4626Interaction {
+            vault: dummy_contract!(IERC4626, H160([0x42; 20])),
+            conversion: Erc4626Conversion::Deposit,
+            amount: 1_000.into(),
+            receiver: H160([0x01; 20]),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x42; 20]));
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "6e553f65
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 0000000000000000000000000101010101010101010101010101010101010101"
+            )
+        );
+    }
+
+    #[test]
+    fn encode_redeem() {
+        let interaction = Erc4626Interaction {
+            vault: dummy_contract!(IERC4626, H160([0x42; 20])),
+            conversion: Erc4626Conversion::Redeem,
+            amount: 1_000.into(),
+            receiver: H160([0x01; 20]),
+        };
+
+        let (_, _, calldata) = interaction.encode();
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "ba087652
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 0000000000000000000000000101010101010101010101010101010101010101
+                 0000000000000000000000000101010101010101010101010101010101010101"
+            )
+        );
+    }
+}
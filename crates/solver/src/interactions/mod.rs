@@ -1,16 +1,28 @@
 pub mod allowances;
 mod balancer_v2;
+mod balancer_v3;
+mod curve;
 mod erc20;
+mod erc4626;
+mod permit2;
+mod solidly;
 mod uniswap_v2;
 mod uniswap_v3;
+mod uniswap_v4;
 mod weth;
 mod zeroex;
 
 pub use {
     balancer_v2::BalancerSwapGivenOutInteraction,
-    erc20::Erc20ApproveInteraction,
+    balancer_v3::BalancerV3SwapGivenOutInteraction,
+    curve::{CurveInteraction, CurvePool},
+    erc20::{Erc20ApproveInteraction, Erc20TransferInteraction},
+    erc4626::{Erc4626Conversion, Erc4626Interaction},
+    permit2::Permit2ApproveInteraction,
+    solidly::SolidlySwapInteraction,
     uniswap_v2::UniswapInteraction,
     uniswap_v3::{ExactOutputSingleParams, UniswapV3Interaction},
+    uniswap_v4::UniswapV4Interaction,
     weth::UnwrapWethInteraction,
     zeroex::ZeroExInteraction,
 };
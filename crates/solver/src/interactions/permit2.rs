@@ -0,0 +1,65 @@
+use {
+    contracts::Permit2,
+    ethcontract::Bytes,
+    primitive_types::{H160, U256},
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// Permit2 expiration value for allowances that are only valid in the block
+/// they are approved in.
+const BLOCK_TIMESTAMP_EXPIRATION: u64 = 0;
+
+/// Approves `spender` to transfer `amount` of `token` from the caller through
+/// Permit2. The approval expires at the end of the block.
+///
+/// Note that the caller needs an ERC20 approval for Permit2 as well.
+#[derive(Clone, Debug)]
+pub struct Permit2ApproveInteraction {
+    pub permit2: Permit2,
+    pub token: H160,
+    pub spender: H160,
+    pub amount: U256,
+}
+
+impl Interaction for Permit2ApproveInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let method = self.permit2.approve(
+            self.token,
+            self.spender,
+            self.amount,
+            BLOCK_TIMESTAMP_EXPIRATION,
+        );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.permit2.address(), 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, hex_literal::hex};
+
+    #[test]
+    fn encode_permit2_approve() {
+        let permit2 = dummy_contract!(Permit2, [0x42; 20]);
+        let interaction = Permit2ApproveInteraction {
+            permit2: permit2.clone(),
+            token: H160([0x01; 20]),
+            spender: H160([0x02; 20]),
+            amount: 1_000.into(),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, permit2.address());
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "87517c45
+                 0000000000000000000000000101010101010101010101010101010101010101
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 0000000000000000000000000000000000000000000000000000000000000000"
+            )
+        );
+    }
+}
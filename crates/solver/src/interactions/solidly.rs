@@ -0,0 +1,76 @@
+use {
+    contracts::ISolidlyRouter,
+    ethcontract::Bytes,
+    primitive_types::{H160, U256},
+    shared::interaction::{EncodedInteraction, Interaction},
+};
+
+/// Swaps exactly `amount_in` of `token_in` for at least `amount_out_min` of
+/// `token_out` over a single Solidly pool through the router.
+#[derive(Clone, Debug)]
+pub struct SolidlySwapInteraction {
+    pub router: ISolidlyRouter,
+    /// The factory that deployed the pool, used by the router for computing
+    /// the pool address.
+    pub factory: H160,
+    pub stable: bool,
+    pub token_in: H160,
+    pub token_out: H160,
+    pub amount_in: U256,
+    pub amount_out_min: U256,
+    pub receiver: H160,
+}
+
+impl Interaction for SolidlySwapInteraction {
+    fn encode(&self) -> EncodedInteraction {
+        let method = self.router.swap_exact_tokens_for_tokens(
+            self.amount_in,
+            self.amount_out_min,
+            vec![(self.token_in, self.token_out, self.stable, self.factory)],
+            self.receiver,
+            *super::balancer_v2::NEVER,
+        );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.router.address(), 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, hex_literal::hex};
+
+    #[test]
+    fn encode_swap_exact_tokens_for_tokens() {
+        let router = dummy_contract!(ISolidlyRouter, H160([0x42; 20]));
+        let interaction = SolidlySwapInteraction {
+            router,
+            factory: H160([0x03; 20]),
+            stable: true,
+            token_in: H160([0x01; 20]),
+            token_out: H160([0x02; 20]),
+            amount_in: 1_000.into(),
+            amount_out_min: 999.into(),
+            receiver: H160([0x04; 20]),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, H160([0x42; 20]));
+        assert_eq!(value, 0.into());
+        assert_eq!(
+            calldata.0,
+            hex!(
+                "cac88ea9
+                 00000000000000000000000000000000000000000000000000000000000003e8
+                 00000000000000000000000000000000000000000000000000000000000003e7
+                 00000000000000000000000000000000000000000000000000000000000000a0
+                 0000000000000000000000000404040404040404040404040404040404040404
+                 8000000000000000000000000000000000000000000000000000000000000000
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000101010101010101010101010101010101010101
+                 0000000000000000000000000202020202020202020202020202020202020202
+                 0000000000000000000000000000000000000000000000000000000000000001
+                 0000000000000000000000000303030303030303030303030303030303030303"
+            )
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use {
+    contracts::UniswapV4UniversalRouter,
+    ethcontract::{
+        Bytes,
+        I256,
+        common::abi::{self, Token},
+    },
+    primitive_types::U256,
+    shared::{
+        http_solver::model::TokenAmount,
+        interaction::{EncodedInteraction, Interaction},
+        sources::uniswap_v4::PoolKey,
+    },
+};
+
+/// Universal Router command executing a sequence of Uniswap V4 actions.
+const V4_SWAP: u8 = 0x10;
+
+/// Uniswap V4 router actions.
+///
+/// <https://github.com/Uniswap/v4-periphery/blob/main/src/libraries/Actions.sol>
+mod actions {
+    pub const SWAP_EXACT_IN_SINGLE: u8 = 0x06;
+    pub const SETTLE: u8 = 0x0b;
+    pub const TAKE_ALL: u8 = 0x0f;
+}
+
+/// Settle the whole open delta of a currency.
+const OPEN_DELTA: u64 = 0;
+
+/// A single pool exact input swap through the Universal Router.
+///
+/// The router pays for the swap from its own balance, so the input tokens have
+/// to be transferred to the router before executing this interaction. The
+/// output tokens are sent to the caller.
+#[derive(Debug)]
+pub struct UniswapV4Interaction {
+    pub router: UniswapV4UniversalRouter,
+    pub key: PoolKey,
+    pub token_amount_in: TokenAmount,
+    pub token_amount_out_min: TokenAmount,
+    pub deadline: U256,
+}
+
+impl UniswapV4Interaction {
+    fn swap_input(&self) -> Vec<u8> {
+        let zero_for_one = self.token_amount_in.token == self.key.currency0;
+        let swap = abi::encode(&[Token::Tuple(vec![
+            Token::Tuple(vec![
+                Token::Address(self.key.currency0),
+                Token::Address(self.key.currency1),
+                Token::Uint(self.key.fee.into()),
+                Token::Int(I256::from(self.key.tick_spacing).into_raw()),
+                Token::Address(self.key.hooks),
+            ]),
+            Token::Bool(zero_for_one),
+            Token::Uint(self.token_amount_in.amount),
+            Token::Uint(self.token_amount_out_min.amount),
+            Token::Bytes(Vec::new()),
+        ])]);
+        let settle = abi::encode(&[
+            Token::Address(self.token_amount_in.token),
+            Token::Uint(OPEN_DELTA.into()),
+            Token::Bool(false),
+        ]);
+        let take_all = abi::encode(&[
+            Token::Address(self.token_amount_out_min.token),
+            Token::Uint(self.token_amount_out_min.amount),
+        ]);
+
+        abi::encode(&[
+            Token::Bytes(vec![
+                actions::SWAP_EXACT_IN_SINGLE,
+                actions::SETTLE,
+                actions::TAKE_ALL,
+            ]),
+            Token::Array(vec![
+                Token::Bytes(swap),
+                Token::Bytes(settle),
+                Token::Bytes(take_all),
+            ]),
+        ])
+    }
+}
+
+impl Interaction for UniswapV4Interaction {
+    fn encode(&self) -> EncodedInteraction {
+        let method = self.router.execute(
+            Bytes(vec![V4_SWAP]),
+            vec![Bytes(self.swap_input())],
+            self.deadline,
+        );
+        let calldata = method.tx.data.expect("no calldata").0;
+        (self.router.address(), 0.into(), Bytes(calldata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        abi::ParamType,
+        contracts::dummy_contract,
+        hex_literal::hex,
+        primitive_types::H160,
+    };
+
+    #[test]
+    fn encode_uniswap_v4_swap() {
+        let token_a = H160::from_low_u64_be(1);
+        let token_b = H160::from_low_u64_be(2);
+        let router = dummy_contract!(UniswapV4UniversalRouter, H160::from_low_u64_be(3));
+        let interaction = UniswapV4Interaction {
+            router: router.clone(),
+            key: PoolKey {
+                currency0: token_a,
+                currency1: token_b,
+                fee: 500,
+                tick_spacing: 10,
+                hooks: H160::zero(),
+            },
+            token_amount_in: TokenAmount::new(token_b, 100),
+            token_amount_out_min: TokenAmount::new(token_a, 90),
+            deadline: 42.into(),
+        };
+
+        let (target, value, calldata) = interaction.encode();
+        assert_eq!(target, router.address());
+        assert_eq!(value, 0.into());
+        assert_eq!(calldata.0[0..4], hex!("3593564c"));
+
+        let call = abi::decode(
+            &[
+                ParamType::Bytes,
+                ParamType::Array(Box::new(ParamType::Bytes)),
+                ParamType::Uint(256),
+            ],
+            &calldata.0[4..],
+        )
+        .unwrap();
+        assert_eq!(call[0], Token::Bytes(vec![V4_SWAP]));
+        assert_eq!(call[2], Token::Uint(42.into()));
+        let Token::Array(inputs) = &call[1] else {
+            unreachable!()
+        };
+        assert_eq!(inputs, &[Token::Bytes(interaction.swap_input())]);
+
+        let input = abi::decode(
+            &[
+                ParamType::Bytes,
+                ParamType::Array(Box::new(ParamType::Bytes)),
+            ],
+            &interaction.swap_input(),
+        )
+        .unwrap();
+        assert_eq!(input[0], Token::Bytes(hex!("060b0f").to_vec()));
+        let Token::Array(params) = &input[1] else {
+            unreachable!()
+        };
+        let params = params
+            .iter()
+            .map(|param| param.clone().into_bytes().unwrap())
+            .collect::<Vec<_>>();
+
+        let swap = abi::decode(
+            &[ParamType::Tuple(vec![
+                ParamType::Tuple(vec![
+                    ParamType::Address,
+                    ParamType::Address,
+                    ParamType::Uint(24),
+                    ParamType::Int(24),
+                    ParamType::Address,
+                ]),
+                ParamType::Bool,
+                ParamType::Uint(128),
+                ParamType::Uint(128),
+                ParamType::Bytes,
+            ])],
+            &params[0],
+        )
+        .unwrap();
+        assert_eq!(
+            swap,
+            vec![Token::Tuple(vec![
+                Token::Tuple(vec![
+                    Token::Address(token_a),
+                    Token::Address(token_b),
+                    Token::Uint(500.into()),
+                    Token::Int(10.into()),
+                    Token::Address(H160::zero()),
+                ]),
+                Token::Bool(false),
+                Token::Uint(100.into()),
+                Token::Uint(90.into()),
+                Token::Bytes(Vec::new()),
+            ])]
+        );
+
+        let settle = abi::decode(
+            &[ParamType::Address, ParamType::Uint(256), ParamType::Bool],
+            &params[1],
+        )
+        .unwrap();
+        assert_eq!(
+            settle,
+            vec![
+                Token::Address(token_b),
+                Token::Uint(0.into()),
+                Token::Bool(false),
+            ]
+        );
+
+        let take_all =
+            abi::decode(&[ParamType::Address, ParamType::Uint(256)], &params[2]).unwrap();
+        assert_eq!(
+            take_all,
+            vec![Token::Address(token_a), Token::Uint(90.into())]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Module for providing Balancer V3 pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{
+            BalancerV3SwapGivenOutInteraction,
+            Permit2ApproveInteraction,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances},
+        },
+        liquidity::{AmmOrderExecution, BalancerV3PoolOrder, Liquidity, SettlementHandling},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::Result,
+    contracts::{BalancerV3BatchRouter, Permit2},
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::balancer_v3::pool_fetching::BalancerV3PoolFetching,
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for Balancer V3 weighted and stable pools.
+pub struct BalancerV3Liquidity {
+    batch_router: BalancerV3BatchRouter,
+    permit2: Permit2,
+    pool_fetcher: Arc<dyn BalancerV3PoolFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+}
+
+impl BalancerV3Liquidity {
+    pub fn new(
+        web3: Web3,
+        pool_fetcher: Arc<dyn BalancerV3PoolFetching>,
+        settlement: H160,
+        batch_router: BalancerV3BatchRouter,
+        permit2: Permit2,
+    ) -> Self {
+        let allowance_manager = AllowanceManager::new(web3, settlement);
+        Self {
+            batch_router,
+            permit2,
+            pool_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for BalancerV3Liquidity {
+    /// Returns relevant Balancer V3 pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+
+        // The batch router pulls the input tokens through Permit2, so the
+        // settlement contract needs to approve Permit2 for them.
+        let tokens = pools
+            .iter()
+            .flat_map(|pool| pool.tokens.iter().map(|token| token.address))
+            .collect();
+        let allowances = self
+            .allowance_manager
+            .get_allowances(tokens, self.permit2.address())
+            .await?;
+
+        let inner = Arc::new(Inner {
+            batch_router: self.batch_router.clone(),
+            permit2: self.permit2.clone(),
+            allowances,
+        });
+        Ok(pools
+            .into_iter()
+            .map(|pool| {
+                let settlement_handling = Arc::new(SettlementHandler {
+                    pool: pool.address,
+                    inner: inner.clone(),
+                });
+                Liquidity::BalancerV3(BalancerV3PoolOrder {
+                    pool,
+                    settlement_handling,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    pool: H160,
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    batch_router: BalancerV3BatchRouter,
+    permit2: Permit2,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(
+        pool: H160,
+        batch_router: BalancerV3BatchRouter,
+        permit2: Permit2,
+        allowances: Allowances,
+    ) -> Self {
+        Self {
+            pool,
+            inner: Arc::new(Inner {
+                batch_router,
+                permit2,
+                allowances,
+            }),
+        }
+    }
+
+    pub fn batch_router(&self) -> &BalancerV3BatchRouter {
+        &self.inner.batch_router
+    }
+
+    pub fn permit2(&self) -> &Permit2 {
+        &self.inner.permit2
+    }
+
+    /// Returns the interactions for swapping at most `input_max` for exactly
+    /// `output`: the Permit2 approval of the batch router and the swap itself.
+    pub fn swap(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> (Permit2ApproveInteraction, BalancerV3SwapGivenOutInteraction) {
+        let permit2_approval = Permit2ApproveInteraction {
+            permit2: self.inner.permit2.clone(),
+            token: input_max.token,
+            spender: self.inner.batch_router.address(),
+            amount: input_max.amount,
+        };
+        let swap = BalancerV3SwapGivenOutInteraction {
+            batch_router: self.inner.batch_router.clone(),
+            pool: self.pool,
+            asset_in_max: input_max,
+            asset_out: output,
+        };
+        (permit2_approval, swap)
+    }
+}
+
+impl SettlementHandling<BalancerV3PoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let approval = self
+            .inner
+            .allowances
+            .approve_token(execution.input_max.clone())?;
+        let (permit2_approval, swap) = self.swap(execution.input_max, execution.output);
+        if let Some(approval) = approval {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        encoder.append_to_execution_plan_internalizable(
+            Arc::new(permit2_approval),
+            execution.internalizable,
+        );
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::interactions::allowances::Approval,
+        contracts::dummy_contract,
+        maplit::hashmap,
+        shared::{http_solver::model::InternalizationStrategy, interaction::Interaction},
+    };
+
+    #[test]
+    fn swap_approves_batch_router_on_permit2() {
+        let (token_in, token_out) = (H160([0x01; 20]), H160([0x02; 20]));
+        let batch_router = dummy_contract!(BalancerV3BatchRouter, [0x03; 20]);
+        let permit2 = dummy_contract!(Permit2, [0x04; 20]);
+        let handler = SettlementHandler::new(
+            H160([0x05; 20]),
+            batch_router.clone(),
+            permit2.clone(),
+            Allowances::new(permit2.address(), hashmap! { token_in => 100.into() }),
+        );
+
+        let (permit2_approval, swap) = handler.swap(
+            TokenAmount::new(token_in, 100),
+            TokenAmount::new(token_out, 90),
+        );
+        assert_eq!(permit2_approval.token, token_in);
+        assert_eq!(permit2_approval.spender, batch_router.address());
+        assert_eq!(permit2_approval.amount, 100.into());
+        assert_eq!(swap.encode().0, batch_router.address());
+    }
+
+    #[test]
+    fn encode_approves_permit2_if_needed() {
+        let (token_in, token_out) = (H160([0x01; 20]), H160([0x02; 20]));
+        let permit2 = dummy_contract!(Permit2, [0x04; 20]);
+        let handler = SettlementHandler::new(
+            H160([0x05; 20]),
+            dummy_contract!(BalancerV3BatchRouter, [0x03; 20]),
+            permit2.clone(),
+            Allowances::new(permit2.address(), hashmap! { token_in => 100.into() }),
+        );
+
+        let mut encoder = SettlementEncoder::new(Default::default());
+        for amount in [100, 101] {
+            handler
+                .encode(
+                    AmmOrderExecution {
+                        input_max: TokenAmount::new(token_in, amount),
+                        output: TokenAmount::new(token_out, 90),
+                        internalizable: false,
+                    },
+                    &mut encoder,
+                )
+                .unwrap();
+        }
+
+        let [_, interactions, _] = encoder
+            .finish(InternalizationStrategy::SkipInternalizableInteraction)
+            .interactions;
+        assert_eq!(interactions.len(), 5);
+        assert_eq!(
+            interactions[2],
+            Approval {
+                token: token_in,
+                spender: permit2.address(),
+            }
+            .encode(),
+        );
+    }
+}
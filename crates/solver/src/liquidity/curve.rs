@@ -0,0 +1,140 @@
+//! Module for providing Curve pool liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{
+            CurveInteraction,
+            CurvePool,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances},
+        },
+        liquidity::{AmmOrderExecution, CurvePoolOrder, Liquidity, SettlementHandling},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Context, Result},
+    contracts::{CurveCryptoSwapPool, CurveStableSwapPool},
+    futures::future,
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::curve::{self, PoolKind, pool_fetching::CurvePoolFetching},
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for Curve StableSwap and CryptoSwap pools.
+pub struct CurveLiquidity {
+    web3: Web3,
+    pool_fetcher: Arc<dyn CurvePoolFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+}
+
+impl CurveLiquidity {
+    pub fn new(web3: Web3, pool_fetcher: Arc<dyn CurvePoolFetching>, settlement: H160) -> Self {
+        let allowance_manager = AllowanceManager::new(web3.clone(), settlement);
+        Self {
+            web3,
+            pool_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for CurveLiquidity {
+    /// Returns relevant Curve pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+
+        // Every Curve pool pulls the tokens it swaps itself, so allowances are
+        // needed per pool.
+        let allowances = future::try_join_all(pools.iter().map(|pool| {
+            let tokens = pool.tokens.iter().map(|token| token.address).collect();
+            self.allowance_manager.get_allowances(tokens, pool.address)
+        }))
+        .await?;
+
+        Ok(pools
+            .into_iter()
+            .zip(allowances)
+            .map(|(pool, allowances)| {
+                let settlement_handling =
+                    Arc::new(SettlementHandler::new(&pool, &self.web3, allowances));
+                Liquidity::Curve(CurvePoolOrder {
+                    pool,
+                    settlement_handling,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    pool: CurvePool,
+    /// The pool tokens in the order of the pool's coin indices.
+    tokens: Vec<H160>,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(pool: &curve::Pool, web3: &Web3, allowances: Allowances) -> Self {
+        Self {
+            pool: match pool.kind {
+                PoolKind::StableSwap(_) => {
+                    CurvePool::StableSwap(CurveStableSwapPool::at(web3, pool.address))
+                }
+                PoolKind::CryptoSwap(_) => {
+                    CurvePool::CryptoSwap(CurveCryptoSwapPool::at(web3, pool.address))
+                }
+            },
+            tokens: pool.tokens.iter().map(|token| token.address).collect(),
+            allowances,
+        }
+    }
+
+    /// Returns the swap interaction for an execution.
+    ///
+    /// Curve pools only support swapping exact input amounts, so the whole
+    /// `input_max` amount is sold for at least the `output` amount.
+    pub fn swap(&self, input_max: TokenAmount, output: TokenAmount) -> Result<CurveInteraction> {
+        let index = |token: H160| {
+            self.tokens
+                .iter()
+                .position(|t| *t == token)
+                .with_context(|| format!("token {token:?} not in Curve pool"))
+        };
+        Ok(CurveInteraction {
+            pool: self.pool.clone(),
+            i: index(input_max.token)?,
+            j: index(output.token)?,
+            amount_in: input_max.amount,
+            min_amount_out: output.amount,
+        })
+    }
+}
+
+impl SettlementHandling<CurvePoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let approval = self.allowances.approve_token(execution.input_max.clone())?;
+        let swap = self.swap(execution.input_max, execution.output)?;
+        if let Some(approval) = approval {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Module for providing ERC-4626 vault liquidity to the solvers.
+
+use {
+    crate::{
+        interactions::{
+            Erc4626Conversion,
+            Erc4626Interaction,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances},
+        },
+        liquidity::{AmmOrderExecution, Erc4626VaultOrder, Liquidity, SettlementHandling},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Result, bail},
+    contracts::IERC4626,
+    futures::future,
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::erc4626::{Vault, vault_fetching::Erc4626VaultFetching},
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for converting between ERC-4626 vault assets and
+/// shares.
+pub struct Erc4626Liquidity {
+    web3: Web3,
+    settlement: H160,
+    vault_fetcher: Arc<dyn Erc4626VaultFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+}
+
+impl Erc4626Liquidity {
+    pub fn new(web3: Web3, vault_fetcher: Arc<dyn Erc4626VaultFetching>, settlement: H160) -> Self {
+        let allowance_manager = AllowanceManager::new(web3.clone(), settlement);
+        Self {
+            web3,
+            settlement,
+            vault_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for Erc4626Liquidity {
+    /// Returns relevant ERC-4626 vaults given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let vaults = self.vault_fetcher.fetch(pairs, block).await?;
+
+        // Every vault pulls the deposited assets itself, so allowances are
+        // needed per vault.
+        let allowances = future::try_join_all(vaults.iter().map(|vault| {
+            self.allowance_manager
+                .get_allowances(HashSet::from([vault.asset]), vault.address)
+        }))
+        .await?;
+
+        Ok(vaults
+            .into_iter()
+            .zip(allowances)
+            .map(|(vault, allowances)| {
+                let settlement_handling = Arc::new(SettlementHandler::new(
+                    &vault,
+                    &self.web3,
+                    self.settlement,
+                    allowances,
+                ));
+                Liquidity::Erc4626(Erc4626VaultOrder {
+                    vault,
+                    settlement_handling,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    vault: IERC4626,
+    asset: H160,
+    settlement: H160,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(vault: &Vault, web3: &Web3, settlement: H160, allowances: Allowances) -> Self {
+        Self {
+            vault: IERC4626::at(web3, vault.address),
+            asset: vault.asset,
+            settlement,
+            allowances,
+        }
+    }
+
+    pub fn vault(&self) -> &IERC4626 {
+        &self.vault
+    }
+
+    /// Returns the conversion interaction for an execution.
+    ///
+    /// Vault conversions are executed with exact input amounts, so the whole
+    /// `input_max` amount is deposited or redeemed. Unlike AMM swaps, vaults
+    /// don't take a minimum output amount, so the `output` amount isn't
+    /// enforced on-chain and the conversion yields whatever the vault's rate
+    /// is at execution time.
+    pub fn swap(&self, input_max: TokenAmount, output: TokenAmount) -> Result<Erc4626Interaction> {
+        let vault = self.vault.address();
+        let conversion = match (input_max.token, output.token) {
+            (input, output) if input == self.asset && output == vault => Erc4626Conversion::Deposit,
+            (input, output) if input == vault && output == self.asset => Erc4626Conversion::Redeem,
+            _ => bail!("tokens are not the vault asset and shares"),
+        };
+        Ok(Erc4626Interaction {
+            vault: self.vault.clone(),
+            conversion,
+            amount: input_max.amount,
+            receiver: self.settlement,
+        })
+    }
+}
+
+impl SettlementHandling<Erc4626VaultOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let swap = self.swap(execution.input_max.clone(), execution.output)?;
+        // Only deposits pull tokens from the settlement contract, shares are
+        // redeemed by their owner.
+        if swap.conversion == Erc4626Conversion::Deposit
+            && let Some(approval) = self.allowances.approve_token(execution.input_max)?
+        {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::interactions::allowances::Approval,
+        maplit::hashmap,
+        shared::{http_solver::model::InternalizationStrategy, interaction::Interaction},
+    };
+
+    #[test]
+    fn encode_approves_deposits_only() {
+        let (asset, vault) = (H160([0x01; 20]), H160([0x02; 20]));
+        let handler = SettlementHandler::new(
+            &Vault {
+                address: vault,
+                asset,
+                assets_to_shares: 1.into(),
+                shares_to_assets: 1.into(),
+            },
+            &ethrpc::dummy::web3(),
+            H160([0x03; 20]),
+            Allowances::new(vault, hashmap! { asset => 100.into() }),
+        );
+
+        let mut encoder = SettlementEncoder::new(Default::default());
+        for (input, output) in [(asset, vault), (vault, asset)] {
+            handler
+                .encode(
+                    AmmOrderExecution {
+                        input_max: TokenAmount::new(input, 101),
+                        output: TokenAmount::new(output, 90),
+                        internalizable: false,
+                    },
+                    &mut encoder,
+                )
+                .unwrap();
+        }
+
+        let [_, interactions, _] = encoder
+            .finish(InternalizationStrategy::SkipInternalizableInteraction)
+            .interactions;
+        assert_eq!(interactions.len(), 3);
+        assert_eq!(
+            interactions[0],
+            Approval {
+                token: asset,
+                spender: vault,
+            }
+            .encode(),
+        );
+        assert!(
+            interactions[1..]
+                .iter()
+                .all(|(target, ..)| *target == vault)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_tokens() {
+        let (asset, vault) = (H160([0x01; 20]), H160([0x02; 20]));
+        let handler = SettlementHandler::new(
+            &Vault {
+                address: vault,
+                asset,
+                assets_to_shares: 1.into(),
+                shares_to_assets: 1.into(),
+            },
+            &ethrpc::dummy::web3(),
+            H160([0x03; 20]),
+            Allowances::empty(vault),
+        );
+
+        assert!(
+            handler
+                .swap(
+                    TokenAmount::new(asset, 100),
+                    TokenAmount::new(H160([0x04; 20]), 90),
+                )
+                .is_err()
+        );
+    }
+}
@@ -1,8 +1,13 @@
 pub mod balancer_v2;
+pub mod balancer_v3;
+pub mod curve;
+pub mod erc4626;
 pub mod order_converter;
 pub mod slippage;
+pub mod solidly;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
+pub mod uniswap_v4;
 pub mod zeroex;
 
 #[cfg(test)]
@@ -45,6 +50,10 @@ pub enum Liquidity {
     BalancerStable(StablePoolOrder),
     LimitOrder(LimitOrder),
     Concentrated(ConcentratedLiquidity),
+    Curve(CurvePoolOrder),
+    BalancerV3(BalancerV3PoolOrder),
+    Solidly(SolidlyPoolOrder),
+    Erc4626(Erc4626VaultOrder),
 }
 
 /// A trait associating some liquidity model to how it is executed and encoded
@@ -325,6 +334,70 @@ impl std::fmt::Debug for StablePoolOrder {
     }
 }
 
+/// Curve StableSwap or CryptoSwap pool with any number of tokens.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct CurvePoolOrder {
+    pub pool: shared::sources::curve::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for CurvePoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Curve Pool AMM {:?}", self.pool.address)
+    }
+}
+
+/// Balancer V3 weighted or stable pool with any number of tokens.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct BalancerV3PoolOrder {
+    pub pool: shared::sources::balancer_v3::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for BalancerV3PoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Balancer V3 Pool AMM {:?}", self.pool.address)
+    }
+}
+
+/// Solidly stable or volatile pool.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct SolidlyPoolOrder {
+    pub pool: shared::sources::solidly::Pool,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for SolidlyPoolOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Solidly Pool AMM {:?}", self.pool.address)
+    }
+}
+
+/// ERC-4626 vault converting between its asset and shares.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Derivative))]
+#[cfg_attr(test, derivative(PartialEq))]
+pub struct Erc4626VaultOrder {
+    pub vault: shared::sources::erc4626::Vault,
+    #[cfg_attr(test, derivative(PartialEq = "ignore"))]
+    pub settlement_handling: Arc<dyn SettlementHandling<Self>>,
+}
+
+impl std::fmt::Debug for Erc4626VaultOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ERC-4626 Vault {:?}", self.vault.address)
+    }
+}
+
 pub fn token_pairs<T>(reserves: &BTreeMap<H160, T>) -> Vec<TokenPair> {
     reserves
         .keys()
@@ -368,6 +441,38 @@ impl Settleable for StablePoolOrder {
     }
 }
 
+impl Settleable for CurvePoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
+impl Settleable for BalancerV3PoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
+impl Settleable for SolidlyPoolOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
+impl Settleable for Erc4626VaultOrder {
+    type Execution = AmmOrderExecution;
+
+    fn settlement_handling(&self) -> &dyn SettlementHandling<Self> {
+        &*self.settlement_handling
+    }
+}
+
 /// Concentrated type of liquidity with ticks (e.g. UniswapV3)
 #[derive(Clone)]
 #[cfg_attr(test, derive(Derivative))]
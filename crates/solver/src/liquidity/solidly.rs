@@ -0,0 +1,233 @@
+//! Module for providing Solidly stable and volatile pool liquidity to the
+//! solvers.
+
+use {
+    crate::{
+        interactions::{
+            SolidlySwapInteraction,
+            allowances::{AllowanceManager, AllowanceManaging, Allowances},
+        },
+        liquidity::{AmmOrderExecution, Liquidity, SettlementHandling, SolidlyPoolOrder},
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::{Result, ensure},
+    contracts::ISolidlyRouter,
+    model::TokenPair,
+    primitive_types::H160,
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::solidly::pool_fetching::SolidlyPoolFetching,
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+/// A liquidity provider for the pools of a Solidly factory.
+pub struct SolidlyLiquidity {
+    router: ISolidlyRouter,
+    factory: H160,
+    settlement: H160,
+    pool_fetcher: Arc<dyn SolidlyPoolFetching>,
+    allowance_manager: Box<dyn AllowanceManaging>,
+}
+
+impl SolidlyLiquidity {
+    pub fn new(
+        web3: Web3,
+        pool_fetcher: Arc<dyn SolidlyPoolFetching>,
+        settlement: H160,
+        router: ISolidlyRouter,
+        factory: H160,
+    ) -> Self {
+        let allowance_manager = AllowanceManager::new(web3, settlement);
+        Self {
+            router,
+            factory,
+            settlement,
+            pool_fetcher,
+            allowance_manager: Box::new(allowance_manager),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for SolidlyLiquidity {
+    /// Returns relevant Solidly pools given a list of off-chain orders.
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(pairs, block).await?;
+
+        let tokens = pools
+            .iter()
+            .flat_map(|pool| {
+                let (token0, token1) = pool.tokens.get();
+                [token0, token1]
+            })
+            .collect();
+        let allowances = self
+            .allowance_manager
+            .get_allowances(tokens, self.router.address())
+            .await?;
+
+        let inner = Arc::new(Inner {
+            router: self.router.clone(),
+            factory: self.factory,
+            settlement: self.settlement,
+            allowances,
+        });
+        Ok(pools
+            .into_iter()
+            .map(|pool| {
+                let settlement_handling = Arc::new(SettlementHandler {
+                    stable: pool.stable,
+                    inner: inner.clone(),
+                });
+                Liquidity::Solidly(SolidlyPoolOrder {
+                    pool,
+                    settlement_handling,
+                })
+            })
+            .collect())
+    }
+}
+
+pub struct SettlementHandler {
+    stable: bool,
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    router: ISolidlyRouter,
+    factory: H160,
+    settlement: H160,
+    allowances: Allowances,
+}
+
+impl SettlementHandler {
+    pub fn new(
+        stable: bool,
+        router: ISolidlyRouter,
+        factory: H160,
+        settlement: H160,
+        allowances: Allowances,
+    ) -> Self {
+        Self {
+            stable,
+            inner: Arc::new(Inner {
+                router,
+                factory,
+                settlement,
+                allowances,
+            }),
+        }
+    }
+
+    pub fn router(&self) -> &ISolidlyRouter {
+        &self.inner.router
+    }
+
+    pub fn factory(&self) -> H160 {
+        self.inner.factory
+    }
+
+    /// Returns the swap interaction for an execution.
+    ///
+    /// The router is used with exact input amounts, so the whole `input_max`
+    /// amount is sold for at least the `output` amount.
+    pub fn swap(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> Result<SolidlySwapInteraction> {
+        ensure!(input_max.token != output.token, "swap of identical tokens");
+        Ok(SolidlySwapInteraction {
+            router: self.inner.router.clone(),
+            factory: self.inner.factory,
+            stable: self.stable,
+            token_in: input_max.token,
+            token_out: output.token,
+            amount_in: input_max.amount,
+            amount_out_min: output.amount,
+            receiver: self.inner.settlement,
+        })
+    }
+}
+
+impl SettlementHandling<SolidlyPoolOrder> for SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let approval = self
+            .inner
+            .allowances
+            .approve_token(execution.input_max.clone())?;
+        let swap = self.swap(execution.input_max, execution.output)?;
+        if let Some(approval) = approval {
+            encoder.append_to_execution_plan_internalizable(
+                Arc::new(approval),
+                execution.internalizable,
+            );
+        }
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::interactions::allowances::Approval,
+        contracts::dummy_contract,
+        maplit::hashmap,
+        shared::{http_solver::model::InternalizationStrategy, interaction::Interaction},
+    };
+
+    #[test]
+    fn encode_approves_router_if_needed() {
+        let (token_in, token_out) = (H160([0x01; 20]), H160([0x02; 20]));
+        let router = dummy_contract!(ISolidlyRouter, [0x03; 20]);
+        let handler = SettlementHandler::new(
+            true,
+            router.clone(),
+            H160([0x04; 20]),
+            H160([0x05; 20]),
+            Allowances::new(router.address(), hashmap! { token_in => 100.into() }),
+        );
+
+        let mut encoder = SettlementEncoder::new(Default::default());
+        for amount in [100, 101] {
+            handler
+                .encode(
+                    AmmOrderExecution {
+                        input_max: TokenAmount::new(token_in, amount),
+                        output: TokenAmount::new(token_out, 90),
+                        internalizable: false,
+                    },
+                    &mut encoder,
+                )
+                .unwrap();
+        }
+
+        let [_, interactions, _] = encoder
+            .finish(InternalizationStrategy::SkipInternalizableInteraction)
+            .interactions;
+        assert_eq!(interactions.len(), 3);
+        assert_eq!(
+            interactions[1],
+            Approval {
+                token: token_in,
+                spender: router.address(),
+            }
+            .encode(),
+        );
+        assert_eq!(interactions[2].0, router.address());
+    }
+}
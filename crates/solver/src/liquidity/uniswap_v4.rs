@@ -0,0 +1,201 @@
+//! Module for providing Uniswap V4 pool liquidity to the solvers.
+
+use {
+    super::{AmmOrderExecution, ConcentratedLiquidity, SettlementHandling},
+    crate::{
+        interactions::{Erc20TransferInteraction, UniswapV4Interaction},
+        liquidity::Liquidity,
+        liquidity_collector::LiquidityCollecting,
+        settlement::SettlementEncoder,
+    },
+    anyhow::Result,
+    contracts::{ERC20, UniswapV4UniversalRouter},
+    model::TokenPair,
+    primitive_types::{H160, H256, U256},
+    shared::{
+        ethrpc::Web3,
+        http_solver::model::TokenAmount,
+        recent_block_cache::Block,
+        sources::{
+            uniswap_v3::{
+                graph_api::Token,
+                pool_fetching::{PoolInfo, PoolStats},
+            },
+            uniswap_v4::{PoolKey, pool_fetching::UniswapV4PoolFetching},
+        },
+        token_info::TokenInfoFetching,
+    },
+    std::{collections::HashSet, sync::Arc},
+};
+
+// 1h timeout for Uniswap V4 interactions
+const TIMEOUT: u32 = 3600;
+
+/// Rough estimate of the gas needed for transferring the input tokens to the
+/// router and swapping on a single pool.
+const MEAN_GAS: u64 = 150_000;
+
+pub struct UniswapV4Liquidity {
+    inner: Arc<Inner>,
+    pool_fetcher: Arc<dyn UniswapV4PoolFetching>,
+    token_infos: Arc<dyn TokenInfoFetching>,
+}
+
+pub struct Inner {
+    pub web3: Web3,
+    pub router: UniswapV4UniversalRouter,
+    pub pool_manager: H160,
+}
+
+impl UniswapV4Liquidity {
+    pub fn new(
+        web3: Web3,
+        router: UniswapV4UniversalRouter,
+        pool_manager: H160,
+        pool_fetcher: Arc<dyn UniswapV4PoolFetching>,
+        token_infos: Arc<dyn TokenInfoFetching>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                web3,
+                router,
+                pool_manager,
+            }),
+            pool_fetcher,
+            token_infos,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LiquidityCollecting for UniswapV4Liquidity {
+    /// Given a list of offchain orders returns the list of AMM liquidity to be
+    /// considered
+    async fn get_liquidity(
+        &self,
+        pairs: HashSet<TokenPair>,
+        block: Block,
+    ) -> Result<Vec<Liquidity>> {
+        let pools = self.pool_fetcher.fetch(&pairs, block).await?;
+        let tokens = pools
+            .iter()
+            .flat_map(|pool| pool.tokens)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let token_infos = self.token_infos.get_token_infos(&tokens).await;
+
+        Ok(pools
+            .into_iter()
+            .filter_map(|pool| {
+                let (token0, token1) = pool.tokens.get();
+                let token = |id: H160| {
+                    let decimals = token_infos.get(&id)?.decimals?;
+                    Some(Token { id, decimals })
+                };
+                let tokens = vec![token(token0)?, token(token1)?];
+
+                Some(Liquidity::Concentrated(ConcentratedLiquidity {
+                    tokens: pool.tokens,
+                    pool: PoolInfo {
+                        address: self.inner.pool_manager,
+                        tokens,
+                        state: pool.state,
+                        gas_stats: PoolStats {
+                            mean_gas: MEAN_GAS.into(),
+                        },
+                    },
+                    settlement_handling: Arc::new(UniswapV4SettlementHandler {
+                        inner: self.inner.clone(),
+                        id: pool.id,
+                        key: pool.key,
+                    }),
+                }))
+            })
+            .collect())
+    }
+}
+
+pub struct UniswapV4SettlementHandler {
+    pub inner: Arc<Inner>,
+    pub id: H256,
+    pub key: PoolKey,
+}
+
+impl UniswapV4SettlementHandler {
+    /// Returns the interactions for transferring the input tokens to the
+    /// router and swapping them.
+    ///
+    /// Swaps are encoded as exact input swaps, so the whole `input_max` amount
+    /// is sold for at least the `output` amount.
+    pub fn settle(
+        &self,
+        input_max: TokenAmount,
+        output: TokenAmount,
+    ) -> (Erc20TransferInteraction, UniswapV4Interaction) {
+        (
+            Erc20TransferInteraction {
+                token: ERC20::at(&self.inner.web3, input_max.token),
+                receiver: self.inner.router.address(),
+                amount: input_max.amount,
+            },
+            UniswapV4Interaction {
+                router: self.inner.router.clone(),
+                key: self.key,
+                token_amount_in: input_max,
+                token_amount_out_min: output,
+                deadline: U256::from(model::time::now_in_epoch_seconds().saturating_add(TIMEOUT)),
+            },
+        )
+    }
+}
+
+impl SettlementHandling<ConcentratedLiquidity> for UniswapV4SettlementHandler {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    // Creates the required interactions to convert the given input into output.
+    // Assumes slippage is already applied to the `input_max` field.
+    fn encode(&self, execution: AmmOrderExecution, encoder: &mut SettlementEncoder) -> Result<()> {
+        let (transfer, swap) = self.settle(execution.input_max, execution.output);
+        encoder
+            .append_to_execution_plan_internalizable(Arc::new(transfer), execution.internalizable);
+        encoder.append_to_execution_plan_internalizable(Arc::new(swap), execution.internalizable);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::dummy_contract, shared::interaction::Interaction};
+
+    #[test]
+    fn settle_transfers_input_to_router() {
+        let token_a = H160::from_low_u64_be(1);
+        let token_b = H160::from_low_u64_be(2);
+        let router = H160::from_low_u64_be(3);
+        let handler = UniswapV4SettlementHandler {
+            inner: Arc::new(Inner {
+                web3: ethrpc::dummy::web3(),
+                router: dummy_contract!(UniswapV4UniversalRouter, router),
+                pool_manager: H160::from_low_u64_be(4),
+            }),
+            id: H256::zero(),
+            key: PoolKey {
+                currency0: token_a,
+                currency1: token_b,
+                ..Default::default()
+            },
+        };
+
+        let (transfer, swap) = handler.settle(
+            TokenAmount::new(token_a, 100),
+            TokenAmount::new(token_b, 90),
+        );
+        assert_eq!(transfer.token.address(), token_a);
+        assert_eq!(transfer.receiver, router);
+        assert_eq!(transfer.amount, 100.into());
+        assert_eq!(swap.encode().0, router);
+    }
+}
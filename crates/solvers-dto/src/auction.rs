@@ -167,6 +167,9 @@ pub enum Liquidity {
     Stable(StablePool),
     ConcentratedLiquidity(ConcentratedLiquidityPool),
     LimitOrder(ForeignLimitOrder),
+    Curve(CurvePool),
+    Solidly(SolidlyPool),
+    Erc4626(Erc4626Vault),
 }
 
 #[serde_as]
@@ -244,12 +247,111 @@ pub struct StableReserve {
     pub scaling_factor: BigDecimal,
 }
 
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurvePool {
+    pub id: String,
+    pub address: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gas_estimate: U256,
+    /// The pool tokens in the order of the pool's coin indices.
+    pub tokens: Vec<CurveReserve>,
+    pub parameters: CurveParameters,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurveReserve {
+    pub address: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub balance: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub rate: U256,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CurveParameters {
+    #[serde(rename_all = "camelCase")]
+    StableSwap {
+        #[serde_as(as = "HexOrDecimalU256")]
+        amplification_parameter: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        fee: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        offpeg_fee_multiplier: U256,
+    },
+    #[serde(rename_all = "camelCase")]
+    CryptoSwap {
+        #[serde_as(as = "HexOrDecimalU256")]
+        a: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        gamma: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        d: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        price_scale: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        mid_fee: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        out_fee: U256,
+        #[serde_as(as = "HexOrDecimalU256")]
+        fee_gamma: U256,
+    },
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolidlyPool {
+    pub id: String,
+    pub address: H160,
+    pub router: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gas_estimate: U256,
+    pub tokens: HashMap<H160, SolidlyReserve>,
+    pub fee: BigDecimal,
+    pub stable: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolidlyReserve {
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub balance: U256,
+    pub decimals: u8,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc4626Vault {
+    pub id: String,
+    /// The vault address, which is also the address of the share token.
+    pub address: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub gas_estimate: U256,
+    pub asset: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub assets_to_shares: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub shares_to_assets: U256,
+}
+
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConcentratedLiquidityPool {
     pub id: String,
     pub address: H160,
+    /// The ID of a Uniswap V4 pool within its pool manager, since all pools
+    /// share the pool manager's address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_id: Option<H256>,
     pub router: H160,
     #[serde_as(as = "HexOrDecimalU256")]
     pub gas_estimate: U256,
@@ -84,6 +84,9 @@ pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
                     concentrated_liquidity_pool::to_domain(liquidity)
                 }
                 Liquidity::LimitOrder(liquidity) => Ok(foreign_limit_order::to_domain(liquidity)),
+                Liquidity::Curve(liquidity) => curve_pool::to_domain(liquidity),
+                Liquidity::Solidly(liquidity) => solidly_pool::to_domain(liquidity),
+                Liquidity::Erc4626(liquidity) => Ok(erc4626_vault::to_domain(liquidity)),
             })
             .try_collect()?,
         gas_price: auction::GasPrice(eth::Ether(auction.effective_gas_price)),
@@ -243,6 +246,113 @@ mod concentrated_liquidity_pool {
     }
 }
 
+mod curve_pool {
+    use super::*;
+
+    pub fn to_domain(pool: &CurvePool) -> Result<liquidity::Liquidity, Error> {
+        let reserves = liquidity::curve::Reserves::new(
+            pool.tokens
+                .iter()
+                .map(|token| liquidity::curve::Reserve {
+                    asset: eth::Asset {
+                        token: eth::TokenAddress(token.address),
+                        amount: token.balance,
+                    },
+                    rate: token.rate,
+                })
+                .collect(),
+        )
+        .ok_or("invalid Curve pool tokens")?;
+
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(pool.id.clone()),
+            address: pool.address,
+            gas: eth::Gas(pool.gas_estimate),
+            state: liquidity::State::Curve(liquidity::curve::Pool {
+                reserves,
+                kind: match pool.parameters {
+                    CurveParameters::StableSwap {
+                        amplification_parameter,
+                        fee,
+                        offpeg_fee_multiplier,
+                    } => liquidity::curve::Kind::StableSwap {
+                        amplification_parameter,
+                        fee,
+                        offpeg_fee_multiplier,
+                    },
+                    CurveParameters::CryptoSwap {
+                        a,
+                        gamma,
+                        d,
+                        price_scale,
+                        mid_fee,
+                        out_fee,
+                        fee_gamma,
+                    } => liquidity::curve::Kind::CryptoSwap {
+                        a,
+                        gamma,
+                        d,
+                        price_scale,
+                        mid_fee,
+                        out_fee,
+                        fee_gamma,
+                    },
+                },
+            }),
+        })
+    }
+}
+
+mod solidly_pool {
+    use {super::*, itertools::Itertools};
+
+    pub fn to_domain(pool: &SolidlyPool) -> Result<liquidity::Liquidity, Error> {
+        let reserves = {
+            let (a, b) = pool
+                .tokens
+                .iter()
+                .map(|(token, reserve)| liquidity::solidly::Reserve {
+                    asset: eth::Asset {
+                        token: eth::TokenAddress(*token),
+                        amount: reserve.balance,
+                    },
+                    decimals: reserve.decimals,
+                })
+                .collect_tuple()
+                .ok_or("invalid number of Solidly pool tokens")?;
+            liquidity::solidly::Reserves::new(a, b).ok_or("invalid Solidly pool reserves")?
+        };
+
+        Ok(liquidity::Liquidity {
+            id: liquidity::Id(pool.id.clone()),
+            address: pool.address,
+            gas: eth::Gas(pool.gas_estimate),
+            state: liquidity::State::Solidly(liquidity::solidly::Pool {
+                reserves,
+                fee: conv::decimal_to_rational(&pool.fee).ok_or("invalid Solidly pool fee")?,
+                stable: pool.stable,
+            }),
+        })
+    }
+}
+
+mod erc4626_vault {
+    use super::*;
+
+    pub fn to_domain(vault: &Erc4626Vault) -> liquidity::Liquidity {
+        liquidity::Liquidity {
+            id: liquidity::Id(vault.id.clone()),
+            address: vault.address,
+            gas: eth::Gas(vault.gas_estimate),
+            state: liquidity::State::Erc4626(liquidity::erc4626::Vault {
+                asset: eth::TokenAddress(vault.asset),
+                assets_to_shares: vault.assets_to_shares,
+                shares_to_assets: vault.shares_to_assets,
+            }),
+        }
+    }
+}
+
 mod foreign_limit_order {
     use super::*;
 
@@ -94,6 +94,30 @@ impl<'a> Solver<'a> {
         solver::Route::new(segments)
     }
 
+    /// Updates the state of the liquidity with the specified ID to reflect that
+    /// it was used to swap `input` for `output`. This allows routing multiple
+    /// orders over the same liquidity within a single settlement.
+    ///
+    /// Returns `None` if the swap cannot be applied to the liquidity state.
+    pub fn apply(
+        &mut self,
+        id: &liquidity::Id,
+        input: eth::Asset,
+        output: eth::Asset,
+    ) -> Option<()> {
+        // Multi-token pools have a copy of their state for each token pair,
+        // so make sure to update all of them.
+        for liquidity in self
+            .onchain_liquidity
+            .values_mut()
+            .flatten()
+            .filter(|liquidity| liquidity.id == *id)
+        {
+            liquidity.source.apply(input, output)?;
+        }
+        Some(())
+    }
+
     fn traverse_path(
         &self,
         path: &[&OnchainLiquidity],
@@ -192,6 +216,49 @@ fn to_boundary_liquidity(
                         }
                     }
                 }
+                liquidity::State::Curve(pool) => {
+                    let boundary_pool =
+                        boundary::liquidity::curve::to_boundary_pool(liquidity.address, pool);
+                    for pair in pool.reserves.token_pairs() {
+                        let token_pair = to_boundary_token_pair(&pair);
+                        onchain_liquidity
+                            .entry(token_pair)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair,
+                                source: LiquiditySource::Curve(boundary_pool.clone()),
+                            });
+                    }
+                }
+                liquidity::State::Solidly(pool) => {
+                    if let Some(boundary_pool) =
+                        boundary::liquidity::solidly::to_boundary_pool(liquidity.address, pool)
+                    {
+                        onchain_liquidity
+                            .entry(boundary_pool.tokens)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair: boundary_pool.tokens,
+                                source: LiquiditySource::Solidly(boundary_pool),
+                            });
+                    }
+                }
+                liquidity::State::Erc4626(vault) => {
+                    let boundary_vault =
+                        boundary::liquidity::erc4626::to_boundary_vault(liquidity.address, vault);
+                    if let Some(token_pair) = boundary_vault.tokens() {
+                        onchain_liquidity
+                            .entry(token_pair)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair,
+                                source: LiquiditySource::Erc4626(boundary_vault),
+                            });
+                    }
+                }
                 liquidity::State::LimitOrder(limit_order) => {
                     if let Some(token_pair) =
                         TokenPair::new(limit_order.maker.token.0, limit_order.taker.token.0)
@@ -206,8 +273,20 @@ fn to_boundary_liquidity(
                             })
                     }
                 }
-                // The baseline solver does not currently support other AMMs.
-                _ => {}
+                liquidity::State::Concentrated(pool) => {
+                    if let Some(boundary_pool) =
+                        boundary::liquidity::concentrated::to_boundary_pool(liquidity.address, pool)
+                    {
+                        onchain_liquidity
+                            .entry(boundary_pool.tokens)
+                            .or_default()
+                            .push(OnchainLiquidity {
+                                id: liquidity.id.clone(),
+                                token_pair: boundary_pool.tokens,
+                                source: LiquiditySource::Concentrated(boundary_pool),
+                            });
+                    }
+                }
             };
             onchain_liquidity
         })
@@ -225,15 +304,88 @@ enum LiquiditySource {
     ConstantProduct(boundary::liquidity::constant_product::Pool),
     WeightedProduct(boundary::liquidity::weighted_product::Pool),
     Stable(boundary::liquidity::stable::Pool),
+    Concentrated(boundary::liquidity::concentrated::Pool),
+    Curve(boundary::liquidity::curve::Pool),
+    Solidly(boundary::liquidity::solidly::Pool),
+    Erc4626(boundary::liquidity::erc4626::Vault),
     LimitOrder(liquidity::limit_order::LimitOrder),
 }
 
+impl LiquiditySource {
+    /// Updates the liquidity state to reflect a swap of `input` for `output`.
+    fn apply(&mut self, input: eth::Asset, output: eth::Asset) -> Option<()> {
+        match self {
+            LiquiditySource::ConstantProduct(pool) => {
+                let input_amount = u128::try_from(input.amount).ok()?;
+                let output_amount = u128::try_from(output.amount).ok()?;
+                let (reserve_in, reserve_out) = if input.token.0 == pool.tokens.get().0 {
+                    (&mut pool.reserves.0, &mut pool.reserves.1)
+                } else {
+                    (&mut pool.reserves.1, &mut pool.reserves.0)
+                };
+                *reserve_in = reserve_in.checked_add(input_amount)?;
+                *reserve_out = reserve_out.checked_sub(output_amount)?;
+            }
+            LiquiditySource::WeightedProduct(pool) => {
+                let balance_in = &mut pool.reserves.get_mut(&input.token.0)?.common.balance;
+                *balance_in = balance_in.checked_add(input.amount)?;
+                let balance_out = &mut pool.reserves.get_mut(&output.token.0)?.common.balance;
+                *balance_out = balance_out.checked_sub(output.amount)?;
+            }
+            LiquiditySource::Stable(pool) => {
+                let balance_in = &mut pool.reserves.get_mut(&input.token.0)?.balance;
+                *balance_in = balance_in.checked_add(input.amount)?;
+                let balance_out = &mut pool.reserves.get_mut(&output.token.0)?.balance;
+                *balance_out = balance_out.checked_sub(output.amount)?;
+            }
+            LiquiditySource::Concentrated(pool) => {
+                pool.apply_swap(output.token.0, (input.amount, input.token.0))?;
+            }
+            LiquiditySource::Curve(pool) => {
+                let balance_in = &mut pool
+                    .tokens
+                    .iter_mut()
+                    .find(|token| token.address == input.token.0)?
+                    .balance;
+                *balance_in = balance_in.checked_add(input.amount)?;
+                let balance_out = &mut pool
+                    .tokens
+                    .iter_mut()
+                    .find(|token| token.address == output.token.0)?
+                    .balance;
+                *balance_out = balance_out.checked_sub(output.amount)?;
+            }
+            LiquiditySource::Solidly(pool) => {
+                let (reserve_in, reserve_out) = if input.token.0 == pool.tokens.get().0 {
+                    (&mut pool.reserves.0, &mut pool.reserves.1)
+                } else {
+                    (&mut pool.reserves.1, &mut pool.reserves.0)
+                };
+                *reserve_in = reserve_in.checked_add(input.amount)?;
+                *reserve_out = reserve_out.checked_sub(output.amount)?;
+            }
+            // Vault conversion rates are not affected by deposits and
+            // redemptions.
+            LiquiditySource::Erc4626(_) => {}
+            LiquiditySource::LimitOrder(limit_order) => {
+                limit_order.maker.amount = limit_order.maker.amount.checked_sub(output.amount)?;
+                limit_order.taker.amount = limit_order.taker.amount.checked_sub(input.amount)?;
+            }
+        }
+        Some(())
+    }
+}
+
 impl BaselineSolvable for OnchainLiquidity {
     fn get_amount_out(&self, out_token: H160, input: (U256, H160)) -> Option<U256> {
         match &self.source {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_out(out_token, input),
             LiquiditySource::Stable(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Concentrated(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Curve(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Solidly(pool) => pool.get_amount_out(out_token, input),
+            LiquiditySource::Erc4626(vault) => vault.get_amount_out(out_token, input),
             LiquiditySource::LimitOrder(limit_order) => {
                 limit_order.get_amount_out(out_token, input)
             }
@@ -245,6 +397,10 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::WeightedProduct(pool) => pool.get_amount_in(in_token, out),
             LiquiditySource::Stable(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Concentrated(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Curve(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Solidly(pool) => pool.get_amount_in(in_token, out),
+            LiquiditySource::Erc4626(vault) => vault.get_amount_in(in_token, out),
             LiquiditySource::LimitOrder(limit_order) => limit_order.get_amount_in(in_token, out),
         }
     }
@@ -254,6 +410,10 @@ impl BaselineSolvable for OnchainLiquidity {
             LiquiditySource::ConstantProduct(pool) => pool.gas_cost(),
             LiquiditySource::WeightedProduct(pool) => pool.gas_cost(),
             LiquiditySource::Stable(pool) => pool.gas_cost(),
+            LiquiditySource::Concentrated(pool) => pool.gas_cost(),
+            LiquiditySource::Curve(pool) => pool.gas_cost(),
+            LiquiditySource::Solidly(pool) => pool.gas_cost(),
+            LiquiditySource::Erc4626(vault) => vault.gas_cost(),
             LiquiditySource::LimitOrder(limit_order) => limit_order.gas_cost(),
         }
     }
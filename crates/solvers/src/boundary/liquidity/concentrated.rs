@@ -0,0 +1,42 @@
+pub use shared::sources::uniswap_v3::swap::Pool;
+use {
+    crate::domain::liquidity,
+    ethereum_types::{H160, U256},
+    model::TokenPair,
+};
+
+/// Converts a domain pool into a [`shared`] Uniswap V3 pool. Returns `None` if
+/// the domain pool cannot be represented as a boundary pool.
+pub fn to_boundary_pool(address: H160, pool: &liquidity::concentrated::Pool) -> Option<Pool> {
+    let (token0, token1) = pool.tokens.get();
+    let tokens = TokenPair::new(token0.0, token1.0).expect("tokens are distinct by construction");
+
+    // Uniswap V3 fees are specified in hundredths of a bip, so make sure that
+    // the fee can be represented exactly in those units.
+    let fee = {
+        let pips = pool.fee.0.numer().checked_mul(U256::from(1_000_000))?;
+        let denom = *pool.fee.0.denom();
+        if denom.is_zero() || !(pips % denom).is_zero() {
+            return None;
+        }
+        let fee = pips / denom;
+        if fee >= U256::from(1_000_000) {
+            return None;
+        }
+        fee.as_u32()
+    };
+
+    Some(Pool {
+        address,
+        tokens,
+        sqrt_price: pool.sqrt_price.0,
+        liquidity: pool.liquidity.0,
+        tick: pool.tick.0,
+        liquidity_net: pool
+            .liquidity_net
+            .iter()
+            .map(|(tick, liquidity_net)| (tick.0, liquidity_net.0))
+            .collect(),
+        fee,
+    })
+}
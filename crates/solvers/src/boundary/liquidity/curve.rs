@@ -0,0 +1,50 @@
+pub use shared::sources::curve::Pool;
+use {
+    crate::domain::liquidity,
+    ethereum_types::H160,
+    shared::sources::curve::{PoolKind, Token, crypto, stable},
+};
+
+/// Converts a domain pool into a [`shared`] Curve pool.
+pub fn to_boundary_pool(address: H160, pool: &liquidity::curve::Pool) -> Pool {
+    Pool {
+        address,
+        tokens: pool
+            .reserves
+            .iter()
+            .map(|reserve| Token {
+                address: reserve.asset.token.0,
+                balance: reserve.asset.amount,
+                rate: reserve.rate,
+            })
+            .collect(),
+        kind: match pool.kind {
+            liquidity::curve::Kind::StableSwap {
+                amplification_parameter,
+                fee,
+                offpeg_fee_multiplier,
+            } => PoolKind::StableSwap(stable::Parameters {
+                amplification_parameter,
+                fee,
+                offpeg_fee_multiplier,
+            }),
+            liquidity::curve::Kind::CryptoSwap {
+                a,
+                gamma,
+                d,
+                price_scale,
+                mid_fee,
+                out_fee,
+                fee_gamma,
+            } => PoolKind::CryptoSwap(crypto::Parameters {
+                a,
+                gamma,
+                d,
+                price_scale,
+                mid_fee,
+                out_fee,
+                fee_gamma,
+            }),
+        },
+    }
+}
@@ -0,0 +1,12 @@
+pub use shared::sources::erc4626::Vault;
+use {crate::domain::liquidity, ethereum_types::H160};
+
+/// Converts a domain vault into a [`shared`] ERC-4626 vault.
+pub fn to_boundary_vault(address: H160, vault: &liquidity::erc4626::Vault) -> Vault {
+    Vault {
+        address,
+        asset: vault.asset.0,
+        assets_to_shares: vault.assets_to_shares,
+        shares_to_assets: vault.shares_to_assets,
+    }
+}
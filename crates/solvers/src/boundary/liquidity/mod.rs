@@ -1,4 +1,8 @@
+pub mod concentrated;
 pub mod constant_product;
+pub mod curve;
+pub mod erc4626;
 mod limit_order;
+pub mod solidly;
 pub mod stable;
 pub mod weighted_product;
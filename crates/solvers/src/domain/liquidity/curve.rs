@@ -0,0 +1,73 @@
+use {
+    crate::domain::{eth, liquidity},
+    ethereum_types::U256,
+    itertools::Itertools as _,
+};
+
+/// The state of a Curve StableSwap or CryptoSwap pool.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub reserves: Reserves,
+    pub kind: Kind,
+}
+
+/// A representation of Curve pool reserves.
+///
+/// Unlike Balancer pools, the reserves are kept in the order of the pool's
+/// coin indices, since this order is needed for encoding swaps.
+#[derive(Clone, Debug)]
+pub struct Reserves(Vec<Reserve>);
+
+impl Reserves {
+    /// Returns a new reserve instance for specified reserve entries. Returns
+    /// `None` if there are fewer than two tokens or if it encounters duplicate
+    /// entries for a token.
+    pub fn new(reserves: Vec<Reserve>) -> Option<Self> {
+        if reserves.len() < 2 || !reserves.iter().map(|r| r.asset.token).all_unique() {
+            return None;
+        }
+
+        Some(Self(reserves))
+    }
+
+    /// Returns an iterator over the token reserves.
+    pub fn iter(&self) -> impl Iterator<Item = Reserve> + '_ {
+        self.0.iter().cloned()
+    }
+
+    /// Returns an iterator over the tokens pairs handled by the pool reserves.
+    pub fn token_pairs(&self) -> impl Iterator<Item = liquidity::TokenPair> + '_ {
+        self.0
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| liquidity::TokenPair::new(a.asset.token, b.asset.token).expect("a != b"))
+    }
+}
+
+/// A Curve pool token reserve.
+#[derive(Clone, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The rate scaled by 1e18 that normalizes the token balance to 18
+    /// decimals.
+    pub rate: U256,
+}
+
+/// The kind of Curve pool along with its invariant parameters.
+#[derive(Clone, Debug)]
+pub enum Kind {
+    StableSwap {
+        amplification_parameter: U256,
+        fee: U256,
+        offpeg_fee_multiplier: U256,
+    },
+    CryptoSwap {
+        a: U256,
+        gamma: U256,
+        d: U256,
+        price_scale: U256,
+        mid_fee: U256,
+        out_fee: U256,
+        fee_gamma: U256,
+    },
+}
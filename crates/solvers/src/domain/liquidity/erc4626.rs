@@ -0,0 +1,16 @@
+//! ERC-4626 vault.
+
+use {crate::domain::eth, ethereum_types::U256};
+
+/// The conversion state of an ERC-4626 vault. The vault address, which is
+/// also the address of the share token, is the liquidity address.
+#[derive(Clone, Debug)]
+pub struct Vault {
+    pub asset: eth::TokenAddress,
+    /// The shares received for `1e18` assets, as returned by
+    /// `previewDeposit` and discounted by a safety margin.
+    pub assets_to_shares: U256,
+    /// The assets received for `1e18` shares, as returned by `previewRedeem`
+    /// and discounted by a safety margin.
+    pub shares_to_assets: U256,
+}
@@ -2,7 +2,10 @@
 
 pub mod concentrated;
 pub mod constant_product;
+pub mod curve;
+pub mod erc4626;
 pub mod limit_order;
+pub mod solidly;
 pub mod stable;
 pub mod weighted_product;
 
@@ -29,6 +32,9 @@ pub enum State {
     Stable(stable::Pool),
     Concentrated(concentrated::Pool),
     LimitOrder(limit_order::LimitOrder),
+    Curve(curve::Pool),
+    Solidly(solidly::Pool),
+    Erc4626(erc4626::Vault),
 }
 
 /// An ordered token pair.
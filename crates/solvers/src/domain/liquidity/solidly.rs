@@ -0,0 +1,54 @@
+//! Solidly stable or volatile pool.
+
+use {
+    crate::domain::{eth, liquidity},
+    std::cmp::Ordering,
+};
+
+/// Velodrome V2 or Aerodrome like pool state.
+#[derive(Clone, Debug)]
+pub struct Pool {
+    pub reserves: Reserves,
+    pub fee: eth::Rational,
+    /// Whether the pool uses the `x³y + y³x = k` stable invariant instead of
+    /// the constant product one.
+    pub stable: bool,
+}
+
+impl Pool {
+    /// Returns the pool's token pair.
+    pub fn tokens(&self) -> liquidity::TokenPair {
+        liquidity::TokenPair::new(self.reserves.0.asset.token, self.reserves.1.asset.token)
+            .expect("pool reserve assets have different tokens")
+    }
+}
+
+/// Solidly pool reserves.
+#[derive(Clone, Debug)]
+pub struct Reserves(Reserve, Reserve);
+
+impl Reserves {
+    /// Creates a new Solidly pool reserves with the specified reserves.
+    /// Returns `None` if the reserves are denominated in the same token.
+    pub fn new(a: Reserve, b: Reserve) -> Option<Self> {
+        match a.asset.token.cmp(&b.asset.token) {
+            Ordering::Less => Some(Self(a, b)),
+            Ordering::Equal => None,
+            Ordering::Greater => Some(Self(b, a)),
+        }
+    }
+
+    /// Get the reserves.
+    pub fn get(&self) -> (Reserve, Reserve) {
+        (self.0, self.1)
+    }
+}
+
+/// A Solidly pool token reserve.
+#[derive(Clone, Copy, Debug)]
+pub struct Reserve {
+    pub asset: eth::Asset,
+    /// The token decimals, used by stable pools for normalizing the reserves
+    /// to 18 decimals.
+    pub decimals: u8,
+}
@@ -4,7 +4,10 @@ use {
         util,
     },
     ethereum_types::{Address, U256},
-    std::{collections::HashMap, slice},
+    std::{
+        collections::{HashMap, HashSet},
+        slice,
+    },
 };
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -37,6 +40,76 @@ impl Solution {
         }
     }
 
+    /// Merges another solution into this one. Returns `None` if the solutions
+    /// are not compatible, in which case `self` is left unchanged.
+    ///
+    /// Solutions are compatible if they settle distinct orders and their
+    /// clearing prices can be unified. For that, the prices of `other` are
+    /// rescaled so that the price of a token that both solutions share matches.
+    /// Any additional shared token must already have consistent prices. Note
+    /// that rescaling rounds in favour of the settlement, so the limit prices
+    /// of the orders of `other` are verified again with the merged prices.
+    ///
+    /// Since buffer internalizations depend on all interactions of a solution,
+    /// they are reset and need to be recomputed for the merged solution.
+    pub fn merge(&mut self, other: Self) -> Option<()> {
+        let uids = self
+            .trades
+            .iter()
+            .filter_map(|trade| match trade {
+                Trade::Fulfillment(fulfillment) => Some(fulfillment.order().uid),
+                Trade::Jit(_) => None,
+            })
+            .collect::<HashSet<_>>();
+        let distinct = other.trades.iter().all(|trade| match trade {
+            Trade::Fulfillment(fulfillment) => !uids.contains(&fulfillment.order().uid),
+            Trade::Jit(_) => true,
+        });
+        if !distinct {
+            return None;
+        }
+
+        let prices = self.prices.merge(&other)?;
+        let satisfied = other.trades.iter().all(|trade| match trade {
+            Trade::Fulfillment(fulfillment) => fulfillment.satisfies_limit_price(&prices),
+            // JIT orders are created by the solver, and their prices can't be
+            // changed after the fact.
+            Trade::Jit(_) => false,
+        });
+        if !satisfied {
+            return None;
+        }
+
+        let Self {
+            id: _,
+            prices: _,
+            trades,
+            pre_interactions,
+            interactions,
+            post_interactions,
+            gas,
+            flashloans,
+        } = other;
+        self.prices = prices;
+        self.trades.extend(trades);
+        self.pre_interactions.extend(pre_interactions);
+        self.interactions.extend(interactions);
+        self.post_interactions.extend(post_interactions);
+        self.gas = self
+            .gas
+            .zip(gas)
+            .map(|(a, b)| eth::Gas(a.0.saturating_add(b.0)));
+        self.flashloans.extend(flashloans);
+
+        for interaction in self.interactions.iter_mut() {
+            match interaction {
+                Interaction::Liquidity(interaction) => interaction.internalize = false,
+                Interaction::Custom(interaction) => interaction.internalize = false,
+            }
+        }
+        Some(())
+    }
+
     /// Returns `self` with eligible interactions internalized using the
     /// Settlement contract buffers.
     ///
@@ -209,6 +282,64 @@ impl ClearingPrices {
     pub fn new(prices: impl IntoIterator<Item = (eth::TokenAddress, U256)>) -> Self {
         Self(prices.into_iter().collect())
     }
+
+    /// Computes the clearing prices for merging the solution `other` with a
+    /// solution with these prices. Returns `None` if the prices cannot be
+    /// unified. See [`Solution::merge`] for more details.
+    fn merge(&self, other: &Solution) -> Option<Self> {
+        let shared = other
+            .prices
+            .0
+            .iter()
+            .filter_map(|(token, theirs)| Some((*theirs, *self.0.get(token)?)))
+            .collect::<Vec<_>>();
+
+        // Rescale the other prices by `ours / theirs` for the first shared
+        // token, making sure any remaining shared token is consistent with it.
+        let (numerator, denominator) = match shared.as_slice() {
+            [] => (U256::one(), U256::one()),
+            [(theirs, ours), rest @ ..] => {
+                let consistent = rest
+                    .iter()
+                    .all(|(t, o)| t.full_mul(*ours) == o.full_mul(*theirs));
+                if theirs.is_zero() || !consistent {
+                    return None;
+                }
+                (*ours, *theirs)
+            }
+        };
+
+        let mut prices = self.0.clone();
+        for (token, price) in &other.prices.0 {
+            if prices.contains_key(token) {
+                continue;
+            }
+
+            // Round the price of tokens that are bought up and of tokens that
+            // are sold down, so that the orders never receive more or pay less
+            // than what their routes provide.
+            let (bought, sold) = other.trades.iter().fold((false, false), |acc, trade| {
+                let (sell, buy) = match trade {
+                    Trade::Fulfillment(fulfillment) => (
+                        fulfillment.order().sell.token,
+                        fulfillment.order().buy.token,
+                    ),
+                    Trade::Jit(jit) => (jit.order.sell.token, jit.order.buy.token),
+                };
+                (acc.0 || buy == *token, acc.1 || sell == *token)
+            });
+            let scaled = match (bought, sold) {
+                (true, true) => return None,
+                (true, false) => util::math::div_ceil(price.checked_mul(numerator)?, denominator)?,
+                (false, _) => price.checked_mul(numerator)? / denominator,
+            };
+            if scaled.is_zero() {
+                return None;
+            }
+            prices.insert(*token, scaled);
+        }
+        Some(Self(prices))
+    }
 }
 
 /// A trade which executes an order as part of this solution.
@@ -280,6 +411,32 @@ impl Fulfillment {
         }
     }
 
+    /// Returns whether the order's limit price is satisfied when executing it
+    /// at the specified clearing prices, accounting for solver computed fees.
+    fn satisfies_limit_price(&self, prices: &ClearingPrices) -> bool {
+        let order = &self.order;
+        let satisfied = || {
+            let sell_price = *prices.0.get(&order.sell.token)?;
+            let buy_price = *prices.0.get(&order.buy.token)?;
+            let fee = self.fee.surplus().unwrap_or_default();
+            let (sell, buy) = match order.side {
+                order::Side::Buy => (
+                    util::math::div_ceil(self.executed.checked_mul(buy_price)?, sell_price)?
+                        .checked_add(fee)?,
+                    self.executed,
+                ),
+                order::Side::Sell => (
+                    self.executed.checked_add(fee)?,
+                    self.executed
+                        .checked_mul(sell_price)?
+                        .checked_div(buy_price)?,
+                ),
+            };
+            Some(order.sell.amount.checked_mul(buy)? >= order.buy.amount.checked_mul(sell)?)
+        };
+        satisfied().unwrap_or(false)
+    }
+
     /// Returns the solver computed fee that was charged to the order as an
     /// asset (token address and amount). Returns `None` if the fulfillment
     /// does not include a solver computed fee.
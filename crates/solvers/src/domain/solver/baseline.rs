@@ -0,0 +1,324 @@
+//! "Baseline" solver implementation.
+//!
+//! The baseline solver is a simple solver implementation that finds the best
+//! path of at most length `max_hops + 1` over a set of on-chain liquidity. It
+//! **does not** try to split large orders into multiple parts and route them
+//! over separate paths.
+//!
+//! In addition to the individual solutions for each order, the baseline solver
+//! proposes a single solution settling all orders that can be executed together
+//! so that the settlement overhead is only paid once.
+
+use {
+    super::{ADDITIONAL_TRADE_GAS, Config, Request},
+    crate::{
+        boundary,
+        domain::{
+            auction,
+            eth,
+            order::{self, Order},
+            solution,
+        },
+    },
+    ethereum_types::U256,
+    std::{cmp, collections::HashSet},
+};
+
+pub struct Baseline {
+    pub(super) weth: eth::WethAddress,
+
+    /// Set of tokens to additionally consider as intermediary hops when
+    /// path-finding. This allows paths of the kind `TOKEN1 -> WETH -> TOKEN2`
+    /// to be considered.
+    pub(super) base_tokens: HashSet<eth::TokenAddress>,
+
+    /// Maximum number of hops that can be considered in a trading path. A hop
+    /// is an intermediary token within a trading path. For example:
+    /// - A value of 0 indicates that only a direct trade is allowed: `A -> B`
+    /// - A value of 1 indicates that a single intermediary token can appear
+    ///   within a trading path: `A -> B -> C`
+    /// - A value of 2 indicates: `A -> B -> C -> D`
+    /// - etc.
+    pub(super) max_hops: usize,
+
+    /// The maximum number of attempts to solve a partially fillable order.
+    /// Basically we continuously halve the amount to execute until we find a
+    /// valid solution or exceed this count.
+    max_partial_attempts: usize,
+
+    /// Units of gas that get added to the gas estimate for executing a
+    /// computed trade route to arrive at a gas estimate for a whole settlement.
+    pub(super) solution_gas_offset: eth::SignedGas,
+
+    /// The amount of the native token to use to estimate native price of a
+    /// token
+    native_token_price_estimation_amount: eth::U256,
+}
+
+impl Baseline {
+    /// Creates a new baseline solver for the specified configuration.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            weth: config.weth,
+            base_tokens: config.base_tokens.iter().copied().collect(),
+            max_hops: config.max_hops,
+            max_partial_attempts: config.max_partial_attempts,
+            solution_gas_offset: config.solution_gas_offset,
+            native_token_price_estimation_amount: config.native_token_price_estimation_amount,
+        }
+    }
+
+    pub(super) fn solve(
+        &self,
+        auction: auction::Auction,
+        sender: tokio::sync::mpsc::UnboundedSender<solution::Solution>,
+    ) {
+        let boundary_solver =
+            boundary::baseline::Solver::new(&self.weth, &self.base_tokens, &auction.liquidity);
+
+        let mut solved = Vec::new();
+        for (i, order) in auction.orders.iter().enumerate() {
+            let solution = self
+                .solve_order(&auction, &boundary_solver, order, self.solution_gas_offset)
+                .map(|solution| solution.with_id(solution::Id(i as u64)));
+            if let Some(solution) = solution {
+                solved.push(order);
+                if sender.send(solution).is_err() {
+                    tracing::debug!("deadline hit, receiver dropped");
+                    return;
+                }
+            }
+        }
+
+        if solved.len() < 2 {
+            return;
+        }
+        if let Some(solution) = self.solve_merged(&auction, &solved) {
+            let solution = solution.with_id(solution::Id(auction.orders.len() as u64));
+            if sender.send(solution).is_err() {
+                tracing::debug!("deadline hit, receiver dropped");
+            }
+        }
+    }
+
+    /// Computes a solution settling as many of the specified orders together
+    /// as possible. Orders are routed one after the other, accounting for the
+    /// changes in liquidity state caused by the routes of the previous orders,
+    /// and their solutions are merged when their clearing prices are
+    /// compatible. Returns `None` if fewer than two orders could be merged.
+    fn solve_merged(
+        &self,
+        auction: &auction::Auction,
+        orders: &[&Order],
+    ) -> Option<solution::Solution> {
+        let mut boundary_solver =
+            boundary::baseline::Solver::new(&self.weth, &self.base_tokens, &auction.liquidity);
+        let additional_trade_gas =
+            eth::SignedGas::from(i64::try_from(ADDITIONAL_TRADE_GAS).unwrap_or(i64::MAX));
+
+        let mut merged: Option<solution::Solution> = None;
+        let mut count = 0;
+        for order in orders {
+            // Only the first order pays for the settlement overhead, subsequent
+            // orders only pay for their additional trade.
+            let gas_offset = match merged {
+                Some(_) => additional_trade_gas,
+                None => self.solution_gas_offset,
+            };
+            let Some(solution) = self.solve_order(auction, &boundary_solver, order, gas_offset)
+            else {
+                continue;
+            };
+
+            let swaps = solution
+                .interactions
+                .iter()
+                .filter_map(|interaction| match interaction {
+                    solution::Interaction::Liquidity(interaction) => Some((
+                        interaction.liquidity.id.clone(),
+                        interaction.input,
+                        interaction.output,
+                    )),
+                    solution::Interaction::Custom(_) => None,
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(merged) = merged.as_mut() {
+                if merged.merge(solution).is_none() {
+                    tracing::trace!(order =% order.uid, "incompatible with merged solution");
+                    continue;
+                }
+            } else {
+                merged = Some(solution);
+            }
+            count += 1;
+
+            let applied = swaps
+                .into_iter()
+                .all(|(id, input, output)| boundary_solver.apply(&id, input, output).is_some());
+            if !applied {
+                // The liquidity state no longer reflects the merged solution,
+                // so we can't route any more orders on top of it.
+                tracing::debug!("failed to update liquidity state for merged solution");
+                break;
+            }
+        }
+
+        let merged = merged.filter(|_| count > 1)?;
+        Some(merged.with_buffers_internalizations(&auction.tokens))
+    }
+
+    /// Computes a solution settling a single order by routing it over the
+    /// on-chain liquidity of the auction. The `gas_offset` is added to the gas
+    /// of the route to account for the rest of the settlement.
+    pub(super) fn solve_order(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        order: &Order,
+        gas_offset: eth::SignedGas,
+    ) -> Option<solution::Solution> {
+        let sell_token_price = self.sell_token_price(auction, boundary_solver, order)?;
+
+        self.requests_for_order(order).find_map(|request| {
+            tracing::trace!(order =% order.uid, ?request, "finding route");
+
+            let route = boundary_solver.route(request, self.max_hops)?;
+            let interactions = route.interactions();
+
+            // The baseline solver generates a path with swapping
+            // for exact output token amounts. This leads to
+            // potential rounding errors for buy orders, where we
+            // can buy slightly more than intended. Fix this by
+            // capping the output amount to the order's buy amount
+            // for buy orders.
+            let mut output = route.output();
+            if let order::Side::Buy = order.side {
+                output.amount = cmp::min(output.amount, order.buy.amount);
+            }
+
+            let gas = route.gas() + gas_offset;
+            let fee = sell_token_price
+                .ether_value(eth::Ether(gas.0.checked_mul(auction.gas_price.0.0)?))?
+                .into();
+
+            Some(
+                solution::Single {
+                    order: order.clone(),
+                    input: route.input(),
+                    output,
+                    interactions,
+                    gas,
+                }
+                .into_solution(fee)?
+                .with_buffers_internalizations(&auction.tokens),
+            )
+        })
+    }
+
+    /// Returns the price of the order's sell token in the native token. This
+    /// uses the auction's reference price if available and falls back to
+    /// estimating it over the on-chain liquidity otherwise.
+    pub(super) fn sell_token_price(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        order: &Order,
+    ) -> Option<auction::Price> {
+        let sell_token = order.sell.token;
+        match auction.tokens.reference_price(&sell_token) {
+            Some(price) => Some(price),
+            None if sell_token == self.weth.0.into() => {
+                // Early return if the sell token is native token
+                Some(auction::Price(eth::Ether(eth::U256::exp10(18))))
+            }
+            None => {
+                // Estimate the price of the sell token in the native token
+                let native_price_request = self.native_price_request(order);
+                match boundary_solver.route(native_price_request, self.max_hops) {
+                    Some(route) => {
+                        // how many units of buy_token are bought for one unit of sell_token
+                        // (buy_amount / sell_amount).
+                        let price = self.native_token_price_estimation_amount.to_f64_lossy()
+                            / route.input().amount.to_f64_lossy();
+                        let price = to_normalized_price(price)?;
+
+                        Some(auction::Price(eth::Ether(price)))
+                    }
+                    _ => {
+                        // This is to allow quotes to be generated for tokens for which the sell
+                        // token price is not available, so we default to fee=0
+                        Some(auction::Price(eth::Ether(eth::U256::MAX)))
+                    }
+                }
+            }
+        }
+    }
+
+    fn requests_for_order(&self, order: &Order) -> impl Iterator<Item = Request> + use<> {
+        let order::Order {
+            sell, buy, side, ..
+        } = order.clone();
+
+        let n = if order.partially_fillable {
+            self.max_partial_attempts
+        } else {
+            1
+        };
+
+        (0..n)
+            .map(move |i| {
+                let divisor = U256::one() << i;
+                Request {
+                    sell: eth::Asset {
+                        token: sell.token,
+                        amount: sell.amount / divisor,
+                    },
+                    buy: eth::Asset {
+                        token: buy.token,
+                        amount: buy.amount / divisor,
+                    },
+                    side,
+                }
+            })
+            .filter(|r| !r.sell.amount.is_zero() && !r.buy.amount.is_zero())
+    }
+
+    fn native_price_request(&self, order: &Order) -> Request {
+        let sell = eth::Asset {
+            token: order.sell.token,
+            // Note that we intentionally do not use [`eth::U256::max_value()`]
+            // as an order with this would cause overflows with the smart
+            // contract, so buy orders requiring excessively large sell amounts
+            // would not work anyway. Instead we use `2 ** 144`, the rationale
+            // being that Uniswap V2 pool reserves are 112-bit integers. Noting
+            // that `256 - 112 = 144`, this means that we can use it to trade a full
+            // `type(uint112).max` without overflowing a `uint256` on the smart
+            // contract level. Requiring to trade more than `type(uint112).max`
+            // is unlikely and would not work with Uniswap V2 anyway.
+            amount: eth::U256::one() << 144,
+        };
+
+        let buy = eth::Asset {
+            token: self.weth.0.into(),
+            amount: self.native_token_price_estimation_amount,
+        };
+
+        Request {
+            sell,
+            buy,
+            side: order::Side::Buy,
+        }
+    }
+}
+
+fn to_normalized_price(price: f64) -> Option<U256> {
+    let uint_max = 2.0_f64.powi(256);
+
+    let price_in_eth = 1e18 * price;
+    if price_in_eth.is_normal() && price_in_eth >= 1. && price_in_eth < uint_max {
+        Some(U256::from_f64_lossy(price_in_eth))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,463 @@
+//! "CoW" solver implementation.
+//!
+//! The CoW solver matches Coincidence of Wants between the orders of an
+//! auction. It looks for:
+//! - Ring trades, where every order receives exactly the amount that the next
+//!   order in the ring sells, so no on-chain liquidity is needed at all.
+//! - Direct matches between two opposing orders where the amounts don't line up
+//!   exactly. In that case, only the residual amount is routed over on-chain
+//!   liquidity and both orders are settled at the same uniform clearing price.
+//!
+//! Orders that are not part of any match are routed individually the same way
+//! the baseline solver does. Only fill-or-kill sell orders are considered for
+//! matching.
+
+use {
+    super::{ADDITIONAL_TRADE_GAS, Config, DEADLINE_SLACK, Request, Route, baseline::Baseline},
+    crate::{
+        boundary,
+        domain::{
+            auction,
+            eth,
+            order::{self, Order},
+            solution,
+        },
+    },
+    ethereum_types::U256,
+    itertools::Itertools,
+    std::collections::{HashMap, HashSet},
+};
+
+pub struct Cow {
+    /// The baseline solver used for routing residual amounts and orders that
+    /// could not be matched.
+    baseline: Baseline,
+
+    /// The maximum number of orders that can be part of a single ring trade.
+    max_ring_length: usize,
+}
+
+impl Cow {
+    /// Creates a new CoW solver for the specified configuration.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            baseline: Baseline::new(config),
+            max_ring_length: config.max_ring_length,
+        }
+    }
+
+    pub(super) fn solve(
+        &self,
+        auction: auction::Auction,
+        sender: tokio::sync::mpsc::UnboundedSender<solution::Solution>,
+    ) {
+        let boundary_solver = boundary::baseline::Solver::new(
+            &self.baseline.weth,
+            &self.baseline.base_tokens,
+            &auction.liquidity,
+        );
+
+        let mut next_id = 0;
+        let mut send = |solution: solution::Solution| {
+            let solution = solution.with_id(solution::Id(next_id));
+            next_id += 1;
+            let sent = sender.send(solution).is_ok();
+            if !sent {
+                tracing::debug!("deadline hit, receiver dropped");
+            }
+            sent
+        };
+
+        // Searching for matches can take a while for large auctions, so stop
+        // as soon as there is no time left to settle them.
+        let deadline = auction.deadline.clone().reduce(DEADLINE_SLACK);
+        let expired = || {
+            let expired = deadline.remaining().is_none();
+            if expired {
+                tracing::debug!("deadline hit, stopping CoW search");
+            }
+            expired
+        };
+
+        let candidates = auction
+            .orders
+            .iter()
+            .filter(|order| is_matchable(order))
+            .collect::<Vec<_>>();
+        let mut matched = HashSet::new();
+
+        for ring in rings(&candidates, self.max_ring_length) {
+            if expired() {
+                return;
+            }
+            if ring.iter().any(|order| matched.contains(&order.uid)) {
+                continue;
+            }
+            let Some(solution) = self.settle_ring(&auction, &boundary_solver, &ring) else {
+                continue;
+            };
+            let uids = ring.iter().map(|order| order.uid).collect::<Vec<_>>();
+            tracing::debug!(orders = ?uids, "found ring trade");
+            matched.extend(uids);
+            if !send(solution) {
+                return;
+            }
+        }
+
+        for (a, b) in opposing_pairs(&candidates) {
+            if expired() {
+                return;
+            }
+            if matched.contains(&a.uid) || matched.contains(&b.uid) {
+                continue;
+            }
+            let Some(solution) = self
+                .settle_with_residual(&auction, &boundary_solver, a, b)
+                .or_else(|| self.settle_with_residual(&auction, &boundary_solver, b, a))
+            else {
+                continue;
+            };
+            tracing::debug!(orders = ?[a.uid, b.uid], "found CoW with residual");
+            matched.extend([a.uid, b.uid]);
+            if !send(solution) {
+                return;
+            }
+        }
+
+        for order in &auction.orders {
+            if matched.contains(&order.uid) {
+                continue;
+            }
+            let Some(solution) = self.baseline.solve_order(
+                &auction,
+                &boundary_solver,
+                order,
+                self.baseline.solution_gas_offset,
+            ) else {
+                continue;
+            };
+            if !send(solution) {
+                return;
+            }
+        }
+    }
+
+    /// Settles a ring of orders, where each order buys the token that the next
+    /// order sells, without using any on-chain liquidity.
+    ///
+    /// In order for the settlement to balance, each order must receive exactly
+    /// the amount that the next order in the ring sells. This uniquely
+    /// determines the clearing prices: for every order `i` with executed sell
+    /// amount `e_i`, the uniform clearing price of its sell token must satisfy
+    /// `e_i * p_i = k` for some constant `k`.
+    fn settle_ring(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        ring: &[&Order],
+    ) -> Option<solution::Solution> {
+        let gas = settlement_gas(ring.len()) + self.baseline.solution_gas_offset;
+        let executions = ring
+            .iter()
+            .map(|order| self.execution(auction, boundary_solver, order, gas, ring.len()))
+            .collect::<Option<Vec<_>>>()?;
+
+        for (i, order) in ring.iter().enumerate() {
+            let (received, _) = executions[(i + 1) % ring.len()];
+            if received < order.buy.amount {
+                return None;
+            }
+        }
+
+        let prices = ring
+            .iter()
+            .enumerate()
+            .map(|(i, order)| {
+                let price = executions
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .try_fold(U256::one(), |price, (_, (executed, _))| {
+                        price.checked_mul(*executed)
+                    })?;
+                Some((order.sell.token, price))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let trades = ring
+            .iter()
+            .zip(&executions)
+            .map(|(order, (executed, fee))| {
+                Some(solution::Trade::Fulfillment(solution::Fulfillment::new(
+                    (*order).clone(),
+                    *executed,
+                    *fee,
+                )?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(solution::Solution {
+            prices: solution::ClearingPrices::new(prices),
+            trades,
+            gas: Some(gas),
+            ..Default::default()
+        })
+    }
+
+    /// Settles two opposing orders against each other, where the `excess`
+    /// order sells more than the `other` order can absorb. The residual amount
+    /// of the `excess` order's sell token is routed over on-chain liquidity.
+    fn settle_with_residual(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        excess: &Order,
+        other: &Order,
+    ) -> Option<solution::Solution> {
+        let base_gas = settlement_gas(2) + self.baseline.solution_gas_offset;
+
+        // Fees depend on the gas used by the residual route, which in turn
+        // depends on the executed amounts. Find a route ignoring fees first to
+        // get a gas estimate for computing fees.
+        let (route, _) = self.residual_route(
+            boundary_solver,
+            (excess, excess.sell.amount),
+            (other, other.sell.amount),
+        )?;
+        let gas = eth::Gas(base_gas.0.saturating_add(route.gas().0));
+
+        let (e1, fee1) = self.execution(auction, boundary_solver, excess, gas, 2)?;
+        let (e2, fee2) = self.execution(auction, boundary_solver, other, gas, 2)?;
+        let (route, residual) = self.residual_route(boundary_solver, (excess, e1), (other, e2))?;
+        let gas = eth::Gas(base_gas.0.saturating_add(route.gas().0));
+
+        // The `other` order receives `e1 - residual` and the `excess` order
+        // receives `e1 * e2 / (e1 - residual)`, which the route was verified
+        // to cover.
+        let received = e1.checked_mul(e2)? / e1.checked_sub(residual)?;
+        if received < excess.buy.amount {
+            return None;
+        }
+
+        let trades = [(excess, e1, fee1), (other, e2, fee2)]
+            .into_iter()
+            .map(|(order, executed, fee)| {
+                Some(solution::Trade::Fulfillment(solution::Fulfillment::new(
+                    order.clone(),
+                    executed,
+                    fee,
+                )?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(
+            solution::Solution {
+                prices: solution::ClearingPrices::new([
+                    (excess.sell.token, e2),
+                    (other.sell.token, e1 - residual),
+                ]),
+                trades,
+                interactions: route.interactions(),
+                gas: Some(gas),
+                ..Default::default()
+            }
+            .with_buffers_internalizations(&auction.tokens),
+        )
+    }
+
+    /// Finds the largest residual amount of the `excess` order's sell token
+    /// that can be routed over on-chain liquidity such that both orders can be
+    /// settled at a uniform clearing price.
+    ///
+    /// With executed sell amounts `e1` and `e2` and a residual `x`, the
+    /// `other` order receives `e1 - x` and the `excess` order receives
+    /// `e1 * e2 / (e1 - x)`. The route has to cover the difference between
+    /// the latter and `e2`. Larger residuals improve the price for the
+    /// `excess` order, so we search for the largest one that the on-chain
+    /// liquidity can cover without violating the `other` order's limit price.
+    fn residual_route<'a>(
+        &self,
+        boundary_solver: &boundary::baseline::Solver<'a>,
+        (excess, e1): (&Order, U256),
+        (other, e2): (&Order, U256),
+    ) -> Option<(Route<'a>, U256)> {
+        let find_route = |residual: U256| {
+            let required = e1.checked_mul(e2)? / e1.checked_sub(residual)?;
+            let request = Request {
+                sell: eth::Asset {
+                    token: excess.sell.token,
+                    amount: residual,
+                },
+                buy: eth::Asset {
+                    token: excess.buy.token,
+                    amount: required.checked_sub(e2)?,
+                },
+                side: order::Side::Sell,
+            };
+            boundary_solver.route(request, self.baseline.max_hops)
+        };
+
+        let max = e1.checked_sub(other.buy.amount.max(U256::one()))?;
+        if max.is_zero() {
+            return None;
+        }
+        if let Some(route) = find_route(max) {
+            return Some((route, max));
+        }
+
+        // Binary search for the largest residual that can be routed. Note that
+        // this assumes that on-chain liquidity has non-increasing marginal
+        // prices, which is the case for all AMMs that we support.
+        let (mut lo, mut hi) = (U256::zero(), max);
+        let mut best = None;
+        while hi - lo > U256::one() {
+            let mid = lo + (hi - lo) / 2;
+            match find_route(mid) {
+                Some(route) => {
+                    lo = mid;
+                    best = Some(route);
+                }
+                None => hi = mid,
+            }
+        }
+        best.map(|route| (route, lo))
+    }
+
+    /// Computes the executed sell amount and fee for an order that is settled
+    /// as part of a CoW with the specified number of orders and total gas.
+    /// Limit orders pay for an equal share of the settlement gas with a
+    /// surplus fee.
+    fn execution(
+        &self,
+        auction: &auction::Auction,
+        boundary_solver: &boundary::baseline::Solver,
+        order: &Order,
+        gas: eth::Gas,
+        orders: usize,
+    ) -> Option<(U256, solution::Fee)> {
+        if !order.solver_determines_fee() {
+            return Some((order.sell.amount, solution::Fee::Protocol));
+        }
+
+        let sell_token_price = self
+            .baseline
+            .sell_token_price(auction, boundary_solver, order)?;
+        let cost = gas.0.checked_mul(auction.gas_price.0.0)? / U256::from(orders);
+        let fee = sell_token_price.ether_value(eth::Ether(cost))?;
+        let executed = order.sell.amount.checked_sub(fee)?;
+        if executed.is_zero() {
+            return None;
+        }
+        Some((executed, solution::Fee::Surplus(fee.into())))
+    }
+}
+
+/// Returns `true` if the order can be matched with other orders.
+fn is_matchable(order: &Order) -> bool {
+    order.side == order::Side::Sell
+        && !order.partially_fillable
+        && order.flashloan_hint.is_none()
+        && !order.sell.amount.is_zero()
+}
+
+/// Returns the gas needed for the trades of a settlement with the specified
+/// number of orders, excluding the overhead of the first trade which is part
+/// of the solution gas offset.
+fn settlement_gas(orders: usize) -> eth::Gas {
+    let additional = u64::try_from(orders.saturating_sub(1)).unwrap_or(u64::MAX);
+    eth::Gas(U256::from(ADDITIONAL_TRADE_GAS) * U256::from(additional))
+}
+
+/// Finds all rings of at most `max_length` orders, where each order buys the
+/// token that the next order in the ring sells and all sell tokens are
+/// distinct. Shorter rings are returned first.
+fn rings<'a>(orders: &[&'a Order], max_length: usize) -> Vec<Vec<&'a Order>> {
+    let mut by_sell_token = HashMap::<_, Vec<_>>::new();
+    for (i, order) in orders.iter().enumerate() {
+        by_sell_token.entry(order.sell.token).or_default().push(i);
+    }
+
+    let mut rings = Vec::new();
+    for start in 0..orders.len() {
+        extend_ring(
+            orders,
+            &by_sell_token,
+            max_length,
+            &mut vec![start],
+            &mut rings,
+        );
+    }
+    rings.sort_by_key(Vec::len);
+
+    rings
+        .into_iter()
+        .map(|ring| ring.into_iter().map(|i| orders[i]).collect())
+        .collect()
+}
+
+fn extend_ring(
+    orders: &[&Order],
+    by_sell_token: &HashMap<eth::TokenAddress, Vec<usize>>,
+    max_length: usize,
+    path: &mut Vec<usize>,
+    rings: &mut Vec<Vec<usize>>,
+) {
+    if path.len() >= max_length {
+        return;
+    }
+    let start = path[0];
+    let last = orders[*path.last().expect("path is never empty")];
+    let Some(next) = by_sell_token.get(&last.buy.token) else {
+        return;
+    };
+
+    for &i in next {
+        // Only consider rings where the starting order has the lowest index,
+        // this ensures that each ring is only found once.
+        if i <= start {
+            continue;
+        }
+        let order = orders[i];
+        if order.buy.token == orders[start].sell.token {
+            rings.push(path.iter().copied().chain([i]).collect());
+        } else if path.len() + 1 < max_length
+            && !path
+                .iter()
+                .any(|&j| orders[j].sell.token == order.buy.token)
+        {
+            path.push(i);
+            extend_ring(orders, by_sell_token, max_length, path, rings);
+            path.pop();
+        }
+    }
+}
+
+/// Returns all pairs of orders trading in opposite directions on the same
+/// token pair. Pairs are produced lazily, so the search can be stopped at any
+/// point.
+fn opposing_pairs<'a>(orders: &[&'a Order]) -> impl Iterator<Item = (&'a Order, &'a Order)> {
+    let mut by_pair = HashMap::<_, Vec<_>>::new();
+    for order in orders {
+        by_pair
+            .entry((order.sell.token, order.buy.token))
+            .or_default()
+            .push(*order);
+    }
+
+    // Only start from groups selling the lower token of the pair, so that
+    // each pair of orders is only returned once. Groups are visited in the
+    // order in which they first appear in the auction.
+    let groups = orders
+        .iter()
+        .map(|order| (order.sell.token, order.buy.token))
+        .filter(|(sell, buy)| sell < buy)
+        .unique()
+        .filter_map(|(sell, buy)| {
+            let opposing = by_pair.get(&(buy, sell))?;
+            Some((by_pair[&(sell, buy)].clone(), opposing.clone()))
+        })
+        .collect::<Vec<_>>();
+    groups
+        .into_iter()
+        .flat_map(|(sells, buys)| sells.into_iter().cartesian_product(buys))
+}
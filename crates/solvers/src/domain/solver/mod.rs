@@ -0,0 +1,169 @@
+//! Solver engine implementations.
+//!
+//! The solver engines share the same on-chain liquidity routing logic, but
+//! differ in how they use it to settle the orders of an auction:
+//! - [`baseline`] routes each order individually over on-chain liquidity.
+//! - [`cow`] matches Coincidence of Wants between the orders of the auction and
+//!   only routes the residual amounts over on-chain liquidity.
+
+use {
+    crate::{
+        domain::{auction, eth, liquidity, order, solution},
+        infra::metrics,
+    },
+    ethereum_types::U256,
+    shared::price_estimation::gas,
+    std::sync::Arc,
+};
+
+pub mod baseline;
+pub mod cow;
+
+pub struct Solver(Arc<Inner>);
+
+/// The amount of time we aim the solver to finish before the final deadline is
+/// reached.
+const DEADLINE_SLACK: chrono::Duration = chrono::Duration::milliseconds(500);
+
+/// The gas needed for each additional trade in a settlement.
+const ADDITIONAL_TRADE_GAS: u64 = gas::TRADE + 2 * gas::ERC20_TRANSFER;
+
+pub struct Config {
+    pub weth: eth::WethAddress,
+    pub base_tokens: Vec<eth::TokenAddress>,
+    pub max_hops: usize,
+    pub max_partial_attempts: usize,
+    pub solution_gas_offset: eth::SignedGas,
+    pub native_token_price_estimation_amount: eth::U256,
+    pub max_ring_length: usize,
+}
+
+enum Inner {
+    Baseline(baseline::Baseline),
+    Cow(cow::Cow),
+}
+
+impl Solver {
+    /// Creates a new baseline solver for the specified configuration.
+    pub fn baseline(config: Config) -> Self {
+        Self(Arc::new(Inner::Baseline(baseline::Baseline::new(&config))))
+    }
+
+    /// Creates a new CoW matching solver for the specified configuration.
+    pub fn cow(config: Config) -> Self {
+        Self(Arc::new(Inner::Cow(cow::Cow::new(&config))))
+    }
+
+    /// Solves the specified auction, returning a vector of all possible
+    /// solutions.
+    pub async fn solve(&self, auction: auction::Auction) -> Vec<solution::Solution> {
+        metrics::solve(&auction);
+        let deadline = auction.deadline.clone();
+        // Make sure to push the CPU-heavy code to a separate thread in order to
+        // not lock up the [`tokio`] runtime and cause it to slow down handling
+        // the real async things. For larger settlements, this can block in the
+        // 100s of ms.
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let remaining = auction
+            .deadline
+            .clone()
+            .reduce(DEADLINE_SLACK)
+            .remaining()
+            .unwrap_or_default();
+
+        let inner = self.0.clone();
+        let span = tracing::Span::current();
+        let background_work = async move {
+            let _entered = span.enter();
+            match inner.as_ref() {
+                Inner::Baseline(solver) => solver.solve(auction, sender),
+                Inner::Cow(solver) => solver.solve(auction, sender),
+            }
+        };
+
+        if tokio::time::timeout(remaining, tokio::spawn(background_work))
+            .await
+            .is_err()
+        {
+            tracing::debug!("reached timeout while solving orders");
+        }
+
+        let mut solutions = vec![];
+        while let Ok(solution) = receiver.try_recv() {
+            solutions.push(solution);
+        }
+        metrics::solved(&deadline, &solutions);
+        solutions
+    }
+}
+
+/// A baseline routing request.
+#[derive(Debug)]
+pub struct Request {
+    pub sell: eth::Asset,
+    pub buy: eth::Asset,
+    pub side: order::Side,
+}
+
+/// A trading route.
+#[derive(Debug)]
+pub struct Route<'a> {
+    segments: Vec<Segment<'a>>,
+}
+
+/// A segment in a trading route.
+#[derive(Debug)]
+pub struct Segment<'a> {
+    pub liquidity: &'a liquidity::Liquidity,
+    // TODO: There is no type-level guarantee here that both `input.token` and
+    // `output.token` are valid for the liquidity in this segment. This is
+    // unfortunate because this type leaks out of this module (currently into
+    // the `boundary::baseline` module) but should no longer need to be `pub`
+    // once the `boundary::baseline` module gets refactored into the domain
+    // logic, so I think it is fine for now.
+    pub input: eth::Asset,
+    pub output: eth::Asset,
+    pub gas: eth::Gas,
+}
+
+impl<'a> Route<'a> {
+    pub fn new(segments: Vec<Segment<'a>>) -> Option<Self> {
+        if segments.is_empty() {
+            return None;
+        }
+        Some(Self { segments })
+    }
+
+    fn input(&self) -> eth::Asset {
+        self.segments[0].input
+    }
+
+    fn output(&self) -> eth::Asset {
+        self.segments
+            .last()
+            .expect("route has at least one segment by construction")
+            .output
+    }
+
+    fn gas(&self) -> eth::Gas {
+        eth::Gas(self.segments.iter().fold(U256::zero(), |acc, segment| {
+            acc.saturating_add(segment.gas.0)
+        }))
+    }
+
+    /// Returns the liquidity interactions for executing the route.
+    fn interactions(&self) -> Vec<solution::Interaction> {
+        self.segments
+            .iter()
+            .map(|segment| {
+                solution::Interaction::Liquidity(solution::LiquidityInteraction {
+                    liquidity: segment.liquidity.clone(),
+                    input: segment.input,
+                    output: segment.output,
+                    // TODO does the baseline solver know about this optimization?
+                    internalize: false,
+                })
+            })
+            .collect()
+    }
+}
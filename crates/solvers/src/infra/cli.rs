@@ -35,4 +35,10 @@ pub enum Command {
         #[clap(long, env)]
         config: PathBuf,
     },
+    /// match Coincidence of Wants between orders and route the residual via
+    /// provided onchain liquidity
+    Cow {
+        #[clap(long, env)]
+        config: PathBuf,
+    },
 }
@@ -48,6 +48,12 @@ struct Config {
     /// token
     #[serde_as(as = "serialize::U256")]
     native_token_price_estimation_amount: eth::U256,
+
+    /// The maximum number of orders that can be part of a single ring trade
+    /// when matching Coincidence of Wants. Only used by the `cow` solver
+    /// engine.
+    #[serde(default = "default_max_ring_length")]
+    max_ring_length: usize,
 }
 
 /// Load the driver configuration from a TOML file.
@@ -84,6 +90,7 @@ pub async fn load(path: &Path) -> solver::Config {
         max_partial_attempts: config.max_partial_attempts,
         solution_gas_offset: config.solution_gas_offset.into(),
         native_token_price_estimation_amount: config.native_token_price_estimation_amount,
+        max_ring_length: config.max_ring_length,
     }
 }
 
@@ -110,3 +117,8 @@ where
 fn default_gas_offset() -> i64 {
     SETTLEMENT_OVERHEAD.try_into().unwrap()
 }
+
+/// Returns the default maximum number of orders in a ring trade.
+fn default_max_ring_length() -> usize {
+    3
+}
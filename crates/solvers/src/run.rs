@@ -31,7 +31,11 @@ async fn run_with(args: cli::Args, bind: Option<oneshot::Sender<SocketAddr>>) {
     let solver = match args.command {
         cli::Command::Baseline { config } => {
             let config = config::load(&config).await;
-            solver::Solver::new(config)
+            solver::Solver::baseline(config)
+        }
+        cli::Command::Cow { config } => {
+            let config = config::load(&config).await;
+            solver::Solver::cow(config)
         }
     };
 
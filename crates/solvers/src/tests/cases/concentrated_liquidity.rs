@@ -0,0 +1,131 @@
+//! Test case to verify baseline computation of Uniswap V3 concentrated
+//! liquidity, including crossing initialized ticks.
+
+use {crate::tests, serde_json::json};
+
+#[tokio::test]
+async fn test() {
+    let engine = tests::SolverEngine::new(
+        "baseline",
+        tests::Config::String(
+            r#"
+                chain-id = "1"
+                base-tokens = []
+                max-hops = 0
+                max-partial-attempts = 1
+                native-token-price-estimation-amount = "100000000000000000"
+            "#
+            .to_owned(),
+        ),
+    )
+    .await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                },
+                "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": true
+                },
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "buyToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                    "sellAmount": "5000000000000000",
+                    "fullSellAmount": "5000000000000000",
+                    "buyAmount": "1",
+                    "fullBuyAmount": "1",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [
+                {
+                    "kind": "concentratedLiquidity",
+                    "tokens": [
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab"
+                    ],
+                    "sqrtPrice": "79228162514264337593543950336",
+                    "liquidity": "1000000000000000000",
+                    "tick": 0,
+                    "liquidityNet": {
+                        "-120": "2000000000000000000",
+                        "-60": "-1000000000000000000",
+                        "60": "-1000000000000000000"
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0xc2e9f25be6257c210d7adf0d4cd6e3e881ba25f8",
+                    "router": "0xe592427a0aece92de3edee1f18e0157c05861564",
+                    "gasEstimate": "108163"
+                },
+            ],
+            "effectiveGasPrice": "1000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "4962211200082182",
+                    "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "5000000000000000"
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a",
+                        "executedAmount": "5000000000000000"
+                    }
+                ],
+                "preInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "liquidity",
+                        "internalize": false,
+                        "id": "0",
+                        "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                        "inputAmount": "5000000000000000",
+                        "outputAmount": "4962211200082182"
+                    },
+                ],
+                "postInteractions": [],
+                "gas": 214554,
+            }]
+        }),
+    );
+}
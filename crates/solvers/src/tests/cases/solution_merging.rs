@@ -0,0 +1,272 @@
+//! Test case to verify that the baseline solver merges the solutions of
+//! individually routed orders into a single solution, accounting for the
+//! changes in liquidity state caused by the previously routed orders.
+
+use {crate::tests, serde_json::json};
+
+#[tokio::test]
+async fn shared_token() {
+    let engine = tests::SolverEngine::new(
+        "baseline",
+        tests::Config::String(
+            r#"
+                chain-id = "1"
+                base-tokens = []
+                max-hops = 1
+                max-partial-attempts = 1
+                native-token-price-estimation-amount = "100000000000000000"
+            "#
+            .to_owned(),
+        ),
+    )
+    .await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                },
+                "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+                    "decimals": 18,
+                    "symbol": "COW",
+                    "referencePrice": "50000000000000",
+                    "availableBalance": "0",
+                    "trusted": false
+                },
+                "0x6b175474e89094c44da98b954eedeac495271d0f": {
+                    "decimals": 18,
+                    "symbol": "DAI",
+                    "referencePrice": "333333333333333",
+                    "availableBalance": "0",
+                    "trusted": false
+                }
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "buyToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                    "sellAmount": "1000000000000000000",
+                    "fullSellAmount": "1000000000000000000",
+                    "buyAmount": "19000000000000000000000",
+                    "fullBuyAmount": "19000000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                },
+                {
+                    "uid": "0x2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b\
+                              2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b\
+                              2b2b2b2b",
+                    "sellToken": "0x6b175474e89094c44da98b954eedeac495271d0f",
+                    "buyToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                    "sellAmount": "3000000000000000000000",
+                    "fullSellAmount": "3000000000000000000000",
+                    "buyAmount": "18000000000000000000000",
+                    "fullBuyAmount": "18000000000000000000000",
+                    "feePolicies": [],
+                    "validTo": 0,
+                    "kind": "sell",
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "partiallyFillable": false,
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "class": "market",
+                    "appData": "0x6000000000000000000000000000000000000000000000000000000000000007",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                            "balance": "100000000000000000000"
+                        },
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": {
+                            "balance": "2000000000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "0",
+                    "address": "0x97b744df0b59d93a866304f97431d8efad29a08d",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                },
+                {
+                    "kind": "constantProduct",
+                    "tokens": {
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                            "balance": "100000000000000000000"
+                        },
+                        "0x6b175474e89094c44da98b954eedeac495271d0f": {
+                            "balance": "300000000000000000000000"
+                        }
+                    },
+                    "fee": "0.003",
+                    "id": "1",
+                    "address": "0xa478c2975ab1ea89e8196811f51a7b7ade33eb11",
+                    "router": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+                    "gasEstimate": "110000"
+                }
+            ],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await;
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [
+                {
+                    "id": 0,
+                    "prices": {
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "19743160687941225977009",
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "1000000000000000000"
+                    },
+                    "trades": [
+                        {
+                            "kind": "fulfillment",
+                            "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                      2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                      2a2a2a2a",
+                            "executedAmount": "1000000000000000000"
+                        }
+                    ],
+                    "preInteractions": [],
+                    "interactions": [
+                        {
+                            "kind": "liquidity",
+                            "internalize": false,
+                            "id": "0",
+                            "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                            "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                            "inputAmount": "1000000000000000000",
+                            "outputAmount": "19743160687941225977009"
+                        }
+                    ],
+                    "postInteractions": [],
+                    "gas": 166391,
+                },
+                {
+                    "id": 1,
+                    "prices": {
+                        "0x6b175474e89094c44da98b954eedeac495271d0f": "19492090719486852005787",
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "3000000000000000000000"
+                    },
+                    "trades": [
+                        {
+                            "kind": "fulfillment",
+                            "order": "0x2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b\
+                                      2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b\
+                                      2b2b2b2b",
+                            "executedAmount": "3000000000000000000000"
+                        }
+                    ],
+                    "preInteractions": [],
+                    "interactions": [
+                        {
+                            "kind": "liquidity",
+                            "internalize": false,
+                            "id": "1",
+                            "inputToken": "0x6b175474e89094c44da98b954eedeac495271d0f",
+                            "outputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                            "inputAmount": "3000000000000000000000",
+                            "outputAmount": "987158034397061298"
+                        },
+                        {
+                            "kind": "liquidity",
+                            "internalize": false,
+                            "id": "0",
+                            "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                            "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                            "inputAmount": "987158034397061298",
+                            "outputAmount": "19492090719486852005787"
+                        }
+                    ],
+                    "postInteractions": [],
+                    "gas": 226391,
+                },
+                {
+                    "id": 2,
+                    "prices": {
+                        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "19743160687941225977009",
+                        "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab": "1000000000000000000",
+                        "0x6b175474e89094c44da98b954eedeac495271d0f": "6370143726458302977"
+                    },
+                    "trades": [
+                        {
+                            "kind": "fulfillment",
+                            "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                      2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                      2a2a2a2a",
+                            "executedAmount": "1000000000000000000"
+                        },
+                        {
+                            "kind": "fulfillment",
+                            "order": "0x2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b\
+                                      2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b2b\
+                                      2b2b2b2b",
+                            "executedAmount": "3000000000000000000000"
+                        }
+                    ],
+                    "preInteractions": [],
+                    "interactions": [
+                        {
+                            "kind": "liquidity",
+                            "internalize": false,
+                            "id": "0",
+                            "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                            "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                            "inputAmount": "1000000000000000000",
+                            "outputAmount": "19743160687941225977009"
+                        },
+                        {
+                            "kind": "liquidity",
+                            "internalize": false,
+                            "id": "1",
+                            "inputToken": "0x6b175474e89094c44da98b954eedeac495271d0f",
+                            "outputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                            "inputAmount": "3000000000000000000000",
+                            "outputAmount": "987158034397061298"
+                        },
+                        {
+                            "kind": "liquidity",
+                            "internalize": false,
+                            "id": "0",
+                            "inputToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                            "outputToken": "0xdef1ca1fb7fbcdc777520aa7f396b4e015f497ab",
+                            "inputAmount": "987158034397061298",
+                            "outputAmount": "19110431179374908933907"
+                        }
+                    ],
+                    "postInteractions": [],
+                    "gas": 385417,
+                },
+            ]
+        }),
+    );
+}
@@ -0,0 +1,108 @@
+use {
+    clap::Parser,
+    ethcontract::H160,
+    shared::{
+        arguments::display_option,
+        current_block,
+        ethrpc,
+        http_client,
+        logging_args_with_default_filter,
+    },
+    url::Url,
+};
+
+logging_args_with_default_filter!(LoggingArguments, "warn,watch_tower=debug,shared=debug");
+
+#[derive(Parser)]
+pub struct Arguments {
+    #[clap(flatten)]
+    pub http_client: http_client::Arguments,
+
+    #[clap(flatten)]
+    pub ethrpc: ethrpc::Arguments,
+
+    #[clap(flatten)]
+    pub current_block: current_block::Arguments,
+
+    #[clap(flatten)]
+    pub logging: LoggingArguments,
+
+    /// Url of the Postgres database. By default connects to locally running
+    /// postgres.
+    #[clap(long, env, default_value = "postgresql://")]
+    pub db_url: Url,
+
+    /// The Ethereum node URL to connect to.
+    #[clap(long, env, default_value = "http://localhost:8545")]
+    pub node_url: Url,
+
+    /// The expected chain ID that the services are expected to run against.
+    /// This can be optionally specified in order to check at startup whether
+    /// the connected nodes match to detect misconfigurations.
+    #[clap(long, env)]
+    pub chain_id: Option<u64>,
+
+    /// The orderbook the discrete orders get placed in.
+    #[clap(long, env, default_value = "http://localhost:8080")]
+    pub orderbook_url: Url,
+
+    /// Address of the ComposableCoW contract. Defaults to the canonical
+    /// deployment of the connected chain.
+    #[clap(long, env)]
+    pub composable_cow: Option<H160>,
+
+    /// The block from which to start indexing conditional orders if none were
+    /// indexed yet.
+    #[clap(long, env, default_value = "0")]
+    pub composable_cow_start_block: u64,
+
+    /// How many conditional orders get polled at the same time.
+    #[clap(long, env, default_value = "20")]
+    pub max_concurrent_polls: usize,
+
+    /// The port at which we serve our metrics
+    #[clap(long, env, default_value = "9591")]
+    pub metrics_port: u16,
+}
+
+impl std::fmt::Display for Arguments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Arguments {
+            http_client,
+            ethrpc,
+            current_block,
+            logging,
+            db_url,
+            node_url,
+            chain_id,
+            orderbook_url,
+            composable_cow,
+            composable_cow_start_block,
+            max_concurrent_polls,
+            metrics_port,
+        } = self;
+
+        write!(f, "{}", http_client)?;
+        write!(f, "{}", ethrpc)?;
+        write!(f, "{}", current_block)?;
+        write!(f, "{}", logging)?;
+        let _intentionally_ignored = db_url;
+        writeln!(f, "db_url: SECRET")?;
+        writeln!(f, "node_url: {}", node_url)?;
+        display_option(f, "chain_id", chain_id)?;
+        writeln!(f, "orderbook_url: {}", orderbook_url)?;
+        display_option(
+            f,
+            "composable_cow",
+            &composable_cow.map(|a| format!("{a:?}")),
+        )?;
+        writeln!(
+            f,
+            "composable_cow_start_block: {}",
+            composable_cow_start_block
+        )?;
+        writeln!(f, "max_concurrent_polls: {}", max_concurrent_polls)?;
+        writeln!(f, "metrics_port: {}", metrics_port)?;
+        Ok(())
+    }
+}
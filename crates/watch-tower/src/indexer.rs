@@ -0,0 +1,160 @@
+use {
+    anyhow::{Context, Result},
+    contracts::composable_cow::{self, Event},
+    database::{byte_array::ByteArray, conditional_orders::ConditionalOrder},
+    ethcontract::{
+        H160,
+        common::abi::{self, Token},
+        web3::signing::keccak256,
+    },
+    ethrpc::block_stream::RangeInclusive,
+    shared::{event_handling::EventStoring, impl_event_retrieving},
+    sqlx::PgPool,
+};
+
+impl_event_retrieving! {
+    pub ComposableCoWContract for composable_cow
+}
+
+/// This name is used to store the latest indexed block in the db.
+const INDEX_NAME: &str = "composable_cow";
+
+/// Stores the conditional orders created through ComposableCoW.
+pub struct Indexer {
+    db: PgPool,
+    start_index: u64,
+}
+
+impl Indexer {
+    pub fn new(db: PgPool, start_index: u64) -> Self {
+        Self { db, start_index }
+    }
+
+    async fn insert(
+        &self,
+        ex: &mut sqlx::PgConnection,
+        events: Vec<ethcontract::Event<Event>>,
+    ) -> Result<()> {
+        for event in events {
+            let Some(meta) = event.meta else {
+                tracing::warn!(?event, "event does not contain required meta data");
+                continue;
+            };
+            // Orders created through merkle roots are not emitted individually
+            // and would require fetching their proofs from off-chain storage.
+            let Event::ConditionalOrderCreated(created) = event.data else {
+                continue;
+            };
+            let (handler, salt, static_input) = created.params;
+            let order = ConditionalOrder {
+                owner: ByteArray(created.owner.0),
+                hash: ByteArray(params_hash(handler, salt.0, &static_input.0)),
+                handler: ByteArray(handler.0),
+                salt: ByteArray(salt.0),
+                static_input: static_input.0,
+                block_number: i64::try_from(meta.block_number)?,
+                log_index: i64::try_from(meta.log_index)?,
+                active: true,
+                ..Default::default()
+            };
+            tracing::debug!(
+                owner = ?created.owner,
+                hash = ?order.hash,
+                "indexed conditional order",
+            );
+            database::conditional_orders::upsert(ex, &order).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the hash ComposableCoW identifies conditional orders with, i.e.
+/// `keccak256(abi.encode(params))`.
+pub fn params_hash(handler: H160, salt: [u8; 32], static_input: &[u8]) -> [u8; 32] {
+    keccak256(&abi::encode(&[Token::Tuple(vec![
+        Token::Address(handler),
+        Token::FixedBytes(salt.to_vec()),
+        Token::Bytes(static_input.to_vec()),
+    ])]))
+}
+
+#[async_trait::async_trait]
+impl EventStoring<Event> for Indexer {
+    async fn last_event_block(&self) -> Result<u64> {
+        let mut ex = self.db.acquire().await?;
+        let last_block: u64 = database::last_indexed_blocks::fetch(&mut ex, INDEX_NAME)
+            .await?
+            .unwrap_or_default()
+            .try_into()
+            .context("last block is not u64")?;
+        Ok(last_block.max(self.start_index))
+    }
+
+    async fn persist_last_indexed_block(&mut self, last_block: u64) -> Result<()> {
+        let mut ex = self.db.acquire().await?;
+        database::last_indexed_blocks::update(
+            &mut ex,
+            INDEX_NAME,
+            i64::try_from(last_block).context("new value of counter is not i64")?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn replace_events(
+        &mut self,
+        events: Vec<ethcontract::Event<Event>>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        let mut transaction = self.db.begin().await?;
+        database::conditional_orders::delete_from_block(
+            &mut transaction,
+            i64::try_from(*range.start())?,
+        )
+        .await?;
+        self.insert(&mut transaction, events).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn append_events(&mut self, events: Vec<ethcontract::Event<Event>>) -> Result<()> {
+        let mut transaction = self.db.begin().await?;
+        self.insert(&mut transaction, events).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex};
+
+    #[test]
+    fn encodes_params_like_solidity() {
+        // The parameters contain dynamic data so they are encoded with an offset.
+        let handler = H160([1; 20]);
+        let encoded = abi::encode(&[Token::Tuple(vec![
+            Token::Address(handler),
+            Token::FixedBytes(vec![2; 32]),
+            Token::Bytes(vec![3; 2]),
+        ])]);
+        let mut expected = Vec::new();
+        expected.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000020"
+        ));
+        expected.extend([0; 12]);
+        expected.extend([1; 20]);
+        expected.extend([2; 32]);
+        expected.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000060"
+        ));
+        expected.extend(hex!(
+            "0000000000000000000000000000000000000000000000000000000000000002"
+        ));
+        expected.extend(hex!(
+            "0303000000000000000000000000000000000000000000000000000000000000"
+        ));
+        assert_eq!(encoded, expected);
+        assert_eq!(params_hash(handler, [2; 32], &[3; 2]), keccak256(&expected));
+    }
+}
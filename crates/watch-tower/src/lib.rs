@@ -0,0 +1,136 @@
+//! The watch-tower indexes conditional orders (e.g. TWAP, stop-loss) created
+//! through ComposableCoW, polls them every block for the discrete orders they
+//! currently allow and places those in the orderbook as EIP-1271 orders.
+
+pub mod arguments;
+mod indexer;
+mod orderbook;
+mod poller;
+
+use {
+    crate::{
+        arguments::Arguments,
+        indexer::{ComposableCoWContract, Indexer},
+        orderbook::Orderbook,
+        poller::Poller,
+    },
+    clap::Parser,
+    contracts::{ComposableCoW, GPv2Settlement},
+    futures::StreamExt,
+    model::DomainSeparator,
+    observe::metrics::LivenessChecking,
+    shared::{event_handling::EventHandler, http_client::HttpClientFactory},
+    sqlx::PgPool,
+    std::{
+        sync::{Arc, RwLock},
+        time::{Duration, Instant},
+    },
+};
+
+/// The watch-tower is considered unhealthy if it didn't complete a loop for
+/// this long.
+const MAX_DELAY_BETWEEN_LOOPS: Duration = Duration::from_secs(5 * 60);
+
+pub async fn start(args: impl Iterator<Item = String>) {
+    let args = Arguments::parse_from(args);
+    observe::tracing::initialize(
+        args.logging.log_filter.as_str(),
+        args.logging.log_stderr_threshold,
+    );
+    observe::panic_hook::install();
+    tracing::info!("running watch-tower with validated arguments:\n{}", args);
+    observe::metrics::setup_registry(Some("watch_tower".into()), None);
+    run(args).await;
+}
+
+pub async fn run(args: Arguments) {
+    let http_factory = HttpClientFactory::new(&args.http_client);
+    let web3 = shared::ethrpc::web3(&args.ethrpc, &http_factory, &args.node_url, "base");
+    let chain_id = web3
+        .eth()
+        .chain_id()
+        .await
+        .expect("Could not get chainId")
+        .as_u64();
+    if let Some(expected_chain_id) = args.chain_id {
+        assert_eq!(
+            chain_id, expected_chain_id,
+            "connected to node with incorrect chain ID",
+        );
+    }
+
+    let pg_pool = PgPool::connect_lazy(args.db_url.as_str()).expect("failed to create database");
+
+    let settlement = GPv2Settlement::deployed(&web3)
+        .await
+        .expect("Couldn't load deployed settlement");
+    let domain_separator = DomainSeparator::new(chain_id, settlement.address());
+    let composable_cow = match args.composable_cow {
+        Some(address) => ComposableCoW::at(&web3, address),
+        None => ComposableCoW::deployed(&web3)
+            .await
+            .expect("Couldn't load deployed ComposableCoW"),
+    };
+
+    let liveness = Arc::new(Liveness {
+        // Program will be healthy at the start even if no loop was ran yet.
+        last_successful_loop: RwLock::new(Instant::now()),
+    });
+    observe::metrics::serve_metrics(liveness.clone(), ([0, 0, 0, 0], args.metrics_port).into());
+
+    let mut indexer = EventHandler::new(
+        args.current_block.retriever(web3.clone()),
+        ComposableCoWContract::new(composable_cow.clone()),
+        Indexer::new(pg_pool.clone(), args.composable_cow_start_block),
+        None,
+    );
+    let poller = Poller::new(
+        pg_pool,
+        web3,
+        composable_cow,
+        Orderbook::new(http_factory.create(), args.orderbook_url.clone()),
+        domain_separator,
+        args.max_concurrent_polls,
+    );
+
+    let current_block = args
+        .current_block
+        .stream(args.node_url.clone())
+        .await
+        .expect("failed to create current block stream");
+    let mut blocks = ethrpc::block_stream::into_stream(current_block);
+    while let Some(block) = blocks.next().await {
+        // Index first, so that orders created in this block get polled right
+        // away.
+        if let Err(err) = indexer.update_events().await {
+            tracing::warn!(
+                ?err,
+                block = block.number,
+                "failed to index conditional orders"
+            );
+            continue;
+        }
+        match poller.poll(&block).await {
+            Ok(()) => *liveness.last_successful_loop.write().unwrap() = Instant::now(),
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    block = block.number,
+                    "failed to poll conditional orders"
+                )
+            }
+        }
+    }
+}
+
+struct Liveness {
+    last_successful_loop: RwLock<Instant>,
+}
+
+#[async_trait::async_trait]
+impl LivenessChecking for Liveness {
+    async fn is_alive(&self) -> bool {
+        Instant::now().duration_since(*self.last_successful_loop.read().unwrap())
+            < MAX_DELAY_BETWEEN_LOOPS
+    }
+}
@@ -0,0 +1,106 @@
+use {
+    model::order::OrderCreation,
+    reqwest::{Client, StatusCode},
+    serde::Deserialize,
+    url::Url,
+};
+
+/// Places the discrete orders in the orderbook.
+pub struct Orderbook {
+    client: Client,
+    base: Url,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Error {
+    error_type: String,
+    description: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlaceError {
+    /// The orderbook rejected the order (e.g. because of insufficient
+    /// balance). Placing it again only succeeds once the owner's state
+    /// changes.
+    #[error("{error_type}: {description}")]
+    Rejected {
+        error_type: String,
+        description: String,
+    },
+    /// The request failed for reasons unrelated to the order (e.g. network
+    /// errors, rate limiting or server errors).
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl Orderbook {
+    pub fn new(client: Client, base: Url) -> Self {
+        Self { client, base }
+    }
+
+    /// Places the order. Orders which were already placed count as placed.
+    pub async fn place(&self, order: &OrderCreation) -> Result<(), PlaceError> {
+        let url = shared::url::join(&self.base, "api/v1/orders");
+        let response = self
+            .client
+            .post(url)
+            .json(order)
+            .send()
+            .await
+            .map_err(anyhow::Error::from)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.map_err(anyhow::Error::from)?;
+        classify(status, &body)
+    }
+}
+
+fn classify(status: StatusCode, body: &str) -> Result<(), PlaceError> {
+    let rejected = status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS;
+    match serde_json::from_str::<Error>(body) {
+        Ok(err) if err.error_type == "DuplicatedOrder" => Ok(()),
+        Ok(err) if rejected => Err(PlaceError::Rejected {
+            error_type: err.error_type,
+            description: err.description,
+        }),
+        Ok(err) => Err(anyhow::anyhow!("{}: {}", err.error_type, err.description).into()),
+        Err(_) => Err(anyhow::anyhow!("{status}: {body}").into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_errors() {
+        let body = |error_type: &str| {
+            serde_json::json!({ "errorType": error_type, "description": "" }).to_string()
+        };
+
+        assert!(classify(StatusCode::BAD_REQUEST, &body("DuplicatedOrder")).is_ok());
+        assert!(matches!(
+            classify(StatusCode::BAD_REQUEST, &body("InsufficientBalance")),
+            Err(PlaceError::Rejected { error_type, .. }) if error_type == "InsufficientBalance"
+        ));
+        assert!(matches!(
+            classify(StatusCode::TOO_MANY_REQUESTS, &body("TooManyRequests")),
+            Err(PlaceError::Other(_))
+        ));
+        assert!(matches!(
+            classify(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &body("InternalServerError")
+            ),
+            Err(PlaceError::Other(_))
+        ));
+        assert!(matches!(
+            classify(StatusCode::BAD_GATEWAY, "<html>bad gateway</html>"),
+            Err(PlaceError::Other(_))
+        ));
+    }
+}
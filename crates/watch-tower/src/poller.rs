@@ -0,0 +1,650 @@
+use {
+    crate::orderbook::{Orderbook, PlaceError},
+    anyhow::{Context, Result},
+    app_data::AppDataHash,
+    chrono::{DateTime, Utc},
+    contracts::ComposableCoW,
+    database::{
+        byte_array::ByteArray,
+        conditional_orders::{ConditionalOrder, PollResult},
+    },
+    ethcontract::{
+        Bytes,
+        H160,
+        U256,
+        common::abi::{self, ParamType, Token},
+        tokens::Tokenize,
+        web3::signing::keccak256,
+    },
+    ethrpc::{Web3, block_stream::BlockInfo},
+    futures::{StreamExt, stream},
+    model::{
+        DomainSeparator,
+        order::{
+            BuyTokenDestination,
+            OrderCreation,
+            OrderCreationAppData,
+            OrderKind,
+            SellTokenSource,
+        },
+        signature::Signature,
+    },
+    sqlx::PgPool,
+    web3::types::{BlockId, BlockNumber, CallRequest},
+};
+
+/// The `GPv2Order.Data` struct and the signature returned by
+/// `getTradeableOrderWithSignature`.
+type TradeableOrder = (
+    (
+        H160,
+        H160,
+        H160,
+        U256,
+        U256,
+        u32,
+        Bytes<[u8; 32]>,
+        U256,
+        Bytes<[u8; 32]>,
+        bool,
+        Bytes<[u8; 32]>,
+        Bytes<[u8; 32]>,
+    ),
+    Bytes<Vec<u8>>,
+);
+
+/// Why a conditional order doesn't have a discrete order to place right now.
+/// Handlers signal this with the custom errors of `IConditionalOrder`.
+#[derive(Debug, Eq, PartialEq)]
+enum PollError {
+    TryNextBlock(String),
+    TryAtBlock(u64, String),
+    TryAtEpoch(u64, String),
+    Never(String),
+    OrderNotValid(String),
+    /// The owner removed the conditional order.
+    SingleOrderNotAuthed,
+}
+
+impl PollError {
+    fn decode(revert_data: &[u8]) -> Option<Self> {
+        if revert_data.len() < 4 {
+            return None;
+        }
+        let (selector, data) = revert_data.split_at(4);
+        let is = |signature: &str| keccak256(signature.as_bytes())[..4] == *selector;
+        let decode = |params: &[ParamType]| abi::decode(params, data).ok();
+
+        if is("SingleOrderNotAuthed()") {
+            return Some(Self::SingleOrderNotAuthed);
+        }
+        let with_message = [
+            (
+                "PollTryNextBlock(string)",
+                Self::TryNextBlock as fn(String) -> Self,
+            ),
+            ("PollNever(string)", Self::Never),
+            ("OrderNotValid(string)", Self::OrderNotValid),
+        ];
+        for (signature, error) in with_message {
+            if is(signature) {
+                let mut tokens = decode(&[ParamType::String])?.into_iter();
+                return Some(error(tokens.next()?.into_string()?));
+            }
+        }
+        let with_deadline = [
+            (
+                "PollTryAtBlock(uint256,string)",
+                Self::TryAtBlock as fn(u64, String) -> Self,
+            ),
+            ("PollTryAtEpoch(uint256,string)", Self::TryAtEpoch),
+        ];
+        for (signature, error) in with_deadline {
+            if is(signature) {
+                let mut tokens = decode(&[ParamType::Uint(256), ParamType::String])?.into_iter();
+                let deadline = tokens.next()?.into_uint()?;
+                let message = tokens.next()?.into_string()?;
+                return Some(error(u64::try_from(deadline).unwrap_or(u64::MAX), message));
+            }
+        }
+        None
+    }
+
+    /// When to poll the conditional order again.
+    fn into_poll_result(self, order: &ConditionalOrder, block_number: i64) -> PollResult {
+        let mut result = PollResult {
+            active: true,
+            last_order_uid: order.last_order_uid,
+            ..Default::default()
+        };
+        match self {
+            Self::TryNextBlock(_) => result.next_poll_block = Some(block_number + 1),
+            Self::TryAtBlock(block, _) => {
+                result.next_poll_block = Some(i64::try_from(block).unwrap_or(i64::MAX))
+            }
+            Self::TryAtEpoch(timestamp, _) => {
+                result.next_poll_timestamp = i64::try_from(timestamp)
+                    .ok()
+                    .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                    .or(Some(DateTime::<Utc>::MAX_UTC))
+            }
+            Self::OrderNotValid(message) => {
+                result.next_poll_block = Some(block_number + 1);
+                result.last_error = Some(format!("OrderNotValid: {message}"));
+            }
+            Self::Never(message) => {
+                result.active = false;
+                result.last_error = Some(format!("PollNever: {message}"));
+            }
+            Self::SingleOrderNotAuthed => {
+                result.active = false;
+                result.last_error = Some("SingleOrderNotAuthed".to_string());
+            }
+        }
+        result
+    }
+}
+
+/// Polling backs off exponentially after consecutive failures. Transient
+/// failures are retried at least this often (in blocks)...
+const MAX_TRANSIENT_BACKOFF: i64 = 32;
+/// ...while permanent failures are retried at least this often, as they only
+/// resolve once the owner acts.
+const MAX_PERMANENT_BACKOFF: i64 = 1024;
+
+/// Why polling a conditional order or placing its discrete order failed.
+#[derive(Debug)]
+enum Failure {
+    /// Likely resolves by itself (e.g. RPC or network errors).
+    Transient(anyhow::Error),
+    /// Only resolves once the owner's state changes (e.g. the orderbook
+    /// rejected the discrete order or the handler returned an invalid one).
+    Permanent(anyhow::Error),
+}
+
+impl Failure {
+    /// When to poll the conditional order again.
+    fn into_poll_result(self, order: &ConditionalOrder, block_number: i64) -> PollResult {
+        let (err, max_backoff) = match self {
+            Self::Transient(err) => (err, MAX_TRANSIENT_BACKOFF),
+            Self::Permanent(err) => (err, MAX_PERMANENT_BACKOFF),
+        };
+        let backoff = 2_i64
+            .saturating_pow(u32::try_from(order.failed_polls).unwrap_or_default())
+            .min(max_backoff);
+        PollResult {
+            active: true,
+            next_poll_block: Some(block_number.saturating_add(backoff)),
+            last_order_uid: order.last_order_uid,
+            last_error: Some(format!("{err:#}")),
+            failed_polls: order.failed_polls.saturating_add(1),
+            ..Default::default()
+        }
+    }
+}
+
+/// Extracts the revert data from the error of an `eth_call`.
+fn revert_data(err: &web3::Error) -> Option<Vec<u8>> {
+    let web3::Error::Rpc(err) = err else {
+        return None;
+    };
+    let data = err.data.as_ref()?.as_str()?;
+    // Some nodes prefix the revert data.
+    let data = data.trim_start_matches("Reverted ");
+    hex::decode(data.strip_prefix("0x")?).ok()
+}
+
+/// Decodes the output of `getTradeableOrderWithSignature`.
+fn decode_tradeable_order(output: &[u8], owner: H160) -> Result<OrderCreation> {
+    let function = ComposableCoW::raw_contract()
+        .interface
+        .abi
+        .function("getTradeableOrderWithSignature")
+        .unwrap();
+    let tokens = function.decode_output(output).context("decode")?;
+    let (data, signature): TradeableOrder = Tokenize::from_token(Token::Tuple(tokens))?;
+    let receiver = match data.2 {
+        H160(bytes) if bytes == [0u8; 20] => None,
+        receiver => Some(receiver),
+    };
+    Ok(OrderCreation {
+        sell_token: data.0,
+        buy_token: data.1,
+        receiver,
+        sell_amount: data.3,
+        buy_amount: data.4,
+        valid_to: data.5,
+        app_data: OrderCreationAppData::Hash {
+            hash: AppDataHash(data.6.0),
+        },
+        fee_amount: data.7,
+        kind: OrderKind::from_contract_bytes(data.8.0)?,
+        partially_fillable: data.9,
+        sell_token_balance: SellTokenSource::from_contract_bytes(data.10.0)?,
+        buy_token_balance: BuyTokenDestination::from_contract_bytes(data.11.0)?,
+        from: Some(owner),
+        signature: Signature::Eip1271(signature.0),
+        quote_id: None,
+    })
+}
+
+/// Polls the conditional orders for discrete orders and places them in the
+/// orderbook.
+pub struct Poller {
+    db: PgPool,
+    web3: Web3,
+    composable_cow: ComposableCoW,
+    orderbook: Orderbook,
+    domain_separator: DomainSeparator,
+    max_concurrent_polls: usize,
+}
+
+impl Poller {
+    pub fn new(
+        db: PgPool,
+        web3: Web3,
+        composable_cow: ComposableCoW,
+        orderbook: Orderbook,
+        domain_separator: DomainSeparator,
+        max_concurrent_polls: usize,
+    ) -> Self {
+        Self {
+            db,
+            web3,
+            composable_cow,
+            orderbook,
+            domain_separator,
+            max_concurrent_polls,
+        }
+    }
+
+    /// Polls all conditional orders which are due at the given block.
+    pub async fn poll(&self, block: &BlockInfo) -> Result<()> {
+        let block_number = i64::try_from(block.number)?;
+        let timestamp = DateTime::from_timestamp(i64::try_from(block.timestamp)?, 0)
+            .context("invalid block timestamp")?;
+        let due = {
+            let mut ex = self.db.acquire().await?;
+            database::conditional_orders::due(&mut ex, block_number, timestamp).await?
+        };
+        tracing::debug!(
+            block = block.number,
+            orders = due.len(),
+            "polling conditional orders"
+        );
+
+        stream::iter(due)
+            .for_each_concurrent(self.max_concurrent_polls, |order| async move {
+                let result = self.poll_order(&order, block.number).await;
+                if let Err(err) = self.store(&order, &result).await {
+                    tracing::warn!(
+                        ?err,
+                        owner = ?order.owner,
+                        hash = ?order.hash,
+                        "failed to store poll result",
+                    );
+                }
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn poll_order(&self, order: &ConditionalOrder, block: u64) -> PollResult {
+        let block_number = i64::try_from(block).unwrap_or(i64::MAX);
+        let fail = |failure: Failure| {
+            tracing::warn!(
+                ?failure,
+                owner = ?order.owner,
+                hash = ?order.hash,
+                failed_polls = order.failed_polls.saturating_add(1),
+                "failed to poll conditional order",
+            );
+            Metrics::poll(match failure {
+                Failure::Transient(_) => "error",
+                Failure::Permanent(_) => "rejected",
+            });
+            failure.into_poll_result(order, block_number)
+        };
+
+        let creation = match self.tradeable_order(order, block).await {
+            Ok(Ok(creation)) => creation,
+            Ok(Err(poll_error)) => {
+                tracing::debug!(
+                    ?poll_error,
+                    owner = ?order.owner,
+                    hash = ?order.hash,
+                    "no discrete order",
+                );
+                Metrics::poll("not_tradeable");
+                return poll_error.into_poll_result(order, block_number);
+            }
+            Err(failure) => return fail(failure),
+        };
+
+        // The same discrete order is usually returned until it expires, so it
+        // only needs to be placed once.
+        let owner = H160(order.owner.0);
+        let uid = creation.data().uid(&self.domain_separator, &owner);
+        let result = PollResult {
+            active: true,
+            next_poll_block: Some(block_number + 1),
+            last_order_uid: Some(ByteArray(uid.0)),
+            ..Default::default()
+        };
+        if order.last_order_uid == Some(ByteArray(uid.0)) {
+            Metrics::poll("already_placed");
+            return result;
+        }
+        match self.orderbook.place(&creation).await {
+            Ok(()) => {
+                tracing::info!(
+                    order_uid = %uid,
+                    ?owner,
+                    hash = ?order.hash,
+                    "placed discrete order",
+                );
+                Metrics::poll("placed");
+                result
+            }
+            Err(err) => {
+                let is_rejected = matches!(err, PlaceError::Rejected { .. });
+                let err = anyhow::Error::from(err).context("failed to place discrete order");
+                fail(if is_rejected {
+                    Failure::Permanent(err)
+                } else {
+                    Failure::Transient(err)
+                })
+            }
+        }
+    }
+
+    /// Asks ComposableCoW for the current discrete order of the conditional
+    /// order.
+    async fn tradeable_order(
+        &self,
+        order: &ConditionalOrder,
+        block: u64,
+    ) -> Result<Result<OrderCreation, PollError>, Failure> {
+        let owner = H160(order.owner.0);
+        let call = self.composable_cow.get_tradeable_order_with_signature(
+            owner,
+            (
+                H160(order.handler.0),
+                Bytes(order.salt.0),
+                Bytes(order.static_input.clone()),
+            ),
+            Bytes(Vec::new()),
+            Vec::new(),
+        );
+        // The custom errors signalling when to poll again are only available
+        // in the raw response of the node.
+        let request = CallRequest {
+            to: Some(self.composable_cow.address()),
+            data: call.tx.data,
+            ..Default::default()
+        };
+        let block = BlockId::Number(BlockNumber::Number(block.into()));
+        let output = match self.web3.eth().call(request, Some(block)).await {
+            Ok(output) => output,
+            Err(err) => {
+                return match revert_data(&err).as_deref().and_then(PollError::decode) {
+                    Some(poll_error) => Ok(Err(poll_error)),
+                    None => Err(Failure::Transient(
+                        anyhow::Error::from(err).context("getTradeableOrderWithSignature"),
+                    )),
+                };
+            }
+        };
+        decode_tradeable_order(&output.0, owner)
+            .map(Ok)
+            .map_err(Failure::Permanent)
+    }
+
+    async fn store(&self, order: &ConditionalOrder, result: &PollResult) -> Result<()> {
+        let mut ex = self.db.acquire().await?;
+        database::conditional_orders::update_poll_result(
+            &mut ex,
+            &order.owner,
+            &order.hash,
+            result,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+#[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
+#[metric(subsystem = "watch_tower")]
+struct Metrics {
+    /// Outcomes of polling conditional orders.
+    #[metric(labels("result"))]
+    polls: prometheus::IntCounterVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Self::instance(observe::metrics::get_storage_registry())
+            .expect("unexpected error getting metrics instance")
+    }
+
+    fn poll(result: &str) {
+        Self::get().polls.with_label_values(&[result]).inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::indexer::{Indexer, params_hash},
+        contracts::composable_cow::{self, event_data::ConditionalOrderCreated},
+        ethcontract::{EventMetadata, jsonrpc::ErrorCode},
+        ethrpc::{Web3Transport, mock::MockTransport},
+        serde_json::json,
+        shared::event_handling::EventStoring,
+    };
+
+    fn revert(signature: &str, params: &[Token]) -> Vec<u8> {
+        let mut data = keccak256(signature.as_bytes())[..4].to_vec();
+        data.extend(abi::encode(params));
+        data
+    }
+
+    #[test]
+    fn decodes_poll_errors() {
+        assert_eq!(
+            PollError::decode(&revert(
+                "PollTryNextBlock(string)",
+                &[Token::String("no balance".into())]
+            )),
+            Some(PollError::TryNextBlock("no balance".into()))
+        );
+        assert_eq!(
+            PollError::decode(&revert(
+                "PollTryAtEpoch(uint256,string)",
+                &[
+                    Token::Uint(1_700_000_000.into()),
+                    Token::String("not started".into())
+                ]
+            )),
+            Some(PollError::TryAtEpoch(1_700_000_000, "not started".into()))
+        );
+        assert_eq!(
+            PollError::decode(&revert(
+                "PollTryAtBlock(uint256,string)",
+                &[Token::Uint(U256::MAX), Token::String("later".into())]
+            )),
+            Some(PollError::TryAtBlock(u64::MAX, "later".into()))
+        );
+        assert_eq!(
+            PollError::decode(&revert(
+                "PollNever(string)",
+                &[Token::String("done".into())]
+            )),
+            Some(PollError::Never("done".into()))
+        );
+        assert_eq!(
+            PollError::decode(&revert("SingleOrderNotAuthed()", &[])),
+            Some(PollError::SingleOrderNotAuthed)
+        );
+        assert_eq!(
+            PollError::decode(&revert("Error(string)", &[Token::String("oops".into())])),
+            None
+        );
+        assert_eq!(PollError::decode(&[1, 2]), None);
+    }
+
+    #[test]
+    fn schedules_next_poll() {
+        let order = ConditionalOrder {
+            last_order_uid: Some(ByteArray([1; 56])),
+            ..Default::default()
+        };
+
+        let result = PollError::TryNextBlock(String::new()).into_poll_result(&order, 10);
+        assert!(result.active);
+        assert_eq!(result.next_poll_block, Some(11));
+        assert_eq!(result.last_order_uid, order.last_order_uid);
+
+        let result = PollError::TryAtBlock(20, String::new()).into_poll_result(&order, 10);
+        assert_eq!(result.next_poll_block, Some(20));
+
+        let result = PollError::TryAtEpoch(100, String::new()).into_poll_result(&order, 10);
+        assert_eq!(result.next_poll_block, None);
+        assert_eq!(result.next_poll_timestamp, DateTime::from_timestamp(100, 0));
+
+        let result = PollError::Never("done".into()).into_poll_result(&order, 10);
+        assert!(!result.active);
+        assert_eq!(result.last_error.as_deref(), Some("PollNever: done"));
+
+        let result = PollError::SingleOrderNotAuthed.into_poll_result(&order, 10);
+        assert!(!result.active);
+    }
+
+    #[test]
+    fn backs_off_failed_polls() {
+        let order = |failed_polls| ConditionalOrder {
+            last_order_uid: Some(ByteArray([1; 56])),
+            failed_polls,
+            ..Default::default()
+        };
+        let transient =
+            |order| Failure::Transient(anyhow::anyhow!("timeout")).into_poll_result(&order, 10);
+        let permanent =
+            |order| Failure::Permanent(anyhow::anyhow!("rejected")).into_poll_result(&order, 10);
+
+        let result = transient(order(0));
+        assert!(result.active);
+        assert_eq!(result.next_poll_block, Some(11));
+        assert_eq!(result.failed_polls, 1);
+        assert_eq!(result.last_order_uid, Some(ByteArray([1; 56])));
+        assert_eq!(result.last_error.as_deref(), Some("timeout"));
+
+        assert_eq!(transient(order(3)).next_poll_block, Some(18));
+        assert_eq!(transient(order(3)).failed_polls, 4);
+        assert_eq!(transient(order(20)).next_poll_block, Some(10 + 32));
+        assert_eq!(permanent(order(20)).next_poll_block, Some(10 + 1024));
+        assert_eq!(permanent(order(i32::MAX)).failed_polls, i32::MAX);
+
+        // Successful polls reset the backoff.
+        let result = PollError::TryNextBlock(String::new()).into_poll_result(&order(5), 10);
+        assert_eq!(result.next_poll_block, Some(11));
+        assert_eq!(result.failed_polls, 0);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_polls_indexed_orders() {
+        let db = PgPool::connect("postgresql://").await.unwrap();
+        database::clear_DANGER(&db).await.unwrap();
+
+        let owner = H160([1; 20]);
+        let handler = H160([2; 20]);
+        let event = |salt: u8, block_number: u64| ethcontract::Event {
+            data: composable_cow::Event::ConditionalOrderCreated(ConditionalOrderCreated {
+                owner,
+                params: (handler, Bytes([salt; 32]), Bytes(vec![salt])),
+            }),
+            meta: Some(EventMetadata {
+                block_number,
+                ..Default::default()
+            }),
+        };
+        let fetch = |salt: u8| {
+            let db = db.clone();
+            async move {
+                let mut ex = db.acquire().await.unwrap();
+                let hash = params_hash(handler, [salt; 32], &[salt]);
+                database::conditional_orders::fetch(&mut ex, &ByteArray(owner.0), &ByteArray(hash))
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+
+        let mut indexer = Indexer::new(db.clone(), 0);
+        indexer
+            .append_events(vec![event(1, 1), event(2, 2)])
+            .await
+            .unwrap();
+
+        // The node is unreachable when polling the first order and the handler
+        // of the second order signals that it never produces a discrete order.
+        let transport = MockTransport::new();
+        transport
+            .mock()
+            .expect_execute()
+            .returning(|method, params| {
+                assert_eq!(method, "eth_call");
+                let data = params[0]["data"].as_str().unwrap();
+                if !data.contains(&"02".repeat(32)) {
+                    return Err(web3::Error::Unreachable);
+                }
+                let revert = revert("PollNever(string)", &[Token::String("done".into())]);
+                Err(web3::Error::Rpc(ethcontract::jsonrpc::Error {
+                    code: ErrorCode::ServerError(3),
+                    message: "execution reverted".to_string(),
+                    data: Some(json!(format!("0x{}", hex::encode(revert)))),
+                }))
+            });
+        let web3 = Web3::new(Web3Transport::new(transport));
+        let poller = Poller::new(
+            db.clone(),
+            web3.clone(),
+            ComposableCoW::at(&web3, H160([3; 20])),
+            Orderbook::new(reqwest::Client::new(), "http://localhost".parse().unwrap()),
+            DomainSeparator::default(),
+            2,
+        );
+        let block = |number| BlockInfo {
+            number,
+            ..Default::default()
+        };
+
+        poller.poll(&block(10)).await.unwrap();
+        let order = fetch(1).await;
+        assert!(order.active);
+        assert_eq!(order.failed_polls, 1);
+        assert_eq!(order.next_poll_block, Some(11));
+        let order = fetch(2).await;
+        assert!(!order.active);
+        assert_eq!(order.last_error.as_deref(), Some("PollNever: done"));
+
+        // Failing orders are polled less and less often.
+        poller.poll(&block(11)).await.unwrap();
+        assert_eq!(fetch(1).await.next_poll_block, Some(13));
+        poller.poll(&block(12)).await.unwrap();
+        assert_eq!(fetch(1).await.failed_polls, 2);
+        poller.poll(&block(13)).await.unwrap();
+        let order = fetch(1).await;
+        assert_eq!(order.failed_polls, 3);
+        assert_eq!(order.next_poll_block, Some(17));
+
+        // Creating the conditional order again resets its polling state.
+        indexer.append_events(vec![event(1, 20)]).await.unwrap();
+        let order = fetch(1).await;
+        assert!(order.active);
+        assert_eq!(order.failed_polls, 0);
+        assert_eq!(order.next_poll_block, None);
+    }
+}
@@ -0,0 +1,19 @@
+-- Publish every inserted order event on the `order_events` channel, so that
+-- services can stream the lifecycle of orders without polling the table.
+CREATE FUNCTION notify_order_event() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify(
+        'order_events',
+        json_build_object(
+            'order_uid', encode(NEW.order_uid, 'hex'),
+            'timestamp', NEW.timestamp,
+            'label', NEW.label
+        )::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_order_event
+AFTER INSERT ON order_events
+FOR EACH ROW EXECUTE FUNCTION notify_order_event();
@@ -0,0 +1,13 @@
+-- Supports paginating through the orders of a user with a cursor on the
+-- creation timestamp and uid.
+DROP INDEX user_order_creation_timestamp;
+CREATE INDEX user_order_creation_timestamp ON orders USING BTREE (owner, creation_timestamp DESC, uid DESC);
+
+DROP INDEX jit_user_order_creation_timestamp;
+CREATE INDEX jit_user_order_creation_timestamp ON jit_orders USING BTREE (owner, creation_timestamp DESC, uid DESC);
+
+-- Supports filtering the orders of a user by their class.
+CREATE INDEX user_order_class_creation_timestamp ON orders USING BTREE (owner, class, creation_timestamp DESC, uid DESC);
+
+-- Supports filtering the orders of a user by their app data.
+CREATE INDEX user_order_app_data ON orders USING BTREE (owner, app_data);
@@ -0,0 +1,10 @@
+-- The latest reason why an order was filtered from the auction, so that the
+-- API can explain why an order is not being executed.
+CREATE TABLE order_filter_reasons (
+    order_uid bytea PRIMARY KEY,
+    timestamp timestamptz NOT NULL,
+    reason text NOT NULL
+);
+
+-- Allow cleaning up old reasons together with the order events.
+CREATE INDEX order_filter_reasons_timestamp ON order_filter_reasons USING BTREE (timestamp);
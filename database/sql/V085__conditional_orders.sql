@@ -0,0 +1,28 @@
+-- Conditional orders (e.g. TWAP, stop-loss) created through ComposableCoW and
+-- the state of the watch-tower polling them for discrete orders.
+CREATE TABLE conditional_orders (
+    owner bytea NOT NULL,
+    -- ComposableCoW's hash of the conditional order parameters.
+    hash bytea NOT NULL,
+    handler bytea NOT NULL,
+    salt bytea NOT NULL,
+    static_input bytea NOT NULL,
+    block_number bigint NOT NULL,
+    log_index bigint NOT NULL,
+    -- Whether the conditional order may still produce discrete orders.
+    active boolean NOT NULL DEFAULT true,
+    -- Don't poll the order before this block or time.
+    next_poll_block bigint,
+    next_poll_timestamp timestamptz,
+    last_order_uid bytea,
+    last_error text,
+    -- Consecutive failed polls, used to back off polling the order.
+    failed_polls integer NOT NULL DEFAULT 0,
+    PRIMARY KEY (owner, hash)
+);
+
+-- Allow deleting the orders of reorged blocks.
+CREATE INDEX conditional_orders_event_index ON conditional_orders USING BTREE (block_number, log_index);
+
+-- Allow finding the orders to poll.
+CREATE INDEX conditional_orders_active ON conditional_orders USING BTREE (next_poll_block) WHERE active;